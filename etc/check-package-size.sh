@@ -19,8 +19,12 @@ indent cargo diet -n --package-size-limit 25KB
 (enter git-features && indent cargo diet -n --package-size-limit 8KB)
 (enter git-ref && indent cargo diet -n --package-size-limit 4KB)
 (enter git-url && indent cargo diet -n --package-size-limit 6KB)
-(enter git-object && indent cargo diet -n --package-size-limit 15KB)
+(enter git-object && indent cargo diet -n --package-size-limit 35KB)
 (enter git-commitgraph && indent cargo diet -n --package-size-limit 15KB)
+(enter git-diff && indent cargo diet -n --package-size-limit 20KB)
+(enter git-traverse && indent cargo diet -n --package-size-limit 6KB)
+(enter git-sign && indent cargo diet -n --package-size-limit 30KB)
+(enter git-mailmap && indent cargo diet -n --package-size-limit 10KB)
 (enter git-odb && indent cargo diet -n --package-size-limit 55KB)
 (enter git-protocol && indent cargo diet -n --package-size-limit 20KB)
 (enter git-packetline && indent cargo diet -n --package-size-limit 7KB)
@@ -0,0 +1,166 @@
+//! Slide groups of changed lines to produce diffs that are easier to read, the way git does it without its
+//! indent heuristic.
+
+/// The lines of a file along with a flag for each one telling whether it changed.
+pub(crate) struct File<'a> {
+    pub tokens: &'a [u32],
+    /// One flag per line, with an additional unchanged line before the first and after the last one.
+    changed: Vec<bool>,
+}
+
+impl<'a> File<'a> {
+    pub fn new(tokens: &'a [u32]) -> Self {
+        File {
+            tokens,
+            changed: vec![false; tokens.len() + 2],
+        }
+    }
+
+    /// The flags of all lines, to be set by diff algorithms.
+    pub fn changed_mut(&mut self) -> &mut [bool] {
+        let len = self.tokens.len();
+        &mut self.changed[1..=len]
+    }
+
+    pub fn is_changed(&self, line: usize) -> bool {
+        self.changed[line + 1]
+    }
+
+    fn at(&self, line: isize) -> bool {
+        self.changed[(line + 1) as usize]
+    }
+
+    fn set(&mut self, line: isize, changed: bool) {
+        self.changed[(line + 1) as usize] = changed;
+    }
+
+    fn len(&self) -> isize {
+        self.tokens.len() as isize
+    }
+}
+
+/// A group of changed lines, which is empty if `start == end`.
+struct Group {
+    start: isize,
+    end: isize,
+}
+
+impl Group {
+    fn first(file: &File<'_>) -> Self {
+        let mut end = 0;
+        while file.at(end) {
+            end += 1;
+        }
+        Group { start: 0, end }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    /// Move to the next group, or return false if this is the last one.
+    fn next(&mut self, file: &File<'_>) -> bool {
+        if self.end == file.len() {
+            return false;
+        }
+        self.start = self.end + 1;
+        self.end = self.start;
+        while file.at(self.end) {
+            self.end += 1;
+        }
+        true
+    }
+
+    /// Move to the previous group, or return false if this is the first one.
+    fn previous(&mut self, file: &File<'_>) -> bool {
+        if self.start == 0 {
+            return false;
+        }
+        self.end = self.start - 1;
+        self.start = self.end;
+        while file.at(self.start - 1) {
+            self.start -= 1;
+        }
+        true
+    }
+
+    /// Slide the group down by one line if the line after it equals its first line, possibly merging it with the
+    /// following group.
+    fn slide_down(&mut self, file: &mut File<'_>) -> bool {
+        if self.end < file.len() && file.tokens[self.start as usize] == file.tokens[self.end as usize] {
+            file.set(self.start, false);
+            file.set(self.end, true);
+            self.start += 1;
+            self.end += 1;
+            while file.at(self.end) {
+                self.end += 1;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Slide the group up by one line if the line before it equals its last line, possibly merging it with the
+    /// previous group.
+    fn slide_up(&mut self, file: &mut File<'_>) -> bool {
+        if self.start > 0 && file.tokens[self.start as usize - 1] == file.tokens[self.end as usize - 1] {
+            self.start -= 1;
+            self.end -= 1;
+            file.set(self.start, true);
+            file.set(self.end, false);
+            while file.at(self.start - 1) {
+                self.start -= 1;
+            }
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Move groups of changed lines in `file` as far down as possible, merging them where they touch, but prefer
+/// positions where they line up with changes in `other`.
+pub(crate) fn compact(file: &mut File<'_>, other: &File<'_>) {
+    let (mut group, mut other_group) = (Group::first(file), Group::first(other));
+    loop {
+        if !group.is_empty() {
+            let mut earliest_end;
+            let mut end_matching_other;
+            loop {
+                let size = group.end - group.start;
+                end_matching_other = None;
+
+                while group.slide_up(file) {
+                    assert!(other_group.previous(other), "group sync broken sliding up");
+                }
+                earliest_end = group.end;
+                if !other_group.is_empty() {
+                    end_matching_other = Some(group.end);
+                }
+
+                while group.slide_down(file) {
+                    assert!(other_group.next(other), "group sync broken sliding down");
+                    if !other_group.is_empty() {
+                        end_matching_other = Some(group.end);
+                    }
+                }
+                if size == group.end - group.start {
+                    break;
+                }
+            }
+
+            if group.end != earliest_end && end_matching_other.is_some() {
+                while other_group.is_empty() {
+                    assert!(group.slide_up(file), "match disappeared");
+                    assert!(other_group.previous(other), "group sync broken sliding to match");
+                }
+            }
+        }
+
+        if !group.next(file) {
+            break;
+        }
+        assert!(other_group.next(other), "group sync broken moving to next group");
+    }
+}
@@ -0,0 +1,219 @@
+//! The histogram diff algorithm as implemented by git's xdiff library.
+use crate::blob::myers;
+use std::collections::HashMap;
+
+/// Lines occurring more often than this in a region are not used to anchor the diff.
+const MAX_CHAIN_LENGTH: usize = 64;
+
+/// Mark lines of `a` and `b` as changed in `changed_a` and `changed_b` respectively, which have the same lengths as
+/// the lines they belong to.
+pub(crate) fn diff(a: &[u32], b: &[u32], changed_a: &mut [bool], changed_b: &mut [bool]) {
+    State {
+        a,
+        b,
+        changed_a,
+        changed_b,
+    }
+    .diff(1, a.len(), 1, b.len())
+}
+
+struct State<'a> {
+    a: &'a [u32],
+    b: &'a [u32],
+    changed_a: &'a mut [bool],
+    changed_b: &'a mut [bool],
+}
+
+/// The first line of a token within a region of `a`, and how often it occurs there.
+#[derive(Clone, Copy)]
+struct Record {
+    line: usize,
+    count: usize,
+}
+
+/// The longest common subsequence found so far, with inclusive and one-based line numbers, and zero lines if there
+/// is none.
+#[derive(Default)]
+struct Region {
+    start_a: usize,
+    end_a: usize,
+    start_b: usize,
+    end_b: usize,
+}
+
+struct Index<'a> {
+    /// The record of each token in `a`.
+    records: HashMap<u32, Record>,
+    /// The next line with the same token for each line in the scanned region, or 0 if there is none.
+    next_lines: Vec<usize>,
+    start_a: usize,
+    /// The occurrence count of the rarest line in the best common subsequence so far.
+    count: usize,
+    has_common: bool,
+    a: &'a [u32],
+}
+
+impl<'a> Index<'a> {
+    /// Line numbers are one-based, and all lines refer to the region in `a` this index was built from.
+    fn next_line(&self, line: usize) -> usize {
+        self.next_lines[line - self.start_a]
+    }
+
+    fn count_of(&self, line: usize) -> usize {
+        self.records[&self.a[line - 1]].count
+    }
+}
+
+impl<'a> State<'a> {
+    /// Diff the regions of `count_a` lines starting at `line_a` and `count_b` lines starting at `line_b`, with line
+    /// numbers being one-based.
+    fn diff(&mut self, mut line_a: usize, mut count_a: usize, mut line_b: usize, mut count_b: usize) {
+        loop {
+            if count_a == 0 {
+                self.changed_b[line_b - 1..][..count_b]
+                    .iter_mut()
+                    .for_each(|c| *c = true);
+                return;
+            } else if count_b == 0 {
+                self.changed_a[line_a - 1..][..count_a]
+                    .iter_mut()
+                    .for_each(|c| *c = true);
+                return;
+            }
+
+            let (lcs, fall_back) = self.find_lcs(line_a, count_a, line_b, count_b);
+            if fall_back {
+                let (range_a, range_b) = (line_a - 1..line_a - 1 + count_a, line_b - 1..line_b - 1 + count_b);
+                myers::diff(
+                    &self.a[range_a.clone()],
+                    &self.b[range_b.clone()],
+                    false,
+                    &mut self.changed_a[range_a],
+                    &mut self.changed_b[range_b],
+                );
+                return;
+            }
+            if lcs.start_a == 0 && lcs.start_b == 0 {
+                self.changed_a[line_a - 1..][..count_a]
+                    .iter_mut()
+                    .for_each(|c| *c = true);
+                self.changed_b[line_b - 1..][..count_b]
+                    .iter_mut()
+                    .for_each(|c| *c = true);
+                return;
+            }
+
+            self.diff(line_a, lcs.start_a - line_a, line_b, lcs.start_b - line_b);
+            let (end_a, end_b) = (line_a + count_a - 1, line_b + count_b - 1);
+            count_a = end_a - lcs.end_a;
+            line_a = lcs.end_a + 1;
+            count_b = end_b - lcs.end_b;
+            line_b = lcs.end_b + 1;
+        }
+    }
+
+    /// Return the longest common subsequence made of the least frequent lines, and whether the region should be
+    /// diffed with the Myers algorithm instead as all common lines are too frequent.
+    fn find_lcs(&self, line_a: usize, count_a: usize, line_b: usize, count_b: usize) -> (Region, bool) {
+        let end_a = line_a + count_a - 1;
+        let mut index = Index {
+            records: HashMap::new(),
+            next_lines: vec![0; count_a],
+            start_a: line_a,
+            count: MAX_CHAIN_LENGTH + 1,
+            has_common: false,
+            a: self.a,
+        };
+        for line in (line_a..=end_a).rev() {
+            let token = self.a[line - 1];
+            match index.records.get_mut(&token) {
+                Some(record) => {
+                    index.next_lines[line - line_a] = record.line;
+                    record.line = line;
+                    record.count += 1;
+                }
+                None => {
+                    index.records.insert(token, Record { line, count: 1 });
+                }
+            }
+        }
+
+        let mut lcs = Region::default();
+        let mut line = line_b;
+        while line < line_b + count_b {
+            line = self.try_lcs(&mut index, &mut lcs, line, line_a, count_a, line_b, count_b);
+        }
+        let fall_back = index.has_common && MAX_CHAIN_LENGTH < index.count;
+        (lcs, fall_back)
+    }
+
+    /// Try to find a longer or rarer common subsequence containing `b_line`, and return the next line in `b` to try.
+    #[allow(clippy::too_many_arguments)]
+    fn try_lcs(
+        &self,
+        index: &mut Index<'_>,
+        lcs: &mut Region,
+        b_line: usize,
+        line_a: usize,
+        count_a: usize,
+        line_b: usize,
+        count_b: usize,
+    ) -> usize {
+        let (a, b) = (self.a, self.b);
+        let (end_a, end_b) = (line_a + count_a - 1, line_b + count_b - 1);
+        let mut b_next = b_line + 1;
+        let record = match index.records.get(&b[b_line - 1]) {
+            Some(record) => *record,
+            None => return b_next,
+        };
+        if record.count > index.count {
+            index.has_common = true;
+            return b_next;
+        }
+        index.has_common = true;
+
+        let mut a_line = record.line;
+        loop {
+            let mut next = index.next_line(a_line);
+            let (mut start_a, mut start_b, mut last_a, mut last_b) = (a_line, b_line, a_line, b_line);
+            let mut count = record.count;
+
+            while line_a < start_a && line_b < start_b && a[start_a - 2] == b[start_b - 2] {
+                start_a -= 1;
+                start_b -= 1;
+                if count > 1 {
+                    count = count.min(index.count_of(start_a));
+                }
+            }
+            while last_a < end_a && last_b < end_b && a[last_a] == b[last_b] {
+                last_a += 1;
+                last_b += 1;
+                if count > 1 {
+                    count = count.min(index.count_of(last_a));
+                }
+            }
+
+            if b_next <= last_b {
+                b_next = last_b + 1;
+            }
+            if lcs.end_a - lcs.start_a < last_a - start_a || count < index.count {
+                *lcs = Region {
+                    start_a,
+                    end_a: last_a,
+                    start_b,
+                    end_b: last_b,
+                };
+                index.count = count;
+            }
+
+            while next != 0 && next <= last_a {
+                next = index.next_line(next);
+            }
+            if next == 0 {
+                break;
+            }
+            a_line = next;
+        }
+        b_next
+    }
+}
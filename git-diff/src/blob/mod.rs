@@ -0,0 +1,119 @@
+//! Line-based diffs between the data of two blobs.
+use std::{collections::HashMap, ops::Range};
+
+mod compact;
+mod histogram;
+mod myers;
+
+pub mod unified;
+
+/// The amount of bytes at the beginning of a blob which are checked for NUL bytes to determine if it's binary.
+const BINARY_PEEK_LEN: usize = 8000;
+
+/// The algorithm used to compute the lines that changed, similar to git's `diff.algorithm`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Algorithm {
+    /// The Myers algorithm, with heuristics to speed up diffs with many changes.
+    Myers,
+    /// The Myers algorithm without any heuristics, producing the smallest possible diff.
+    MyersMinimal,
+    /// The histogram algorithm, which anchors diffs on lines occurring rarely and falls back to
+    /// [`Myers`][Algorithm::Myers] if there are none.
+    Histogram,
+}
+
+/// A group of consecutive lines that changed, as ranges of line indices into the previous and current lines, one of
+/// which may be empty.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Change {
+    pub previous: Range<usize>,
+    pub current: Range<usize>,
+}
+
+/// The result of [`diff()`], the lines of both sides along with the changes between them.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Diff<'a> {
+    /// All lines of the previous data, each with its line terminator if it has one.
+    pub previous: Vec<&'a [u8]>,
+    /// All lines of the current data, each with its line terminator if it has one.
+    pub current: Vec<&'a [u8]>,
+    /// The changes in the order of the lines they affect.
+    pub changes: Vec<Change>,
+}
+
+/// Returns true if `data` looks binary the way git sees it, that is it contains a NUL byte within its first 8000 bytes.
+pub fn is_binary(data: &[u8]) -> bool {
+    data[..data.len().min(BINARY_PEEK_LEN)].contains(&0)
+}
+
+/// Split `data` into lines, keeping the line terminators.
+pub fn lines(data: &[u8]) -> Vec<&[u8]> {
+    data.split_inclusive(|b| *b == b'\n').collect()
+}
+
+/// Compute the changes needed to turn the lines of `previous` into the lines of `current` using `algorithm`.
+///
+/// Changes are placed like git places them if its indent heuristic is disabled, and lines are compared byte by byte.
+pub fn diff<'a>(previous: &'a [u8], current: &'a [u8], algorithm: Algorithm) -> Diff<'a> {
+    let (previous, current) = (lines(previous), lines(current));
+    let mut tokens_by_line = HashMap::<&[u8], u32>::new();
+    let mut tokenize = |lines: &[&'a [u8]]| -> Vec<u32> {
+        lines
+            .iter()
+            .map(|line| {
+                let next_token = tokens_by_line.len() as u32;
+                *tokens_by_line.entry(line).or_insert(next_token)
+            })
+            .collect()
+    };
+    let (previous_tokens, current_tokens) = (tokenize(&previous), tokenize(&current));
+
+    let mut previous_file = compact::File::new(&previous_tokens);
+    let mut current_file = compact::File::new(&current_tokens);
+    match algorithm {
+        Algorithm::Myers | Algorithm::MyersMinimal => myers::diff(
+            &previous_tokens,
+            &current_tokens,
+            algorithm == Algorithm::MyersMinimal,
+            previous_file.changed_mut(),
+            current_file.changed_mut(),
+        ),
+        Algorithm::Histogram => histogram::diff(
+            &previous_tokens,
+            &current_tokens,
+            previous_file.changed_mut(),
+            current_file.changed_mut(),
+        ),
+    }
+    compact::compact(&mut previous_file, &current_file);
+    compact::compact(&mut current_file, &previous_file);
+
+    let mut changes = Vec::new();
+    let (mut previous_line, mut current_line) = (0, 0);
+    while previous_line < previous.len() || current_line < current.len() {
+        if previous_file.is_changed(previous_line) || current_file.is_changed(current_line) {
+            let (previous_start, current_start) = (previous_line, current_line);
+            while previous_file.is_changed(previous_line) {
+                previous_line += 1;
+            }
+            while current_file.is_changed(current_line) {
+                current_line += 1;
+            }
+            changes.push(Change {
+                previous: previous_start..previous_line,
+                current: current_start..current_line,
+            });
+        } else {
+            previous_line += 1;
+            current_line += 1;
+        }
+    }
+
+    Diff {
+        previous,
+        current,
+        changes,
+    }
+}
@@ -0,0 +1,412 @@
+//! The Myers diff algorithm as implemented by git's xdiff library, including the preparation steps and heuristics
+//! which affect its result.
+use std::collections::HashMap;
+
+/// Beyond this many occurrences a line is considered to occur too often to be worth matching.
+const MAX_EQUAL_LIMIT: usize = 1024;
+/// The amount of lines around a frequent line which are scanned to decide whether to discard it.
+const SIMILAR_SCAN_WINDOW: usize = 100;
+const KEEP_DISCARDED_RUN: usize = 4;
+const SNAKE_COUNT: isize = 20;
+const HEURISTIC_MIN_COST: isize = 256;
+const MAX_COST_MIN: isize = 256;
+const K_HEURISTIC: isize = 4;
+
+/// Mark lines of `a` and `b` as changed in `changed_a` and `changed_b` respectively, which have the same lengths as
+/// the lines they belong to.
+///
+/// If `minimal` is true, the smallest possible diff is produced even if that takes long.
+pub(crate) fn diff(a: &[u32], b: &[u32], minimal: bool, changed_a: &mut [bool], changed_b: &mut [bool]) {
+    let common_prefix = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    let common_suffix = a[common_prefix..]
+        .iter()
+        .rev()
+        .zip(b[common_prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a_range, b_range) = (
+        common_prefix..a.len() - common_suffix,
+        common_prefix..b.len() - common_suffix,
+    );
+
+    let mut occurrences = HashMap::<u32, (usize, usize)>::new();
+    for token in a {
+        occurrences.entry(*token).or_default().0 += 1;
+    }
+    for token in b {
+        occurrences.entry(*token).or_default().1 += 1;
+    }
+    let kind_a = classify(&a[a_range.clone()], |token| occurrences[&token].1, a.len());
+    let kind_b = classify(&b[b_range.clone()], |token| occurrences[&token].0, b.len());
+    let (tokens_a, index_a) = discard(&a[a_range.clone()], &kind_a, &mut changed_a[a_range.clone()]);
+    let (tokens_b, index_b) = discard(&b[b_range.clone()], &kind_b, &mut changed_b[b_range.clone()]);
+
+    let num_diagonals = tokens_a.len() + tokens_b.len() + 3;
+    let mut state = State {
+        a: &tokens_a,
+        b: &tokens_b,
+        forward: vec![0; num_diagonals],
+        backward: vec![0; num_diagonals],
+        diagonal_offset: tokens_b.len() as isize + 1,
+        max_cost: bogo_sqrt(num_diagonals).max(MAX_COST_MIN as usize) as isize,
+        changed_a: &mut changed_a[a_range.clone()],
+        changed_b: &mut changed_b[b_range.clone()],
+        index_a: &index_a,
+        index_b: &index_b,
+    };
+    state.compare(0, tokens_a.len() as isize, 0, tokens_b.len() as isize, minimal);
+}
+
+/// How often a line of one file occurs in the other one.
+#[derive(Clone, Copy, PartialEq)]
+enum Occurrence {
+    None,
+    Some,
+    Many,
+}
+
+fn classify(tokens: &[u32], occurrences_in_other: impl Fn(u32) -> usize, num_lines: usize) -> Vec<Occurrence> {
+    let limit = bogo_sqrt(num_lines).min(MAX_EQUAL_LIMIT);
+    tokens
+        .iter()
+        .map(|token| match occurrences_in_other(*token) {
+            0 => Occurrence::None,
+            n if n >= limit => Occurrence::Many,
+            _ => Occurrence::Some,
+        })
+        .collect()
+}
+
+/// Mark lines which can't match, and lines that occur very often among those, as changed, and return the tokens of
+/// all remaining lines along with their index in `tokens`.
+fn discard(tokens: &[u32], kinds: &[Occurrence], changed: &mut [bool]) -> (Vec<u32>, Vec<usize>) {
+    let mut kept_tokens = Vec::with_capacity(tokens.len());
+    let mut kept_index = Vec::with_capacity(tokens.len());
+    for (idx, (token, kind)) in tokens.iter().zip(kinds).enumerate() {
+        let keep = match kind {
+            Occurrence::Some => true,
+            Occurrence::Many => !should_discard_frequent_line(kinds, idx),
+            Occurrence::None => false,
+        };
+        if keep {
+            kept_tokens.push(*token);
+            kept_index.push(idx);
+        } else {
+            changed[idx] = true;
+        }
+    }
+    (kept_tokens, kept_index)
+}
+
+/// A line occurring very often is discarded if it's surrounded by lines that don't match at all, as it would
+/// otherwise split a large change into many small ones.
+fn should_discard_frequent_line(kinds: &[Occurrence], idx: usize) -> bool {
+    let start = idx.saturating_sub(SIMILAR_SCAN_WINDOW);
+    let end = (idx + SIMILAR_SCAN_WINDOW).min(kinds.len() - 1);
+    let scan = |lines: &mut dyn Iterator<Item = usize>| {
+        let (mut unmatched, mut frequent) = (0, 1);
+        for line in lines {
+            match kinds[line] {
+                Occurrence::None => unmatched += 1,
+                Occurrence::Many => frequent += 1,
+                Occurrence::Some => break,
+            }
+        }
+        (unmatched, frequent)
+    };
+    let (unmatched_before, frequent_before) = scan(&mut (start..idx).rev());
+    if unmatched_before == 0 {
+        return false;
+    }
+    let (unmatched_after, frequent_after) = scan(&mut (idx + 1..=end));
+    if unmatched_after == 0 {
+        return false;
+    }
+    let (unmatched, frequent) = (unmatched_before + unmatched_after, frequent_before + frequent_after);
+    frequent * KEEP_DISCARDED_RUN < frequent + unmatched
+}
+
+/// An integer square root approximation.
+fn bogo_sqrt(mut n: usize) -> usize {
+    let mut i = 1;
+    while n > 0 {
+        i <<= 1;
+        n >>= 2;
+    }
+    i
+}
+
+struct Split {
+    a: isize,
+    b: isize,
+    minimal_before: bool,
+    minimal_after: bool,
+}
+
+struct State<'a> {
+    a: &'a [u32],
+    b: &'a [u32],
+    /// The furthest reaching paths in `a` per diagonal for the forward and backward search.
+    forward: Vec<isize>,
+    backward: Vec<isize>,
+    /// Diagonals range from `-(b.len() + 1)` to `a.len() + 1`.
+    diagonal_offset: isize,
+    max_cost: isize,
+    changed_a: &'a mut [bool],
+    changed_b: &'a mut [bool],
+    index_a: &'a [usize],
+    index_b: &'a [usize],
+}
+
+impl<'a> State<'a> {
+    fn compare(&mut self, mut start_a: isize, mut end_a: isize, mut start_b: isize, mut end_b: isize, minimal: bool) {
+        while start_a < end_a && start_b < end_b && self.a[start_a as usize] == self.b[start_b as usize] {
+            start_a += 1;
+            start_b += 1;
+        }
+        while start_a < end_a && start_b < end_b && self.a[end_a as usize - 1] == self.b[end_b as usize - 1] {
+            end_a -= 1;
+            end_b -= 1;
+        }
+
+        if start_a == end_a {
+            for idx in start_b..end_b {
+                self.changed_b[self.index_b[idx as usize]] = true;
+            }
+        } else if start_b == end_b {
+            for idx in start_a..end_a {
+                self.changed_a[self.index_a[idx as usize]] = true;
+            }
+        } else {
+            let split = self.split(start_a, end_a, start_b, end_b, minimal);
+            self.compare(start_a, split.a, start_b, split.b, split.minimal_before);
+            self.compare(split.a, end_a, split.b, end_b, split.minimal_after);
+        }
+    }
+
+    fn forward(&mut self, diagonal: isize) -> &mut isize {
+        &mut self.forward[(diagonal + self.diagonal_offset) as usize]
+    }
+
+    fn backward(&mut self, diagonal: isize) -> &mut isize {
+        &mut self.backward[(diagonal + self.diagonal_offset) as usize]
+    }
+
+    /// Find the middle snake of the shortest edit script, or a good enough split point if that's too costly
+    /// and `minimal` is false.
+    fn split(&mut self, start_a: isize, end_a: isize, start_b: isize, end_b: isize, minimal: bool) -> Split {
+        let (a, b) = (self.a, self.b);
+        let (min_diagonal, max_diagonal) = (start_a - end_b, end_a - start_b);
+        let (forward_mid, backward_mid) = (start_a - start_b, end_a - end_b);
+        let odd = (forward_mid - backward_mid) & 1 != 0;
+        let (mut forward_min, mut forward_max) = (forward_mid, forward_mid);
+        let (mut backward_min, mut backward_max) = (backward_mid, backward_mid);
+
+        *self.forward(forward_mid) = start_a;
+        *self.backward(backward_mid) = end_a;
+
+        let mut cost = 1;
+        loop {
+            let mut got_snake = false;
+
+            if forward_min > min_diagonal {
+                forward_min -= 1;
+                *self.forward(forward_min - 1) = -1;
+            } else {
+                forward_min += 1;
+            }
+            if forward_max < max_diagonal {
+                forward_max += 1;
+                *self.forward(forward_max + 1) = -1;
+            } else {
+                forward_max -= 1;
+            }
+            let mut d = forward_max;
+            while d >= forward_min {
+                let mut i1 = if *self.forward(d - 1) >= *self.forward(d + 1) {
+                    *self.forward(d - 1) + 1
+                } else {
+                    *self.forward(d + 1)
+                };
+                let previous_i1 = i1;
+                let mut i2 = i1 - d;
+                while i1 < end_a && i2 < end_b && a[i1 as usize] == b[i2 as usize] {
+                    i1 += 1;
+                    i2 += 1;
+                }
+                if i1 - previous_i1 > SNAKE_COUNT {
+                    got_snake = true;
+                }
+                *self.forward(d) = i1;
+                if odd && backward_min <= d && d <= backward_max && *self.backward(d) <= i1 {
+                    return Split {
+                        a: i1,
+                        b: i2,
+                        minimal_before: true,
+                        minimal_after: true,
+                    };
+                }
+                d -= 2;
+            }
+
+            if backward_min > min_diagonal {
+                backward_min -= 1;
+                *self.backward(backward_min - 1) = isize::MAX;
+            } else {
+                backward_min += 1;
+            }
+            if backward_max < max_diagonal {
+                backward_max += 1;
+                *self.backward(backward_max + 1) = isize::MAX;
+            } else {
+                backward_max -= 1;
+            }
+            let mut d = backward_max;
+            while d >= backward_min {
+                let mut i1 = if *self.backward(d - 1) < *self.backward(d + 1) {
+                    *self.backward(d - 1)
+                } else {
+                    *self.backward(d + 1) - 1
+                };
+                let previous_i1 = i1;
+                let mut i2 = i1 - d;
+                while i1 > start_a && i2 > start_b && a[i1 as usize - 1] == b[i2 as usize - 1] {
+                    i1 -= 1;
+                    i2 -= 1;
+                }
+                if previous_i1 - i1 > SNAKE_COUNT {
+                    got_snake = true;
+                }
+                *self.backward(d) = i1;
+                if !odd && forward_min <= d && d <= forward_max && i1 <= *self.forward(d) {
+                    return Split {
+                        a: i1,
+                        b: i2,
+                        minimal_before: true,
+                        minimal_after: true,
+                    };
+                }
+                d -= 2;
+            }
+
+            if minimal {
+                cost += 1;
+                continue;
+            }
+
+            // Prefer long snakes if the edit script gets expensive.
+            if got_snake && cost > HEURISTIC_MIN_COST {
+                let mut best = 0;
+                let mut split = None;
+                let mut d = forward_max;
+                while d >= forward_min {
+                    let distance = (d - forward_mid).abs();
+                    let i1 = *self.forward(d);
+                    let i2 = i1 - d;
+                    let v = (i1 - start_a) + (i2 - start_b) - distance;
+                    if v > K_HEURISTIC * cost
+                        && v > best
+                        && start_a + SNAKE_COUNT <= i1
+                        && i1 < end_a
+                        && start_b + SNAKE_COUNT <= i2
+                        && i2 < end_b
+                        && (1..=SNAKE_COUNT).all(|k| a[(i1 - k) as usize] == b[(i2 - k) as usize])
+                    {
+                        best = v;
+                        split = Some((i1, i2));
+                    }
+                    d -= 2;
+                }
+                if let Some((i1, i2)) = split {
+                    return Split {
+                        a: i1,
+                        b: i2,
+                        minimal_before: true,
+                        minimal_after: false,
+                    };
+                }
+
+                let mut best = 0;
+                let mut d = backward_max;
+                while d >= backward_min {
+                    let distance = (d - backward_mid).abs();
+                    let i1 = *self.backward(d);
+                    let i2 = i1 - d;
+                    let v = (end_a - i1) + (end_b - i2) - distance;
+                    if v > K_HEURISTIC * cost
+                        && v > best
+                        && start_a < i1
+                        && i1 <= end_a - SNAKE_COUNT
+                        && start_b < i2
+                        && i2 <= end_b - SNAKE_COUNT
+                        && (0..SNAKE_COUNT).all(|k| a[(i1 + k) as usize] == b[(i2 + k) as usize])
+                    {
+                        best = v;
+                        split = Some((i1, i2));
+                    }
+                    d -= 2;
+                }
+                if let Some((i1, i2)) = split {
+                    return Split {
+                        a: i1,
+                        b: i2,
+                        minimal_before: false,
+                        minimal_after: true,
+                    };
+                }
+            }
+
+            // Give up and take the furthest reaching path if the edit script gets too expensive.
+            if cost >= self.max_cost {
+                let (mut forward_best, mut forward_best_a) = (-1, -1);
+                let mut d = forward_max;
+                while d >= forward_min {
+                    let mut i1 = (*self.forward(d)).min(end_a);
+                    let mut i2 = i1 - d;
+                    if end_b < i2 {
+                        i1 = end_b + d;
+                        i2 = end_b;
+                    }
+                    if forward_best < i1 + i2 {
+                        forward_best = i1 + i2;
+                        forward_best_a = i1;
+                    }
+                    d -= 2;
+                }
+
+                let (mut backward_best, mut backward_best_a) = (isize::MAX, isize::MAX);
+                let mut d = backward_max;
+                while d >= backward_min {
+                    let mut i1 = start_a.max(*self.backward(d));
+                    let mut i2 = i1 - d;
+                    if i2 < start_b {
+                        i1 = start_b + d;
+                        i2 = start_b;
+                    }
+                    if i1 + i2 < backward_best {
+                        backward_best = i1 + i2;
+                        backward_best_a = i1;
+                    }
+                    d -= 2;
+                }
+
+                return if (end_a + end_b) - backward_best < forward_best - (start_a + start_b) {
+                    Split {
+                        a: forward_best_a,
+                        b: forward_best - forward_best_a,
+                        minimal_before: true,
+                        minimal_after: false,
+                    }
+                } else {
+                    Split {
+                        a: backward_best_a,
+                        b: backward_best - backward_best_a,
+                        minimal_before: false,
+                        minimal_after: true,
+                    }
+                };
+            }
+            cost += 1;
+        }
+    }
+}
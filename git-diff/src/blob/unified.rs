@@ -0,0 +1,110 @@
+//! Write diffs in the unified format produced by `git diff`.
+use crate::blob::{Change, Diff};
+use std::io;
+
+/// The maximum amount of bytes of a line shown as function name in a hunk header.
+const MAX_FUNCTION_NAME_LEN: usize = 80;
+
+/// Write all hunks of `diff` to `out` in unified format, each with up to `context_lines` unchanged lines around its
+/// changes.
+///
+/// Hunks closer to each other than twice the amount of context lines are merged, and hunk headers show the closest
+/// line before the hunk which looks like the start of a function, just like `git diff` does without a diff driver.
+pub fn write(mut out: impl io::Write, diff: &Diff<'_>, context_lines: usize) -> io::Result<()> {
+    let (previous, current) = (&diff.previous, &diff.current);
+    let mut changes = &diff.changes[..];
+    let mut function_name: &[u8] = &[];
+    let mut function_search_start = 0;
+
+    while !changes.is_empty() {
+        let hunk = &changes[..hunk_len(changes, context_lines)];
+        changes = &changes[hunk.len()..];
+        let (first, last) = (&hunk[0], &hunk[hunk.len() - 1]);
+
+        let previous_start = first.previous.start.saturating_sub(context_lines);
+        let current_start = first.current.start.saturating_sub(context_lines);
+        let trailing_context = context_lines
+            .min(previous.len() - last.previous.end)
+            .min(current.len() - last.current.end);
+        let previous_end = last.previous.end + trailing_context;
+        let current_end = last.current.end + trailing_context;
+
+        if let Some(name) = find_function_name(&previous[function_search_start.min(previous_start)..previous_start]) {
+            function_name = name;
+        }
+        function_search_start = previous_start;
+
+        write!(out, "@@ -")?;
+        write_range(&mut out, previous_start, previous_end - previous_start)?;
+        write!(out, " +")?;
+        write_range(&mut out, current_start, current_end - current_start)?;
+        write!(out, " @@")?;
+        if !function_name.is_empty() {
+            out.write_all(b" ")?;
+            out.write_all(function_name)?;
+        }
+        out.write_all(b"\n")?;
+
+        let mut current_line = current_start;
+        for change in hunk {
+            for line in &current[current_line..change.current.start] {
+                write_line(&mut out, b' ', line)?;
+            }
+            for line in &previous[change.previous.clone()] {
+                write_line(&mut out, b'-', line)?;
+            }
+            for line in &current[change.current.clone()] {
+                write_line(&mut out, b'+', line)?;
+            }
+            current_line = change.current.end;
+        }
+        for line in &current[current_line..current_end] {
+            write_line(&mut out, b' ', line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Return the amount of changes at the beginning of `changes` which go into the same hunk.
+fn hunk_len(changes: &[Change], context_lines: usize) -> usize {
+    changes
+        .windows(2)
+        .take_while(|pair| pair[1].previous.start - pair[0].previous.end <= 2 * context_lines)
+        .count()
+        + 1
+}
+
+fn write_range(out: &mut impl io::Write, start: usize, len: usize) -> io::Result<()> {
+    let start = if len == 0 { start } else { start + 1 };
+    match len {
+        1 => write!(out, "{}", start),
+        _ => write!(out, "{},{}", start, len),
+    }
+}
+
+fn write_line(out: &mut impl io::Write, prefix: u8, line: &[u8]) -> io::Result<()> {
+    out.write_all(&[prefix])?;
+    out.write_all(line)?;
+    if !line.ends_with(b"\n") {
+        out.write_all(b"\n\\ No newline at end of file\n")?;
+    }
+    Ok(())
+}
+
+/// Return the last of `lines` which looks like the start of a function, without trailing whitespace.
+fn find_function_name<'a>(lines: &[&'a [u8]]) -> Option<&'a [u8]> {
+    lines.iter().rev().find_map(|line| {
+        let first = *line.first()?;
+        if !(first.is_ascii_alphabetic() || first == b'_' || first == b'$') {
+            return None;
+        }
+        let mut name = &line[..line.len().min(MAX_FUNCTION_NAME_LEN)];
+        while let Some((last, rest)) = name.split_last() {
+            if !matches!(last, b' ' | b'\t' | b'\n' | b'\r') {
+                break;
+            }
+            name = rest;
+        }
+        Some(name)
+    })
+}
@@ -0,0 +1,5 @@
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+
+pub mod blob;
+pub mod tree;
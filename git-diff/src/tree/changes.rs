@@ -0,0 +1,182 @@
+use crate::tree::{Action, Change, Delegate};
+use git_object::{
+    borrowed::{self, tree::Entry, TreeIter},
+    bstr::BString,
+    find, owned, TreeMode,
+};
+use std::cmp::Ordering;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error<E>
+where
+    E: std::error::Error + 'static,
+{
+    #[error(transparent)]
+    Find(#[from] find::Error<E>),
+    #[error("The delegate cancelled the operation")]
+    Cancelled,
+}
+
+/// Find all changes needed to turn the tree `previous` into the tree `current` and pass them to `delegate`, with `None`
+/// standing for an empty tree.
+///
+/// Trees are obtained with [`find`][git_object::find].
+/// Subtrees are traversed recursively unless their ids are the same, in the order of their entries, and changes
+/// to a subtree are seen right after the change of the subtree itself, similar to `git diff-tree -r -t`.
+/// Entries that change from a tree to a non-tree or vice versa are seen as deletion and addition.
+pub fn changes<Find, E>(
+    previous: Option<owned::Id>,
+    current: Option<owned::Id>,
+    find: Find,
+    delegate: &mut impl Delegate,
+) -> Result<(), Error<E>>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    E: std::error::Error + 'static,
+{
+    State {
+        find,
+        delegate,
+        buffers: Vec::new(),
+        path: BString::default(),
+    }
+    .trees(previous, current)
+}
+
+struct State<'a, Find, D> {
+    find: Find,
+    delegate: &'a mut D,
+    /// Buffers for tree data, reused across subtrees.
+    buffers: Vec<Vec<u8>>,
+    /// The path of the tree currently being diffed.
+    path: BString,
+}
+
+impl<'a, Find, D, E> State<'a, Find, D>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    D: Delegate,
+    E: std::error::Error + 'static,
+{
+    fn trees(&mut self, previous: Option<owned::Id>, current: Option<owned::Id>) -> Result<(), Error<E>> {
+        if previous == current {
+            return Ok(());
+        }
+        let previous_data = self.load(previous)?;
+        let current_data = self.load(current)?;
+        let res = entries(&previous_data, previous)
+            .and_then(|previous| Ok((previous, entries(&current_data, current)?)))
+            .and_then(|(previous, current)| self.entries(&previous, &current));
+        self.buffers.push(previous_data);
+        self.buffers.push(current_data);
+        res
+    }
+
+    fn entries(&mut self, previous: &[Entry<'_>], current: &[Entry<'_>]) -> Result<(), Error<E>> {
+        let (mut previous, mut current) = (previous.iter().peekable(), current.iter().peekable());
+        loop {
+            match (previous.peek(), current.peek()) {
+                (None, None) => return Ok(()),
+                (Some(lhs), Some(rhs)) => match compare(lhs, rhs) {
+                    Ordering::Less => self.deleted(previous.next().expect("peeked"))?,
+                    Ordering::Greater => self.added(current.next().expect("peeked"))?,
+                    Ordering::Equal => {
+                        self.modified(previous.next().expect("peeked"), current.next().expect("peeked"))?
+                    }
+                },
+                (Some(_), None) => self.deleted(previous.next().expect("peeked"))?,
+                (None, Some(_)) => self.added(current.next().expect("peeked"))?,
+            }
+        }
+    }
+
+    fn deleted(&mut self, entry: &Entry<'_>) -> Result<(), Error<E>> {
+        let oid = entry.oid.into();
+        let previous_len = git_object::tree::push_path_component(&mut self.path, entry.filename);
+        self.visit(Change::Deletion {
+            entry_mode: entry.mode,
+            oid,
+            path: self.path.clone(),
+        })?;
+        if entry.mode == TreeMode::Tree {
+            self.trees(Some(oid), None)?;
+        }
+        self.path.truncate(previous_len);
+        Ok(())
+    }
+
+    fn added(&mut self, entry: &Entry<'_>) -> Result<(), Error<E>> {
+        let oid = entry.oid.into();
+        let previous_len = git_object::tree::push_path_component(&mut self.path, entry.filename);
+        self.visit(Change::Addition {
+            entry_mode: entry.mode,
+            oid,
+            path: self.path.clone(),
+        })?;
+        if entry.mode == TreeMode::Tree {
+            self.trees(None, Some(oid))?;
+        }
+        self.path.truncate(previous_len);
+        Ok(())
+    }
+
+    fn modified(&mut self, previous: &Entry<'_>, current: &Entry<'_>) -> Result<(), Error<E>> {
+        if previous.oid == current.oid && previous.mode == current.mode {
+            return Ok(());
+        }
+        let (previous_oid, oid) = (previous.oid.into(), current.oid.into());
+        let previous_len = git_object::tree::push_path_component(&mut self.path, current.filename);
+        self.visit(Change::Modification {
+            previous_entry_mode: previous.mode,
+            previous_oid,
+            entry_mode: current.mode,
+            oid,
+            path: self.path.clone(),
+        })?;
+        if previous.mode == TreeMode::Tree && current.mode == TreeMode::Tree {
+            self.trees(Some(previous_oid), Some(oid))?;
+        }
+        self.path.truncate(previous_len);
+        Ok(())
+    }
+
+    fn visit(&mut self, change: Change) -> Result<(), Error<E>> {
+        match self.delegate.visit(change) {
+            Action::Continue => Ok(()),
+            Action::Cancel => Err(Error::Cancelled),
+        }
+    }
+
+    /// Return a buffer with the data of the tree `oid`, which is empty if there is no tree.
+    fn load(&mut self, oid: Option<owned::Id>) -> Result<Vec<u8>, Error<E>> {
+        let mut buf = self.buffers.pop().unwrap_or_default();
+        buf.clear();
+        if let Some(oid) = oid {
+            find::load(&mut self.find, oid, &mut buf)?;
+        }
+        Ok(buf)
+    }
+}
+
+fn entries<E>(data: &[u8], oid: Option<owned::Id>) -> Result<Vec<Entry<'_>>, Error<E>>
+where
+    E: std::error::Error + 'static,
+{
+    TreeIter::from_bytes(data).collect::<Result<_, _>>().map_err(|err| {
+        find::Error::Decode {
+            source: err,
+            oid: oid.expect("only trees with data can fail to decode"),
+        }
+        .into()
+    })
+}
+
+/// Compare entries the way trees sort them.
+fn compare(lhs: &Entry<'_>, rhs: &Entry<'_>) -> Ordering {
+    git_object::tree::compare_names(
+        lhs.filename,
+        lhs.mode == TreeMode::Tree,
+        rhs.filename,
+        rhs.mode == TreeMode::Tree,
+    )
+}
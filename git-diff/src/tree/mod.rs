@@ -0,0 +1,57 @@
+use git_object::{bstr::BString, owned, TreeMode};
+
+mod changes;
+pub use changes::{changes, Error};
+
+pub mod rewrites;
+
+mod recorder;
+pub use recorder::Recorder;
+
+/// A change between two trees, as seen at `path`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Change {
+    /// An entry that only exists in the current tree.
+    Addition {
+        entry_mode: TreeMode,
+        oid: owned::Id,
+        path: BString,
+    },
+    /// An entry that only exists in the previous tree.
+    Deletion {
+        entry_mode: TreeMode,
+        oid: owned::Id,
+        path: BString,
+    },
+    /// An entry that exists in both trees, but with a different mode or id.
+    Modification {
+        previous_entry_mode: TreeMode,
+        previous_oid: owned::Id,
+        entry_mode: TreeMode,
+        oid: owned::Id,
+        path: BString,
+    },
+}
+
+impl Change {
+    /// The path of the entry relative to the root of the trees, with components separated by `/`.
+    pub fn path(&self) -> &BString {
+        match self {
+            Change::Addition { path, .. } | Change::Deletion { path, .. } | Change::Modification { path, .. } => path,
+        }
+    }
+}
+
+/// Tells the tree diff whether to continue after a change was seen.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Action {
+    Continue,
+    /// Stop the diff and make it fail with [`Error::Cancelled`].
+    Cancel,
+}
+
+/// Receives all changes found by the tree diff.
+pub trait Delegate {
+    fn visit(&mut self, change: Change) -> Action;
+}
@@ -0,0 +1,14 @@
+use crate::tree::{Action, Change, Delegate};
+
+/// A [`Delegate`] which records all changes it sees.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+pub struct Recorder {
+    pub records: Vec<Change>,
+}
+
+impl Delegate for Recorder {
+    fn visit(&mut self, change: Change) -> Action {
+        self.records.push(change);
+        Action::Continue
+    }
+}
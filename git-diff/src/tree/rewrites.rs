@@ -0,0 +1,536 @@
+//! Find renames and copies among the changes of a tree diff, the way `git diff -M` and `git diff -C` do.
+use crate::tree;
+use git_object::{
+    borrowed,
+    bstr::{BString, ByteSlice},
+    find, owned, TreeMode,
+};
+use std::{cmp::Ordering, collections::HashMap};
+
+/// The score of two files with the same content.
+const MAX_SCORE: u64 = 60_000;
+/// The amount of most similar sources remembered for each destination.
+const CANDIDATES_PER_DESTINATION: usize = 4;
+/// The maximum amount of sources with the same id to consider for an exact rename.
+const MAX_IDENTICAL_CANDIDATES: usize = 100;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error<E>
+where
+    E: std::error::Error + 'static,
+{
+    #[error(transparent)]
+    Find(#[from] find::Error<E>),
+}
+
+/// Configure how renames and copies are found.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    /// The minimum similarity in percent of the contents of two blobs to be considered a rename or copy,
+    /// like the value of `-M` and `-C`. Defaults to 50.
+    pub percentage: u8,
+    /// If the amount of added files times the amount of possible sources exceeds the square of this value, only
+    /// renames and copies with identical or similar blobs and the same file name are found, like `diff.renameLimit`.
+    /// Defaults to 1000, and 0 means there is no limit.
+    pub limit: usize,
+    /// If true, modified files are also considered as sources of copies, like `-C` does.
+    pub copies: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            percentage: 50,
+            limit: 1000,
+            copies: false,
+        }
+    }
+}
+
+/// An entry added at `path` which is a rename or copy of the entry at `source_path`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rewrite {
+    pub source_entry_mode: TreeMode,
+    pub source_oid: owned::Id,
+    pub source_path: BString,
+    pub entry_mode: TreeMode,
+    pub oid: owned::Id,
+    pub path: BString,
+    /// How similar the blobs are in percent, with 100 meaning they are the same.
+    pub similarity: u8,
+}
+
+/// A change between two trees, with renames and copies replacing the changes they originate from.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Change {
+    /// A change that is not part of a rename or copy.
+    Plain(tree::Change),
+    /// An entry that was moved, so its source was deleted.
+    Rename(Rewrite),
+    /// An entry that was copied from a source which still exists, or which is renamed elsewhere.
+    Copy(Rewrite),
+}
+
+impl Change {
+    /// The path of the entry relative to the root of the trees, which is the destination for renames and copies.
+    pub fn path(&self) -> &BString {
+        match self {
+            Change::Plain(change) => change.path(),
+            Change::Rename(rewrite) | Change::Copy(rewrite) => &rewrite.path,
+        }
+    }
+}
+
+/// Find renames and copies among `changes` as produced by [`tree::changes()`][crate::tree::changes()], and return
+/// all changes in the same order, with the additions they were found for replaced by renames or copies and deletions
+/// of renamed entries removed.
+///
+/// Sources with the same id as an added entry are matched first, followed by sources that have the same file name and
+/// are very similar, and finally by the most similar sources overall. If a deleted entry is the source of multiple
+/// entries, the last one of these is its rename and all others are copies.
+/// Similarity is determined like git does it, so `find` is called to obtain blob data, similar to
+/// [`tree::changes()`][crate::tree::changes()].
+pub fn detect<Find, E>(changes: Vec<tree::Change>, find: Find, options: &Options) -> Result<Vec<Change>, Error<E>>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    E: std::error::Error + 'static,
+{
+    let mut state = State {
+        sources: Vec::new(),
+        active_sources: Vec::new(),
+        destinations: Vec::new(),
+        find,
+        buf: Vec::new(),
+        minimum_score: u64::from(options.percentage.min(100)) * MAX_SCORE / 100,
+    };
+    for (idx, change) in changes.iter().enumerate() {
+        match change {
+            tree::Change::Addition { entry_mode, oid, path } if *entry_mode != TreeMode::Tree => {
+                state.destinations.push(Destination {
+                    entry: File::new(*entry_mode, *oid, path.clone()),
+                    change: idx,
+                    source: None,
+                })
+            }
+            tree::Change::Deletion { entry_mode, oid, path } if *entry_mode != TreeMode::Tree => {
+                state.sources.push(Source {
+                    entry: File::new(*entry_mode, *oid, path.clone()),
+                    change: idx,
+                    uses: 0,
+                })
+            }
+            tree::Change::Modification {
+                previous_entry_mode,
+                previous_oid,
+                path,
+                ..
+            } if options.copies && *previous_entry_mode != TreeMode::Tree => state.sources.push(Source {
+                entry: File::new(*previous_entry_mode, *previous_oid, path.clone()),
+                change: idx,
+                // The modification itself keeps using the source, so it can only be copied.
+                uses: 1,
+            }),
+            _ => {}
+        }
+    }
+
+    state.active_sources = (0..state.sources.len()).collect();
+    if !state.sources.is_empty() && !state.destinations.is_empty() {
+        state.find_identical(options.copies);
+        if !options.copies {
+            state.remove_used_sources();
+            state.find_by_file_name()?;
+            state.remove_used_sources();
+        }
+        let remaining = state.destinations.iter().filter(|d| d.source.is_none()).count();
+        let within_limit = options.limit == 0
+            || (remaining as u64).saturating_mul(state.active_sources.len() as u64)
+                <= (options.limit as u64).saturating_mul(options.limit as u64);
+        if remaining != 0 && !state.active_sources.is_empty() && within_limit {
+            state.find_similar(options.copies)?;
+        }
+    }
+    Ok(state.into_changes(changes))
+}
+
+/// The content of a blob in a form suitable to estimate its similarity to other blobs.
+struct Signature {
+    size: u64,
+    /// The hash of each span of the blob along with the amount of bytes in spans with that hash, sorted by hash.
+    spans: Vec<(u32, u64)>,
+}
+
+impl Signature {
+    /// Split `data` into spans that end at a newline or after 64 bytes, ignoring carriage returns before newlines
+    /// unless the data is binary. Like git, a trailing span that isn't complete doesn't contribute.
+    fn new(data: &[u8]) -> Self {
+        const HASH_BASE: u32 = 107_927;
+        let is_text = !crate::blob::is_binary(data);
+        let mut spans = HashMap::<u32, u64>::new();
+        let (mut accum1, mut accum2, mut len) = (0u32, 0u32, 0u64);
+        for (idx, &byte) in data.iter().enumerate() {
+            if is_text && byte == b'\r' && data.get(idx + 1) == Some(&b'\n') {
+                continue;
+            }
+            let previous_accum1 = accum1;
+            accum1 = (accum1 << 7) ^ (accum2 >> 25);
+            accum2 = (accum2 << 7) ^ (previous_accum1 >> 25);
+            accum1 = accum1.wrapping_add(u32::from(byte));
+            len += 1;
+            if len < 64 && byte != b'\n' {
+                continue;
+            }
+            *spans
+                .entry(accum1.wrapping_add(accum2.wrapping_mul(0x61)) % HASH_BASE)
+                .or_default() += len;
+            accum1 = 0;
+            accum2 = 0;
+            len = 0;
+        }
+        let mut spans: Vec<_> = spans.into_iter().collect();
+        spans.sort_unstable();
+        Signature {
+            size: data.len() as u64,
+            spans,
+        }
+    }
+
+    /// The amount of bytes in `self` which also exist in `other`.
+    fn common_bytes(&self, other: &Signature) -> u64 {
+        let (mut lhs, mut rhs) = (self.spans.iter().peekable(), other.spans.iter().peekable());
+        let mut common = 0;
+        while let (Some((lhs_hash, lhs_len)), Some((rhs_hash, rhs_len))) = (lhs.peek(), rhs.peek()) {
+            match lhs_hash.cmp(rhs_hash) {
+                Ordering::Less => {
+                    lhs.next();
+                }
+                Ordering::Greater => {
+                    rhs.next();
+                }
+                Ordering::Equal => {
+                    common += lhs_len.min(rhs_len);
+                    lhs.next();
+                    rhs.next();
+                }
+            }
+        }
+        common
+    }
+}
+
+struct File {
+    mode: TreeMode,
+    oid: owned::Id,
+    path: BString,
+    signature: Option<Signature>,
+}
+
+impl File {
+    fn new(mode: TreeMode, oid: owned::Id, path: BString) -> Self {
+        File {
+            mode,
+            oid,
+            path,
+            signature: None,
+        }
+    }
+
+    fn is_regular(&self) -> bool {
+        matches!(self.mode, TreeMode::Blob | TreeMode::BlobExecutable)
+    }
+
+    fn file_name(&self) -> &[u8] {
+        self.path.rsplit_str("/").next().expect("at least one component")
+    }
+
+    /// Returns true if the file names of both paths are equal.
+    fn has_same_file_name(&self, other: &File) -> bool {
+        self.file_name() == other.file_name()
+    }
+}
+
+struct Source {
+    entry: File,
+    /// The index of the change this source belongs to.
+    change: usize,
+    /// The amount of renames, copies or modifications using this source.
+    uses: usize,
+}
+
+struct Destination {
+    entry: File,
+    /// The index of the addition this destination belongs to.
+    change: usize,
+    /// The index of the source in the list of all sources along with its score, once found.
+    source: Option<(usize, u64)>,
+}
+
+/// A possible source of a destination, ordered by how well it matches.
+#[derive(Clone, Copy)]
+struct Candidate {
+    score: u64,
+    same_file_name: bool,
+    destination: usize,
+    source: usize,
+}
+
+impl Candidate {
+    /// Order better candidates first.
+    fn compare(&self, other: &Candidate) -> Ordering {
+        other
+            .score
+            .cmp(&self.score)
+            .then_with(|| other.same_file_name.cmp(&self.same_file_name))
+    }
+}
+
+struct State<Find> {
+    sources: Vec<Source>,
+    /// The indices of sources that can still be used, in order.
+    active_sources: Vec<usize>,
+    destinations: Vec<Destination>,
+    find: Find,
+    buf: Vec<u8>,
+    minimum_score: u64,
+}
+
+impl<Find, E> State<Find>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    E: std::error::Error + 'static,
+{
+    fn record(&mut self, destination: usize, source: usize, score: u64) {
+        self.sources[source].uses += 1;
+        self.destinations[destination].source = Some((source, score));
+    }
+
+    fn remove_used_sources(&mut self) {
+        let sources = &self.sources;
+        self.active_sources.retain(|idx| sources[*idx].uses == 0);
+    }
+
+    /// Match destinations with sources that have the same id, preferring unused sources and those with the same file name.
+    fn find_identical(&mut self, copies: bool) {
+        let mut sources_by_id = HashMap::<owned::Id, Vec<usize>>::new();
+        for (idx, source) in self.sources.iter().enumerate() {
+            sources_by_id.entry(source.entry.oid).or_default().push(idx);
+        }
+        for destination_idx in 0..self.destinations.len() {
+            let destination = &self.destinations[destination_idx].entry;
+            let candidates = match sources_by_id.get(&destination.oid) {
+                Some(candidates) => candidates,
+                None => continue,
+            };
+            let mut best = None;
+            let mut considered = 0;
+            for &source_idx in candidates {
+                let source = &self.sources[source_idx];
+                if (!source.entry.is_regular() || !destination.is_regular()) && source.entry.mode != destination.mode {
+                    continue;
+                }
+                if source.uses != 0 && !copies {
+                    continue;
+                }
+                let score = usize::from(source.uses == 0) + usize::from(source.entry.has_same_file_name(destination));
+                if best.is_none_or(|(_, best_score)| score > best_score) {
+                    best = Some((source_idx, score));
+                    if score == 2 {
+                        break;
+                    }
+                }
+                considered += 1;
+                if considered == MAX_IDENTICAL_CANDIDATES {
+                    break;
+                }
+            }
+            if let Some((source_idx, _)) = best {
+                self.record(destination_idx, source_idx, MAX_SCORE);
+            }
+        }
+    }
+
+    /// Match destinations with sources whose file names are unique among all remaining sources and destinations, if
+    /// they are similar enough.
+    fn find_by_file_name(&mut self) -> Result<(), Error<E>> {
+        let minimum_score = self.minimum_score + (MAX_SCORE - self.minimum_score) / 2;
+        let unique_by_file_name = |files: &mut dyn Iterator<Item = (usize, &File)>| {
+            let mut by_file_name = HashMap::<&[u8], Option<usize>>::new();
+            for (idx, file) in files {
+                by_file_name
+                    .entry(file.file_name())
+                    .and_modify(|idx| *idx = None)
+                    .or_insert(Some(idx));
+            }
+            by_file_name
+                .into_iter()
+                .filter_map(|(name, idx)| idx.map(|idx| (name.to_owned(), idx)))
+                .collect::<HashMap<_, _>>()
+        };
+        let sources = unique_by_file_name(&mut self.active_sources.iter().map(|idx| (*idx, &self.sources[*idx].entry)));
+        let destinations = unique_by_file_name(
+            &mut self
+                .destinations
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| d.source.is_none())
+                .map(|(idx, d)| (idx, &d.entry)),
+        );
+
+        for source_idx in self.active_sources.clone() {
+            let file_name = self.sources[source_idx].entry.file_name();
+            let destination_idx = match (sources.get(file_name), destinations.get(file_name)) {
+                (Some(_), Some(destination_idx)) => *destination_idx,
+                _ => continue,
+            };
+            if self.destinations[destination_idx].source.is_some() {
+                continue;
+            }
+            let score = self.similarity(source_idx, destination_idx, minimum_score)?;
+            if score >= minimum_score {
+                self.record(destination_idx, source_idx, score);
+            }
+        }
+        Ok(())
+    }
+
+    /// Match the remaining destinations with their most similar sources.
+    fn find_similar(&mut self, copies: bool) -> Result<(), Error<E>> {
+        let mut candidates = Vec::new();
+        for destination_idx in 0..self.destinations.len() {
+            if self.destinations[destination_idx].source.is_some() {
+                continue;
+            }
+            let mut best = [Candidate {
+                score: 0,
+                same_file_name: false,
+                destination: usize::MAX,
+                source: 0,
+            }; CANDIDATES_PER_DESTINATION];
+            for source_idx in self.active_sources.clone() {
+                let candidate = Candidate {
+                    score: self.similarity(source_idx, destination_idx, self.minimum_score)?,
+                    same_file_name: self.sources[source_idx]
+                        .entry
+                        .has_same_file_name(&self.destinations[destination_idx].entry),
+                    destination: destination_idx,
+                    source: source_idx,
+                };
+                let worst = (1..best.len()).fold(0, |worst, idx| {
+                    if best[idx].compare(&best[worst]) == Ordering::Greater {
+                        idx
+                    } else {
+                        worst
+                    }
+                });
+                if best[worst].compare(&candidate) == Ordering::Greater {
+                    best[worst] = candidate;
+                }
+            }
+            candidates.extend_from_slice(&best);
+        }
+        candidates.sort_by(Candidate::compare);
+
+        for allow_used_sources in if copies { &[false, true][..] } else { &[false][..] } {
+            for candidate in &candidates {
+                if candidate.destination == usize::MAX || candidate.score < self.minimum_score {
+                    break;
+                }
+                if self.destinations[candidate.destination].source.is_some()
+                    || (!allow_used_sources && self.sources[candidate.source].uses != 0)
+                {
+                    continue;
+                }
+                self.record(candidate.destination, candidate.source, candidate.score);
+            }
+        }
+        Ok(())
+    }
+
+    /// Estimate how similar a source and a destination are, returning 0 if they can't reach `minimum_score`.
+    fn similarity(&mut self, source: usize, destination: usize, minimum_score: u64) -> Result<u64, Error<E>> {
+        if !self.sources[source].entry.is_regular() || !self.destinations[destination].entry.is_regular() {
+            return Ok(0);
+        }
+        let source_size = self.signature(source, true)?.size;
+        let destination_size = self.signature(destination, false)?.size;
+        let max_size = source_size.max(destination_size);
+        let delta_size = max_size - source_size.min(destination_size);
+        if max_size * (MAX_SCORE - minimum_score) < delta_size * MAX_SCORE || destination_size == 0 {
+            return Ok(0);
+        }
+        let source = self.sources[source].entry.signature.as_ref().expect("computed");
+        let destination = self.destinations[destination]
+            .entry
+            .signature
+            .as_ref()
+            .expect("computed");
+        Ok(source.common_bytes(destination) * MAX_SCORE / max_size)
+    }
+
+    fn signature(&mut self, idx: usize, is_source: bool) -> Result<&Signature, Error<E>> {
+        let file = if is_source {
+            &mut self.sources[idx].entry
+        } else {
+            &mut self.destinations[idx].entry
+        };
+        if file.signature.is_none() {
+            find::load(&mut self.find, file.oid, &mut self.buf)?;
+            file.signature = Some(Signature::new(&self.buf));
+        }
+        Ok(file.signature.as_ref().expect("just set"))
+    }
+
+    fn into_changes(self, changes: Vec<tree::Change>) -> Vec<Change> {
+        let mut uses_by_change = HashMap::new();
+        for source in &self.sources {
+            uses_by_change.insert(source.change, source.uses);
+        }
+        let renamed_deletions = uses_by_change.clone();
+        let mut destination_by_change = HashMap::new();
+        for destination in &self.destinations {
+            if let Some((source, score)) = destination.source {
+                destination_by_change.insert(destination.change, (&self.sources[source], score));
+            }
+        }
+
+        let mut out = Vec::with_capacity(changes.len());
+        for (idx, change) in changes.into_iter().enumerate() {
+            if let tree::Change::Deletion { .. } = change {
+                if renamed_deletions.get(&idx).is_some_and(|uses| *uses != 0) {
+                    continue;
+                }
+            }
+            let (source, score) = match destination_by_change.get(&idx) {
+                Some(source) => *source,
+                None => {
+                    out.push(Change::Plain(change));
+                    continue;
+                }
+            };
+            let (entry_mode, oid, path) = match change {
+                tree::Change::Addition { entry_mode, oid, path } => (entry_mode, oid, path),
+                _ => unreachable!("destinations are additions"),
+            };
+            let rewrite = Rewrite {
+                source_entry_mode: source.entry.mode,
+                source_oid: source.entry.oid,
+                source_path: source.entry.path.clone(),
+                entry_mode,
+                oid,
+                path,
+                similarity: (score * 100 / MAX_SCORE) as u8,
+            };
+            let uses = uses_by_change.get_mut(&source.change).expect("all sources are known");
+            *uses -= 1;
+            out.push(if *uses > 0 {
+                Change::Copy(rewrite)
+            } else {
+                Change::Rename(rewrite)
+            });
+        }
+        out
+    }
+}
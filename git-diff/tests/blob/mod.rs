@@ -0,0 +1,120 @@
+use git_diff::blob::{self, Algorithm, Change};
+use git_object::bstr::ByteSlice;
+
+fn unified(previous: &str, current: &str, algorithm: Algorithm, context_lines: usize) -> String {
+    let mut out = Vec::new();
+    let diff = blob::diff(previous.as_bytes(), current.as_bytes(), algorithm);
+    blob::unified::write(&mut out, &diff, context_lines).expect("in-memory write");
+    out.to_str().expect("valid UTF-8").to_owned()
+}
+
+#[test]
+fn lines_keep_their_terminator() {
+    assert_eq!(blob::lines(b""), Vec::<&[u8]>::new());
+    assert_eq!(blob::lines(b"a\n\nb"), vec![&b"a\n"[..], b"\n", b"b"]);
+    assert_eq!(blob::lines(b"a\r\n"), vec![&b"a\r\n"[..]]);
+}
+
+#[test]
+fn binary_detection_looks_for_nul_bytes_at_the_beginning() {
+    assert!(!blob::is_binary(b""));
+    assert!(!blob::is_binary(b"text\n\xff\xfe"));
+    assert!(blob::is_binary(b"x\0y"));
+    let mut late_nul = vec![b'x'; 8000];
+    late_nul.push(0);
+    assert!(!blob::is_binary(&late_nul), "only the first 8000 bytes are considered");
+}
+
+#[test]
+fn equal_data_has_no_changes() {
+    for algorithm in &[Algorithm::Myers, Algorithm::MyersMinimal, Algorithm::Histogram] {
+        let diff = blob::diff(b"a\nb\n", b"a\nb\n", *algorithm);
+        assert!(diff.changes.is_empty());
+        assert_eq!(unified("a\nb\n", "a\nb\n", *algorithm, 3), "");
+    }
+}
+
+#[test]
+fn changes_are_line_ranges() {
+    let diff = blob::diff(b"a\nb\nc\n", b"a\nB\nc\nd\n", Algorithm::Myers);
+    assert_eq!(
+        diff.changes,
+        vec![
+            Change {
+                previous: 1..2,
+                current: 1..2
+            },
+            Change {
+                previous: 3..3,
+                current: 3..4
+            }
+        ]
+    );
+}
+
+#[test]
+fn additions_and_deletions_of_everything() {
+    assert_eq!(unified("", "a\nb\n", Algorithm::Myers, 3), "@@ -0,0 +1,2 @@\n+a\n+b\n");
+    assert_eq!(unified("a\n", "", Algorithm::Histogram, 3), "@@ -1 +0,0 @@\n-a\n");
+}
+
+#[test]
+fn hunk_headers_show_the_function_before_the_hunk() {
+    let previous = "fn a() {\n    1\n}\n\nfn b() {\n    2\n    3\n    4\n    5\n    6\n}\n";
+    let current = previous.replace("    3\n", "    three\n");
+    assert_eq!(
+        unified(previous, &current, Algorithm::Myers, 3),
+        "@@ -4,7 +4,7 @@ fn a() {\n \n fn b() {\n     2\n-    3\n+    three\n     4\n     5\n     6\n"
+    );
+}
+
+#[test]
+fn close_changes_share_a_hunk_unless_context_is_small() {
+    let previous = "1\n2\n3\n4\n5\n6\n7\n";
+    let current = "one\n2\n3\n4\n5\n6\nseven\n";
+    assert_eq!(
+        unified(previous, current, Algorithm::Myers, 3),
+        "@@ -1,7 +1,7 @@\n-1\n+one\n 2\n 3\n 4\n 5\n 6\n-7\n+seven\n"
+    );
+    assert_eq!(
+        unified(previous, current, Algorithm::Myers, 1),
+        "@@ -1,2 +1,2 @@\n-1\n+one\n 2\n@@ -6,2 +6,2 @@\n 6\n-7\n+seven\n"
+    );
+    assert_eq!(
+        unified(previous, current, Algorithm::Myers, 0),
+        "@@ -1 +1 @@\n-1\n+one\n@@ -7 +7 @@\n-7\n+seven\n"
+    );
+}
+
+#[test]
+fn missing_newlines_at_the_end_are_marked() {
+    assert_eq!(
+        unified("a\nb", "a\nb\n", Algorithm::Myers, 3),
+        "@@ -1,2 +1,2 @@\n a\n-b\n\\ No newline at end of file\n+b\n"
+    );
+}
+
+#[test]
+fn changes_slide_to_the_end_of_equal_lines() {
+    assert_eq!(
+        unified("a\nb\n", "a\nb\na\nb\n", Algorithm::Myers, 0),
+        "@@ -2,0 +3,2 @@ b\n+a\n+b\n"
+    );
+}
+
+#[test]
+fn histogram_prefers_unique_lines_as_anchors() {
+    let (previous, current) = ("c\na\na\n", "a\nd\nc\n");
+    assert_eq!(
+        unified(previous, current, Algorithm::Myers, 1),
+        "@@ -1,3 +1,3 @@\n-c\n-a\n a\n+d\n+c\n"
+    );
+    assert_eq!(
+        unified(previous, current, Algorithm::MyersMinimal, 1),
+        "@@ -1,3 +1,3 @@\n-c\n-a\n a\n+d\n+c\n"
+    );
+    assert_eq!(
+        unified(previous, current, Algorithm::Histogram, 1),
+        "@@ -1,3 +1,3 @@\n+a\n+d\n c\n-a\n-a\n"
+    );
+}
@@ -0,0 +1,209 @@
+mod rewrites;
+
+use git_diff::tree::{self, Action, Change, Delegate, Recorder};
+use git_object::{bstr::ByteSlice, find, owned, TreeMode};
+use std::{cell::RefCell, collections::HashMap, convert::Infallible};
+
+/// An object database with made-up ids.
+#[derive(Default)]
+struct Db {
+    trees: HashMap<owned::Id, Vec<u8>>,
+    lookups: RefCell<Vec<owned::Id>>,
+}
+
+fn id(n: u8) -> owned::Id {
+    owned::Id::from([n; 20])
+}
+
+impl Db {
+    fn tree(&mut self, entries: &[(TreeMode, &str, owned::Id)]) -> owned::Id {
+        let mut data = Vec::new();
+        owned::Tree {
+            entries: entries
+                .iter()
+                .map(|(mode, name, oid)| owned::tree::Entry {
+                    mode: *mode,
+                    filename: (*name).into(),
+                    oid: *oid,
+                })
+                .collect(),
+        }
+        .write_to(&mut data)
+        .expect("in-memory write");
+        let oid = id(200 + self.trees.len() as u8);
+        self.trees.insert(oid, data);
+        oid
+    }
+
+    fn changes(&self, previous: Option<owned::Id>, current: Option<owned::Id>) -> Vec<Change> {
+        let mut recorder = Recorder::default();
+        tree::changes(
+            previous,
+            current,
+            |oid, buf| -> Option<Result<(), Infallible>> {
+                let oid = owned::Id::from(oid);
+                self.lookups.borrow_mut().push(oid);
+                buf.extend_from_slice(self.trees.get(&oid)?);
+                Some(Ok(()))
+            },
+            &mut recorder,
+        )
+        .expect("diff to succeed");
+        recorder.records
+    }
+}
+
+fn addition(entry_mode: TreeMode, oid: owned::Id, path: &str) -> Change {
+    Change::Addition {
+        entry_mode,
+        oid,
+        path: path.into(),
+    }
+}
+
+fn deletion(entry_mode: TreeMode, oid: owned::Id, path: &str) -> Change {
+    Change::Deletion {
+        entry_mode,
+        oid,
+        path: path.into(),
+    }
+}
+
+#[test]
+fn empty_trees_and_identical_trees_have_no_changes() {
+    let mut db = Db::default();
+    let empty = db.tree(&[]);
+    let tree = db.tree(&[(TreeMode::Blob, "a", id(1))]);
+    assert_eq!(db.changes(None, None), vec![]);
+    assert_eq!(db.changes(None, Some(empty)), vec![]);
+    assert_eq!(db.changes(Some(tree), Some(tree)), vec![]);
+    assert!(db.lookups.borrow().len() == 1, "only the empty tree was looked up");
+}
+
+#[test]
+fn additions_and_deletions_recurse_into_trees() {
+    let mut db = Db::default();
+    let sub = db.tree(&[(TreeMode::Blob, "b", id(2)), (TreeMode::Link, "c", id(3))]);
+    let tree = db.tree(&[(TreeMode::BlobExecutable, "a", id(1)), (TreeMode::Tree, "d", sub)]);
+    assert_eq!(
+        db.changes(None, Some(tree)),
+        vec![
+            addition(TreeMode::BlobExecutable, id(1), "a"),
+            addition(TreeMode::Tree, sub, "d"),
+            addition(TreeMode::Blob, id(2), "d/b"),
+            addition(TreeMode::Link, id(3), "d/c"),
+        ]
+    );
+    assert_eq!(
+        db.changes(Some(tree), None),
+        vec![
+            deletion(TreeMode::BlobExecutable, id(1), "a"),
+            deletion(TreeMode::Tree, sub, "d"),
+            deletion(TreeMode::Blob, id(2), "d/b"),
+            deletion(TreeMode::Link, id(3), "d/c"),
+        ]
+    );
+}
+
+#[test]
+fn modifications_skip_identical_subtrees() {
+    let mut db = Db::default();
+    let unchanged = db.tree(&[(TreeMode::Blob, "x", id(9))]);
+    let sub_before = db.tree(&[(TreeMode::Blob, "b", id(2)), (TreeMode::Blob, "c", id(3))]);
+    let sub_after = db.tree(&[(TreeMode::Blob, "b", id(4)), (TreeMode::BlobExecutable, "c", id(3))]);
+    let before = db.tree(&[
+        (TreeMode::Blob, "a", id(1)),
+        (TreeMode::Tree, "sub", sub_before),
+        (TreeMode::Tree, "unchanged", unchanged),
+    ]);
+    let after = db.tree(&[
+        (TreeMode::Blob, "a", id(1)),
+        (TreeMode::Tree, "sub", sub_after),
+        (TreeMode::Tree, "unchanged", unchanged),
+    ]);
+    assert_eq!(
+        db.changes(Some(before), Some(after)),
+        vec![
+            Change::Modification {
+                previous_entry_mode: TreeMode::Tree,
+                previous_oid: sub_before,
+                entry_mode: TreeMode::Tree,
+                oid: sub_after,
+                path: "sub".into()
+            },
+            Change::Modification {
+                previous_entry_mode: TreeMode::Blob,
+                previous_oid: id(2),
+                entry_mode: TreeMode::Blob,
+                oid: id(4),
+                path: "sub/b".into()
+            },
+            Change::Modification {
+                previous_entry_mode: TreeMode::Blob,
+                previous_oid: id(3),
+                entry_mode: TreeMode::BlobExecutable,
+                oid: id(3),
+                path: "sub/c".into()
+            },
+        ]
+    );
+    assert!(
+        !db.lookups.borrow().contains(&unchanged),
+        "trees with the same id are never looked up"
+    );
+}
+
+#[test]
+fn entries_changing_between_tree_and_non_tree_are_deleted_and_added() {
+    let mut db = Db::default();
+    let sub = db.tree(&[(TreeMode::Blob, "b", id(2))]);
+    let before = db.tree(&[(TreeMode::Blob, "a", id(1)), (TreeMode::Blob, "a.b", id(3))]);
+    let after = db.tree(&[(TreeMode::Blob, "a.b", id(3)), (TreeMode::Tree, "a", sub)]);
+    assert_eq!(
+        db.changes(Some(before), Some(after)),
+        vec![
+            deletion(TreeMode::Blob, id(1), "a"),
+            addition(TreeMode::Tree, sub, "a"),
+            addition(TreeMode::Blob, id(2), "a/b"),
+        ]
+    );
+}
+
+#[test]
+fn delegates_can_cancel_and_lookup_failures_are_reported() {
+    struct CancelAfterFirst(usize);
+    impl Delegate for CancelAfterFirst {
+        fn visit(&mut self, _change: Change) -> Action {
+            self.0 += 1;
+            Action::Cancel
+        }
+    }
+    let mut db = Db::default();
+    let tree = db.tree(&[(TreeMode::Blob, "a", id(1)), (TreeMode::Blob, "b", id(2))]);
+    let find = |oid: git_object::borrowed::Id<'_>, buf: &mut Vec<u8>| -> Option<Result<(), Infallible>> {
+        buf.extend_from_slice(db.trees.get(&owned::Id::from(oid))?);
+        Some(Ok(()))
+    };
+
+    let mut delegate = CancelAfterFirst(0);
+    assert!(matches!(
+        tree::changes(None, Some(tree), find, &mut delegate),
+        Err(tree::Error::Cancelled)
+    ));
+    assert_eq!(delegate.0, 1);
+
+    let missing = id(100);
+    assert!(matches!(
+        tree::changes(Some(missing), Some(tree), find, &mut Recorder::default()),
+        Err(tree::Error::Find(find::Error::NotFound { oid })) if oid == missing
+    ));
+
+    let corrupt = |_oid: git_object::borrowed::Id<'_>, buf: &mut Vec<u8>| -> Option<Result<(), Infallible>> {
+        buf.extend_from_slice(b"100644 a".as_bytes());
+        Some(Ok(()))
+    };
+    assert!(matches!(
+        tree::changes(None, Some(tree), corrupt, &mut Recorder::default()),
+        Err(tree::Error::Find(find::Error::Decode { .. }))
+    ));
+}
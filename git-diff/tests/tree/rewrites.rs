@@ -0,0 +1,198 @@
+use git_diff::tree::{
+    rewrites::{self, Change, Options, Rewrite},
+    Change as TreeChange,
+};
+use git_object::{find, owned, TreeMode};
+use std::{collections::HashMap, convert::Infallible};
+
+fn id(n: u8) -> owned::Id {
+    owned::Id::from([n; 20])
+}
+
+fn lines(range: std::ops::Range<usize>) -> Vec<u8> {
+    range.map(|n| format!("line {}\n", n)).collect::<String>().into_bytes()
+}
+
+fn addition(oid: owned::Id, path: &str) -> TreeChange {
+    TreeChange::Addition {
+        entry_mode: TreeMode::Blob,
+        oid,
+        path: path.into(),
+    }
+}
+
+fn deletion(oid: owned::Id, path: &str) -> TreeChange {
+    TreeChange::Deletion {
+        entry_mode: TreeMode::Blob,
+        oid,
+        path: path.into(),
+    }
+}
+
+fn rewrite(source_oid: owned::Id, source_path: &str, oid: owned::Id, path: &str, similarity: u8) -> Rewrite {
+    Rewrite {
+        source_entry_mode: TreeMode::Blob,
+        source_oid,
+        source_path: source_path.into(),
+        entry_mode: TreeMode::Blob,
+        oid,
+        path: path.into(),
+        similarity,
+    }
+}
+
+fn detect(blobs: &[(owned::Id, Vec<u8>)], changes: Vec<TreeChange>, options: &Options) -> Vec<Change> {
+    let blobs: HashMap<_, _> = blobs.iter().cloned().collect();
+    rewrites::detect(
+        changes,
+        |oid, buf| -> Option<Result<(), Infallible>> {
+            buf.extend_from_slice(blobs.get(&owned::Id::from(oid))?);
+            Some(Ok(()))
+        },
+        options,
+    )
+    .expect("all blobs to be present")
+}
+
+#[test]
+fn identical_blobs_are_renamed_once_and_copied_only_if_enabled() {
+    let changes = vec![
+        addition(id(1), "a"),
+        addition(id(1), "b"),
+        deletion(id(1), "c"),
+        addition(id(2), "d"),
+    ];
+    assert_eq!(
+        detect(&[], changes.clone(), &Options::default()),
+        vec![
+            Change::Rename(rewrite(id(1), "c", id(1), "a", 100)),
+            Change::Plain(addition(id(1), "b")),
+            Change::Plain(addition(id(2), "d")),
+        ],
+        "blob data isn't needed if nothing can be similar"
+    );
+    assert_eq!(
+        detect(
+            &[(id(1), lines(0..10)), (id(2), lines(20..30))],
+            changes,
+            &Options {
+                copies: true,
+                ..Default::default()
+            }
+        ),
+        vec![
+            Change::Copy(rewrite(id(1), "c", id(1), "a", 100)),
+            Change::Rename(rewrite(id(1), "c", id(1), "b", 100)),
+            Change::Plain(addition(id(2), "d")),
+        ]
+    );
+}
+
+#[test]
+fn similar_blobs_are_renamed_if_they_exceed_the_threshold() {
+    let blobs = [(id(1), lines(0..10)), (id(2), lines(0..9))];
+    let changes = vec![deletion(id(1), "dir/a"), addition(id(2), "b")];
+    assert_eq!(
+        detect(&blobs, changes.clone(), &Options::default()),
+        vec![Change::Rename(rewrite(id(1), "dir/a", id(2), "b", 90))]
+    );
+    assert_eq!(
+        detect(
+            &blobs,
+            changes.clone(),
+            &Options {
+                percentage: 95,
+                ..Default::default()
+            }
+        ),
+        changes.into_iter().map(Change::Plain).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn copies_are_found_in_modified_files_only_if_enabled() {
+    let blobs = [(id(1), lines(0..10)), (id(2), lines(0..11)), (id(3), lines(0..10))];
+    let modification = TreeChange::Modification {
+        previous_entry_mode: TreeMode::Blob,
+        previous_oid: id(1),
+        entry_mode: TreeMode::Blob,
+        oid: id(2),
+        path: "a".into(),
+    };
+    let changes = vec![modification.clone(), addition(id(3), "b")];
+    assert_eq!(
+        detect(&blobs, changes.clone(), &Options::default()),
+        changes.iter().cloned().map(Change::Plain).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        detect(
+            &blobs,
+            changes,
+            &Options {
+                copies: true,
+                ..Default::default()
+            }
+        ),
+        vec![
+            Change::Plain(modification),
+            Change::Copy(rewrite(id(1), "a", id(3), "b", 100))
+        ]
+    );
+}
+
+#[test]
+fn the_limit_only_prevents_finding_similar_blobs() {
+    let blobs = [
+        (id(1), lines(0..10)),
+        (id(2), lines(0..9)),
+        (id(3), lines(20..30)),
+        (id(4), lines(20..29)),
+    ];
+    let changes = vec![
+        deletion(id(1), "a"),
+        deletion(id(3), "b"),
+        addition(id(2), "c"),
+        addition(id(4), "d"),
+        deletion(id(5), "e"),
+        addition(id(5), "f"),
+    ];
+    let mut expected: Vec<_> = changes[..4].iter().cloned().map(Change::Plain).collect();
+    expected.push(Change::Rename(rewrite(id(5), "e", id(5), "f", 100)));
+    assert_eq!(
+        detect(
+            &blobs,
+            changes.clone(),
+            &Options {
+                limit: 1,
+                ..Default::default()
+            }
+        ),
+        expected
+    );
+    assert_eq!(
+        detect(
+            &blobs,
+            changes,
+            &Options {
+                limit: 2,
+                ..Default::default()
+            }
+        ),
+        vec![
+            Change::Rename(rewrite(id(1), "a", id(2), "c", 90)),
+            Change::Rename(rewrite(id(3), "b", id(4), "d", 90)),
+            Change::Rename(rewrite(id(5), "e", id(5), "f", 100)),
+        ]
+    );
+}
+
+#[test]
+fn missing_blobs_are_reported() {
+    let changes = vec![deletion(id(1), "a"), addition(id(2), "b")];
+    let res = rewrites::detect(
+        changes,
+        |_oid, _buf| -> Option<Result<(), Infallible>> { None },
+        &Options::default(),
+    );
+    assert!(matches!(res, Err(rewrites::Error::Find(find::Error::NotFound { .. }))));
+}
@@ -0,0 +1,37 @@
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+//! Parse `.mailmap` files and use them to map the names and emails of signatures to canonical ones, like
+//! `git check-mailmap` and `git log --use-mailmap` do.
+
+use git_object::bstr::BStr;
+
+pub mod load;
+pub mod parse;
+#[doc(inline)]
+pub use parse::parse;
+
+mod snapshot;
+pub use snapshot::Snapshot;
+
+/// A line of a `.mailmap` file, which maps signatures with the old email, and optionally the old name, to a new name,
+/// a new email, or both.
+///
+/// The four forms of lines are
+///
+/// * `Proper Name <commit@email>`, to change the name only,
+/// * `<proper@email> <commit@email>`, to change the email only,
+/// * `Proper Name <proper@email> <commit@email>`, to change both,
+/// * `Proper Name <proper@email> Commit Name <commit@email>`, to change both if the name matches as well.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry<'a> {
+    /// The name to use instead, or `None` to keep it.
+    #[cfg_attr(feature = "serde1", serde(borrow))]
+    pub new_name: Option<&'a BStr>,
+    /// The email to use instead, or `None` to keep it.
+    pub new_email: Option<&'a BStr>,
+    /// The name signatures must have in addition to `old_email`, or `None` if any name matches.
+    pub old_name: Option<&'a BStr>,
+    /// The email of signatures to map.
+    pub old_email: &'a BStr,
+}
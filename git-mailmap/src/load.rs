@@ -0,0 +1,43 @@
+//! Obtain snapshots from `.mailmap` files in the worktree or in trees of the object database.
+use crate::Snapshot;
+use git_object::{
+    borrowed::{self, TreeIter},
+    find, owned, TreeMode,
+};
+use std::{io, path::Path};
+
+/// The path of the file in the root tree of a commit, like in `HEAD:.mailmap`.
+pub const FILENAME: &str = ".mailmap";
+
+/// Read the snapshot from the `.mailmap` file at `path`, like the one configured with `mailmap.file`.
+pub fn from_file(path: impl AsRef<Path>) -> io::Result<Snapshot> {
+    std::fs::read(path).map(|data| Snapshot::from_bytes(&data))
+}
+
+/// Read the snapshot from the [`.mailmap`][FILENAME] file in the tree `root`, like `mailmap.blob` does with
+/// `HEAD:.mailmap`, or return `None` if there is no such file. Objects are obtained with [`find`][git_object::find].
+pub fn from_tree<Find, E>(root: owned::Id, mut find: Find) -> Result<Option<Snapshot>, find::Error<E>>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    E: std::error::Error + 'static,
+{
+    let mut buf = Vec::new();
+    find::load(&mut find, root, &mut buf)?;
+    let mut blob = None;
+    for entry in TreeIter::from_bytes(&buf) {
+        let entry = entry.map_err(|err| find::Error::Decode { source: err, oid: root })?;
+        if entry.filename == FILENAME {
+            if let TreeMode::Blob | TreeMode::BlobExecutable = entry.mode {
+                blob = Some(owned::Id::from(entry.oid));
+            }
+            break;
+        }
+    }
+    match blob {
+        Some(oid) => {
+            find::load(&mut find, oid, &mut buf)?;
+            Ok(Some(Snapshot::from_bytes(&buf)))
+        }
+        None => Ok(None),
+    }
+}
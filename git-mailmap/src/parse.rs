@@ -0,0 +1,77 @@
+//! Read the entries of `.mailmap` files the way git does.
+use crate::Entry;
+use git_object::bstr::{self, BStr, BString, ByteSlice};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Line {line_number} has no email in angle brackets: {line:?}")]
+    MissingEmail { line_number: usize, line: BString },
+}
+
+/// Return an iterator over the entries of the `.mailmap` file `data`, skipping blank lines and comments.
+///
+/// Lines without an email in angle brackets are errors, which git silently ignores. Anything after the
+/// last email of a line is ignored, as are surrounding spaces of names.
+pub fn parse(data: &[u8]) -> Lines<'_> {
+    Lines {
+        lines: data.lines(),
+        line_number: 0,
+    }
+}
+
+/// An iterator over the entries of a `.mailmap` file, created by [`parse()`].
+pub struct Lines<'a> {
+    lines: bstr::Lines<'a>,
+    line_number: usize,
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = Result<Entry<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for line in &mut self.lines {
+            self.line_number += 1;
+            if line.trim_ascii().is_empty() || line.starts_with(b"#") {
+                continue;
+            }
+            return Some(entry(line).ok_or_else(|| Error::MissingEmail {
+                line_number: self.line_number,
+                line: line.into(),
+            }));
+        }
+        None
+    }
+}
+
+fn entry(line: &[u8]) -> Option<Entry<'_>> {
+    let (name1, email1, rest) = name_and_email(line)?;
+    if email1.is_empty() {
+        return None;
+    }
+    Some(match name_and_email(rest) {
+        Some((name2, email2, _)) => Entry {
+            new_name: name1,
+            new_email: Some(email1),
+            old_name: name2,
+            old_email: email2,
+        },
+        None => Entry {
+            new_name: name1,
+            new_email: None,
+            old_name: None,
+            old_email: email1,
+        },
+    })
+}
+
+/// Parse `Name <email>` at the start of `line` into the name, if there is one, the email and whatever follows.
+fn name_and_email(line: &[u8]) -> Option<(Option<&BStr>, &BStr, &[u8])> {
+    let left = line.find_byte(b'<')?;
+    let right = left + 1 + line[left + 1..].find_byte(b'>')?;
+    let name = line[..left].trim_ascii();
+    Some((
+        (!name.is_empty()).then(|| name.as_bstr()),
+        line[left + 1..right].as_bstr(),
+        &line[right + 1..],
+    ))
+}
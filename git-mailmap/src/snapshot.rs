@@ -0,0 +1,129 @@
+use crate::Entry;
+use git_object::{borrowed, bstr::BString, owned};
+use std::cmp::Ordering;
+
+/// The mapping of all entries of one or more `.mailmap` files, used to resolve the names and emails of signatures.
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+pub struct Snapshot {
+    /// Sorted by `old_email` without regard to case.
+    entries_by_old_email: Vec<EmailEntry>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct EmailEntry {
+    old_email: BString,
+    new_name: Option<BString>,
+    new_email: Option<BString>,
+    /// Sorted by `old_name` without regard to case.
+    entries_by_old_name: Vec<NameEntry>,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct NameEntry {
+    old_name: BString,
+    new_name: Option<BString>,
+    new_email: Option<BString>,
+}
+
+impl Snapshot {
+    /// Create a snapshot from the `.mailmap` file `data`, ignoring lines that can't be parsed like git does.
+    pub fn from_bytes(data: &[u8]) -> Self {
+        let mut snapshot = Snapshot::default();
+        snapshot.merge(crate::parse(data).filter_map(Result::ok));
+        snapshot
+    }
+
+    /// Add `entries` to this snapshot, which take precedence over the ones that are already present and map the
+    /// same email, or the same name and email.
+    ///
+    /// Entries without old name only replace the new name or new email that they specify.
+    pub fn merge<'a>(&mut self, entries: impl IntoIterator<Item = Entry<'a>>) -> &mut Self {
+        for entry in entries {
+            let email_entry = match self
+                .entries_by_old_email
+                .binary_search_by(|e| cmp_ignore_case(&e.old_email, entry.old_email))
+            {
+                Ok(index) => &mut self.entries_by_old_email[index],
+                Err(index) => {
+                    self.entries_by_old_email.insert(
+                        index,
+                        EmailEntry {
+                            old_email: entry.old_email.to_owned(),
+                            new_name: None,
+                            new_email: None,
+                            entries_by_old_name: Vec::new(),
+                        },
+                    );
+                    &mut self.entries_by_old_email[index]
+                }
+            };
+            match entry.old_name {
+                None => {
+                    if let Some(name) = entry.new_name {
+                        email_entry.new_name = Some(name.to_owned());
+                    }
+                    if let Some(email) = entry.new_email {
+                        email_entry.new_email = Some(email.to_owned());
+                    }
+                }
+                Some(old_name) => {
+                    let name_entry = NameEntry {
+                        old_name: old_name.to_owned(),
+                        new_name: entry.new_name.map(ToOwned::to_owned),
+                        new_email: entry.new_email.map(ToOwned::to_owned),
+                    };
+                    let names = &mut email_entry.entries_by_old_name;
+                    match names.binary_search_by(|e| cmp_ignore_case(&e.old_name, old_name)) {
+                        Ok(index) => names[index] = name_entry,
+                        Err(index) => names.insert(index, name_entry),
+                    }
+                }
+            }
+        }
+        self
+    }
+
+    /// Return `signature` with the name and email it is mapped to, or `None` if no entry maps it.
+    ///
+    /// Emails and names are compared without regard to case, and an entry for the name and email of `signature` takes
+    /// precedence over one for its email only.
+    pub fn try_resolve(&self, signature: &borrowed::Signature<'_>) -> Option<owned::Signature> {
+        let email_entry = self
+            .entries_by_old_email
+            .binary_search_by(|e| cmp_ignore_case(&e.old_email, signature.email))
+            .ok()
+            .map(|index| &self.entries_by_old_email[index])?;
+        let (new_name, new_email) = email_entry
+            .entries_by_old_name
+            .binary_search_by(|e| cmp_ignore_case(&e.old_name, signature.name))
+            .ok()
+            .map(|index| &email_entry.entries_by_old_name[index])
+            .map_or((&email_entry.new_name, &email_entry.new_email), |e| {
+                (&e.new_name, &e.new_email)
+            });
+        if new_name.is_none() && new_email.is_none() {
+            return None;
+        }
+        Some(owned::Signature {
+            name: new_name
+                .as_ref()
+                .map_or_else(|| signature.name.to_owned(), Clone::clone),
+            email: new_email
+                .as_ref()
+                .map_or_else(|| signature.email.to_owned(), Clone::clone),
+            time: signature.time,
+        })
+    }
+
+    /// Return `signature` with the name and email it is mapped to, or unchanged if no entry maps it.
+    pub fn resolve(&self, signature: &borrowed::Signature<'_>) -> owned::Signature {
+        self.try_resolve(signature).unwrap_or_else(|| signature.clone().into())
+    }
+}
+
+/// Compare like `strcasecmp()`, which git uses for names and emails in mailmaps.
+fn cmp_ignore_case(a: &[u8], b: &[u8]) -> Ordering {
+    a.iter()
+        .map(|b| b.to_ascii_lowercase())
+        .cmp(b.iter().map(|b| b.to_ascii_lowercase()))
+}
@@ -0,0 +1,95 @@
+use crate::{contact, fixture_bytes, fixture_path, signature};
+use git_mailmap::load;
+use git_object::{find, owned, TreeMode};
+use pretty_assertions::assert_eq;
+use std::{collections::HashMap, convert::Infallible};
+
+/// An object database with made-up ids.
+#[derive(Default)]
+struct Db {
+    objects: HashMap<owned::Id, Vec<u8>>,
+}
+
+impl Db {
+    fn insert(&mut self, data: Vec<u8>) -> owned::Id {
+        let oid = owned::Id::from([self.objects.len() as u8 + 1; 20]);
+        self.objects.insert(oid, data);
+        oid
+    }
+
+    fn tree(&mut self, entries: &[(TreeMode, &str, owned::Id)]) -> owned::Id {
+        let mut data = Vec::new();
+        owned::Tree {
+            entries: entries
+                .iter()
+                .map(|(mode, name, oid)| owned::tree::Entry {
+                    mode: *mode,
+                    filename: (*name).into(),
+                    oid: *oid,
+                })
+                .collect(),
+        }
+        .write_to(&mut data)
+        .expect("in-memory write");
+        self.insert(data)
+    }
+
+    fn find(&self) -> impl FnMut(git_object::borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), Infallible>> + '_ {
+        move |oid, buf| {
+            buf.extend_from_slice(self.objects.get(&owned::Id::from(oid))?);
+            Some(Ok(()))
+        }
+    }
+}
+
+#[test]
+fn from_file() -> Result<(), Box<dyn std::error::Error>> {
+    let snapshot = load::from_file(fixture_path("mailmap"))?;
+    assert_eq!(
+        contact(&snapshot.resolve(&signature("nick1 <bugs@example.com>"))),
+        "Other Author <other@author.xx>"
+    );
+    assert_eq!(
+        load::from_file(fixture_path("does-not-exist"))
+            .expect_err("missing")
+            .kind(),
+        std::io::ErrorKind::NotFound
+    );
+    Ok(())
+}
+
+#[test]
+fn from_tree_reads_the_mailmap_blob_of_the_root_tree() -> Result<(), Box<dyn std::error::Error>> {
+    let mut db = Db::default();
+    let blob = db.insert(fixture_bytes("mailmap"));
+    let subtree = db.tree(&[(TreeMode::Blob, ".mailmap", blob)]);
+    let root = db.tree(&[
+        (TreeMode::Blob, ".gitignore", blob),
+        (TreeMode::Blob, ".mailmap", blob),
+        (TreeMode::Tree, "sub", subtree),
+    ]);
+    let snapshot = load::from_tree(root, db.find())?.expect("present");
+    assert_eq!(
+        contact(&snapshot.resolve(&signature("x <JOE@example.com>"))),
+        "Joe Developer <JOE@example.com>"
+    );
+
+    let root = db.tree(&[(TreeMode::Tree, "sub", subtree)]);
+    assert!(
+        load::from_tree(root, db.find())?.is_none(),
+        "only the root tree is searched"
+    );
+    let root = db.tree(&[(TreeMode::Tree, ".mailmap", subtree)]);
+    assert!(load::from_tree(root, db.find())?.is_none(), "it must be a blob");
+    Ok(())
+}
+
+#[test]
+fn missing_objects_are_errors() {
+    let mut db = Db::default();
+    let root = db.tree(&[(TreeMode::Blob, ".mailmap", owned::Id::from([42; 20]))]);
+    assert!(matches!(
+        load::from_tree(root, db.find()),
+        Err(find::Error::NotFound { oid }) if oid == owned::Id::from([42; 20])
+    ));
+}
@@ -0,0 +1,36 @@
+mod load;
+mod parse;
+mod snapshot;
+
+use git_object::{borrowed, owned};
+
+pub fn fixture_path(path: &str) -> std::path::PathBuf {
+    std::path::PathBuf::from("tests/fixtures").join(path)
+}
+
+pub fn fixture_bytes(path: &str) -> Vec<u8> {
+    std::fs::read(fixture_path(path)).expect("fixture to be present and readable")
+}
+
+/// Parse a contact like `git check-mailmap` does, as `Name <email>` or `<email>`.
+pub fn signature(contact: &str) -> borrowed::Signature<'_> {
+    let (name, email) = contact.split_at(contact.find('<').expect("an email"));
+    borrowed::Signature {
+        name: name.trim().into(),
+        email: email.trim_start_matches('<').trim_end_matches('>').into(),
+        time: git_object::Time {
+            time: 1_600_000_000,
+            offset: 0,
+            sign: git_object::Sign::Plus,
+        },
+    }
+}
+
+/// Format `signature` like `git check-mailmap` does.
+pub fn contact(signature: &owned::Signature) -> String {
+    if signature.name.is_empty() {
+        format!("<{}>", signature.email)
+    } else {
+        format!("{} <{}>", signature.name, signature.email)
+    }
+}
@@ -0,0 +1,72 @@
+use git_mailmap::{parse, Entry};
+use pretty_assertions::assert_eq;
+
+fn entry<'a>(
+    new_name: Option<&'a str>,
+    new_email: Option<&'a str>,
+    old_name: Option<&'a str>,
+    old_email: &'a str,
+) -> Entry<'a> {
+    Entry {
+        new_name: new_name.map(Into::into),
+        new_email: new_email.map(Into::into),
+        old_name: old_name.map(Into::into),
+        old_email: old_email.into(),
+    }
+}
+
+#[test]
+fn all_four_forms_are_understood() -> Result<(), parse::Error> {
+    let entries = parse(
+        b"Proper Name <commit@email>\n\
+          <proper@email> <commit@email>\n\
+          Proper Name <proper@email> <commit@email>\n\
+          Proper Name <proper@email> Commit Name <commit@email>\n",
+    )
+    .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        entries,
+        vec![
+            entry(Some("Proper Name"), None, None, "commit@email"),
+            entry(None, Some("proper@email"), None, "commit@email"),
+            entry(Some("Proper Name"), Some("proper@email"), None, "commit@email"),
+            entry(
+                Some("Proper Name"),
+                Some("proper@email"),
+                Some("Commit Name"),
+                "commit@email"
+            ),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn names_are_trimmed_and_comments_blank_lines_and_trailing_text_are_skipped() -> Result<(), parse::Error> {
+    let entries = parse(b"# comment <a@b>\n\n \t\n  Name \t <a@b>  trailing text\r\n<new@b> <>\n")
+        .collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(
+        entries,
+        vec![
+            entry(Some("Name"), None, None, "a@b"),
+            entry(None, Some("new@b"), None, "")
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn lines_without_email_are_errors() {
+    let mut entries = parse(b"A <a@b>\nno email\nempty <>\nB <b@c>\n");
+    assert!(entries.next().expect("an entry").is_ok());
+    assert!(matches!(
+        entries.next(),
+        Some(Err(parse::Error::MissingEmail { line_number: 2, line })) if line == "no email"
+    ));
+    assert!(matches!(
+        entries.next(),
+        Some(Err(parse::Error::MissingEmail { line_number: 3, .. }))
+    ));
+    assert!(entries.next().expect("an entry").is_ok());
+    assert!(entries.next().is_none());
+}
@@ -0,0 +1,46 @@
+use crate::{contact, fixture_bytes, signature};
+use git_mailmap::Snapshot;
+use pretty_assertions::assert_eq;
+
+fn check(snapshot: &Snapshot, input: &str) -> String {
+    contact(&snapshot.resolve(&signature(input)))
+}
+
+#[test]
+fn signatures_are_resolved_like_git_check_mailmap() {
+    let snapshot = Snapshot::from_bytes(&fixture_bytes("mailmap"));
+    for (input, expected) in &[
+        ("Joe Developer <joe@example.com>", "Joe Developer <joe@example.com>"),
+        ("x <JOE@example.com>", "Joe Developer <JOE@example.com>"),
+        ("Someone <jane@laptop.(none)>", "Someone <jane@example.com>"),
+        ("Someone <jane@desktop.(none)>", "Jane Doe <jane@example.com>"),
+        ("JANE <bugs@example.com>", "Jane Doe <jane@example.com>"),
+        ("nick1 <BUGS@example.com>", "Other Author <other@author.xx>"),
+        ("nick2 <bugs@example.com>", "nick2 <bugs@example.com>"),
+        ("Me <me@company.xx>", "Santa <santa@example.com>"),
+        ("joe <joe@example.com>", "Joe <joe@example.com>"),
+        ("Unknown <unknown@example.com>", "Unknown <unknown@example.com>"),
+        ("<jane@laptop.(none)>", "<jane@example.com>"),
+    ] {
+        assert_eq!(&check(&snapshot, input), expected, "{}", input);
+    }
+}
+
+#[test]
+fn try_resolve_only_returns_mapped_signatures_and_keeps_the_time() {
+    let snapshot = Snapshot::from_bytes(b"Name <a@b>\n");
+    let input = signature("Other <A@B>");
+    let resolved = snapshot.try_resolve(&input).expect("mapped");
+    assert_eq!(resolved.name, "Name");
+    assert_eq!(resolved.email, "A@B");
+    assert_eq!(resolved.time, input.time);
+    assert_eq!(snapshot.try_resolve(&signature("Other <c@d>")), None);
+}
+
+#[test]
+fn later_entries_take_precedence_and_only_replace_what_they_specify() {
+    let mut snapshot = Snapshot::from_bytes(b"First <new@b> <a@b>\nA <new@b> a <a@b>\n");
+    snapshot.merge(git_mailmap::parse(b"Second <a@b>\nB <newer@b> A <a@b>\n").filter_map(Result::ok));
+    assert_eq!(check(&snapshot, "x <a@b>"), "Second <new@b>");
+    assert_eq!(check(&snapshot, "a <a@b>"), "B <newer@b>");
+}
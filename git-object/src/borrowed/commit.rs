@@ -79,7 +79,213 @@ impl<'a> Commit<'a> {
     pub fn from_bytes(d: &'a [u8]) -> Result<Commit<'a>, Error> {
         parse(d).map(|(_, t)| t).map_err(Error::from)
     }
+    /// The message split into its title and body, which also provide the summary and trailers.
+    pub fn message(&self) -> commit::Message<'a> {
+        commit::Message::from_bytes(self.message)
+    }
     pub fn extra_headers(&self) -> commit::ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         commit::ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (*k, v.as_ref())))
     }
+    /// The encoding of the message and the names and emails of author and committer, as named by the `encoding`
+    /// header, or UTF-8 if there is none.
+    pub fn message_encoding(&self) -> Result<&'static commit::encoding::Encoding, commit::encoding::Error> {
+        commit::encoding::for_name(self.encoding)
+    }
+    /// Return an owned copy of this commit whose message and signatures are decoded to UTF-8, without `encoding` header.
+    ///
+    /// The `gpgsig` and `gpgsig-sha256` headers are removed if that changes the commit, see [`owned::Commit::reencode()`].
+    pub fn to_utf8(&self) -> Result<owned::Commit, commit::encoding::Error> {
+        let mut commit: owned::Commit = self.clone().into();
+        commit.reencode(None)?;
+        Ok(commit)
+    }
+}
+
+/// A token of a commit as produced by [`CommitIter`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Token<'a> {
+    Tree {
+        id: owned::Id,
+    },
+    Parent {
+        id: owned::Id,
+    },
+    Author {
+        #[cfg_attr(feature = "serde1", serde(borrow))]
+        signature: Signature<'a>,
+    },
+    Committer {
+        signature: Signature<'a>,
+    },
+    Encoding(&'a BStr),
+    ExtraHeader((&'a BStr, Cow<'a, BStr>)),
+    Message(&'a BStr),
+}
+
+impl<'a> Token<'a> {
+    /// Return the id of the tree or parent, or `None` for all other tokens.
+    pub fn id(&self) -> Option<owned::Id> {
+        match self {
+            Token::Tree { id } | Token::Parent { id } => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+enum State {
+    Tree,
+    Parents,
+    Author,
+    Committer,
+    Encoding,
+    ExtraHeaders,
+    Message,
+    Done,
+}
+
+/// An iterator over the tokens of a commit, parsing only as much of the commit as is requested.
+///
+/// This allows to obtain the tree and parents of a commit without parsing signatures or the message, and
+/// doesn't allocate unless multi-line extra headers are encountered.
+/// After an error was returned, the iterator yields no more tokens.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct CommitIter<'a> {
+    data: &'a [u8],
+    state: State,
+}
+
+impl<'a> CommitIter<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> CommitIter<'a> {
+        CommitIter {
+            data,
+            state: State::Tree,
+        }
+    }
+
+    /// Return the id of the commit's tree, parsing nothing but the first header.
+    pub fn tree_id(&mut self) -> Result<owned::Id, Error> {
+        match self.next() {
+            Some(Ok(Token::Tree { id })) => Ok(id),
+            Some(Err(err)) => Err(err),
+            _ => Err(Error::NomDetail(self.data.into(), "tree <40 lowercase hex char>")),
+        }
+    }
+
+    /// Return all parent ids of the commit, skipping the tree if it wasn't consumed yet, without parsing anything
+    /// past the last parent.
+    pub fn parent_ids(mut self) -> impl Iterator<Item = Result<owned::Id, Error>> + 'a {
+        std::iter::from_fn(move || {
+            while let State::Tree | State::Parents = self.state {
+                match Self::next_inner(self.data, &mut self.state) {
+                    Ok((data, token)) => {
+                        self.data = data;
+                        if let Some(Token::Parent { id }) = token {
+                            return Some(Ok(id));
+                        }
+                    }
+                    Err(err) => {
+                        self.state = State::Done;
+                        return Some(Err(err.into()));
+                    }
+                }
+            }
+            None
+        })
+    }
+
+    fn next_inner(i: &'a [u8], state: &mut State) -> IResult<&'a [u8], Option<Token<'a>>, Error> {
+        use State::*;
+        Ok(match *state {
+            Tree => {
+                let (i, tree) = parse::header_field(i, b"tree", parse::hex_sha1)
+                    .map_err(Error::context("tree <40 lowercase hex char>"))?;
+                *state = Parents;
+                (
+                    i,
+                    Some(Token::Tree {
+                        id: owned::Id::from_40_bytes_in_hex(tree).expect("parsing validation"),
+                    }),
+                )
+            }
+            Parents => match opt(|i| parse::header_field(i, b"parent", parse::hex_sha1))(i)
+                .map_err(Error::context("parent <40 lowercase hex char>"))?
+            {
+                (i, Some(parent)) => (
+                    i,
+                    Some(Token::Parent {
+                        id: owned::Id::from_40_bytes_in_hex(parent).expect("parsing validation"),
+                    }),
+                ),
+                (i, None) => {
+                    *state = Author;
+                    (i, None)
+                }
+            },
+            Author => {
+                let (i, signature) = parse::header_field(i, b"author", parse::signature)
+                    .map_err(Error::context("author <signature>"))?;
+                *state = Committer;
+                (i, Some(Token::Author { signature }))
+            }
+            Committer => {
+                let (i, signature) = parse::header_field(i, b"committer", parse::signature)
+                    .map_err(Error::context("committer <signature>"))?;
+                *state = Encoding;
+                (i, Some(Token::Committer { signature }))
+            }
+            Encoding => {
+                let (i, encoding) = opt(|i| parse::header_field(i, b"encoding", is_not(NL)))(i)
+                    .map_err(Error::context("encoding <encoding>"))?;
+                *state = ExtraHeaders;
+                (i, encoding.map(|encoding| Token::Encoding(encoding.as_bstr())))
+            }
+            ExtraHeaders => {
+                let (i, extra_header) = opt(alt((
+                    |i| parse::any_header_field_multi_line(i).map(|(i, (k, o))| (i, (k.as_bstr(), Cow::Owned(o)))),
+                    |i| {
+                        parse::any_header_field(i, is_not(NL))
+                            .map(|(i, (k, o))| (i, (k.as_bstr(), Cow::Borrowed(o.as_bstr()))))
+                    },
+                )))(i)
+                .map_err(Error::context("<field> <single-line|multi-line>"))?;
+                match extra_header {
+                    Some(extra_header) => (i, Some(Token::ExtraHeader(extra_header))),
+                    None => {
+                        *state = Message;
+                        (i, None)
+                    }
+                }
+            }
+            Message => {
+                let (i, message) = all_consuming(parse_message)(i)?;
+                *state = Done;
+                (i, Some(Token::Message(message)))
+            }
+            Done => (i, None),
+        })
+    }
+}
+
+impl<'a> Iterator for CommitIter<'a> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.state != State::Done {
+            match Self::next_inner(self.data, &mut self.state) {
+                Ok((data, token)) => {
+                    self.data = data;
+                    if let Some(token) = token {
+                        return Some(Ok(token));
+                    }
+                }
+                Err(err) => {
+                    self.state = State::Done;
+                    return Some(Err(err.into()));
+                }
+            }
+        }
+        None
+    }
 }
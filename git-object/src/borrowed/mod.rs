@@ -1,18 +1,20 @@
 //! Borrowed objects are expected to be deserialized from bytes that acts as backing store, and they
 //! can not be serialized directly. Instead, one will convert them into their `owned` counterparts,
 //! which support serialization.
-mod commit;
-pub use commit::Commit;
+pub mod commit;
+#[doc(inline)]
+pub use commit::{Commit, CommitIter};
 
 mod id;
 pub use id::*;
 
-mod tag;
-pub use tag::Tag;
+pub mod tag;
+#[doc(inline)]
+pub use tag::{Tag, TagIter};
 
 pub mod tree;
 #[doc(inline)]
-pub use tree::Tree;
+pub use tree::{Tree, TreeIter};
 
 mod blob {
     use std::convert::Infallible;
@@ -106,3 +106,119 @@ impl<'a> Tag<'a> {
         parse(d).map(|(_, t)| t).map_err(Error::from)
     }
 }
+
+/// A token of a tag as produced by [`TagIter`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Token<'a> {
+    Target {
+        id: owned::Id,
+    },
+    TargetKind(crate::Kind),
+    Name(&'a BStr),
+    Tagger(#[cfg_attr(feature = "serde1", serde(borrow))] Option<Signature<'a>>),
+    Body {
+        message: &'a BStr,
+        pgp_signature: Option<&'a BStr>,
+    },
+}
+
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+enum State {
+    Target,
+    TargetKind,
+    Name,
+    Tagger,
+    Body,
+    Done,
+}
+
+/// An iterator over the tokens of a tag, parsing only as much of the tag as is requested.
+///
+/// This allows to obtain the target of a tag without parsing its tagger or message, and doesn't allocate.
+/// After an error was returned, the iterator yields no more tokens.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct TagIter<'a> {
+    data: &'a [u8],
+    state: State,
+}
+
+impl<'a> TagIter<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> TagIter<'a> {
+        TagIter {
+            data,
+            state: State::Target,
+        }
+    }
+
+    /// Return the id of the tag's target, parsing nothing but the first header.
+    pub fn target_id(&mut self) -> Result<owned::Id, Error> {
+        match self.next() {
+            Some(Ok(Token::Target { id })) => Ok(id),
+            Some(Err(err)) => Err(err),
+            _ => Err(Error::NomDetail(self.data.into(), "object <40 lowercase hex char>")),
+        }
+    }
+
+    fn next_inner(i: &'a [u8], state: &mut State) -> IResult<&'a [u8], Token<'a>, Error> {
+        use State::*;
+        Ok(match *state {
+            Target => {
+                let (i, target) = parse::header_field(i, b"object", parse::hex_sha1)
+                    .map_err(Error::context("object <40 lowercase hex char>"))?;
+                *state = TargetKind;
+                (
+                    i,
+                    Token::Target {
+                        id: owned::Id::from_40_bytes_in_hex(target).expect("parsing validation"),
+                    },
+                )
+            }
+            TargetKind => {
+                let (i, kind) = parse::header_field(i, b"type", take_while1(is_alphabetic))
+                    .map_err(Error::context("type <object kind>"))?;
+                let kind = crate::Kind::from_bytes(kind).map_err(|e| nom::Err::Error(Error::ParseKindError(e)))?;
+                *state = Name;
+                (i, Token::TargetKind(kind))
+            }
+            Name => {
+                let (i, tag_version) = parse::header_field(i, b"tag", take_while1(|b| b != NL[0]))
+                    .map_err(Error::context("tag <version>"))?;
+                *state = Tagger;
+                (i, Token::Name(tag_version.as_bstr()))
+            }
+            Tagger => {
+                let (i, signature) = opt(|i| parse::header_field(i, b"tagger", parse::signature))(i)
+                    .map_err(Error::context("tagger <signature>"))?;
+                *state = Body;
+                (i, Token::Tagger(signature))
+            }
+            Body => {
+                let (i, (message, pgp_signature)) = all_consuming(parse_message)(i)?;
+                *state = Done;
+                (i, Token::Body { message, pgp_signature })
+            }
+            Done => unreachable!("BUG: must not be called once done"),
+        })
+    }
+}
+
+impl<'a> Iterator for TagIter<'a> {
+    type Item = Result<Token<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.state == State::Done {
+            return None;
+        }
+        Some(match Self::next_inner(self.data, &mut self.state) {
+            Ok((data, token)) => {
+                self.data = data;
+                Ok(token)
+            }
+            Err(err) => {
+                self.state = State::Done;
+                Err(err.into())
+            }
+        })
+    }
+}
@@ -70,3 +70,38 @@ impl<'a> Tree<'a> {
         parse(d).map(|(_, t)| t).map_err(Error::from)
     }
 }
+
+/// An iterator over the entries of a tree, parsing each entry only when it is requested.
+///
+/// This allows to stop at the first matching entry without parsing the remaining ones, and doesn't allocate.
+/// After an error was returned, the iterator yields no more entries.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct TreeIter<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> TreeIter<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> TreeIter<'a> {
+        TreeIter { data }
+    }
+}
+
+impl<'a> Iterator for TreeIter<'a> {
+    type Item = Result<Entry<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        match parse_entry(self.data) {
+            Ok((data, entry)) => {
+                self.data = data;
+                Some(Ok(entry))
+            }
+            Err(err) => {
+                self.data = &[];
+                Some(Err(err.into()))
+            }
+        }
+    }
+}
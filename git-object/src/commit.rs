@@ -1,6 +1,11 @@
 use crate::borrowed;
 use bstr::{BStr, ByteSlice};
 
+pub mod encoding;
+pub mod message;
+#[doc(inline)]
+pub use message::Message;
+
 pub struct ExtraHeaders<I> {
     inner: I,
 }
@@ -0,0 +1,51 @@
+//! Decode the messages and signatures of commits with an `encoding` header to UTF-8, and encode them with another
+//! encoding.
+use bstr::{BStr, BString};
+use quick_error::quick_error;
+
+/// A character encoding, as named by the `encoding` header of commits.
+pub use encoding_rs::Encoding;
+/// The encoding of commits without `encoding` header.
+pub use encoding_rs::UTF_8;
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Unknown(name: BString) {
+            display("The encoding '{}' is unknown", name)
+        }
+        Unsupported(name: &'static str) {
+            display("Text cannot be encoded as {}", name)
+        }
+        Unmappable(name: &'static str) {
+            display("The text contains characters which cannot be encoded as {}", name)
+        }
+    }
+}
+
+/// Return the encoding named `name`, which is case-insensitive and may be any of the usual aliases like `latin1`, or
+/// UTF-8 if there is no name, like it is assumed for commits without `encoding` header.
+pub fn for_name(name: Option<&BStr>) -> Result<&'static Encoding, Error> {
+    match name {
+        Some(name) => Encoding::for_label(name.trim_ascii()).ok_or_else(|| Error::Unknown(name.to_owned())),
+        None => Ok(UTF_8),
+    }
+}
+
+/// Convert `text` from the encoding `from` to the encoding `to`.
+///
+/// Malformed input is decoded to the replacement character, but characters which cannot be encoded are an error.
+pub(crate) fn convert(text: &[u8], from: &'static Encoding, to: &'static Encoding) -> Result<BString, Error> {
+    if from == to {
+        return Ok(text.into());
+    }
+    if to.output_encoding() != to {
+        return Err(Error::Unsupported(to.name()));
+    }
+    let text = from.decode_without_bom_handling(text).0;
+    let (encoded, _, has_unmappable) = to.encode(&text);
+    if has_unmappable {
+        return Err(Error::Unmappable(to.name()));
+    }
+    Ok(encoded.into_owned().into())
+}
@@ -0,0 +1,82 @@
+//! Split commit messages into their title and body, and read and add the trailers at their end.
+use bstr::{BStr, ByteSlice};
+use std::borrow::Cow;
+
+pub mod trailer;
+#[doc(inline)]
+pub use trailer::Trailer;
+
+/// A commit message split into its title and body the way git does it.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Message<'a> {
+    /// The first paragraph of the message without the newline that ends it, which usually is a single line.
+    #[cfg_attr(feature = "serde1", serde(borrow))]
+    pub title: &'a BStr,
+    /// Everything after the blank lines that follow the title, or `None` if there is nothing.
+    pub body: Option<&'a BStr>,
+}
+
+impl<'a> Message<'a> {
+    /// Split `message` into its title and body, ignoring blank lines before the title.
+    pub fn from_bytes(message: &'a [u8]) -> Self {
+        let mut lines = message.lines_with_terminator().map(|line| {
+            let start = line.as_ptr() as usize - message.as_ptr() as usize;
+            (start, line)
+        });
+        let mut title = None;
+        let mut body_start = message.len();
+        for (start, line) in &mut lines {
+            match (is_blank(line), &mut title) {
+                (true, None) => continue,
+                (true, Some(_)) => {
+                    body_start = lines
+                        .find(|(_, line)| !is_blank(line))
+                        .map_or(message.len(), |(start, _)| start);
+                    break;
+                }
+                (false, None) => title = Some(start..start + line.len()),
+                (false, Some(title)) => title.end = start + line.len(),
+            }
+        }
+        let title = title.map_or(&message[message.len()..], |title| &message[title]);
+        Message {
+            title: title.strip_suffix(b"\n").unwrap_or(title).as_bstr(),
+            body: Some(&message[body_start..])
+                .filter(|body| !body.is_empty())
+                .map(ByteSlice::as_bstr),
+        }
+    }
+
+    /// The title on a single line like `git log --format=%s` shows it, with the lines of multi-line titles
+    /// joined by spaces.
+    pub fn summary(&self) -> Cow<'a, BStr> {
+        let title = self.title;
+        if !title.contains(&b'\n') && title.last().is_none_or(|b| !b.is_ascii_whitespace()) {
+            return Cow::Borrowed(title);
+        }
+        let mut summary = Vec::with_capacity(title.len());
+        for line in title.lines() {
+            if !summary.is_empty() {
+                summary.push(b' ');
+            }
+            summary.extend_from_slice(line.trim_ascii_end());
+        }
+        Cow::Owned(summary.into())
+    }
+
+    /// The trailers at the end of the body, in the order in which they appear.
+    pub fn trailers(&self) -> trailer::Iter<'a> {
+        match self.body {
+            Some(body) => {
+                let block = trailer::block(body);
+                trailer::Iter::new(&body[block])
+            }
+            None => trailer::Iter::new(&[]),
+        }
+    }
+}
+
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
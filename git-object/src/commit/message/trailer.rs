@@ -0,0 +1,301 @@
+//! Read and add the `token: value` lines at the end of commit messages like `git interpret-trailers` does.
+use super::{is_blank, Message};
+use bstr::{BStr, BString, ByteSlice};
+use std::{borrow::Cow, ops::Range};
+
+/// Lines which make the paragraph they are in a trailer block even if most of its other lines aren't trailers.
+const GIT_GENERATED_PREFIXES: &[&[u8]] = &[b"Signed-off-by: ", b"(cherry picked from commit "];
+
+/// A `token: value` line at the end of a commit message, like `Signed-off-by: Name <email>`.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trailer<'a> {
+    /// The token before the separator, like `Signed-off-by`.
+    #[cfg_attr(feature = "serde1", serde(borrow))]
+    pub token: &'a BStr,
+    /// The value after the separator without surrounding whitespace, with its continuation lines joined by spaces.
+    pub value: Cow<'a, BStr>,
+}
+
+/// An iterator over the trailers of a trailer block, which skips its lines that aren't trailers.
+pub struct Iter<'a> {
+    lines: std::iter::Peekable<bstr::LinesWithTerminator<'a>>,
+}
+
+impl<'a> Iter<'a> {
+    pub(crate) fn new(block: &'a [u8]) -> Self {
+        Iter {
+            lines: block.lines_with_terminator().peekable(),
+        }
+    }
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = Trailer<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = self.lines.next()?;
+            let separator = match separator(line) {
+                Some(pos) => pos,
+                None => continue,
+            };
+            let mut value = Cow::Borrowed(line[separator + 1..].trim_ascii().as_bstr());
+            while let Some(continuation) = self.lines.next_if(|line| is_continuation(line)) {
+                let value = value.to_mut();
+                if !value.is_empty() {
+                    value.push(b' ');
+                }
+                value.extend_from_slice(continuation.trim_ascii());
+            }
+            return Some(Trailer {
+                token: line[..separator].trim_ascii_end().as_bstr(),
+                value,
+            });
+        }
+    }
+}
+
+/// Where to add a trailer, like `trailer.where`.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Where {
+    /// After all other trailers.
+    #[default]
+    End,
+    /// Before all other trailers.
+    Start,
+    /// After the last trailer with the same token, or after all other trailers if there is none.
+    After,
+    /// Before the first trailer with the same token, or before all other trailers if there is none.
+    Before,
+}
+
+/// What to do if a trailer with the same token already exists, like `trailer.ifExists`.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum IfExists {
+    /// Add the trailer unless the trailer next to where it would be added has the same token and value.
+    #[default]
+    AddIfDifferentNeighbor,
+    /// Add the trailer unless a trailer with the same token and value exists.
+    AddIfDifferent,
+    /// Add the trailer in any case.
+    Add,
+    /// Replace the last trailer with the same token, or the first one if it is added before other trailers.
+    Replace,
+    /// Leave the message as it is.
+    DoNothing,
+}
+
+/// What to do if no trailer with the same token exists, like `trailer.ifMissing`.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum IfMissing {
+    /// Add the trailer.
+    #[default]
+    Add,
+    /// Leave the message as it is.
+    DoNothing,
+}
+
+/// Configure how [`add()`] adds trailers, with defaults like the ones of `git interpret-trailers`.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    pub position: Where,
+    pub if_exists: IfExists,
+    pub if_missing: IfMissing,
+}
+
+/// A line of a trailer block along with its continuation lines.
+struct Item<'a> {
+    range: Range<usize>,
+    trailer: Option<Trailer<'a>>,
+}
+
+/// Add the trailer `token: value` to `message` according to `options` like `git interpret-trailers --trailer` does,
+/// and return true if `message` was changed.
+///
+/// Tokens are compared without regard to case, as are the values of trailers with the same token. Other trailers
+/// are kept as they are. If there is no trailer block yet, a new one is started after a blank line.
+pub fn add(message: &mut BString, token: &BStr, value: &BStr, options: Options) -> bool {
+    let token = token.trim_ascii();
+    let value = value.trim_ascii();
+    let block = match Message::from_bytes(&message[..]).body {
+        Some(body) => {
+            let offset = message.len() - body.len();
+            let block = block(body);
+            offset + block.start..offset + block.end
+        }
+        None => {
+            let end = end_of_content(message);
+            end..end
+        }
+    };
+
+    let mut items: Vec<Item<'_>> = Vec::new();
+    let mut start = block.start;
+    for line in message[block.clone()].lines_with_terminator() {
+        let end = start + line.len();
+        match items.last_mut() {
+            Some(item) if is_continuation(line) => item.range.end = end,
+            _ => items.push(Item {
+                range: start..end,
+                trailer: None,
+            }),
+        }
+        start = end;
+    }
+    for item in &mut items {
+        item.trailer = Iter::new(&message[item.range.clone()]).next();
+    }
+
+    let same_token = |item: &Item<'_>| {
+        item.trailer
+            .as_ref()
+            .is_some_and(|trailer| trailer.token.eq_ignore_ascii_case(token))
+    };
+    let same_trailer = |item: &Item<'_>| {
+        same_token(item)
+            && item
+                .trailer
+                .as_ref()
+                .is_some_and(|trailer| trailer.value.eq_ignore_ascii_case(value))
+    };
+    let is_after = matches!(options.position, Where::End | Where::After);
+    let found = if is_after {
+        items.iter().rposition(same_token)
+    } else {
+        items.iter().position(same_token)
+    };
+
+    let mut entries: Vec<Option<usize>> = (0..items.len()).map(Some).collect();
+    match found {
+        Some(found) => {
+            let neighbor = match options.position {
+                Where::After | Where::Before => found,
+                Where::End => items.len() - 1,
+                Where::Start => 0,
+            };
+            match options.if_exists {
+                IfExists::DoNothing => return false,
+                IfExists::AddIfDifferent if items.iter().any(same_trailer) => return false,
+                IfExists::AddIfDifferentNeighbor if same_trailer(&items[neighbor]) => return false,
+                _ => {}
+            }
+            entries.insert(if is_after { neighbor + 1 } else { neighbor }, None);
+            if options.if_exists == IfExists::Replace {
+                entries.retain(|entry| *entry != Some(found));
+            }
+        }
+        None => match options.if_missing {
+            IfMissing::DoNothing => return false,
+            IfMissing::Add => entries.insert(if is_after { entries.len() } else { 0 }, None),
+        },
+    }
+
+    let mut out = Vec::with_capacity(message.len() + token.len() + value.len() + 4);
+    out.extend_from_slice(&message[..block.start]);
+    if block.is_empty() {
+        if !out.is_empty() && !out.ends_with(b"\n") {
+            out.push(b'\n');
+        }
+        if !out.lines_with_terminator().last().is_some_and(is_blank) {
+            out.push(b'\n');
+        }
+    }
+    for entry in entries {
+        match entry {
+            Some(index) => {
+                out.extend_from_slice(&message[items[index].range.clone()]);
+                if !out.ends_with(b"\n") {
+                    out.push(b'\n');
+                }
+            }
+            None => {
+                out.extend_from_slice(token);
+                out.push(b':');
+                if !value.is_empty() {
+                    out.push(b' ');
+                    out.extend_from_slice(value);
+                }
+                out.push(b'\n');
+            }
+        }
+    }
+    out.extend_from_slice(&message[block.end..]);
+    *message = out.into();
+    true
+}
+
+/// Return the range of the trailer block at the end of the message `body`, or an empty range where it would start.
+///
+/// Like git, the last paragraph is a trailer block if it only consists of trailers and their continuation lines, or if
+/// at least a quarter of its lines are trailers and one of these was generated by git.
+pub(crate) fn block(body: &[u8]) -> Range<usize> {
+    let end = end_of_content(body);
+    let (mut trailers, mut non_trailers, mut continuations) = (0, 0, 0);
+    let mut has_git_generated_trailer = false;
+    let mut block_start = 0;
+    let lines: Vec<_> = body[..end].lines_with_terminator().collect();
+    for line in lines.into_iter().rev() {
+        if is_blank(line) {
+            block_start = line.as_ptr() as usize - body.as_ptr() as usize + line.len();
+            break;
+        }
+        if GIT_GENERATED_PREFIXES.iter().any(|prefix| line.starts_with(prefix)) {
+            trailers += 1;
+            continuations = 0;
+            has_git_generated_trailer = true;
+        } else if separator(line).is_some() {
+            trailers += 1;
+            continuations = 0;
+        } else if is_continuation(line) {
+            continuations += 1;
+        } else {
+            non_trailers += 1 + continuations;
+            continuations = 0;
+        }
+    }
+    non_trailers += continuations;
+    if (has_git_generated_trailer && trailers * 3 >= non_trailers) || (trailers > 0 && non_trailers == 0) {
+        block_start..end
+    } else {
+        end..end
+    }
+}
+
+/// The end of `message` without the empty lines at its end, which git keeps after the trailers it adds.
+fn end_of_content(message: &[u8]) -> usize {
+    let mut end = message.len();
+    while end > 0 && message[..end].ends_with(b"\n\n") {
+        end -= 1;
+    }
+    if end == 1 && message[0] == b'\n' {
+        end = 0;
+    }
+    end
+}
+
+fn is_continuation(line: &[u8]) -> bool {
+    matches!(line.first(), Some(b' ') | Some(b'\t'))
+}
+
+/// Return the position of the `:` separating the token from the value, where the token consists of alphanumeric
+/// characters and dashes and may be followed by whitespace.
+fn separator(line: &[u8]) -> Option<usize> {
+    let token_len = line
+        .iter()
+        .position(|b| !(b.is_ascii_alphanumeric() || *b == b'-'))
+        .unwrap_or(line.len());
+    if token_len == 0 {
+        return None;
+    }
+    let separator = token_len
+        + line[token_len..]
+            .iter()
+            .position(|b| *b != b' ' && *b != b'\t')
+            .unwrap_or(line.len() - token_len);
+    (line.get(separator) == Some(&b':')).then_some(separator)
+}
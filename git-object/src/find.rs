@@ -0,0 +1,54 @@
+//! Obtain objects through a function provided by the caller, which allows algorithms to work with any object database.
+//!
+//! Such a `find` function is called with the id of an object and a buffer, which it fills with the object's data, or
+//! returns `None` if the object doesn't exist. Errors it returns are passed on as [`Error::Find`].
+use crate::{borrowed, owned};
+use std::fmt;
+
+/// An error obtaining an object through a `find` function with [`load()`], or decoding it.
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The object doesn't exist
+    NotFound { oid: owned::Id },
+    /// The `find` function failed to obtain the object
+    Find { source: E, oid: owned::Id },
+    /// The object was obtained but could not be decoded
+    Decode { source: borrowed::Error, oid: owned::Id },
+}
+
+impl<E> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotFound { oid } => write!(f, "The object {} could not be found", oid),
+            Error::Find { oid, .. } => write!(f, "The object {} could not be obtained", oid),
+            Error::Decode { oid, .. } => write!(f, "The object {} could not be decoded", oid),
+        }
+    }
+}
+
+impl<E> std::error::Error for Error<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::NotFound { .. } => None,
+            Error::Find { source, .. } => Some(source),
+            Error::Decode { source, .. } => Some(source),
+        }
+    }
+}
+
+/// Clear `buf` and fill it with the data of the object `oid` using `find`.
+pub fn load<E>(
+    find: impl FnOnce(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    oid: owned::Id,
+    buf: &mut Vec<u8>,
+) -> Result<(), Error<E>> {
+    buf.clear();
+    match find(oid.to_borrowed(), buf) {
+        Some(Ok(())) => Ok(()),
+        Some(Err(err)) => Err(Error::Find { source: err, oid }),
+        None => Err(Error::NotFound { oid }),
+    }
+}
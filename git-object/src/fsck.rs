@@ -0,0 +1,618 @@
+//! Validation of objects beyond what's needed to parse them, similar to what `git fsck` does.
+//!
+//! Checks operate on the raw object data to also see what the parsers would reject or silently accept, and
+//! produce [findings][Finding] named like git's fsck message ids, each with a [severity][Severity] that can be
+//! configured in the [`Options`].
+use crate::{Kind, SHA1_SIZE};
+use bstr::{BStr, ByteSlice};
+use quick_error::quick_error;
+use std::{cmp::Ordering, collections::BTreeMap, collections::HashSet, fmt, str::FromStr};
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        UnknownCheck(name: String) {
+            display("Unknown fsck message id: '{}'", name)
+        }
+        UnknownSeverity(name: String) {
+            display("Unknown fsck severity: '{}', expected one of 'error', 'warn', 'info' or 'ignore'", name)
+        }
+    }
+}
+
+/// How bad a [`Finding`] is.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    /// Don't report the finding at all.
+    Ignore,
+    /// The finding is informational and doesn't indicate a problem.
+    Info,
+    /// The finding is suspicious but the object is usable.
+    Warning,
+    /// The object is invalid and should be rejected.
+    Error,
+}
+
+impl FromStr for Severity {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "error" => Severity::Error,
+            "warn" => Severity::Warning,
+            "info" => Severity::Info,
+            "ignore" => Severity::Ignore,
+            _ => return Err(Error::UnknownSeverity(s.into())),
+        })
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+            Severity::Ignore => "ignore",
+        })
+    }
+}
+
+macro_rules! checks {
+    ($($(#[$doc:meta])* $variant:ident => $id:literal, $severity:ident;)*) => {
+        /// All checks performed on objects, named after the fsck message id git uses for them.
+        #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+        #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+        pub enum Check {
+            $($(#[$doc])* $variant,)*
+        }
+
+        impl Check {
+            /// All checks in the order of their declaration.
+            pub fn all() -> &'static [Check] {
+                &[$(Check::$variant),*]
+            }
+
+            /// The message id git uses for this check, as in `fsck.<id>` configuration keys.
+            pub fn id(&self) -> &'static str {
+                match self {
+                    $(Check::$variant => $id,)*
+                }
+            }
+
+            /// The severity of this check unless configured otherwise, matching the one used by git.
+            pub fn default_severity(&self) -> Severity {
+                match self {
+                    $(Check::$variant => Severity::$severity,)*
+                }
+            }
+        }
+    };
+}
+
+checks! {
+    /// A header line of a commit or tag contains a NUL byte.
+    NulInHeader => "nulInHeader", Error;
+    /// The headers of a commit or tag are not terminated by a newline.
+    UnterminatedHeader => "unterminatedHeader", Error;
+
+    /// A tree can't be parsed into entries.
+    BadTree => "badTree", Error;
+    /// The entries of a tree are not sorted in the way git sorts them.
+    TreeNotSorted => "treeNotSorted", Error;
+    /// A tree contains more than one entry with the same name.
+    DuplicateEntries => "duplicateEntries", Error;
+    /// The mode of a tree entry starts with a zero.
+    ZeroPaddedFilemode => "zeroPaddedFilemode", Warning;
+    /// The mode of a tree entry isn't one git would write.
+    BadFilemode => "badFilemode", Info;
+    /// A tree entry has an empty name.
+    EmptyName => "emptyName", Warning;
+    /// A tree entry name contains a slash.
+    FullPathname => "fullPathname", Warning;
+    /// A tree entry is named `.`.
+    HasDot => "hasDot", Warning;
+    /// A tree entry is named `..`.
+    HasDotdot => "hasDotdot", Warning;
+    /// A tree entry is named `.git`, or something that some file systems treat like it.
+    HasDotgit => "hasDotgit", Warning;
+    /// A tree entry points to the null id.
+    NullSha1 => "nullSha1", Warning;
+
+    /// A commit doesn't start with a `tree` header.
+    MissingTree => "missingTree", Error;
+    /// The id in the `tree` header of a commit is invalid.
+    BadTreeSha1 => "badTreeSha1", Error;
+    /// The id in a `parent` header of a commit is invalid.
+    BadParentSha1 => "badParentSha1", Error;
+    /// A commit has no `author` header.
+    MissingAuthor => "missingAuthor", Error;
+    /// A commit has more than one `author` header.
+    MultipleAuthors => "multipleAuthors", Error;
+    /// A commit has no `committer` header.
+    MissingCommitter => "missingCommitter", Error;
+
+    /// A tag doesn't start with an `object` header.
+    MissingObject => "missingObject", Error;
+    /// The id in the `object` header of a tag is invalid.
+    BadObjectSha1 => "badObjectSha1", Error;
+    /// A tag has no `type` header.
+    MissingTypeEntry => "missingTypeEntry", Error;
+    /// The `type` header of a tag doesn't name an object kind.
+    BadType => "badType", Error;
+    /// A tag has no `tag` header.
+    MissingTagEntry => "missingTagEntry", Error;
+    /// The name of a tag can't be used as a reference name.
+    BadTagName => "badTagName", Info;
+    /// A tag has no `tagger` header, which is the case for some very old tags.
+    MissingTaggerEntry => "missingTaggerEntry", Info;
+
+    /// A signature starts with the email, without a name before it.
+    MissingNameBeforeEmail => "missingNameBeforeEmail", Error;
+    /// The name of a signature contains a `>`.
+    BadName => "badName", Error;
+    /// A signature has no email in angle brackets.
+    MissingEmail => "missingEmail", Error;
+    /// The email of a signature isn't separated from the name by a space.
+    MissingSpaceBeforeEmail => "missingSpaceBeforeEmail", Error;
+    /// The email of a signature isn't terminated by `>`, or contains another `<`.
+    BadEmail => "badEmail", Error;
+    /// The time of a signature isn't separated from the email by a space.
+    MissingSpaceBeforeDate => "missingSpaceBeforeDate", Error;
+    /// The time of a signature starts with a zero.
+    ZeroPaddedDate => "zeroPaddedDate", Error;
+    /// The time of a signature doesn't fit into the seconds of a [`Time`][crate::Time].
+    BadDateOverflow => "badDateOverflow", Error;
+    /// The time of a signature isn't a number followed by a space.
+    BadDate => "badDate", Error;
+    /// The timezone of a signature isn't of the form `+HHMM` or `-HHMM`.
+    BadTimezone => "badTimezone", Error;
+}
+
+impl FromStr for Check {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Check::all()
+            .iter()
+            .find(|c| c.id().eq_ignore_ascii_case(s))
+            .copied()
+            .ok_or_else(|| Error::UnknownCheck(s.into()))
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.id())
+    }
+}
+
+/// Configures which checks are reported with which severity.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    severities: BTreeMap<Check, Severity>,
+    strict: bool,
+}
+
+impl Options {
+    /// Options like those git uses when receiving objects with `transfer.fsckObjects`, which turn all warnings
+    /// into errors and are more pedantic about file modes.
+    pub fn strict() -> Self {
+        Options {
+            strict: true,
+            ..Default::default()
+        }
+    }
+
+    /// Report `check` with `severity`, like `fsck.<id> = <severity>` does in git.
+    pub fn with_severity(mut self, check: Check, severity: Severity) -> Self {
+        self.severities.insert(check, severity);
+        self
+    }
+
+    /// The severity at which `check` is reported.
+    pub fn severity(&self, check: Check) -> Severity {
+        match self.severities.get(&check) {
+            Some(severity) => *severity,
+            None => match check.default_severity() {
+                Severity::Warning if self.strict => Severity::Error,
+                severity => severity,
+            },
+        }
+    }
+}
+
+/// A problem found in an object.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Finding {
+    pub check: Check,
+    pub severity: Severity,
+    /// Details about the problem, like the offending tree entry name.
+    pub message: String,
+}
+
+impl Finding {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.check, self.message)
+    }
+}
+
+/// Check the object of `kind` with `data` and return all findings which aren't ignored by `options`.
+///
+/// Blobs are never checked and yield no findings.
+pub fn object(kind: Kind, data: &[u8], options: &Options) -> Vec<Finding> {
+    match kind {
+        Kind::Tree => tree(data, options),
+        Kind::Commit => commit(data, options),
+        Kind::Tag => tag(data, options),
+        Kind::Blob => Vec::new(),
+    }
+}
+
+/// Check the tree with `data` and return all findings which aren't ignored by `options`.
+///
+/// All entries are checked, unless the tree can't be parsed into entries anymore.
+pub fn tree(data: &[u8], options: &Options) -> Vec<Finding> {
+    let mut report = Report::new(options);
+    let mut names = HashSet::new();
+    let mut previous: Option<(&[u8], bool)> = None;
+    let mut data = data;
+    while !data.is_empty() {
+        let entry = match parse_tree_entry(data) {
+            Some(entry) => entry,
+            None => {
+                report.push(Check::BadTree, "cannot be parsed into entries");
+                break;
+            }
+        };
+        data = entry.rest;
+        let name = entry.name.as_bstr();
+
+        if entry.mode.starts_with(b"0") {
+            report.push(
+                Check::ZeroPaddedFilemode,
+                format!("'{}' has mode {}", name, entry.mode.as_bstr()),
+            );
+        }
+        match entry.mode_value {
+            0o100644 | 0o100755 | 0o120000 | 0o40000 | 0o160000 => {}
+            0o100664 if !options.strict => {}
+            _ => report.push(
+                Check::BadFilemode,
+                format!("'{}' has mode {}", name, entry.mode.as_bstr()),
+            ),
+        }
+        if entry.id.iter().all(|b| *b == 0) {
+            report.push(Check::NullSha1, format!("'{}' points to the null id", name));
+        }
+        match entry.name {
+            b"" => report.push(Check::EmptyName, "an entry has an empty name"),
+            b"." => report.push(Check::HasDot, "an entry is named '.'"),
+            b".." => report.push(Check::HasDotdot, "an entry is named '..'"),
+            name if name.contains(&b'/') => {
+                report.push(Check::FullPathname, format!("'{}' contains a slash", name.as_bstr()))
+            }
+            name if is_dotgit(name) => report.push(Check::HasDotgit, format!("'{}' is like .git", name.as_bstr())),
+            _ => {}
+        }
+
+        let is_tree = entry.mode_value == 0o40000;
+        if !names.insert(entry.name) {
+            report.push(Check::DuplicateEntries, format!("'{}' exists more than once", name));
+        } else if let Some((previous_name, previous_is_tree)) = previous {
+            if crate::tree::compare_names(previous_name, previous_is_tree, entry.name, is_tree) != Ordering::Less {
+                report.push(
+                    Check::TreeNotSorted,
+                    format!("'{}' is sorted after '{}'", name, previous_name.as_bstr()),
+                );
+            }
+        }
+        previous = Some((entry.name, is_tree));
+    }
+    report.findings
+}
+
+/// Check the commit with `data` and return all findings which aren't ignored by `options`.
+///
+/// Like git, checking stops at the first finding with [error severity][Severity::Error].
+pub fn commit(data: &[u8], options: &Options) -> Vec<Finding> {
+    let mut report = Report::new(options);
+    check_commit(data, &mut report).ok();
+    report.findings
+}
+
+/// Check the tag with `data` and return all findings which aren't ignored by `options`.
+///
+/// Like git, checking stops at the first finding with [error severity][Severity::Error].
+pub fn tag(data: &[u8], options: &Options) -> Vec<Finding> {
+    let mut report = Report::new(options);
+    check_tag(data, &mut report).ok();
+    report.findings
+}
+
+/// Returned when a finding is an error, to stop checking.
+struct Stop;
+
+struct Report<'a> {
+    options: &'a Options,
+    findings: Vec<Finding>,
+}
+
+impl<'a> Report<'a> {
+    fn new(options: &'a Options) -> Self {
+        Report {
+            options,
+            findings: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, check: Check, message: impl Into<String>) {
+        self.report(check, message).ok();
+    }
+
+    fn report(&mut self, check: Check, message: impl Into<String>) -> Result<(), Stop> {
+        let severity = self.options.severity(check);
+        if severity == Severity::Ignore {
+            return Ok(());
+        }
+        self.findings.push(Finding {
+            check,
+            severity,
+            message: message.into(),
+        });
+        if severity == Severity::Error {
+            Err(Stop)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct TreeEntry<'a> {
+    mode: &'a [u8],
+    mode_value: u32,
+    name: &'a [u8],
+    id: &'a [u8],
+    rest: &'a [u8],
+}
+
+fn parse_tree_entry(data: &[u8]) -> Option<TreeEntry<'_>> {
+    let space = data.find_byte(b' ')?;
+    let mode = &data[..space];
+    if mode.is_empty() || mode.len() > 7 || !mode.iter().all(|b| (b'0'..=b'7').contains(b)) {
+        return None;
+    }
+    let mode_value = mode.iter().fold(0, |acc, b| acc * 8 + u32::from(b - b'0'));
+    let data = &data[space + 1..];
+    let nul = data.find_byte(0)?;
+    let name = &data[..nul];
+    let data = &data[nul + 1..];
+    if data.len() < SHA1_SIZE {
+        return None;
+    }
+    Some(TreeEntry {
+        mode,
+        mode_value,
+        name,
+        id: &data[..SHA1_SIZE],
+        rest: &data[SHA1_SIZE..],
+    })
+}
+
+/// Returns true if `name` could be `.git` on any of the file systems git knows about.
+fn is_dotgit(name: &[u8]) -> bool {
+    is_hfs_dotgit(name) || is_ntfs_dotgit(name)
+}
+
+/// Returns true if `name` is `.git` on HFS+, which is case-insensitive and ignores some unicode code points entirely,
+/// like git's `is_hfs_dotgit()`.
+fn is_hfs_dotgit(name: &[u8]) -> bool {
+    let mut rest = name;
+    // Like git, the end of the name and invalid UTF-8 both yield `None`.
+    let mut next_char = || loop {
+        if rest.is_empty() {
+            return None;
+        }
+        let (c, len) = bstr::decode_utf8(rest);
+        rest = &rest[len..];
+        match c? {
+            '\u{200c}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{206a}'..='\u{206f}' | '\u{feff}' => continue,
+            c => return Some(c),
+        }
+    };
+    next_char() == Some('.')
+        && "git"
+            .chars()
+            .all(|needle| next_char().map(|c| c.to_ascii_lowercase()) == Some(needle))
+        && matches!(next_char(), None | Some('/'))
+}
+
+/// Returns true if `name` is `.git` on NTFS, which is case-insensitive, ignores trailing dots and spaces, knows `git~1`
+/// as short name and allows to name alternate data streams like `.git::$INDEX_ALLOCATION`, like git's
+/// `is_ntfs_dotgit()`.
+fn is_ntfs_dotgit(name: &[u8]) -> bool {
+    let rest = if name.len() >= 4 && name[..4].eq_ignore_ascii_case(b".git") {
+        &name[4..]
+    } else if name.len() >= 5 && name[..3].eq_ignore_ascii_case(b"git") && &name[3..5] == b"~1" {
+        &name[5..]
+    } else {
+        return false;
+    };
+    for b in rest {
+        match b {
+            b'/' | b'\\' | b':' => return true,
+            b'.' | b' ' => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Return the headers of `data` including their last newline, or report why that's not possible.
+fn headers<'a>(data: &'a [u8], report: &mut Report<'_>) -> Result<&'a [u8], Stop> {
+    for (pos, window) in data.windows(2).enumerate() {
+        match window {
+            [0, _] => {
+                return report
+                    .report(Check::NulInHeader, format!("at offset {}", pos))
+                    .map(|_| data)
+            }
+            [b'\n', b'\n'] => return Ok(&data[..pos + 1]),
+            _ => {}
+        }
+    }
+    match data.last() {
+        Some(b'\n') => Ok(data),
+        Some(0) => report
+            .report(Check::NulInHeader, format!("at offset {}", data.len() - 1))
+            .map(|_| data),
+        _ => report
+            .report(Check::UnterminatedHeader, "headers don't end with a newline")
+            .map(|_| data),
+    }
+}
+
+/// If the next line of `data` is a header called `name`, return its value and advance `data` past it.
+fn header<'a>(data: &mut &'a [u8], name: &[u8]) -> Option<&'a [u8]> {
+    let value = data.strip_prefix(name)?.strip_prefix(b" ")?;
+    let end = value.find_byte(b'\n').unwrap_or(value.len());
+    *data = value.get(end + 1..).unwrap_or(&[]);
+    Some(&value[..end])
+}
+
+fn is_hex_id(value: &[u8]) -> bool {
+    value.len() == SHA1_SIZE * 2 && value.iter().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+fn check_commit(data: &[u8], report: &mut Report<'_>) -> Result<(), Stop> {
+    let mut data = headers(data, report)?;
+    match header(&mut data, b"tree") {
+        Some(id) if is_hex_id(id) => {}
+        Some(id) => report.report(Check::BadTreeSha1, format!("'{}' is not a valid id", id.as_bstr()))?,
+        None => return report.report(Check::MissingTree, "the first header isn't 'tree'"),
+    }
+    while let Some(id) = header(&mut data, b"parent") {
+        if !is_hex_id(id) {
+            report.report(Check::BadParentSha1, format!("'{}' is not a valid id", id.as_bstr()))?;
+        }
+    }
+    let mut num_authors = 0;
+    while let Some(author) = header(&mut data, b"author") {
+        num_authors += 1;
+        signature(author.as_bstr(), report)?;
+    }
+    match num_authors {
+        0 => report.report(Check::MissingAuthor, "there is no 'author' header after the parents")?,
+        1 => {}
+        _ => report.report(
+            Check::MultipleAuthors,
+            format!("found {} 'author' headers", num_authors),
+        )?,
+    }
+    match header(&mut data, b"committer") {
+        Some(committer) => signature(committer.as_bstr(), report),
+        None => report.report(
+            Check::MissingCommitter,
+            "there is no 'committer' header after the author",
+        ),
+    }
+}
+
+fn check_tag(data: &[u8], report: &mut Report<'_>) -> Result<(), Stop> {
+    let mut data = headers(data, report)?;
+    match header(&mut data, b"object") {
+        Some(id) if is_hex_id(id) => {}
+        Some(id) => report.report(Check::BadObjectSha1, format!("'{}' is not a valid id", id.as_bstr()))?,
+        None => return report.report(Check::MissingObject, "the first header isn't 'object'"),
+    }
+    match header(&mut data, b"type") {
+        Some(kind) => {
+            if Kind::from_bytes(kind).is_err() {
+                report.report(Check::BadType, format!("'{}' is not an object kind", kind.as_bstr()))?;
+            }
+        }
+        None => return report.report(Check::MissingTypeEntry, "there is no 'type' header after 'object'"),
+    }
+    match header(&mut data, b"tag") {
+        Some(name) => {
+            if !is_valid_tag_name(name) {
+                report.report(
+                    Check::BadTagName,
+                    format!("'{}' is not a valid reference name", name.as_bstr()),
+                )?;
+            }
+        }
+        None => return report.report(Check::MissingTagEntry, "there is no 'tag' header after 'type'"),
+    }
+    match header(&mut data, b"tagger") {
+        Some(tagger) => signature(tagger.as_bstr(), report),
+        None => report.report(Check::MissingTaggerEntry, "there is no 'tagger' header after 'tag'"),
+    }
+}
+
+/// Check a signature like `Name <email> 1234567890 +0100`, in the same order as git does.
+fn signature(value: &BStr, report: &mut Report<'_>) -> Result<(), Stop> {
+    let bad = |report: &mut Report<'_>, check| report.report(check, format!("in '{}'", value));
+    let value: &[u8] = value.as_ref();
+    if value.starts_with(b"<") {
+        return bad(report, Check::MissingNameBeforeEmail);
+    }
+    let email_start = match value.find_byteset(b"<>") {
+        Some(pos) if value[pos] == b'<' => pos,
+        Some(_) => return bad(report, Check::BadName),
+        None => return bad(report, Check::MissingEmail),
+    };
+    if email_start == 0 || value[email_start - 1] != b' ' {
+        return bad(report, Check::MissingSpaceBeforeEmail);
+    }
+    let email_end = match value[email_start + 1..].find_byteset(b"<>") {
+        Some(pos) if value[email_start + 1 + pos] == b'>' => email_start + 1 + pos,
+        _ => return bad(report, Check::BadEmail),
+    };
+    let date = match value[email_end + 1..].strip_prefix(b" ") {
+        Some(date) => date,
+        None => return bad(report, Check::MissingSpaceBeforeDate),
+    };
+    if date.starts_with(b"0") && date.get(1).is_some_and(|b| *b != b' ') {
+        return bad(report, Check::ZeroPaddedDate);
+    }
+    let date_end = date.iter().position(|b| !b.is_ascii_digit()).unwrap_or(date.len());
+    if date[..date_end]
+        .iter()
+        .try_fold(0u32, |acc, b| acc.checked_mul(10)?.checked_add(u32::from(b - b'0')))
+        .is_none()
+    {
+        return bad(report, Check::BadDateOverflow);
+    }
+    let timezone = match date[date_end..].strip_prefix(b" ") {
+        Some(timezone) if date_end > 0 => timezone,
+        _ => return bad(report, Check::BadDate),
+    };
+    match timezone {
+        [b'+', rest @ ..] | [b'-', rest @ ..] if rest.len() == 4 && rest.iter().all(u8::is_ascii_digit) => Ok(()),
+        _ => bad(report, Check::BadTimezone),
+    }
+}
+
+/// Returns true if `refs/tags/<name>` is a valid reference name, following the rules of `git check-ref-format`.
+fn is_valid_tag_name(name: &[u8]) -> bool {
+    !name.is_empty()
+        && name != b"@"
+        && !name.ends_with(b"/")
+        && !name.ends_with(b".")
+        && name.find(b"..").is_none()
+        && name.find(b"@{").is_none()
+        && name
+            .split(|b| *b == b'/')
+            .all(|component| !component.is_empty() && !component.starts_with(b".") && !component.ends_with(b".lock"))
+        && !name.iter().any(|b| *b < 0x20 || *b == 0x7f || b" ~^:?*[\\".contains(b))
+}
@@ -13,6 +13,11 @@ mod types;
 pub use types::*;
 
 pub mod commit;
+pub mod find;
+pub mod fsck;
+pub mod signed;
+pub mod time;
+pub mod tree;
 
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
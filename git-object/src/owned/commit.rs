@@ -1,6 +1,7 @@
 use crate::{
-    commit,
+    commit::{self, encoding},
     owned::{self, ser, NL},
+    signed,
 };
 use bstr::{BStr, BString, ByteSlice};
 use smallvec::SmallVec;
@@ -22,9 +23,50 @@ pub struct Commit {
 }
 
 impl Commit {
+    /// The message split into its title and body, which also provide the summary and trailers.
+    pub fn message(&self) -> commit::Message<'_> {
+        commit::Message::from_bytes(&self.message)
+    }
+    /// Add the trailer `token: value` to the message according to `options`, and return true if it was changed.
+    pub fn add_trailer(&mut self, token: &BStr, value: &BStr, options: commit::message::trailer::Options) -> bool {
+        commit::message::trailer::add(&mut self.message, token, value, options)
+    }
     pub fn extra_headers(&self) -> commit::ExtraHeaders<impl Iterator<Item = (&BStr, &BStr)>> {
         commit::ExtraHeaders::new(self.extra_headers.iter().map(|(k, v)| (k.as_bstr(), v.as_bstr())))
     }
+    /// The encoding of the message and the names and emails of author and committer, as named by the `encoding`
+    /// header, or UTF-8 if there is none.
+    pub fn message_encoding(&self) -> Result<&'static encoding::Encoding, encoding::Error> {
+        encoding::for_name(self.encoding.as_ref().map(|name| name.as_bstr()))
+    }
+    /// Convert the message and the names and emails of author and committer to the encoding `name`, or to UTF-8
+    /// if it is `None`, and name it in the `encoding` header, which is removed for UTF-8 like git does.
+    ///
+    /// If that changes the commit, its `gpgsig` and `gpgsig-sha256` headers are removed as the signatures wouldn't
+    /// match anymore. Nothing is changed if the conversion fails.
+    pub fn reencode(&mut self, name: Option<&BStr>) -> Result<(), encoding::Error> {
+        let from = self.message_encoding()?;
+        let to = encoding::for_name(name)?;
+        let convert = |text: &BString| encoding::convert(text, from, to);
+        let message = convert(&self.message)?;
+        let author = (convert(&self.author.name)?, convert(&self.author.email)?);
+        let committer = (convert(&self.committer.name)?, convert(&self.committer.email)?);
+        let encoding = name.filter(|_| to != encoding::UTF_8).map(ToOwned::to_owned);
+
+        let is_changed = message != self.message
+            || (&author.0, &author.1) != (&self.author.name, &self.author.email)
+            || (&committer.0, &committer.1) != (&self.committer.name, &self.committer.email)
+            || encoding != self.encoding;
+        if is_changed {
+            self.extra_headers
+                .retain(|(name, _)| name != signed::SIGNATURE_HEADER && name != signed::SIGNATURE_HEADER_SHA256);
+        }
+        self.message = message;
+        (self.author.name, self.author.email) = author;
+        (self.committer.name, self.committer.email) = committer;
+        self.encoding = encoding;
+        Ok(())
+    }
     pub fn write_to(&self, mut out: impl io::Write) -> io::Result<()> {
         ser::trusted_header_id(b"tree", &self.tree, &mut out)?;
         for parent in &self.parents {
@@ -0,0 +1,93 @@
+//! Separate the signatures of commits and tags from the data they sign, the way git does to verify them.
+use bstr::{BStr, ByteSlice};
+use std::borrow::Cow;
+
+/// The lines with which signatures start that git knows, OpenPGP, X.509 and SSH in this order.
+const SIGNATURE_STARTS: &[&[u8]] = &[
+    b"-----BEGIN PGP SIGNATURE-----",
+    b"-----BEGIN PGP MESSAGE-----",
+    b"-----BEGIN SIGNED MESSAGE-----",
+    b"-----BEGIN SSH SIGNATURE-----",
+];
+
+/// The header of commits which holds their signature.
+pub(crate) const SIGNATURE_HEADER: &[u8] = b"gpgsig";
+/// The header of commits which holds their signature in a repository using SHA-256, which is never part of the
+/// signed data either.
+pub(crate) const SIGNATURE_HEADER_SHA256: &[u8] = b"gpgsig-sha256";
+
+/// A signature along with the exact data it signs.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Signed<'a> {
+    /// The object data without its signature.
+    pub payload: Cow<'a, BStr>,
+    /// The signature, armored, with a trailing newline.
+    pub signature: Cow<'a, BStr>,
+}
+
+/// Extract the signature and the data it signs from the serialized commit `data`, or return `None` if it is unsigned.
+///
+/// The payload is the commit without its `gpgsig` header, which is also where the signature is taken from. A
+/// `gpgsig-sha256` header is removed from the payload as well.
+pub fn commit(data: &[u8]) -> Option<Signed<'_>> {
+    let mut payload = Vec::with_capacity(data.len());
+    let mut signature = Vec::new();
+    let mut in_signature = None;
+    let mut rest = data;
+    while !rest.is_empty() {
+        let end = rest.find_byte(b'\n').map_or(rest.len(), |pos| pos + 1);
+        let (line, remainder) = rest.split_at(end);
+        rest = remainder;
+        if line == b"\n" {
+            payload.extend_from_slice(line);
+            payload.extend_from_slice(rest);
+            break;
+        }
+        if let (Some(is_primary), Some(continuation)) = (in_signature, line.strip_prefix(b" ")) {
+            if is_primary {
+                signature.extend_from_slice(continuation);
+            }
+            continue;
+        }
+        in_signature = None;
+        if let Some(value) = header_value(line, SIGNATURE_HEADER) {
+            signature.extend_from_slice(value);
+            in_signature = Some(true);
+        } else if header_value(line, SIGNATURE_HEADER_SHA256).is_some() {
+            in_signature = Some(false);
+        } else {
+            payload.extend_from_slice(line);
+        }
+    }
+    if signature.is_empty() {
+        return None;
+    }
+    Some(Signed {
+        payload: Cow::Owned(payload.into()),
+        signature: Cow::Owned(signature.into()),
+    })
+}
+
+/// Extract the signature and the data it signs from the serialized tag `data`, or return `None` if it is unsigned.
+///
+/// The signature starts at the last line starting an OpenPGP, X.509 or SSH signature and extends to the end of the
+/// tag, and the payload is everything before it.
+pub fn tag(data: &[u8]) -> Option<Signed<'_>> {
+    let mut start = None;
+    let mut pos = 0;
+    while pos < data.len() {
+        let line = &data[pos..];
+        if SIGNATURE_STARTS.iter().any(|prefix| line.starts_with(prefix)) {
+            start = Some(pos);
+        }
+        pos += line.find_byte(b'\n').map_or(line.len(), |end| end + 1);
+    }
+    start.map(|start| Signed {
+        payload: Cow::Borrowed(data[..start].as_bstr()),
+        signature: Cow::Borrowed(data[start..].as_bstr()),
+    })
+}
+
+fn header_value<'a>(line: &'a [u8], name: &[u8]) -> Option<&'a [u8]> {
+    line.strip_prefix(name)?.strip_prefix(b" ")
+}
@@ -0,0 +1,72 @@
+//! Parse dates in the formats git accepts, and format [`Time`] in the styles of `git log --date`.
+use crate::Time;
+use std::{str::FromStr, time::SystemTime};
+
+pub mod format;
+#[doc(inline)]
+pub use format::Format;
+pub mod parse;
+#[doc(inline)]
+pub use parse::parse;
+
+const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
+impl Time {
+    /// Format this time in `format` like `git log --date=<format>` does, in the time zone it was recorded in.
+    ///
+    /// [`Format::Relative`] is relative to the current time.
+    pub fn format(&self, format: Format) -> String {
+        self.format_with_now(format, SystemTime::now())
+    }
+
+    /// Format this time like [`format()`][Time::format()], but relative to `now` if `format` is [`Format::Relative`].
+    pub fn format_with_now(&self, format: Format, now: SystemTime) -> String {
+        format::format(self, format, now)
+    }
+}
+
+impl FromStr for Time {
+    type Err = parse::Error;
+
+    /// Parse `s` like [`parse()`] does, with relative dates being relative to the current time and UTC as local time
+    /// zone.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse(s, SystemTime::now(), 0)
+    }
+}
+
+/// Return the seconds since the unix epoch of `time`, or 0 if it is before it.
+fn seconds_since_epoch(time: SystemTime) -> i64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs() as i64)
+}
+
+/// Return the number of days since 1970-01-01 of the given day of the proleptic Gregorian calendar, where `day` may
+/// be larger than the number of days in `month` to denote days in the months that follow.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let (year, month) = (year + (month - 1).div_euclid(12), (month - 1).rem_euclid(12) + 1);
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468 + day - 1
+}
+
+/// Return year, month and day of the day `days` after 1970-01-01, the inverse of [`days_from_civil()`].
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
@@ -0,0 +1,138 @@
+//! Format [`Time`] like `git log --date=<format>` does.
+use super::{civil_from_days, seconds_since_epoch, SECONDS_PER_DAY};
+use crate::Time;
+use quick_error::quick_error;
+use std::{str::FromStr, time::SystemTime};
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// The styles in which git shows dates, as selected by `--date` or `log.date`.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Format {
+    /// Like `Thu Sep 17 14:26:40 2020 +0200`.
+    #[default]
+    Default,
+    /// Like `2020-09-17 14:26:40 +0200`, similar to ISO 8601.
+    Iso8601,
+    /// Like `2020-09-17T14:26:40+02:00`, which is strict ISO 8601.
+    Iso8601Strict,
+    /// Like `Thu, 17 Sep 2020 14:26:40 +0200`, as used in emails.
+    Rfc2822,
+    /// Like `2 weeks ago`.
+    Relative,
+    /// Like `2020-09-17`.
+    Short,
+    /// Like `1600345600 +0200`, which is how times are stored in objects.
+    Raw,
+    /// Like `1600345600`, the seconds since the unix epoch.
+    Unix,
+}
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        UnknownFormat(name: String) {
+            display("Unknown date format '{}', expected one of default, iso, iso-strict, rfc, relative, short, raw or unix", name)
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    /// Parse the names `--date` accepts, like `iso` or `rfc2822`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "default" => Format::Default,
+            "iso" | "iso8601" => Format::Iso8601,
+            "iso-strict" | "iso8601-strict" => Format::Iso8601Strict,
+            "rfc" | "rfc2822" => Format::Rfc2822,
+            "relative" => Format::Relative,
+            "short" => Format::Short,
+            "raw" => Format::Raw,
+            "unix" => Format::Unix,
+            _ => return Err(Error::UnknownFormat(s.into())),
+        })
+    }
+}
+
+pub(crate) fn format(time: &Time, format: Format, now: SystemTime) -> String {
+    let local = time.time as i64 + time.offset as i64;
+    let days = local.div_euclid(SECONDS_PER_DAY);
+    let seconds_of_day = local.rem_euclid(SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+    let (hour, minute, second) = (seconds_of_day / 3600, seconds_of_day / 60 % 60, seconds_of_day % 60);
+    let weekday = WEEKDAYS[(days + 4).rem_euclid(7) as usize];
+    let month_name = MONTHS[month as usize - 1];
+    let sign = if time.offset < 0 { '-' } else { '+' };
+    let (offset_hours, offset_minutes) = (time.offset.abs() / 3600, time.offset.abs() / 60 % 60);
+
+    match format {
+        Format::Default => format!(
+            "{} {} {} {:02}:{:02}:{:02} {} {}{:02}{:02}",
+            weekday, month_name, day, hour, minute, second, year, sign, offset_hours, offset_minutes
+        ),
+        Format::Iso8601 => format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02} {}{:02}{:02}",
+            year, month, day, hour, minute, second, sign, offset_hours, offset_minutes
+        ),
+        Format::Iso8601Strict => format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}{}{:02}:{:02}",
+            year, month, day, hour, minute, second, sign, offset_hours, offset_minutes
+        ),
+        Format::Rfc2822 => format!(
+            "{}, {} {} {} {:02}:{:02}:{:02} {}{:02}{:02}",
+            weekday, day, month_name, year, hour, minute, second, sign, offset_hours, offset_minutes
+        ),
+        Format::Relative => relative(time.time as i64, seconds_since_epoch(now)),
+        Format::Short => format!("{:04}-{:02}-{:02}", year, month, day),
+        Format::Raw => format!("{} {}{:02}{:02}", time.time, sign, offset_hours, offset_minutes),
+        Format::Unix => time.time.to_string(),
+    }
+}
+
+/// Describe how long before `now` the time `then` was, with the rounding of git.
+fn relative(then: i64, now: i64) -> String {
+    fn plural(count: i64, unit: &str) -> String {
+        format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+    }
+    if now < then {
+        return "in the future".into();
+    }
+    let seconds = now - then;
+    if seconds < 90 {
+        return format!("{} ago", plural(seconds, "second"));
+    }
+    let minutes = (seconds + 30) / 60;
+    if minutes < 90 {
+        return format!("{} ago", plural(minutes, "minute"));
+    }
+    let hours = (minutes + 30) / 60;
+    if hours < 36 {
+        return format!("{} ago", plural(hours, "hour"));
+    }
+    let days = (hours + 12) / 24;
+    if days < 14 {
+        return format!("{} ago", plural(days, "day"));
+    }
+    if days < 70 {
+        return format!("{} ago", plural((days + 3) / 7, "week"));
+    }
+    if days < 365 {
+        return format!("{} ago", plural((days + 15) / 30, "month"));
+    }
+    if days < 1825 {
+        let total_months = (days * 12 * 2 + 365) / (365 * 2);
+        let (years, months) = (total_months / 12, total_months % 12);
+        return if months == 0 {
+            format!("{} ago", plural(years, "year"))
+        } else {
+            format!("{}, {} ago", plural(years, "year"), plural(months, "month"))
+        };
+    }
+    format!("{} ago", plural((days + 183) / 365, "year"))
+}
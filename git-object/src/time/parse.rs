@@ -0,0 +1,329 @@
+//! Parse the absolute and relative dates git accepts, like `--since` and `GIT_AUTHOR_DATE` do.
+use super::{civil_from_days, days_from_civil, seconds_since_epoch, SECONDS_PER_DAY};
+use crate::{Sign, Time};
+use quick_error::quick_error;
+use std::{convert::TryFrom, time::SystemTime};
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Unrecognized(input: String) {
+            display("The date '{}' could not be understood", input)
+        }
+        OutOfRange(input: String) {
+            display("The date '{}' is before 1970 or too far in the future", input)
+        }
+    }
+}
+
+const MONTHS: [&str; 12] = [
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+const WEEKDAYS: [&str; 7] = [
+    "sunday",
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+];
+const NUMBERS: [&str; 11] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+];
+
+/// Parse `input` as one of the dates git accepts, with relative dates being relative to `now`, in the local time zone
+/// whose offset from UTC is `offset` seconds.
+///
+/// Accepted are
+///
+/// * the raw format of objects like `1600345600 +0200`, and `@1600345600` with an optional time zone,
+/// * RFC 2822 dates like `Thu, 17 Sep 2020 14:26:40 +0200`, and the default format of `git log`,
+/// * ISO 8601 dates like `2020-09-17T14:26:40+02:00` or `2020-09-17 14:26:40 +0200` or `2020-09-17`,
+/// * relative dates like `now`, `yesterday`, `2 weeks ago`, `last month` or `1 year 3 days ago`.
+///
+/// Like `--since` in git, dates without time zone and relative dates are in the local time zone, and dates without time
+/// keep the local time of day of `now`.
+pub fn parse(input: &str, now: SystemTime, offset: i32) -> Result<Time, Error> {
+    let trimmed = input.trim();
+    let now = seconds_since_epoch(now);
+    if let Some(time) = raw(trimmed).or_else(|| relative(trimmed, now, offset)) {
+        return Ok(time);
+    }
+    let (seconds, offset) = absolute(trimmed, now, offset).ok_or_else(|| Error::Unrecognized(input.into()))?;
+    Ok(Time {
+        time: u32::try_from(seconds).map_err(|_| Error::OutOfRange(input.into()))?,
+        offset,
+        sign: if offset < 0 { Sign::Minus } else { Sign::Plus },
+    })
+}
+
+/// Parse `@<seconds> [<offset>]` or `<seconds> <offset>`.
+fn raw(input: &str) -> Option<Time> {
+    let (input, needs_offset) = match input.strip_prefix('@') {
+        Some(input) => (input, false),
+        None => (input, true),
+    };
+    let mut parts = input.split_ascii_whitespace();
+    let time = parts.next()?;
+    if !time.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let time = time.parse().ok()?;
+    let (offset, sign) = match parts.next() {
+        Some(zone) => {
+            let (offset, len) = offset_at(zone.as_bytes())?;
+            if len != zone.len() {
+                return None;
+            }
+            (offset, if zone.starts_with('-') { Sign::Minus } else { Sign::Plus })
+        }
+        None if needs_offset => return None,
+        None => (0, Sign::Plus),
+    };
+    parts.next().is_none().then_some(Time { time, offset, sign })
+}
+
+/// Parse relative dates made of `<count> <unit>` terms optionally followed by `ago`, with words separated by spaces or
+/// dots, into a time relative to the seconds since the unix epoch `now` in the time zone with `offset`.
+fn relative(input: &str, now: i64, offset: i32) -> Option<Time> {
+    let input = input.to_ascii_lowercase();
+    let words: Vec<_> = input
+        .split(|c: char| c.is_ascii_whitespace() || c == '.')
+        .filter(|word| !word.is_empty())
+        .collect();
+    let (mut seconds, mut months) = (0, 0);
+    match words.as_slice() {
+        ["now"] => {}
+        ["yesterday"] => seconds = SECONDS_PER_DAY,
+        [terms @ .., "ago"] | terms @ [_, _, ..] => {
+            if terms.is_empty() || terms.len() % 2 != 0 {
+                return None;
+            }
+            for term in terms.chunks(2) {
+                let count = match term[0] {
+                    "a" | "an" | "last" => 1,
+                    count => match NUMBERS.iter().position(|number| *number == count) {
+                        Some(count) => count as i64,
+                        None => count.parse::<u32>().ok()? as i64,
+                    },
+                };
+                let unit = term[1].strip_suffix('s').unwrap_or(term[1]);
+                match unit {
+                    "second" | "sec" => seconds += count,
+                    "minute" | "min" => seconds += count * 60,
+                    "hour" => seconds += count * 60 * 60,
+                    "day" => seconds += count * SECONDS_PER_DAY,
+                    "week" => seconds += count * 7 * SECONDS_PER_DAY,
+                    "month" => months += count,
+                    "year" => months += count * 12,
+                    _ => return None,
+                }
+            }
+        }
+        _ => return None,
+    }
+
+    let mut time = now - seconds;
+    if months != 0 {
+        // Go back in calendar months of the local time zone, where days beyond the end of a month continue into the
+        // next one.
+        let local = time + offset as i64;
+        let (year, month, day) = civil_from_days(local.div_euclid(SECONDS_PER_DAY));
+        time = days_from_civil(year, month - months, day) * SECONDS_PER_DAY + local.rem_euclid(SECONDS_PER_DAY)
+            - offset as i64;
+    }
+    Some(Time {
+        time: u32::try_from(time).ok()?,
+        offset,
+        sign: if offset < 0 { Sign::Minus } else { Sign::Plus },
+    })
+}
+
+/// The parts of an absolute date seen so far.
+#[derive(Default)]
+struct Date {
+    year: Option<i64>,
+    month: Option<i64>,
+    day: Option<i64>,
+    time: Option<(i64, i64, i64)>,
+    is_pm: Option<bool>,
+    offset: Option<i32>,
+    seconds: Option<i64>,
+}
+
+/// Parse dates made of month and weekday names, numbers for dates and times, and time zones in any order, like git
+/// does, into the seconds since the unix epoch and the time zone offset, which is `local_offset` unless it is given.
+/// Without a time, it is the time of day of the seconds since the unix epoch `now` in the local time zone.
+fn absolute(input: &str, now: i64, local_offset: i32) -> Option<(i64, i32)> {
+    let input = input.as_bytes();
+    let mut date = Date::default();
+    let mut pos = 0;
+    while pos < input.len() {
+        let rest = &input[pos..];
+        let c = rest[0];
+        pos += if c.is_ascii_whitespace() || c == b',' {
+            1
+        } else if c.is_ascii_alphabetic() {
+            let len = rest.iter().take_while(|b| b.is_ascii_alphabetic()).count();
+            word(&rest[..len].to_ascii_lowercase(), &mut date)?;
+            len
+        } else if (c == b'+' || c == b'-') && date.offset.is_none() {
+            let (offset, len) = offset_at(rest)?;
+            date.offset = Some(offset);
+            len
+        } else if c.is_ascii_digit() {
+            number(rest, &mut date)?
+        } else {
+            return None;
+        };
+    }
+
+    let offset = date.offset.unwrap_or(local_offset);
+    if let Some(seconds) = date.seconds {
+        return Some((seconds, offset));
+    }
+    let (year, month, day) = (date.year?, date.month?, date.day?);
+    let (mut hour, minute, second) = match date.time {
+        Some(time) => time,
+        None if date.is_pm.is_some() => return None,
+        None => {
+            let time_of_day = (now + local_offset as i64).rem_euclid(SECONDS_PER_DAY);
+            (time_of_day / 3600, time_of_day / 60 % 60, time_of_day % 60)
+        }
+    };
+    if let Some(is_pm) = date.is_pm {
+        if !(1..=12).contains(&hour) {
+            return None;
+        }
+        hour = hour % 12 + if is_pm { 12 } else { 0 };
+    }
+    let days_in_month = days_from_civil(year, month + 1, 1) - days_from_civil(year, month, 1);
+    if !(1..=12).contains(&month) || !(1..=days_in_month).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+    let seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY + hour * 3600 + minute * 60 + second;
+    Some((seconds - offset as i64, offset))
+}
+
+/// Handle the lower-case `word` of a date, which is a month or weekday name that may be abbreviated to three letters,
+/// a name of UTC, `am` or `pm`, or the `T` separating date and time.
+fn word(word: &[u8], date: &mut Date) -> Option<()> {
+    let is_name = |name: &str| word.len() >= 3 && name.as_bytes().starts_with(word);
+    if let Some(month) = MONTHS.iter().position(|name| is_name(name)) {
+        date.month.replace(month as i64 + 1).is_none().then_some(())
+    } else if WEEKDAYS.iter().any(|name| is_name(name)) {
+        Some(())
+    } else {
+        match word {
+            b"utc" | b"gmt" | b"z" => date.offset.replace(0).is_none().then_some(()),
+            b"am" | b"pm" => date.is_pm.replace(word == b"pm").is_none().then_some(()),
+            b"t" => Some(()),
+            _ => None,
+        }
+    }
+}
+
+/// Parse the time zone offset like `+0200`, `-07:30` or `+02` at the start of `input`, and return it in seconds along
+/// with the number of bytes it takes.
+fn offset_at(input: &[u8]) -> Option<(i32, usize)> {
+    let sign = match input.first()? {
+        b'+' => 1,
+        b'-' => -1,
+        _ => return None,
+    };
+    let digits = |input: &[u8]| input.iter().take_while(|b| b.is_ascii_digit()).count();
+    let first = digits(&input[1..]);
+    let (hours, minutes, len) = match first {
+        4 => (&input[1..3], &input[3..5], 5),
+        2 if input.get(3) == Some(&b':') && digits(&input[4..]) == 2 => (&input[1..3], &input[4..6], 6),
+        2 => (&input[1..3], &b"00"[..], 3),
+        _ => return None,
+    };
+    let (hours, minutes): (i32, i32) = (to_number(hours)? as i32, to_number(minutes)? as i32);
+    (hours < 24 && minutes < 60).then_some((sign * (hours * 3600 + minutes * 60), len))
+}
+
+/// Handle the number at the start of `input`, which may be the first part of a date like `2020-09-17`, `09/17/2020`
+/// or `17.09.2020`, of a time like `14:26:40` or an hour like `2pm`, a year, a day, or the seconds since the unix epoch,
+/// and return how many bytes it takes.
+fn number(input: &[u8], date: &mut Date) -> Option<usize> {
+    let digits = |input: &[u8]| input.iter().take_while(|b| b.is_ascii_digit()).count();
+    let len = digits(input);
+    let value = to_number(&input[..len])?;
+    let separator = input.get(len).copied();
+    let next_len = input.get(len + 1..).map_or(0, digits);
+
+    let suffix_len = input[len..].iter().take_while(|b| b.is_ascii_alphabetic()).count();
+    let suffix = input[len..len + suffix_len].to_ascii_lowercase();
+    if (suffix == b"am" || suffix == b"pm") && date.time.is_none() {
+        date.time = Some((value, 0, 0));
+        word(&suffix, date)?;
+        return Some(len + suffix_len);
+    }
+
+    if separator == Some(b':') && next_len == 2 && date.time.is_none() {
+        let minute = to_number(&input[len + 1..len + 3])?;
+        let mut end = len + 3;
+        let mut second = 0;
+        if input.get(end) == Some(&b':') && input.get(end + 1..).map_or(0, digits) == 2 {
+            second = to_number(&input[end + 1..end + 3])?;
+            end += 3;
+            if input.get(end) == Some(&b'.') {
+                end += 1 + input.get(end + 1..).map_or(0, digits);
+            }
+        }
+        date.time = Some((value, minute, second));
+        return Some(end);
+    }
+    if let Some(separator @ (b'-' | b'/' | b'.')) = separator {
+        if next_len > 0 && input.get(len + 1 + next_len) == Some(&separator) {
+            let second_start = len + 1;
+            let third_start = second_start + next_len + 1;
+            let third_len = input.get(third_start..).map_or(0, digits);
+            if third_len > 0 && date.year.is_none() {
+                let second = to_number(&input[second_start..second_start + next_len])?;
+                let third = to_number(&input[third_start..third_start + third_len])?;
+                let (year, month, day) = match (len, separator) {
+                    (4, _) => (value, second, third),
+                    (_, b'/') => (third, value, second),
+                    _ => (third, second, value),
+                };
+                date.year = Some(if year < 100 {
+                    year + if year < 70 { 2000 } else { 1900 }
+                } else {
+                    year
+                });
+                date.month.replace(month).is_none().then_some(())?;
+                date.day = Some(day);
+                return Some(third_start + third_len);
+            }
+        }
+    }
+
+    if len >= 9 && date.seconds.is_none() && date.year.is_none() {
+        date.seconds = Some(value);
+    } else if len == 4 && date.year.is_none() {
+        date.year = Some(value);
+    } else if (1..=31).contains(&value) && len <= 2 && date.day.is_none() {
+        date.day = Some(value);
+    } else {
+        return None;
+    }
+    Some(len)
+}
+
+fn to_number(digits: &[u8]) -> Option<i64> {
+    std::str::from_utf8(digits).ok()?.parse().ok()
+}
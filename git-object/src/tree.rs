@@ -0,0 +1,24 @@
+//! Properties of trees shared by their borrowed and owned representations.
+use bstr::{BString, ByteVec};
+use std::cmp::Ordering;
+
+/// Compare the names of two tree entries the way git sorts them in trees, which is as if the names of trees had a
+/// trailing slash. `lhs_is_tree` and `rhs_is_tree` are true if the respective entry is a tree.
+pub fn compare_names(lhs: &[u8], lhs_is_tree: bool, rhs: &[u8], rhs_is_tree: bool) -> Ordering {
+    sort_key(lhs, lhs_is_tree).cmp(sort_key(rhs, rhs_is_tree))
+}
+
+fn sort_key(name: &[u8], is_tree: bool) -> impl Iterator<Item = u8> + '_ {
+    name.iter().copied().chain(if is_tree { Some(b'/') } else { None })
+}
+
+/// Append `name` to `path` as its last component, separated by a slash unless `path` is empty, and return the previous
+/// length of `path` to allow truncating it again.
+pub fn push_path_component(path: &mut BString, name: &[u8]) -> usize {
+    let previous_len = path.len();
+    if previous_len != 0 {
+        path.push_byte(b'/');
+    }
+    path.push_str(name);
+    previous_len
+}
@@ -316,3 +316,110 @@ iyBBl69jASy41Ug/BlFJbw4+ItkShpXwkJKuBBV/JExChmvbxYWaS7QnyYC9UO0=
         Ok(())
     }
 }
+
+mod iter {
+    use crate::{borrowed::fixture_bytes, borrowed::signature, hex_to_id};
+    use git_object::{
+        borrowed::{commit::Token, Commit, CommitIter},
+        bstr::ByteSlice,
+    };
+
+    #[test]
+    fn yields_all_tokens_of_merge_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = fixture_bytes("commit", "merge.txt");
+        assert_eq!(
+            CommitIter::from_bytes(&fixture).collect::<Result<Vec<_>, _>>()?,
+            vec![
+                Token::Tree {
+                    id: hex_to_id("0cf16ce8e229b59a761198975f0c0263229faf82")
+                },
+                Token::Parent {
+                    id: hex_to_id("6a6054db4ce3c1e4e6a37f8c4d7acb63a4d6ad71")
+                },
+                Token::Parent {
+                    id: hex_to_id("c91d592913d47ac4e4a76daf16fd649b276e211e")
+                },
+                Token::Author {
+                    signature: signature(1592454703)
+                },
+                Token::Committer {
+                    signature: signature(1592454738)
+                },
+                Token::Encoding(b"ISO-8859-1".as_bstr()),
+                Token::Message(b"Merge branch 'branch'".as_bstr()),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tokens_match_fully_parsed_commits() -> Result<(), Box<dyn std::error::Error>> {
+        for name in &[
+            "merge.txt",
+            "mergetag.txt",
+            "signed-singleline.txt",
+            "signed-whitespace.txt",
+            "signed-with-encoding.txt",
+            "signed.txt",
+            "two-multiline-headers.txt",
+            "unsigned.txt",
+            "whitespace.txt",
+            "with-encoding.txt",
+        ] {
+            let fixture = fixture_bytes("commit", name);
+            let commit = Commit::from_bytes(&fixture)?;
+            let tokens = CommitIter::from_bytes(&fixture).collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(tokens[0].id(), Some(commit.tree()), "{}", name);
+            assert_eq!(
+                tokens.iter().filter(|t| matches!(t, Token::Parent { .. })).count(),
+                commit.parents.len(),
+                "{}",
+                name
+            );
+            assert_eq!(
+                tokens
+                    .iter()
+                    .filter_map(|t| match t {
+                        Token::ExtraHeader(header) => Some(header.clone()),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>(),
+                commit.extra_headers,
+                "{}",
+                name
+            );
+            assert_eq!(tokens.last(), Some(&Token::Message(commit.message)), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn tree_and_parent_ids_without_parsing_signatures() -> Result<(), Box<dyn std::error::Error>> {
+        let mut fixture = fixture_bytes("commit", "merge.txt");
+        let author_pos = fixture.find(b"author").expect("author header");
+        fixture[author_pos] = b'!';
+
+        let mut iter = CommitIter::from_bytes(&fixture);
+        assert_eq!(iter.tree_id()?, hex_to_id("0cf16ce8e229b59a761198975f0c0263229faf82"));
+        assert_eq!(
+            iter.parent_ids().collect::<Result<Vec<_>, _>>()?,
+            vec![
+                hex_to_id("6a6054db4ce3c1e4e6a37f8c4d7acb63a4d6ad71"),
+                hex_to_id("c91d592913d47ac4e4a76daf16fd649b276e211e")
+            ]
+        );
+        assert_eq!(
+            CommitIter::from_bytes(&fixture).parent_ids().count(),
+            2,
+            "the tree is skipped if it wasn't consumed yet"
+        );
+
+        let mut iter = CommitIter::from_bytes(&fixture).skip(3);
+        assert!(
+            iter.next().expect("an error").is_err(),
+            "the invalid author is reported"
+        );
+        assert!(iter.next().is_none(), "the iterator is fused after an error");
+        Ok(())
+    }
+}
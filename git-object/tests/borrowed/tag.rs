@@ -110,6 +110,62 @@ KLMHist5yj0sw1E4hDTyQa0=
     }
 }
 
+mod iter {
+    use crate::{
+        borrowed::{fixture_bytes, tag::tag_fixture},
+        hex_to_id,
+    };
+    use git_object::borrowed::{tag::Token, Tag, TagIter};
+
+    #[test]
+    fn yields_all_tokens() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = fixture_bytes("tag", "signed.txt");
+        let tag = tag_fixture(9000);
+        assert_eq!(
+            TagIter::from_bytes(&fixture).collect::<Result<Vec<_>, _>>()?,
+            vec![
+                Token::Target { id: tag.target() },
+                Token::TargetKind(tag.target_kind),
+                Token::Name(tag.name),
+                Token::Tagger(tag.signature),
+                Token::Body {
+                    message: tag.message,
+                    pgp_signature: tag.pgp_signature
+                }
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn tokens_match_fully_parsed_tags() -> Result<(), Box<dyn std::error::Error>> {
+        for name in &[
+            "empty.txt",
+            "no-tagger.txt",
+            "signed.txt",
+            "whitespace.txt",
+            "with-newlines.txt",
+        ] {
+            let fixture = fixture_bytes("tag", name);
+            let tag = Tag::from_bytes(&fixture)?;
+            let tokens = TagIter::from_bytes(&fixture).collect::<Result<Vec<_>, _>>()?;
+            assert_eq!(tokens.len(), 5, "{}", name);
+            assert_eq!(tokens[3], Token::Tagger(tag.signature), "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn target_id() -> Result<(), Box<dyn std::error::Error>> {
+        let fixture = fixture_bytes("tag", "signed.txt");
+        assert_eq!(
+            TagIter::from_bytes(&fixture).target_id()?,
+            hex_to_id("ffa700b4aca13b80cb6b98a078e7c96804f8e0ec")
+        );
+        Ok(())
+    }
+}
+
 fn tag_fixture(offset: i32) -> Tag<'static> {
     Tag {
         target: b"ffa700b4aca13b80cb6b98a078e7c96804f8e0ec".as_bstr(),
@@ -75,3 +75,42 @@ mod from_bytes {
         Ok(())
     }
 }
+
+mod iter {
+    use crate::borrowed::fixture_bytes;
+    use git_object::{
+        borrowed::{Tree, TreeIter},
+        bstr::ByteSlice,
+        TreeMode,
+    };
+
+    #[test]
+    fn entries_match_fully_parsed_trees() -> Result<(), Box<dyn std::error::Error>> {
+        for name in &["everything.tree", "maybe-special.tree", "definitely-special.tree"] {
+            let fixture = fixture_bytes("tree", name);
+            assert_eq!(
+                TreeIter::from_bytes(&fixture).collect::<Result<Vec<_>, _>>()?,
+                Tree::from_bytes(&fixture)?.entries,
+                "{}",
+                name
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn stops_at_first_match_and_reports_errors_once() -> Result<(), Box<dyn std::error::Error>> {
+        let mut fixture = fixture_bytes("tree", "everything.tree");
+        let mut iter = TreeIter::from_bytes(&fixture);
+        let entry = iter.find(|e| e.as_ref().is_ok_and(|e| e.filename == "subdir"));
+        assert_eq!(entry.expect("found")?.mode, TreeMode::Tree);
+        assert_eq!(iter.count(), 1, "only the last entry remains");
+
+        let last_entry_pos = fixture.find(b"120000").expect("symlink entry");
+        fixture[last_entry_pos] = b'x';
+        let mut iter = TreeIter::from_bytes(&fixture).skip(4);
+        assert!(iter.next().expect("an error").is_err());
+        assert!(iter.next().is_none(), "the iterator is fused after an error");
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+mod message {
+    use git_object::{
+        bstr::{BString, ByteSlice},
+        commit::{message::trailer, Message},
+    };
+    use pretty_assertions::assert_eq;
+
+    fn trailers(message: &str) -> Vec<(String, String)> {
+        Message::from_bytes(message.as_bytes())
+            .trailers()
+            .map(|trailer| (trailer.token.to_string(), trailer.value.to_string()))
+            .collect()
+    }
+
+    fn add(message: &str, trailer: (&str, &str), options: trailer::Options) -> Option<String> {
+        let mut message = BString::from(message);
+        trailer::add(&mut message, trailer.0.into(), trailer.1.into(), options).then(|| message.to_string())
+    }
+
+    #[test]
+    fn title_and_body_are_separated_by_blank_lines() {
+        let message = Message::from_bytes(b"\n\ntitle\n\n\nbody\n\nmore body\n");
+        assert_eq!(message.title, "title");
+        assert_eq!(message.body, Some(b"body\n\nmore body\n".as_bstr()));
+        assert_eq!(message.summary().as_ref(), "title");
+
+        let message = Message::from_bytes(b"title without newline");
+        assert_eq!(message.title, "title without newline");
+        assert_eq!(message.body, None);
+
+        let message = Message::from_bytes(b"title\n\n \n");
+        assert_eq!(message.title, "title");
+        assert_eq!(message.body, None, "blank lines are no body");
+
+        assert_eq!(Message::from_bytes(b"").title, "");
+    }
+
+    #[test]
+    fn the_summary_joins_multi_line_titles() {
+        let message = Message::from_bytes(b"a title \nspanning lines\t\n\nbody");
+        assert_eq!(message.title, "a title \nspanning lines\t");
+        assert_eq!(message.summary().as_ref(), "a title spanning lines");
+    }
+
+    #[test]
+    fn trailers_are_read_from_the_last_paragraph_with_their_continuation_lines() {
+        assert_eq!(
+            trailers(
+                "title\n\nbody: not a trailer\n\nSigned-off-by: A <a@example.com>\nCo-authored-by : B\n  <b@example.com>\nKey:\n"
+            ),
+            vec![
+                ("Signed-off-by".into(), "A <a@example.com>".into()),
+                ("Co-authored-by".into(), "B <b@example.com>".into()),
+                ("Key".into(), "".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn paragraphs_with_other_lines_need_a_trailer_generated_by_git() {
+        assert_eq!(
+            trailers("title\n\nprose\nmore prose\nSigned-off-by: A\n\n"),
+            vec![("Signed-off-by".into(), "A".into())]
+        );
+        assert_eq!(trailers("title\n\nprose\nKey: value\n"), vec![]);
+        assert_eq!(
+            trailers("title\n\nprose\nmore prose\nprose again\nand again\nSigned-off-by: A\n"),
+            vec![],
+            "less than a quarter of the lines are trailers"
+        );
+    }
+
+    #[test]
+    fn titles_have_no_trailers() {
+        assert_eq!(trailers("Key: value\n"), vec![]);
+        assert_eq!(trailers("title\nSigned-off-by: A\n"), vec![]);
+    }
+
+    #[test]
+    fn trailers_start_a_new_block_if_there_is_none() {
+        let options = trailer::Options::default();
+        assert_eq!(
+            add("title\n\n\n", ("Key", "v"), options).as_deref(),
+            Some("title\n\nKey: v\n\n\n")
+        );
+        assert_eq!(
+            add("multi\nline title\nSigned-off-by: A\n", ("Key", "v"), options).as_deref(),
+            Some("multi\nline title\nSigned-off-by: A\n\nKey: v\n")
+        );
+        assert_eq!(
+            add("title\n\nprose\nKey: a\n", ("Key", "v"), options).as_deref(),
+            Some("title\n\nprose\nKey: a\n\nKey: v\n")
+        );
+    }
+
+    #[test]
+    fn trailers_are_added_where_configured_unless_they_exist() {
+        let message = "title\n\nbody\n\nSigned-off-by: A\nAcked-by: C\n  continued\nSigned-off-by: B\n";
+        let options = trailer::Options::default();
+        assert_eq!(
+            add(message, ("Acked-by", "D"), options).as_deref(),
+            Some("title\n\nbody\n\nSigned-off-by: A\nAcked-by: C\n  continued\nSigned-off-by: B\nAcked-by: D\n")
+        );
+        assert_eq!(
+            add(message, ("signed-off-by", "b"), options),
+            None,
+            "the neighbor is the same"
+        );
+        assert_eq!(
+            add(
+                message,
+                ("Signed-off-by", "A"),
+                trailer::Options {
+                    if_exists: trailer::IfExists::AddIfDifferent,
+                    ..options
+                }
+            ),
+            None
+        );
+        assert_eq!(
+            add(
+                message,
+                ("Acked-by", "D"),
+                trailer::Options {
+                    position: trailer::Where::After,
+                    ..options
+                }
+            )
+            .as_deref(),
+            Some("title\n\nbody\n\nSigned-off-by: A\nAcked-by: C\n  continued\nAcked-by: D\nSigned-off-by: B\n")
+        );
+        assert_eq!(
+            add(
+                message,
+                ("Reviewed-by", "E"),
+                trailer::Options {
+                    position: trailer::Where::Before,
+                    ..options
+                }
+            )
+            .as_deref(),
+            Some("title\n\nbody\n\nReviewed-by: E\nSigned-off-by: A\nAcked-by: C\n  continued\nSigned-off-by: B\n")
+        );
+        assert_eq!(
+            add(
+                message,
+                ("Reviewed-by", "E"),
+                trailer::Options {
+                    if_missing: trailer::IfMissing::DoNothing,
+                    ..options
+                }
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn trailers_can_replace_existing_ones() {
+        let message = "title\n\nbody\n\nSigned-off-by: A\nAcked-by: C\nSigned-off-by: B\n";
+        let replace = trailer::Options {
+            if_exists: trailer::IfExists::Replace,
+            ..Default::default()
+        };
+        assert_eq!(
+            add(message, ("signed-off-by", "N"), replace).as_deref(),
+            Some("title\n\nbody\n\nSigned-off-by: A\nAcked-by: C\nsigned-off-by: N\n")
+        );
+        assert_eq!(
+            add(
+                message,
+                ("Signed-off-by", "N"),
+                trailer::Options {
+                    position: trailer::Where::Start,
+                    ..replace
+                }
+            )
+            .as_deref(),
+            Some("title\n\nbody\n\nSigned-off-by: N\nAcked-by: C\nSigned-off-by: B\n")
+        );
+    }
+}
+
+mod encoding {
+    use crate::fixture_bytes;
+    use git_object::{borrowed, bstr::ByteSlice, commit::encoding, owned};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn messages_and_signatures_are_decoded_to_utf8() -> Result<(), Box<dyn std::error::Error>> {
+        let data = fixture_bytes("commit/latin1.txt");
+        let commit = borrowed::Commit::from_bytes(&data)?;
+        assert_eq!(commit.message_encoding()?.name(), "windows-1252");
+
+        let decoded = commit.to_utf8()?;
+        assert_eq!(decoded.encoding, None);
+        assert_eq!(decoded.message, "Größe ändern\n\nJürgen´s Sätze\n");
+        assert_eq!(decoded.author.name, "Jürgen Müller");
+        assert_eq!(decoded.committer.name, "Jürgen Müller");
+        assert_eq!(decoded.message_encoding()?, encoding::UTF_8);
+        Ok(())
+    }
+
+    #[test]
+    fn commits_can_be_written_with_another_encoding() -> Result<(), Box<dyn std::error::Error>> {
+        let data = fixture_bytes("commit/latin1.txt");
+        let mut commit = borrowed::Commit::from_bytes(&data)?.to_utf8()?;
+        commit.reencode(Some(b"ISO-8859-1".as_bstr()))?;
+
+        let mut buf = Vec::new();
+        commit.write_to(&mut buf)?;
+        assert_eq!(buf.as_bstr(), data.as_bstr(), "it is written like git writes it");
+        Ok(())
+    }
+
+    #[test]
+    fn signatures_are_removed_once_the_commit_changes() -> Result<(), Box<dyn std::error::Error>> {
+        let data = fixture_bytes("commit/signed-with-encoding.txt");
+        let has_signature = |commit: &owned::Commit| commit.extra_headers.iter().any(|(name, _)| name == "gpgsig");
+
+        let mut commit: owned::Commit = borrowed::Commit::from_bytes(&data)?.into();
+        commit.reencode(Some(b"ISO-8859-1".as_bstr()))?;
+        assert!(
+            has_signature(&commit),
+            "the commit is unchanged and its signature still matches"
+        );
+
+        let decoded = borrowed::Commit::from_bytes(&data)?.to_utf8()?;
+        assert!(
+            !has_signature(&decoded),
+            "without the encoding header the signature doesn't match"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_encodings_and_unmappable_characters_are_errors() -> Result<(), Box<dyn std::error::Error>> {
+        let data = fixture_bytes("commit/latin1.txt");
+        let mut commit: owned::Commit = borrowed::Commit::from_bytes(&data)?.into();
+        commit.encoding = Some("klingon".into());
+        assert!(matches!(commit.reencode(None), Err(encoding::Error::Unknown(name)) if name == "klingon"));
+
+        let mut commit = borrowed::Commit::from_bytes(&data)?.to_utf8()?;
+        commit.message = "日本語\n".into();
+        let before = commit.clone();
+        assert!(matches!(
+            commit.reencode(Some(b"latin1".as_bstr())),
+            Err(encoding::Error::Unmappable(_))
+        ));
+        assert_eq!(commit, before, "nothing changes on error");
+        Ok(())
+    }
+}
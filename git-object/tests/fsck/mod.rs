@@ -0,0 +1,400 @@
+use crate::fixture_bytes;
+use git_object::{
+    fsck::{self, Check, Options, Severity},
+    Kind,
+};
+
+fn checks(findings: Vec<fsck::Finding>) -> Vec<(Check, Severity)> {
+    findings.into_iter().map(|f| (f.check, f.severity)).collect()
+}
+
+fn tree(entries: &[(&str, &str, u8)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (mode, name, id_byte) in entries {
+        out.extend_from_slice(mode.as_bytes());
+        out.push(b' ');
+        out.extend_from_slice(name.as_bytes());
+        out.push(0);
+        out.extend_from_slice(&[*id_byte; 20]);
+    }
+    out
+}
+
+const SIGNATURE: &str = "Sebastian Thiel <byronimo@gmail.com> 1528473343 +0230";
+
+fn commit_with_author(author: &str) -> Vec<u8> {
+    format!(
+        "tree 0cf16ce8e229b59a761198975f0c0263229faf82\nauthor {}\ncommitter {}\n\nmessage\n",
+        author, SIGNATURE
+    )
+    .into_bytes()
+}
+
+mod fixtures {
+    use super::*;
+
+    #[test]
+    fn have_no_findings_with_default_options() {
+        for (kind, name) in &[
+            (Kind::Commit, "commit/merge.txt"),
+            (Kind::Commit, "commit/mergetag.txt"),
+            (Kind::Commit, "commit/signed.txt"),
+            (Kind::Commit, "commit/unsigned.txt"),
+            (Kind::Commit, "commit/with-encoding.txt"),
+            (Kind::Tag, "tag/signed.txt"),
+            (Kind::Tag, "tag/empty.txt"),
+            (Kind::Tag, "tag/whitespace.txt"),
+            (Kind::Tree, "tree/everything.tree"),
+        ] {
+            assert_eq!(
+                fsck::object(*kind, &fixture_bytes(name), &Options::default()),
+                vec![],
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn tag_without_tagger_is_reported_as_info() {
+        assert_eq!(
+            checks(fsck::tag(&fixture_bytes("tag/no-tagger.txt"), &Options::strict())),
+            vec![(Check::MissingTaggerEntry, Severity::Info)]
+        );
+    }
+}
+
+mod tree {
+    use super::*;
+
+    #[test]
+    fn unsorted_and_duplicate_entries() {
+        assert_eq!(
+            checks(fsck::tree(
+                &tree(&[("100644", "b", 1), ("100644", "a", 1)]),
+                &Options::default()
+            )),
+            vec![(Check::TreeNotSorted, Severity::Error)]
+        );
+        assert_eq!(
+            checks(fsck::tree(
+                &tree(&[("100644", "a", 1), ("100644", "a.b", 1), ("40000", "a", 1)]),
+                &Options::default()
+            )),
+            vec![(Check::DuplicateEntries, Severity::Error)],
+            "duplicates are found even if they are not adjacent"
+        );
+        assert_eq!(
+            checks(fsck::tree(
+                &tree(&[("100644", "a.b", 1), ("40000", "a", 1), ("100644", "a0", 1)]),
+                &Options::default()
+            )),
+            vec![],
+            "trees sort as if their name had a trailing slash"
+        );
+    }
+
+    #[test]
+    fn suspicious_modes() {
+        assert_eq!(
+            checks(fsck::tree(
+                &tree(&[("040000", "a", 1), ("100664", "b", 1), ("100777", "c", 1)]),
+                &Options::default()
+            )),
+            vec![
+                (Check::ZeroPaddedFilemode, Severity::Warning),
+                (Check::BadFilemode, Severity::Info)
+            ]
+        );
+        assert_eq!(
+            checks(fsck::tree(&tree(&[("100664", "b", 1)]), &Options::strict())),
+            vec![(Check::BadFilemode, Severity::Info)],
+            "strict mode doesn't accept group writable files"
+        );
+    }
+
+    #[test]
+    fn dangerous_names() {
+        assert_eq!(
+            checks(fsck::tree(
+                &tree(&[
+                    ("40000", "..", 1),
+                    ("40000", ".", 1),
+                    ("40000", ".GIT", 1),
+                    ("40000", ".git. ", 1),
+                    ("40000", "GIT~1", 1),
+                    ("100644", "a/b", 1),
+                ]),
+                &Options::default()
+            )),
+            vec![
+                (Check::HasDotdot, Severity::Warning),
+                (Check::HasDot, Severity::Warning),
+                (Check::HasDotgit, Severity::Warning),
+                (Check::HasDotgit, Severity::Warning),
+                (Check::HasDotgit, Severity::Warning),
+                (Check::FullPathname, Severity::Warning),
+            ]
+        );
+        assert_eq!(
+            checks(fsck::tree(&tree(&[("40000", ".git", 1)]), &Options::strict())),
+            vec![(Check::HasDotgit, Severity::Error)],
+            "strict mode rejects what would otherwise be warnings"
+        );
+    }
+
+    #[test]
+    fn names_like_dotgit_on_hfs_and_ntfs() {
+        for name in &[
+            ".g\u{200c}it",
+            "\u{feff}.GIT",
+            ".git\u{200d}",
+            ".\u{202a}g\u{206f}i\u{200e}t",
+            ".git::$INDEX_ALLOCATION",
+            ".GIT. :stream",
+            ".git\\",
+            "git~1",
+            "Git~1. ",
+            "GIT~1::$INDEX_ALLOCATION",
+        ] {
+            assert_eq!(
+                checks(fsck::tree(&tree(&[("40000", name, 1)]), &Options::default())),
+                vec![(Check::HasDotgit, Severity::Warning)],
+                "{:?}",
+                name
+            );
+        }
+        for name in &[
+            ".gitignore",
+            ".g\u{200c}itx",
+            ".git\u{2000}",
+            "\u{200c}git",
+            ".gi\u{0131}",
+            ".git~1",
+            "git~2",
+            "git~1x",
+            "git~",
+        ] {
+            assert_eq!(
+                checks(fsck::tree(&tree(&[("40000", name, 1)]), &Options::default())),
+                vec![],
+                "{:?}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn null_ids_and_truncation() {
+        assert_eq!(
+            checks(fsck::tree(&tree(&[("100644", "a", 0)]), &Options::default())),
+            vec![(Check::NullSha1, Severity::Warning)]
+        );
+        let mut data = tree(&[("100644", "a", 1), ("100644", "b", 1)]);
+        data.pop();
+        assert_eq!(
+            checks(fsck::tree(&data, &Options::default())),
+            vec![(Check::BadTree, Severity::Error)]
+        );
+        assert_eq!(
+            checks(fsck::tree(b"10x644 a\0", &Options::default())),
+            vec![(Check::BadTree, Severity::Error)]
+        );
+    }
+}
+
+mod commit {
+    use super::*;
+
+    #[test]
+    fn signatures() {
+        for (author, expected) in &[
+            ("<byronimo@gmail.com> 1528473343 +0230", Check::MissingNameBeforeEmail),
+            ("Sebastian > <byronimo@gmail.com> 1528473343 +0230", Check::BadName),
+            ("Sebastian Thiel 1528473343 +0230", Check::MissingEmail),
+            (
+                "Sebastian Thiel<byronimo@gmail.com> 1528473343 +0230",
+                Check::MissingSpaceBeforeEmail,
+            ),
+            ("Sebastian Thiel <byronimo@gmail.com 1528473343 +0230", Check::BadEmail),
+            (
+                "Sebastian Thiel <byr<onimo@gmail.com> 1528473343 +0230",
+                Check::BadEmail,
+            ),
+            (
+                "Sebastian Thiel <byronimo@gmail.com>1528473343 +0230",
+                Check::MissingSpaceBeforeDate,
+            ),
+            (
+                "Sebastian Thiel <byronimo@gmail.com> 01528473343 +0230",
+                Check::ZeroPaddedDate,
+            ),
+            (
+                "Sebastian Thiel <byronimo@gmail.com> 99999999999 +0230",
+                Check::BadDateOverflow,
+            ),
+            ("Sebastian Thiel <byronimo@gmail.com> +0230", Check::BadDate),
+            ("Sebastian Thiel <byronimo@gmail.com> 1528473343+0230", Check::BadDate),
+            (
+                "Sebastian Thiel <byronimo@gmail.com> 1528473343 0230",
+                Check::BadTimezone,
+            ),
+            (
+                "Sebastian Thiel <byronimo@gmail.com> 1528473343 +023",
+                Check::BadTimezone,
+            ),
+            (
+                "Sebastian Thiel <byronimo@gmail.com> 1528473343 +02300",
+                Check::BadTimezone,
+            ),
+        ] {
+            assert_eq!(
+                checks(fsck::commit(&commit_with_author(author), &Options::default())),
+                vec![(*expected, Severity::Error)],
+                "{}",
+                author
+            );
+        }
+        assert_eq!(
+            checks(fsck::commit(
+                &commit_with_author("Sebastian Thiel <byronimo@gmail.com> 0 +0230"),
+                &Options::default()
+            )),
+            vec![],
+            "a time of zero is fine"
+        );
+    }
+
+    #[test]
+    fn headers() {
+        let cases: &[(&[u8], Check)] = &[
+            (
+                b"parent 0cf16ce8e229b59a761198975f0c0263229faf82\n\n",
+                Check::MissingTree,
+            ),
+            (b"tree 0CF16CE8E229B59A761198975F0C0263229FAF82\n\n", Check::BadTreeSha1),
+            (
+                b"tree 0cf16ce8e229b59a761198975f0c0263229faf82\nparent abc\n\n",
+                Check::BadParentSha1,
+            ),
+            (
+                b"tree 0cf16ce8e229b59a761198975f0c0263229faf82\n\n",
+                Check::MissingAuthor,
+            ),
+            (
+                b"tree 0cf16ce8e229b59a761198975f0c0263229faf82\nauthor a\0 <b> 1 +0000\n\n",
+                Check::NulInHeader,
+            ),
+            (
+                b"tree 0cf16ce8e229b59a761198975f0c0263229faf82",
+                Check::UnterminatedHeader,
+            ),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(
+                checks(fsck::commit(data, &Options::default())),
+                vec![(*expected, Severity::Error)],
+                "{:?}",
+                expected
+            );
+        }
+        assert_eq!(
+            checks(fsck::commit(
+                format!(
+                    "tree 0cf16ce8e229b59a761198975f0c0263229faf82\nauthor {0}\nauthor {0}\n\n",
+                    SIGNATURE
+                )
+                .as_bytes(),
+                &Options::default().with_severity(Check::MultipleAuthors, Severity::Warning)
+            )),
+            vec![
+                (Check::MultipleAuthors, Severity::Warning),
+                (Check::MissingCommitter, Severity::Error)
+            ],
+            "checking continues after findings that aren't errors"
+        );
+        assert_eq!(
+            checks(fsck::commit(
+                b"tree 0cf16ce8e229b59a761198975f0c0263229faf82\n\n",
+                &Options::default()
+                    .with_severity(Check::MissingAuthor, Severity::Ignore)
+                    .with_severity(Check::MissingCommitter, Severity::Info)
+            )),
+            vec![(Check::MissingCommitter, Severity::Info)]
+        );
+        assert_eq!(
+            checks(fsck::commit(
+                format!(
+                    "{}\0binary message",
+                    String::from_utf8(commit_with_author(SIGNATURE)).unwrap()
+                )
+                .as_bytes(),
+                &Options::default()
+            )),
+            vec![],
+            "NUL bytes are fine in the message"
+        );
+    }
+}
+
+mod tag {
+    use super::*;
+
+    fn tag(kind: &str, name: &str) -> Vec<u8> {
+        format!(
+            "object 0cf16ce8e229b59a761198975f0c0263229faf82\ntype {}\ntag {}\ntagger {}\n\nmessage\n",
+            kind, name, SIGNATURE
+        )
+        .into_bytes()
+    }
+
+    #[test]
+    fn headers() {
+        assert_eq!(checks(fsck::tag(&tag("commit", "v1.0"), &Options::default())), vec![]);
+        assert_eq!(
+            checks(fsck::tag(&tag("cmmit", "v1.0"), &Options::default())),
+            vec![(Check::BadType, Severity::Error)]
+        );
+        for name in &["", "a..b", "a b", "a/", ".a", "a.lock", "a@{1}", "a:b", "a\\b", "a//b"] {
+            assert_eq!(
+                checks(fsck::tag(&tag("commit", name), &Options::default())),
+                vec![(Check::BadTagName, Severity::Info)],
+                "{:?}",
+                name
+            );
+        }
+        let cases: &[(&[u8], Check)] = &[
+            (b"type commit\n\n", Check::MissingObject),
+            (
+                b"object 0cf16ce8e229b59a761198975f0c0263229faf8\n\n",
+                Check::BadObjectSha1,
+            ),
+            (
+                b"object 0cf16ce8e229b59a761198975f0c0263229faf82\ntag v1\n\n",
+                Check::MissingTypeEntry,
+            ),
+            (
+                b"object 0cf16ce8e229b59a761198975f0c0263229faf82\ntype commit\n\n",
+                Check::MissingTagEntry,
+            ),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(
+                checks(fsck::tag(data, &Options::default())),
+                vec![(*expected, Severity::Error)],
+                "{:?}",
+                expected
+            );
+        }
+    }
+}
+
+#[test]
+fn checks_and_severities_parse_from_git_configuration_values() {
+    for check in Check::all() {
+        assert_eq!(check.id().to_ascii_lowercase().parse::<Check>().unwrap(), *check);
+    }
+    assert_eq!("WARN".parse::<Severity>().unwrap(), Severity::Warning);
+    assert!("fatal".parse::<Severity>().is_err());
+    assert!("unknown".parse::<Check>().is_err());
+}
@@ -1,7 +1,12 @@
 use std::path::PathBuf;
 
 mod borrowed;
+mod commit;
+mod fsck;
 mod owned;
+mod signed;
+mod time;
+mod tree;
 
 #[cfg(not(windows))]
 fn fixup(v: Vec<u8>) -> Vec<u8> {
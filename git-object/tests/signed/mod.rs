@@ -0,0 +1,86 @@
+use crate::fixture_bytes;
+use git_object::{bstr::ByteSlice, signed};
+
+const COMMIT_PAYLOAD: &str = "tree 00fc39317701176e326974ce44f5bd545a32ec0b
+parent 09d8d3a12e161a7f6afb522dbe8900a9c09bce06
+author Sebastian Thiel <sebastian.thiel@icloud.com> 1592391367 +0800
+committer Sebastian Thiel <sebastian.thiel@icloud.com> 1592391367 +0800
+
+update tasks
+";
+
+#[test]
+fn commit_signatures_are_taken_from_the_gpgsig_header_which_is_removed_from_the_payload() {
+    let data = fixture_bytes("commit/signed.txt");
+    let signed = signed::commit(&data).expect("signed commit");
+    assert_eq!(signed.payload.as_ref(), COMMIT_PAYLOAD.as_bytes().as_bstr());
+    assert!(signed
+        .signature
+        .starts_with(b"-----BEGIN PGP SIGNATURE-----\n\niQEzBAABCAAdFiEE"));
+    assert!(signed.signature.ends_with(b"\n=5ITV\n-----END PGP SIGNATURE-----\n"));
+
+    let data = fixture_bytes("commit/signed-singleline.txt");
+    let signed = signed::commit(&data).expect("signed commit");
+    assert_eq!(signed.payload.as_ref(), COMMIT_PAYLOAD.as_bytes().as_bstr());
+    assert_eq!(signed.signature.as_ref(), b"magic:signature\n".as_bstr());
+}
+
+#[test]
+fn commit_signatures_for_other_hashes_are_removed_from_the_payload_too() {
+    let data = b"tree 00fc39317701176e326974ce44f5bd545a32ec0b
+gpgsig-sha256 sha256
+ signature
+gpgsig sha1
+ signature
+author a <a@example.com> 1 +0000
+
+gpgsig in the message
+";
+    let signed = signed::commit(data).expect("signed commit");
+    assert_eq!(
+        signed.payload.as_ref(),
+        b"tree 00fc39317701176e326974ce44f5bd545a32ec0b
+author a <a@example.com> 1 +0000
+
+gpgsig in the message
+"
+        .as_bstr()
+    );
+    assert_eq!(signed.signature.as_ref(), b"sha1\nsignature\n".as_bstr());
+}
+
+#[test]
+fn commits_without_gpgsig_header_are_unsigned_even_if_they_merge_signed_tags() {
+    assert_eq!(signed::commit(&fixture_bytes("commit/unsigned.txt")), None);
+    assert_eq!(signed::commit(&fixture_bytes("commit/mergetag.txt")), None);
+}
+
+#[test]
+fn tag_signatures_start_at_the_last_signature_line() {
+    let data = fixture_bytes("tag/signed.txt");
+    let signed = signed::tag(&data).expect("signed tag");
+    assert!(signed.payload.ends_with(b"\n\nfor the signature\n"));
+    assert!(signed
+        .signature
+        .starts_with(b"-----BEGIN PGP SIGNATURE-----\nComment: GPGTools"));
+    assert_eq!(signed.payload.len() + signed.signature.len(), data.len());
+
+    let data = b"object ffa700b4aca13b80cb6b98a078e7c96804f8e0ec
+type commit
+tag 1.0.0
+
+-----BEGIN PGP SIGNATURE-----
+quoted
+-----BEGIN SSH SIGNATURE-----
+ssh
+-----END SSH SIGNATURE-----
+";
+    let signed = signed::tag(data).expect("signed tag");
+    assert!(signed.payload.ends_with(b"\n-----BEGIN PGP SIGNATURE-----\nquoted\n"));
+    assert_eq!(
+        signed.signature.as_ref(),
+        b"-----BEGIN SSH SIGNATURE-----\nssh\n-----END SSH SIGNATURE-----\n".as_bstr()
+    );
+
+    assert_eq!(signed::tag(&fixture_bytes("tag/empty.txt")), None);
+}
@@ -0,0 +1,227 @@
+use git_object::{
+    time::{self, Format},
+    Sign, Time,
+};
+use pretty_assertions::assert_eq;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn at(seconds: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(seconds)
+}
+
+fn time_at(time: u32, offset: i32) -> Time {
+    Time {
+        time,
+        offset,
+        sign: if offset < 0 { Sign::Minus } else { Sign::Plus },
+    }
+}
+
+mod parse {
+    use super::{at, time_at};
+    use git_object::time::{self, parse};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn absolute_dates_in_the_formats_git_writes_and_accepts() {
+        for (input, expected) in &[
+            ("Thu, 17 Sep 2020 14:26:40 +0200", time_at(1_600_345_600, 7200)),
+            ("Thu Sep 17 14:26:40 2020 +0200", time_at(1_600_345_600, 7200)),
+            ("2020-09-17T14:26:40+02:00", time_at(1_600_345_600, 7200)),
+            ("2020-09-17 14:26:40 +0200", time_at(1_600_345_600, 7200)),
+            ("2020-09-17T12:26:40.123Z", time_at(1_600_345_600, 0)),
+            ("17 Sep 2020 14:26:40 -0730", time_at(1_600_379_800, -27000)),
+            ("09/17/2020 14:26 UTC", time_at(1_600_352_760, 0)),
+            ("17.09.2020 02:26:40 PM +0000", time_at(1_600_352_800, 0)),
+            ("17 Sep 2020 2pm", time_at(1_600_351_200, 0)),
+            ("17 Sep 2020 12AM UTC", time_at(1_600_300_800, 0)),
+            ("2020-09-17", time_at(1_600_300_800, 0)),
+        ] {
+            assert_eq!(parse(input, at(0), 0).expect(input), *expected, "{}", input);
+        }
+    }
+
+    #[test]
+    fn dates_without_time_keep_the_time_of_day_of_now() {
+        let now = at(1_600_378_326 - 5 * 86400);
+        for input in &["2020-09-17", "09/17/2020", "17.09.2020", "Sep 17 2020"] {
+            assert_eq!(
+                parse(input, now, 0).expect(input),
+                time_at(1_600_378_326, 0),
+                "{} is at 21:32:06 like now",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn raw_dates_keep_their_time_zone() -> Result<(), time::parse::Error> {
+        assert_eq!(parse("@1600345600", at(0), 0)?, time_at(1_600_345_600, 0));
+        assert_eq!(parse("1600345600 +0200", at(0), 0)?, time_at(1_600_345_600, 7200));
+        let negative_zero = parse("1600345600 -0000", at(0), 0)?;
+        assert_eq!(negative_zero.sign, git_object::Sign::Minus, "like in objects");
+        Ok(())
+    }
+
+    #[test]
+    fn relative_dates_are_relative_to_now() -> Result<(), time::parse::Error> {
+        let now = at(1_600_345_600);
+        assert_eq!(parse("now", now, 0)?, time_at(1_600_345_600, 0));
+        assert_eq!(parse("yesterday", now, 0)?, time_at(1_600_345_600 - 86400, 0));
+        assert_eq!(parse("2 weeks ago", now, 0)?, time_at(1_600_345_600 - 14 * 86400, 0));
+        assert_eq!(parse("2.Weeks.Ago", now, 0)?, time_at(1_600_345_600 - 14 * 86400, 0));
+        assert_eq!(parse("an hour ago", now, 0)?, time_at(1_600_345_600 - 3600, 0));
+        assert_eq!(
+            parse("1 day 3 minutes ago", now, 0)?,
+            time_at(1_600_345_600 - 86400 - 180, 0)
+        );
+        assert_eq!(
+            parse("last year", now, 0)?,
+            parse("2019-09-17 12:26:40 +0000", now, 0)?,
+            "months and years are calendar months and years"
+        );
+        assert_eq!(
+            parse("one month ago", at(1_585_612_800), 0)?,
+            time_at(1_583_107_200, 0),
+            "2020-03-31 minus one month is 2020-03-02, as days beyond the end of a month continue in the next one"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn dates_without_time_zone_and_relative_dates_are_in_the_local_time_zone() -> Result<(), time::parse::Error> {
+        assert_eq!(
+            parse("2020-09-17", at(0), 7200)?,
+            time_at(1_600_300_800, 7200),
+            "it's 02:00 in the local time zone at the start of the epoch"
+        );
+        assert_eq!(
+            parse("2020-09-17 14:26:40 +0000", at(0), 7200)?,
+            time_at(1_600_352_800, 0)
+        );
+        let now = at(1_601_510_400);
+        assert_eq!(parse("now", now, -3600)?, time_at(1_601_510_400, -3600));
+        assert_eq!(
+            parse("one month ago", now, -3600)?,
+            time_at(1_598_832_000, -3600),
+            "it's 2020-09-30 23:00 in the local time zone, and a month earlier is 2020-08-30 23:00"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_dates_are_errors() {
+        for input in &[
+            "",
+            "tomorrow-ish",
+            "2020-02-30",
+            "2 fortnights ago",
+            "25:00 2020-09-17",
+            "@12a",
+        ] {
+            assert!(
+                matches!(parse(input, at(0), 0), Err(time::parse::Error::Unrecognized(_))),
+                "{}",
+                input
+            );
+        }
+        assert!(matches!(
+            parse("1969-12-31 UTC", at(0), 0),
+            Err(time::parse::Error::OutOfRange(_))
+        ));
+    }
+}
+
+mod format {
+    use super::{at, time_at, Format};
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn all_formats_look_like_the_ones_of_git() {
+        let positive = time_at(1_600_345_600, 7200);
+        let negative = time_at(1_000_000_000, -27000);
+        for (format, expected_positive, expected_negative) in &[
+            (
+                Format::Default,
+                "Thu Sep 17 14:26:40 2020 +0200",
+                "Sat Sep 8 18:16:40 2001 -0730",
+            ),
+            (
+                Format::Iso8601,
+                "2020-09-17 14:26:40 +0200",
+                "2001-09-08 18:16:40 -0730",
+            ),
+            (
+                Format::Iso8601Strict,
+                "2020-09-17T14:26:40+02:00",
+                "2001-09-08T18:16:40-07:30",
+            ),
+            (
+                Format::Rfc2822,
+                "Thu, 17 Sep 2020 14:26:40 +0200",
+                "Sat, 8 Sep 2001 18:16:40 -0730",
+            ),
+            (Format::Short, "2020-09-17", "2001-09-08"),
+            (Format::Raw, "1600345600 +0200", "1000000000 -0730"),
+            (Format::Unix, "1600345600", "1000000000"),
+        ] {
+            assert_eq!(positive.format(*format), *expected_positive);
+            assert_eq!(negative.format(*format), *expected_negative);
+        }
+    }
+
+    #[test]
+    fn relative_dates_are_rounded_like_git_does() {
+        let then = time_at(1_600_000_000, 3600);
+        for (seconds_later, expected) in &[
+            (0, "0 seconds ago"),
+            (1, "1 second ago"),
+            (89, "89 seconds ago"),
+            (90, "2 minutes ago"),
+            (60 * 60 + 29 * 60, "89 minutes ago"),
+            (35 * 3600, "35 hours ago"),
+            (36 * 3600, "2 days ago"),
+            (13 * 86400, "13 days ago"),
+            (14 * 86400, "2 weeks ago"),
+            (69 * 86400, "10 weeks ago"),
+            (70 * 86400, "2 months ago"),
+            (364 * 86400, "12 months ago"),
+            (365 * 86400, "1 year ago"),
+            (400 * 86400, "1 year, 1 month ago"),
+            (730 * 86400, "2 years ago"),
+            (1825 * 86400, "5 years ago"),
+        ] {
+            assert_eq!(
+                then.format_with_now(Format::Relative, at(1_600_000_000 + seconds_later)),
+                *expected,
+                "{} seconds later",
+                seconds_later
+            );
+        }
+        assert_eq!(
+            then.format_with_now(Format::Relative, at(1_599_999_999)),
+            "in the future"
+        );
+    }
+
+    #[test]
+    fn names_of_formats_are_those_of_git() {
+        assert_eq!("iso".parse::<Format>().ok(), Some(Format::Iso8601));
+        assert_eq!("iso-strict".parse::<Format>().ok(), Some(Format::Iso8601Strict));
+        assert_eq!("rfc2822".parse::<Format>().ok(), Some(Format::Rfc2822));
+        assert!("local".parse::<Format>().is_err());
+    }
+}
+
+#[test]
+fn times_can_be_parsed_from_strings() -> Result<(), time::parse::Error> {
+    let time: Time = "Thu, 17 Sep 2020 14:26:40 +0200".parse()?;
+    assert_eq!(time.format(Format::Iso8601Strict), "2020-09-17T14:26:40+02:00");
+    assert!(
+        SystemTime::now()
+            .duration_since(at(u64::from("now".parse::<Time>()?.time)))
+            .expect("in the past")
+            < Duration::from_secs(60)
+    );
+    Ok(())
+}
@@ -0,0 +1,27 @@
+mod compare_names {
+    use git_object::tree::compare_names;
+    use std::cmp::Ordering;
+
+    #[test]
+    fn trees_sort_as_if_they_had_a_trailing_slash() {
+        assert_eq!(compare_names(b"a", true, b"a.b", false), Ordering::Greater);
+        assert_eq!(compare_names(b"a", false, b"a.b", false), Ordering::Less);
+        assert_eq!(compare_names(b"a", true, b"a0", false), Ordering::Less);
+        assert_eq!(compare_names(b"a", true, b"a", false), Ordering::Greater);
+        assert_eq!(compare_names(b"a", true, b"a", true), Ordering::Equal);
+    }
+}
+
+mod push_path_component {
+    use git_object::{bstr::BString, tree::push_path_component};
+
+    #[test]
+    fn separates_components_with_a_slash_and_allows_truncation() {
+        let mut path = BString::default();
+        assert_eq!(push_path_component(&mut path, b"a"), 0);
+        assert_eq!(push_path_component(&mut path, b"b"), 1);
+        assert_eq!(path, "a/b");
+        path.truncate(1);
+        assert_eq!(path, "a");
+    }
+}
@@ -1,9 +1,20 @@
 use crate::compound;
-use std::{fs, io, path::PathBuf};
+use git_object::bstr::{BString, ByteSlice, ByteVec};
+use std::{
+    ffi::OsString,
+    fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 pub mod parse;
 pub mod unquote;
 
+/// The environment variable which overrides the location of the objects directory of a repository.
+pub const OBJECT_DIRECTORY_ENV: &str = "GIT_OBJECT_DIRECTORY";
+/// The environment variable holding additional object directories to use as alternates, see [`parse::environment_value()`].
+pub const ALTERNATE_OBJECT_DIRECTORIES_ENV: &str = "GIT_ALTERNATE_OBJECT_DIRECTORIES";
+
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
@@ -14,35 +25,95 @@ pub enum Error {
     Init(#[from] compound::init::Error),
     #[error("Alternates form a cycle: {} -> {}", .0.iter().map(|p| format!("'{}'", p.display())).collect::<Vec<_>>().join(" -> "), .0.first().expect("more than one directories").display())]
     Cycle(Vec<PathBuf>),
+    #[error("The alternate path '{}' cannot be represented as bytes", .0.display())]
+    PathConversion(PathBuf),
 }
 
+/// Resolve all alternates listed in the `info/alternates` file of `objects_directory`, following chains of alternates
+/// to their end.
 pub fn resolve(objects_directory: impl Into<PathBuf>) -> Result<Vec<compound::Db>, Error> {
+    resolve_with_additional(objects_directory, None)
+}
+
+/// As [`resolve()`], but use the objects directory from `GIT_OBJECT_DIRECTORY` if set instead of `objects_directory`
+/// and add all paths listed in `GIT_ALTERNATE_OBJECT_DIRECTORIES` as alternates as well, just like git does.
+///
+/// Relative paths in the environment are interpreted relative to the current working directory.
+pub fn resolve_with_environment(objects_directory: impl Into<PathBuf>) -> Result<Vec<compound::Db>, Error> {
+    resolve_with_environment_values(
+        objects_directory,
+        std::env::var_os(OBJECT_DIRECTORY_ENV),
+        std::env::var_os(ALTERNATE_OBJECT_DIRECTORIES_ENV),
+        std::env::current_dir()?,
+    )
+}
+
+/// As [`resolve_with_environment()`], but with the values of `GIT_OBJECT_DIRECTORY` and
+/// `GIT_ALTERNATE_OBJECT_DIRECTORIES` passed as `object_directory` and `alternate_object_directories` respectively,
+/// and with relative paths in the latter interpreted relative to `current_dir`.
+pub fn resolve_with_environment_values(
+    objects_directory: impl Into<PathBuf>,
+    object_directory: Option<OsString>,
+    alternate_object_directories: Option<OsString>,
+    current_dir: impl AsRef<Path>,
+) -> Result<Vec<compound::Db>, Error> {
+    let objects_directory = object_directory
+        .map(PathBuf::from)
+        .unwrap_or_else(|| objects_directory.into());
+    let additional = match alternate_object_directories {
+        Some(value) => {
+            let current_dir = current_dir.as_ref();
+            parse::environment_value(&Vec::from_os_string(value).map_err(|v| Error::PathConversion(v.into()))?)?
+                .into_iter()
+                .map(|path| current_dir.join(path))
+                .collect()
+        }
+        None => Vec::new(),
+    };
+    resolve_with_additional(objects_directory, additional)
+}
+
+/// As [`resolve()`], but treat the given `additional` object directories as if they were listed in the `info/alternates`
+/// file of `objects_directory`, before its actual content. This is the order in which git searches the directories of
+/// `GIT_ALTERNATE_OBJECT_DIRECTORIES` and the alternates of a repository.
+pub fn resolve_with_additional(
+    objects_directory: impl Into<PathBuf>,
+    additional: impl IntoIterator<Item = PathBuf>,
+) -> Result<Vec<compound::Db>, Error> {
     let relative_base = objects_directory.into();
     let mut dirs = vec![(0, relative_base.clone())];
     let mut out = Vec::new();
     let mut seen = vec![relative_base.canonicalize()?];
+    let mut additional = Some(additional);
     while let Some((depth, dir)) = dirs.pop() {
-        match fs::read(dir.join("info").join("alternates")) {
-            Ok(input) => {
-                for path in parse::content(&input)?.into_iter() {
-                    let path = relative_base.join(path);
-                    let path_canonicalized = path.canonicalize()?;
-                    if seen.contains(&path_canonicalized) {
-                        continue;
-                    }
-                    seen.push(path_canonicalized);
-                    dirs.push((depth + 1, path));
-                }
-            }
-            Err(err) if err.kind() == io::ErrorKind::NotFound => {
-                // Only resolve for repositories with at least one link, otherwise the line below causes infinite recursion
-                if depth != 0 {
-                    // The tail of a chain doesn't have alternates, and thus is the real deal
-                    out.push(compound::Db::at(dir)?);
-                }
-            }
+        let mut paths = match fs::read(dir.join("info").join("alternates")) {
+            Ok(input) => parse::content(&input)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
             Err(err) => return Err(err.into()),
         };
+        if depth == 0 {
+            paths.splice(0..0, additional.take().into_iter().flatten());
+        }
+        if paths.is_empty() {
+            // Only resolve for repositories with at least one link, otherwise the line below causes infinite recursion
+            if depth != 0 {
+                // The tail of a chain doesn't have alternates, and thus is the real deal
+                out.push(compound::Db::at(dir)?);
+            }
+            continue;
+        }
+        let first_new_dir = dirs.len();
+        for path in paths.into_iter() {
+            let path = relative_base.join(path);
+            let path_canonicalized = path.canonicalize()?;
+            if seen.contains(&path_canonicalized) {
+                continue;
+            }
+            seen.push(path_canonicalized);
+            dirs.push((depth + 1, path));
+        }
+        // Pop the alternates in the order they are listed in.
+        dirs[first_new_dir..].reverse();
     }
 
     if out.is_empty() && seen.len() > 1 {
@@ -50,3 +121,79 @@ pub fn resolve(objects_directory: impl Into<PathBuf>) -> Result<Vec<compound::Db
     }
     Ok(out)
 }
+
+/// Add `alternate` to the `info/alternates` file of `objects_directory`, creating it if needed, similar to what
+/// `git clone --reference` does.
+///
+/// Returns `false` if `alternate` was already listed, leaving the file untouched, or `true` if it was added.
+/// The path is written as is, so relative paths will be interpreted relative to `objects_directory` when resolved.
+pub fn append(objects_directory: impl AsRef<Path>, alternate: impl AsRef<Path>) -> Result<bool, Error> {
+    let alternate = alternate.as_ref();
+    let info = objects_directory.as_ref().join("info");
+    let alternates_path = info.join("alternates");
+    let existing = match fs::read(&alternates_path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err.into()),
+    };
+    if parse::content(&existing)?.iter().any(|p| p == alternate) {
+        return Ok(false);
+    }
+    let line = <[u8]>::from_path(alternate).ok_or_else(|| Error::PathConversion(alternate.to_owned()))?;
+
+    fs::create_dir_all(&info)?;
+    let mut file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&alternates_path)?;
+    if !existing.is_empty() && !existing.ends_with(b"\n") {
+        file.write_all(b"\n")?;
+    }
+    file.write_all(&quote_if_needed(line))?;
+    file.write_all(b"\n")?;
+    Ok(true)
+}
+
+/// Turn `path` into an entry of the [`ALTERNATE_OBJECT_DIRECTORIES_ENV`] variable, quoting it like git does if it
+/// starts with a double quote or contains the [separator][parse::PATH_SEPARATOR], so that it is read back as is.
+pub fn environment_entry(path: impl AsRef<Path>) -> Result<OsString, Error> {
+    let path = path.as_ref();
+    let bytes = <[u8]>::from_path(path).ok_or_else(|| Error::PathConversion(path.to_owned()))?;
+    if !(bytes.starts_with(b"\"") || bytes.contains(&parse::PATH_SEPARATOR)) {
+        return Ok(path.into());
+    }
+    Vec::from(quote(bytes))
+        .into_os_string()
+        .map_err(|_| Error::PathConversion(path.to_owned()))
+}
+
+/// Quote `path` if it would otherwise be misinterpreted as comment, be cut short at a newline or be mistaken for a
+/// quoted path when read from an `alternates` file.
+fn quote_if_needed(path: &[u8]) -> BString {
+    if !(path.starts_with(b"\"") || path.starts_with(b"#") || path.contains(&b'\n') || path.contains(&b'\r')) {
+        return path.into();
+    }
+    quote(path)
+}
+
+/// Quote `path` like git's `quote_c_style()` does, so that it can be read back by [`unquote::ansi_c()`].
+fn quote(path: &[u8]) -> BString {
+    let mut out = BString::from("\"");
+    for byte in path {
+        match byte {
+            b'"' => out.push_str(br#"\""#),
+            b'\\' => out.push_str(br"\\"),
+            0x07 => out.push_str(br"\a"),
+            0x08 => out.push_str(br"\b"),
+            b'\t' => out.push_str(br"\t"),
+            b'\n' => out.push_str(br"\n"),
+            0x0b => out.push_str(br"\v"),
+            0x0c => out.push_str(br"\f"),
+            b'\r' => out.push_str(br"\r"),
+            byte if *byte < 0x20 || *byte >= 0x7f => out.push_str(format!("\\{:03o}", byte)),
+            _ => out.push(*byte),
+        }
+    }
+    out.push(b'"');
+    out
+}
@@ -1,5 +1,5 @@
 use crate::alternate::unquote;
-use git_object::bstr::ByteSlice;
+use git_object::bstr::{BStr, ByteSlice};
 use std::{borrow::Cow, path::PathBuf};
 
 #[derive(thiserror::Error, Debug)]
@@ -8,6 +8,21 @@ pub enum Error {
     PathConversion(Vec<u8>),
     #[error("Could not unquote alternate path")]
     Unquote(#[from] unquote::Error),
+    #[error("Quoted alternate path must be followed by a separator or the end of input: {}", String::from_utf8_lossy(&.0))]
+    MissingSeparator(Vec<u8>),
+}
+
+/// The separator between paths in `GIT_ALTERNATE_OBJECT_DIRECTORIES`, which is the same as the one used in `PATH`.
+#[cfg(not(windows))]
+pub const PATH_SEPARATOR: u8 = b':';
+/// The separator between paths in `GIT_ALTERNATE_OBJECT_DIRECTORIES`, which is the same as the one used in `PATH`.
+#[cfg(windows)]
+pub const PATH_SEPARATOR: u8 = b';';
+
+fn to_path(path: Cow<'_, BStr>, original: &[u8]) -> Result<PathBuf, Error> {
+    path.to_path()
+        .map(ToOwned::to_owned)
+        .map_err(|_| Error::PathConversion(original.to_vec()))
 }
 
 pub(crate) fn content(input: &[u8]) -> Result<Vec<PathBuf>, Error> {
@@ -17,16 +32,108 @@ pub(crate) fn content(input: &[u8]) -> Result<Vec<PathBuf>, Error> {
         if line.is_empty() || line.starts_with(b"#") {
             continue;
         }
-        out.push(
+        out.push(to_path(
             if line.starts_with(b"\"") {
                 unquote::ansi_c(line)?
             } else {
                 Cow::Borrowed(line)
+            },
+            line,
+        )?)
+    }
+    Ok(out)
+}
+
+/// Parse the value of the `GIT_ALTERNATE_OBJECT_DIRECTORIES` environment variable, which is a list of paths separated
+/// by [`PATH_SEPARATOR`]. Paths starting with a double quote are unquoted like lines in the `alternates` file, allowing
+/// them to contain the separator. Empty entries are skipped.
+pub fn environment_value(input: &[u8]) -> Result<Vec<PathBuf>, Error> {
+    let mut out = Vec::new();
+    let mut input = input.as_bstr();
+    while !input.is_empty() {
+        let (path, rest) = if input.starts_with(b"\"") {
+            let end = closing_quote(input).ok_or_else(|| unquote::Error::InvalidInput {
+                message: "Missing closing double quote".into(),
+                input: input.to_vec(),
+            })?;
+            let (quoted, rest) = input.split_at(end + 1);
+            match rest.first() {
+                None => {}
+                Some(b) if *b == PATH_SEPARATOR => {}
+                Some(_) => return Err(Error::MissingSeparator(input.to_vec())),
+            }
+            (
+                to_path(unquote::ansi_c(quoted.as_bstr())?, quoted)?,
+                rest.get(1..).unwrap_or_default(),
+            )
+        } else {
+            let end = input.find_byte(PATH_SEPARATOR).unwrap_or_else(|| input.len());
+            let (path, rest) = input.split_at(end);
+            if path.is_empty() {
+                input = rest.get(1..).unwrap_or_default().as_bstr();
+                continue;
             }
-            .to_path()
-            .map(ToOwned::to_owned)
-            .map_err(|_| Error::PathConversion(line.to_vec()))?,
-        )
+            (
+                to_path(Cow::Borrowed(path.as_bstr()), path)?,
+                rest.get(1..).unwrap_or_default(),
+            )
+        };
+        out.push(path);
+        input = rest.as_bstr();
     }
     Ok(out)
 }
+
+/// Returns the position of the double quote closing the one at the beginning of `input`, skipping escaped characters.
+fn closing_quote(input: &[u8]) -> Option<usize> {
+    let mut bytes = input.iter().enumerate().skip(1);
+    while let Some((index, byte)) = bytes.next() {
+        match byte {
+            b'\\' => {
+                bytes.next()?;
+            }
+            b'"' => return Some(index),
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn paths(input: &str) -> Vec<PathBuf> {
+        environment_value(input.as_bytes()).expect("valid input")
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn environment_value_splits_on_separator_and_skips_empty_entries() {
+        assert_eq!(
+            paths("a::/b/c:"),
+            vec![Path::new("a").to_owned(), Path::new("/b/c").to_owned()]
+        );
+        assert!(paths("").is_empty());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn environment_value_unquotes_entries_which_may_contain_the_separator() {
+        assert_eq!(
+            paths(r#""a:b\tc":d:"e""#),
+            vec![
+                Path::new("a:b\tc").to_owned(),
+                Path::new("d").to_owned(),
+                Path::new("e").to_owned()
+            ]
+        );
+    }
+
+    #[test]
+    fn environment_value_rejects_garbage_after_closing_quote() {
+        assert!(matches!(environment_value(br#""a"b"#), Err(Error::MissingSeparator(_))));
+        assert!(matches!(environment_value(br#""a\""#), Err(Error::Unquote(_))));
+    }
+}
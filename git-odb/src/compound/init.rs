@@ -13,17 +13,55 @@ pub enum Error {
 
 /// Instantiation
 impl compound::Db {
+    /// Open the objects directory at `objects_directory` along with its alternates.
+    ///
+    /// Like git, all pack indices in its `pack` directory are loaded regardless of their name, which includes the
+    /// packs we write ourselves as they are named after their hash only.
     pub fn at(objects_directory: impl Into<PathBuf>) -> Result<compound::Db, Error> {
         let loose_objects = objects_directory.into();
+        Self::at_with_alternates(loose_objects.clone(), || crate::alternate::resolve(loose_objects))
+    }
+
+    /// As [`at()`][compound::Db::at()], but use the objects directory in `GIT_OBJECT_DIRECTORY` if set and add the
+    /// alternates listed in `GIT_ALTERNATE_OBJECT_DIRECTORIES`, like git does.
+    pub fn at_with_environment(objects_directory: impl Into<PathBuf>) -> Result<compound::Db, Error> {
+        let loose_objects = std::env::var_os(crate::alternate::OBJECT_DIRECTORY_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| objects_directory.into());
+        Self::at_with_alternates(loose_objects.clone(), || {
+            crate::alternate::resolve_with_environment(loose_objects)
+        })
+    }
+
+    /// Apply the given `limits` to all objects read from this database, including its packs and alternates.
+    pub fn with_limits(self, limits: crate::Limits) -> Self {
+        compound::Db {
+            loose: self.loose.with_limits(limits),
+            packs: self
+                .packs
+                .into_iter()
+                .map(|pack::Bundle { pack, index }| pack::Bundle {
+                    pack: pack.with_limits(limits),
+                    index,
+                })
+                .collect(),
+            alternates: self.alternates.into_iter().map(|db| db.with_limits(limits)).collect(),
+        }
+    }
+
+    fn at_with_alternates(
+        loose_objects: PathBuf,
+        alternates: impl FnOnce() -> Result<Vec<compound::Db>, crate::alternate::Error>,
+    ) -> Result<compound::Db, Error> {
         if !loose_objects.is_dir() {
             return Err(Error::Inaccessible(loose_objects));
         }
-        let packs = if let Ok(entries) = std::fs::read_dir(loose_objects.join("packs")) {
+        let packs = if let Ok(entries) = std::fs::read_dir(loose_objects.join("pack")) {
             let mut packs_and_sizes = entries
                 .filter_map(Result::ok)
                 .filter_map(|e| e.metadata().map(|md| (e.path(), md)).ok())
                 .filter(|(_, md)| md.file_type().is_file())
-                .filter(|(p, _)| p.extension().unwrap_or_default() == "idx" && p.starts_with("pack-"))
+                .filter(|(p, _)| p.extension().unwrap_or_default() == "idx")
                 .map(|(p, md)| pack::Bundle::at(p).map(|b| (b, md.len())))
                 .collect::<Result<Vec<_>, _>>()?;
             packs_and_sizes.sort_by_key(|e| e.1);
@@ -33,9 +71,9 @@ impl compound::Db {
         };
 
         Ok(compound::Db {
-            loose: loose::Db::at(loose_objects.clone()),
+            loose: loose::Db::at(loose_objects),
             packs,
-            alternates: crate::alternate::resolve(loose_objects).map_err(Box::new)?,
+            alternates: alternates().map_err(Box::new)?,
         })
     }
 }
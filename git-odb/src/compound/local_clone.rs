@@ -0,0 +1,134 @@
+use crate::{alternate, compound};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Could not {action} '{path}'")]
+    Io {
+        source: io::Error,
+        action: &'static str,
+        path: PathBuf,
+    },
+    #[error(transparent)]
+    Alternate(#[from] alternate::Error),
+}
+
+/// Determines how objects are made available in the clone.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// Hardlink packs and loose objects, and copy them if hardlinks can't be created, for example across file systems.
+    Link,
+    /// Always copy packs and loose objects.
+    Copy,
+    /// Don't copy any object, but register the source objects directory as alternate instead.
+    ///
+    /// The clone is only usable as long as the source exists and doesn't lose any objects.
+    Reference,
+}
+
+/// Information about a local clone of an objects directory.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    /// The amount of files that were hardlinked.
+    pub files_linked: usize,
+    /// The amount of files that were copied.
+    pub files_copied: usize,
+    /// The amount of bytes in all copied files.
+    pub bytes_copied: u64,
+    /// The objects directories now registered as alternates of the clone.
+    pub alternates: Vec<PathBuf>,
+}
+
+/// Local clones
+impl compound::Db {
+    /// Make all objects of this database available in the objects directory at `destination`, which is created
+    /// if needed, without going through pack generation, similar to what `git clone --local` does.
+    ///
+    /// Alternates of this database are registered as alternates of the clone, too.
+    /// Packs are placed before their indices, so that readers of `destination` only see complete packs.
+    pub fn clone_local(&self, destination: impl AsRef<Path>, mode: Mode) -> Result<Outcome, Error> {
+        let destination = destination.as_ref();
+        fs::create_dir_all(destination).map_err(io_err("create objects directory", destination))?;
+
+        let mut out = Outcome::default();
+        let mut alternates = Vec::new();
+        match mode {
+            Mode::Reference => alternates.push(absolute(&self.loose.path)?),
+            Mode::Link | Mode::Copy => {
+                // Alternates may be relative to the source, and are registered with absolute paths instead.
+                let source_alternates = self.loose.path.join("info").join("alternates");
+                link_or_copy_directory(&self.loose.path, destination, mode, &source_alternates, &mut out)?;
+                for db in &self.alternates {
+                    alternates.push(absolute(&db.loose.path)?);
+                }
+            }
+        }
+        for alternate in alternates {
+            alternate::append(destination, &alternate)?;
+            out.alternates.push(alternate);
+        }
+        Ok(out)
+    }
+}
+
+fn link_or_copy_directory(
+    source: &Path,
+    destination: &Path,
+    mode: Mode,
+    skip: &Path,
+    out: &mut Outcome,
+) -> Result<(), Error> {
+    let mut entries = fs::read_dir(source)
+        .map_err(io_err("read directory", source))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(io_err("read directory", source))?;
+    entries.sort_by_key(|path| (super::quarantine::pack_copy_priority(path), path.clone()));
+
+    for path in entries {
+        let name = path.file_name().expect("directory entries have names");
+        if path == skip || name.to_str().is_some_and(|name| name.starts_with("incoming-")) {
+            // Skip the alternates file we handle separately, and objects in quarantine which aren't part of the source yet.
+            continue;
+        }
+        let target = destination.join(name);
+        if path.is_dir() {
+            match fs::create_dir(&target) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(io_err("create directory", &target)(err)),
+            }
+            link_or_copy_directory(&path, &target, mode, skip, out)?;
+        } else if target.exists() {
+            // Objects are content-addressed, the one we have already is just as good.
+            continue;
+        } else {
+            if mode == Mode::Link && fs::hard_link(&path, &target).is_ok() {
+                out.files_linked += 1;
+                continue;
+            }
+            out.bytes_copied += fs::copy(&path, &target).map_err(io_err("copy object to", &target))?;
+            out.files_copied += 1;
+        }
+    }
+    Ok(())
+}
+
+fn absolute(path: &Path) -> Result<PathBuf, Error> {
+    path.canonicalize()
+        .map_err(io_err("canonicalize objects directory", path))
+}
+
+fn io_err(action: &'static str, path: &Path) -> impl FnOnce(io::Error) -> Error {
+    let path = path.to_owned();
+    move |err| Error::Io {
+        source: err,
+        action,
+        path,
+    }
+}
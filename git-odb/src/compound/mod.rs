@@ -92,5 +92,8 @@ pub mod object {
 pub use object::Object;
 
 pub mod init;
+pub mod local_clone;
 pub mod locate;
+pub mod quarantine;
+pub use quarantine::Quarantine;
 mod write;
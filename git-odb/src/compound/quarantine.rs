@@ -0,0 +1,200 @@
+use crate::{alternate, compound};
+use std::{
+    ffi::OsString,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Could not {action} '{path}'")]
+    Io {
+        source: io::Error,
+        action: &'static str,
+        path: PathBuf,
+    },
+    #[error(transparent)]
+    Alternate(#[from] alternate::Error),
+    #[error(transparent)]
+    Init(#[from] compound::init::Error),
+}
+
+/// The environment variable git sets for hooks to indicate objects are received into a quarantine directory.
+pub const QUARANTINE_PATH_ENV: &str = "GIT_QUARANTINE_PATH";
+
+/// A temporary object directory inside of an objects directory to receive objects into, which are only made available
+/// in the objects directory once [committed][Quarantine::commit()].
+///
+/// Dropping it without committing discards all objects received so far, just like [rolling back][Quarantine::rollback()].
+pub struct Quarantine {
+    // Declared first to be dropped first, releasing all open pack files before the directory is removed.
+    db: compound::Db,
+    directory: tempfile::TempDir,
+    objects_directory: PathBuf,
+}
+
+/// Quarantine
+impl compound::Db {
+    /// Create a new `incoming-*` quarantine directory within our objects directory to receive objects into, similar
+    /// to what `git receive-pack` does.
+    ///
+    /// The main objects directory is registered as alternate of the quarantine directory, so that objects in it can
+    /// refer to objects we already have.
+    pub fn quarantine(&self) -> Result<Quarantine, Error> {
+        let objects_directory = self.loose.path.clone();
+        let directory = tempfile::Builder::new()
+            .prefix("incoming-")
+            .tempdir_in(&objects_directory)
+            .map_err(|err| Error::Io {
+                source: err,
+                action: "create quarantine directory in",
+                path: objects_directory.clone(),
+            })?;
+        let pack_directory = directory.path().join("pack");
+        fs::create_dir(&pack_directory).map_err(|err| Error::Io {
+            source: err,
+            action: "create pack directory",
+            path: pack_directory,
+        })?;
+        let absolute_objects_directory = objects_directory.canonicalize().map_err(|err| Error::Io {
+            source: err,
+            action: "canonicalize",
+            path: objects_directory.clone(),
+        })?;
+        alternate::append(directory.path(), absolute_objects_directory)?;
+
+        let mut db = compound::Db::at(directory.path())?.with_limits(self.loose.limits);
+        db.loose.write_options = self.loose.write_options;
+        db.alternates = vec![compound::Db::at(&objects_directory)?.with_limits(self.loose.limits)];
+        Ok(Quarantine {
+            db,
+            directory,
+            objects_directory,
+        })
+    }
+}
+
+impl Quarantine {
+    /// The database to write received objects to. It can also read all objects of the main objects directory.
+    ///
+    /// Note that packs written into the [pack directory][Quarantine::pack_directory()] after it was created
+    /// are not visible, use [`reload()`][Quarantine::reload()] to pick them up.
+    pub fn db(&self) -> &compound::Db {
+        &self.db
+    }
+
+    /// Re-read the packs in the quarantine directory, for example after writing a received pack into it.
+    pub fn reload(&mut self) -> Result<(), Error> {
+        let alternates = std::mem::take(&mut self.db.alternates);
+        let loose = &self.db.loose;
+        let mut db = compound::Db::at(&loose.path)?.with_limits(loose.limits);
+        db.loose.write_options = loose.write_options;
+        db.alternates = alternates;
+        self.db = db;
+        Ok(())
+    }
+
+    /// The path to the quarantine directory, which is a valid objects directory.
+    pub fn path(&self) -> &Path {
+        self.directory.path()
+    }
+
+    /// The directory to write received packs into.
+    pub fn pack_directory(&self) -> PathBuf {
+        self.directory.path().join("pack")
+    }
+
+    /// The environment variables to set for programs like hooks to see the quarantined objects along with the ones
+    /// already present in the main objects directory.
+    pub fn environment(&self) -> Result<Vec<(&'static str, OsString)>, Error> {
+        Ok(vec![
+            (QUARANTINE_PATH_ENV, self.path().into()),
+            (alternate::OBJECT_DIRECTORY_ENV, self.path().into()),
+            (
+                alternate::ALTERNATE_OBJECT_DIRECTORIES_ENV,
+                alternate::environment_entry(&self.objects_directory)?,
+            ),
+        ])
+    }
+
+    /// Move all received objects into the main objects directory and delete the quarantine directory.
+    ///
+    /// Packs are moved before their indices, so that objects become visible only once the pack is in place.
+    /// Objects that exist already in the main objects directory are left untouched.
+    pub fn commit(self) -> Result<(), Error> {
+        let Quarantine {
+            db,
+            directory,
+            objects_directory,
+        } = self;
+        drop(db);
+        migrate(directory.path(), &objects_directory, true)?;
+        let path = directory.path().to_owned();
+        directory.close().map_err(|err| Error::Io {
+            source: err,
+            action: "remove migrated quarantine directory",
+            path,
+        })
+    }
+
+    /// Delete the quarantine directory along with all objects it received.
+    pub fn rollback(self) -> Result<(), Error> {
+        let Quarantine { db, directory, .. } = self;
+        drop(db);
+        let path = directory.path().to_owned();
+        directory.close().map_err(|err| Error::Io {
+            source: err,
+            action: "remove quarantine directory",
+            path,
+        })
+    }
+}
+
+fn migrate(source: &Path, destination: &Path, is_root: bool) -> Result<(), Error> {
+    let io_err = |action: &'static str, path: &Path| {
+        let path = path.to_owned();
+        move |err| Error::Io {
+            source: err,
+            action,
+            path,
+        }
+    };
+    let mut entries = fs::read_dir(source)
+        .map_err(io_err("read directory", source))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(io_err("read directory", source))?;
+    entries.sort_by_key(|path| (pack_copy_priority(path), path.clone()));
+
+    for path in entries {
+        let name = path.file_name().expect("directory entries have names");
+        if is_root && name == "info" {
+            continue;
+        }
+        let target = destination.join(name);
+        if path.is_dir() {
+            match fs::create_dir(&target) {
+                Ok(()) => {}
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(err) => return Err(io_err("create directory", &target)(err)),
+            }
+            migrate(&path, &target, false)?;
+        } else if target.exists() {
+            // Objects are content-addressed, the one we have already is just as good as the one we received.
+            fs::remove_file(&path).map_err(io_err("remove duplicate object", &path))?;
+        } else {
+            fs::rename(&path, &target).map_err(io_err("move object into place at", &target))?;
+        }
+    }
+    Ok(())
+}
+
+/// Order pack files like git does, so that indices are moved last and objects are only visible once complete.
+pub(crate) fn pack_copy_priority(path: &Path) -> u8 {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("keep") => 1,
+        Some("pack") => 2,
+        Some("idx") => 3,
+        _ => 0,
+    }
+}
@@ -4,12 +4,17 @@ mod zlib;
 
 pub mod alternate;
 pub mod compound;
+pub mod limits;
+#[doc(inline)]
+pub use limits::Limits;
 pub mod loose;
 pub mod pack;
 
 mod sink;
 pub use sink::{sink, Sink};
 
+pub mod tree;
+
 pub(crate) mod hash;
 mod traits;
 
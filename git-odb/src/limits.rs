@@ -0,0 +1,40 @@
+/// The error returned when an object exceeds one of the configured [`Limits`].
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+pub enum Error {
+    #[error("An object of {size} bytes exceeds the maximum object size of {limit} bytes")]
+    ObjectTooLarge { size: u64, limit: u64 },
+    #[error("A delta chain of length {length} exceeds the maximum delta chain length of {limit}")]
+    DeltaChainTooLong { length: u32, limit: u32 },
+}
+
+/// Limits to protect against objects which would take excessive resources to decode, like crafted headers
+/// declaring huge sizes or compressed data that expands far beyond what was declared.
+///
+/// The default imposes no limits. Note that buffers are allocated according to the sizes declared in object headers,
+/// so [`max_object_size`][Limits::max_object_size] also bounds how much memory a crafted header can claim at once.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Limits {
+    /// The maximum size in bytes of decompressed objects and deltas, as well as objects produced by applying a delta.
+    pub max_object_size: Option<u64>,
+    /// The maximum amount of deltas that need to be applied to a base object to produce the final object.
+    pub max_delta_chain_length: Option<u32>,
+}
+
+impl Limits {
+    /// Returns an error if an object or delta of `size` bytes exceeds our maximum object size.
+    pub fn check_object_size(&self, size: u64) -> Result<(), Error> {
+        match self.max_object_size {
+            Some(limit) if size > limit => Err(Error::ObjectTooLarge { size, limit }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns an error if a delta chain of `length` exceeds our maximum delta chain length.
+    pub fn check_delta_chain_length(&self, length: u32) -> Result<(), Error> {
+        match self.max_delta_chain_length {
+            Some(limit) if length > limit => Err(Error::DeltaChainTooLong { length, limit }),
+            _ => Ok(()),
+        }
+    }
+}
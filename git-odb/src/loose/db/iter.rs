@@ -10,17 +10,23 @@ pub enum Error {
 
 /// Iteration and traversal
 impl Db {
+    /// Iterate all objects in the fan-out directories directly below our path, ignoring objects in nested objects
+    /// directories like the ones of a [quarantine][crate::compound::Quarantine].
     pub fn iter(&self) -> impl Iterator<Item = Result<owned::Id, Error>> {
         use std::path::Component::Normal;
+        let root = self.path.clone();
         WalkDir::new(&self.path)
             .min_depth(2)
             .max_depth(3)
             .follow_links(false)
             .into_iter()
-            .filter_map(|res| {
+            .filter_map(move |res| {
                 let mut is_valid_path = false;
                 let e = res.map_err(Error::WalkDir).map(|e| {
                     let p = e.path();
+                    if p.parent().and_then(|p| p.parent()) != Some(root.as_path()) {
+                        return owned::Id::null_sha1();
+                    }
                     let (c1, c2) = p.components().fold((None, None), |(_c1, c2), cn| (c2, Some(cn)));
                     if let (Some(Normal(c1)), Some(Normal(c2))) = (c1, c2) {
                         if c1.len() == 2 && c2.len() == 38 {
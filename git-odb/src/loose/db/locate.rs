@@ -13,6 +13,11 @@ pub enum Error {
     DecompressFile { source: zlib::Error, path: PathBuf },
     #[error(transparent)]
     Decode(#[from] header::Error),
+    #[error("The loose object at '{path}' exceeds a limit")]
+    Limit {
+        source: crate::limits::Error,
+        path: PathBuf,
+    },
     #[error("Could not {action} data at '{path}'")]
     Io {
         source: std::io::Error,
@@ -25,6 +30,12 @@ pub enum Error {
 impl Db {
     const OPEN_ACTION: &'static str = "open";
 
+    /// Return true if the object `id` is stored in this database, which only checks if its file exists without
+    /// opening it.
+    pub fn contains(&self, id: borrowed::Id<'_>) -> bool {
+        sha1_path(id, self.path.clone()).is_file()
+    }
+
     pub fn locate(&self, id: borrowed::Id<'_>) -> Option<Result<Object, Error>> {
         match self.locate_inner(id) {
             Ok(obj) => Some(Ok(obj)),
@@ -79,6 +90,10 @@ impl Db {
         };
 
         let (kind, size, header_size) = header::decode(&decompressed[..consumed_out])?;
+        self.limits.check_object_size(size).map_err(|err| Error::Limit {
+            source: err,
+            path: path.to_owned(),
+        })?;
         let mut decompressed = SmallVec::from_buf(decompressed);
         decompressed.resize(consumed_out, 0);
 
@@ -3,6 +3,10 @@ use std::path::PathBuf;
 
 pub struct Db {
     pub path: PathBuf,
+    /// Options affecting how new objects are written.
+    pub write_options: write::Options,
+    /// Limits for objects to be read, to protect against objects taking excessive resources.
+    pub limits: crate::Limits,
 }
 
 /// Initialization
@@ -12,8 +16,22 @@ impl Db {
     pub fn at(objects_directory: impl Into<PathBuf>) -> Db {
         Db {
             path: objects_directory.into(),
+            write_options: Default::default(),
+            limits: Default::default(),
         }
     }
+
+    /// Refuse to read objects exceeding the given `limits`.
+    pub fn with_limits(mut self, limits: crate::Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Use the given `options` when writing objects.
+    pub fn with_write_options(mut self, options: write::Options) -> Self {
+        self.write_options = options;
+        self
+    }
 }
 
 pub(crate) fn sha1_path(id: borrowed::Id<'_>, mut root: PathBuf) -> PathBuf {
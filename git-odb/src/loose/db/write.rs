@@ -1,7 +1,11 @@
 use super::Db;
 use crate::{hash, loose, zlib::stream::DeflateWriter};
 use git_object::{owned, HashKind};
-use std::{fs, io, io::Write, path::PathBuf};
+use std::{
+    fs, io,
+    io::Write,
+    path::{Path, PathBuf},
+};
 use tempfile::NamedTempFile;
 
 #[derive(thiserror::Error, Debug)]
@@ -19,6 +23,117 @@ pub enum Error {
         source: tempfile::PersistError,
         target: PathBuf,
     },
+    #[error("The zlib compression level must be between 0 and 9, got {0}")]
+    CompressionLevel(u8),
+}
+
+/// Options to control how loose objects are written, see [`Db::write_options`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Options {
+    /// The zlib compression level from 0 (no compression) to 9 (best compression), or `None` to use the default.
+    /// Writing fails with higher levels.
+    ///
+    /// This is what `core.looseCompression` configures.
+    pub compression_level: Option<u8>,
+    /// If true, the object file is synced to disk before it is moved into place, and its directory is synced afterwards.
+    /// This makes objects durable in case of power loss at the cost of performance.
+    ///
+    /// This is what the `loose-object` component of `core.fsync` configures.
+    pub fsync: bool,
+    /// Controls the permissions of newly created object files and directories, as configured by `core.sharedRepository`.
+    pub shared_repository: SharedRepository,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            compression_level: None,
+            fsync: false,
+            shared_repository: SharedRepository::Umask,
+        }
+    }
+}
+
+/// The values of `core.sharedRepository`, determining permissions of newly written objects and object directories.
+///
+/// Like in git, object files are created read-only with `0444` minus the umask of the process, and the permissions
+/// granted by a shared repository are added to the ones the file or directory was created with, unless an explicit
+/// [`Mode`][SharedRepository::Mode] replaces them.
+/// Directories receive the _set-group-id_ bit if the repository is shared, so that objects written by any user belong
+/// to the repository's group.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub enum SharedRepository {
+    /// Use the permissions given by the umask of the current process. Known in git as `umask` or `false`.
+    Umask,
+    /// Make objects readable and directories writable for the group. Known in git as `group` or `true`.
+    Group,
+    /// Like `Group`, but also make objects and directories readable for everybody. Known in git as `all`, `world`
+    /// or `everybody`.
+    All,
+    /// Use the given octal permission bits, like `0640`, for all objects, and derive directory permissions from them.
+    Mode(u32),
+}
+
+impl SharedRepository {
+    /// The read-only permission bits of an object file created with `mode`.
+    pub fn file_mode(&self, mode: u32) -> u32 {
+        let mode = mode & !0o222;
+        self.adjust(mode).unwrap_or(mode)
+    }
+
+    /// The permission bits of a directory containing objects created with `mode`, or `None` to leave them as created.
+    pub fn directory_mode(&self, mode: u32) -> Option<u32> {
+        const SET_GROUP_ID: u32 = 0o2000;
+        self.adjust(mode).map(|mode| mode | SET_GROUP_ID)
+    }
+
+    /// Compute permissions like git's `calc_shared_perm()`.
+    fn adjust(&self, mode: u32) -> Option<u32> {
+        let mut tweak = match self {
+            SharedRepository::Umask => return None,
+            SharedRepository::Group => 0o660,
+            SharedRepository::All => 0o664,
+            SharedRepository::Mode(mode) => mode & 0o666,
+        };
+        if mode & 0o200 == 0 {
+            tweak &= !0o222;
+        }
+        if mode & 0o100 != 0 {
+            tweak |= (tweak & 0o444) >> 2;
+        }
+        Some(match self {
+            SharedRepository::Mode(_) => (mode & !0o777) | tweak,
+            _ => mode | tweak,
+        })
+    }
+}
+
+/// Types for parsing [`SharedRepository`] from configuration values.
+pub mod shared_repository {
+    /// The error returned by [`SharedRepository::from_str()`][std::str::FromStr::from_str()].
+    #[derive(thiserror::Error, Debug)]
+    #[error("Invalid value for core.sharedRepository: '{0}'")]
+    pub struct Error(pub String);
+}
+
+impl std::str::FromStr for SharedRepository {
+    type Err = shared_repository::Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value.to_ascii_lowercase().as_str() {
+            "" | "umask" | "false" | "no" | "off" => SharedRepository::Umask,
+            "group" | "true" | "yes" | "on" => SharedRepository::Group,
+            "all" | "world" | "everybody" => SharedRepository::All,
+            octal if octal.starts_with('0') => u32::from_str_radix(octal, 8)
+                .ok()
+                .filter(|mode| *mode <= 0o777)
+                .map(SharedRepository::Mode)
+                .ok_or_else(|| shared_repository::Error(value.into()))?,
+            _ => return Err(shared_repository::Error(value.into())),
+        })
+    }
 }
 
 impl crate::Write for Db {
@@ -70,12 +185,19 @@ impl Db {
         size: u64,
         hash: HashKind,
     ) -> Result<hash::Write<HashAndTempFile>, Error> {
+        if let Some(level) = self.write_options.compression_level.filter(|level| *level > 9) {
+            return Err(Error::CompressionLevel(level));
+        }
+        let file = NamedTempFile::new_in(&self.path).map_err(|err| Error::Io {
+            source: err,
+            message: "create named temp file in",
+            path: self.path.to_owned(),
+        })?;
         let mut to = hash::Write::new(
-            DeflateWriter::new(NamedTempFile::new_in(&self.path).map_err(|err| Error::Io {
-                source: err,
-                message: "create named temp file in",
-                path: self.path.to_owned(),
-            })?),
+            match self.write_options.compression_level {
+                Some(level) => DeflateWriter::with_level(file, level),
+                None => DeflateWriter::new(file),
+            },
             hash,
         );
 
@@ -96,17 +218,104 @@ impl Db {
         let object_dir = object_path
             .parent()
             .expect("each object path has a 1 hex-bytes directory");
-        if let Err(err) = fs::create_dir(object_dir) {
-            match err.kind() {
+        match fs::create_dir(object_dir) {
+            Ok(()) => {
+                let shared_repository = self.write_options.shared_repository;
+                adjust_mode(object_dir, |mode| shared_repository.directory_mode(mode)).map_err(|err| Error::Io {
+                    source: err,
+                    message: "set permissions of object directory",
+                    path: object_dir.to_owned(),
+                })?;
+            }
+            Err(err) => match err.kind() {
                 io::ErrorKind::AlreadyExists => {}
                 _ => return Err(err.into()),
-            }
+            },
         }
         let file = file.into_inner();
+        let shared_repository = self.write_options.shared_repository;
+        let umask = umask(&self.path).map_err(|err| Error::Io {
+            source: err,
+            message: "determine the umask for object files in",
+            path: self.path.to_owned(),
+        })?;
+        adjust_mode(file.path(), |_| Some(shared_repository.file_mode(0o444 & !umask))).map_err(|err| Error::Io {
+            source: err,
+            message: "set permissions of temporary object file",
+            path: file.path().to_owned(),
+        })?;
+        if self.write_options.fsync {
+            file.as_file().sync_all().map_err(|err| Error::Io {
+                source: err,
+                message: "sync temporary object file",
+                path: file.path().to_owned(),
+            })?;
+        }
         file.persist(&object_path).map_err(|err| Error::Persist {
             source: err,
-            target: object_path,
+            target: object_path.clone(),
         })?;
+        if self.write_options.fsync {
+            sync_directory(object_dir).map_err(|err| Error::Io {
+                source: err,
+                message: "sync object directory",
+                path: object_dir.to_owned(),
+            })?;
+        }
         Ok(id)
     }
 }
+
+#[cfg(unix)]
+fn adjust_mode(path: &Path, adjust: impl FnOnce(u32) -> Option<u32>) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    match adjust(fs::metadata(path)?.permissions().mode()) {
+        Some(mode) => fs::set_permissions(path, fs::Permissions::from_mode(mode)),
+        None => Ok(()),
+    }
+}
+
+/// The umask of the current process, learned from the permissions of a directory created within `dir`.
+///
+/// It is determined only once as it can't be read without changing it, which would affect files created concurrently.
+#[cfg(unix)]
+fn umask(dir: &Path) -> io::Result<u32> {
+    use std::{
+        os::unix::fs::PermissionsExt,
+        sync::atomic::{AtomicU32, Ordering},
+    };
+    const UNKNOWN: u32 = u32::MAX;
+    static UMASK: AtomicU32 = AtomicU32::new(UNKNOWN);
+    match UMASK.load(Ordering::Relaxed) {
+        UNKNOWN => {
+            let probe = tempfile::tempdir_in(dir)?;
+            let probe_dir = probe.path().join("umask");
+            fs::create_dir(&probe_dir)?;
+            let mask = !fs::metadata(&probe_dir)?.permissions().mode() & 0o777;
+            UMASK.store(mask, Ordering::Relaxed);
+            Ok(mask)
+        }
+        mask => Ok(mask),
+    }
+}
+
+#[cfg(not(unix))]
+fn umask(_dir: &Path) -> io::Result<u32> {
+    Ok(0)
+}
+
+#[cfg(not(unix))]
+fn adjust_mode(_path: &Path, _adjust: impl FnOnce(u32) -> Option<u32>) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(unix)]
+fn sync_directory(path: &Path) -> io::Result<()> {
+    fs::File::open(path)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_directory(_path: &Path) -> io::Result<()> {
+    // Directories can't be opened like files on windows, and renames are durable once the file itself was synced.
+    Ok(())
+}
@@ -1,7 +1,7 @@
 use super::stream;
 use crate::{loose, zlib};
 use git_object as object;
-use miniz_oxide::inflate::decompress_to_vec_zlib;
+use miniz_oxide::inflate::decompress_to_vec_zlib_with_limit;
 use object::borrowed;
 use smallvec::SmallVec;
 use std::{io::Read, path::PathBuf};
@@ -18,6 +18,8 @@ pub enum Error {
         action: &'static str,
         path: PathBuf,
     },
+    #[error("The object header declared {expected} bytes, but {actual} bytes were decompressed")]
+    SizeMismatch { expected: usize, actual: usize },
 }
 
 impl loose::Object {
@@ -82,10 +84,18 @@ impl loose::Object {
             })?;
             self.compressed_data = SmallVec::from(buf);
         }
-        self.decompressed_data = SmallVec::from(decompress_to_vec_zlib(&self.compressed_data[..]).unwrap());
+        // Never decompress more than the header declared, which keeps data with a lying header from exhausting memory.
+        let decompressed =
+            decompress_to_vec_zlib_with_limit(&self.compressed_data[..], total_size).map_err(zlib::Error::Inflate)?;
+        if decompressed.len() != total_size {
+            return Err(Error::SizeMismatch {
+                expected: total_size,
+                actual: decompressed.len(),
+            });
+        }
+        self.decompressed_data = SmallVec::from(decompressed);
         self.compressed_data = Default::default();
         self.decompressed_data.shrink_to_fit();
-        assert!(self.decompressed_data.len() == total_size);
         self.decompression_complete = true;
         Ok(())
     }
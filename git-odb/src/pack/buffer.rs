@@ -0,0 +1,18 @@
+use std::ops::Deref;
+
+/// The bytes backing pack data and index files, which are either memory mapped from disk or provided by the caller.
+pub(crate) struct Buffer(Box<dyn AsRef<[u8]> + Send + Sync>);
+
+impl Buffer {
+    pub fn new(data: impl AsRef<[u8]> + Send + Sync + 'static) -> Self {
+        Buffer(Box::new(data))
+    }
+}
+
+impl Deref for Buffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        (*self.0).as_ref()
+    }
+}
@@ -10,7 +10,7 @@ use std::{
 use tempfile::NamedTempFile;
 
 mod error;
-use error::Error;
+pub use error::Error;
 
 mod types;
 pub use types::Outcome;
@@ -19,9 +19,20 @@ use types::PassThrough;
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub struct Options {
+    /// The amount of threads to use when resolving deltas.
     pub thread_limit: Option<usize>,
     pub iteration_mode: pack::data::iter::Mode,
     pub index_kind: pack::index::Kind,
+    /// Limits for entries in the pack and objects produced by resolving deltas. Use these when receiving packs
+    /// from untrusted sources.
+    pub limits: crate::Limits,
+    /// The maximum amount of bytes of resolved objects to keep in memory as bases for deltas while indexing, shared
+    /// by all threads, or `None` to keep all bases along the delta chains currently resolved.
+    ///
+    /// Evicted bases are resolved again from the closest base still in memory, or from their pack entries, trading
+    /// time for memory. Besides these, each thread holds the base and object it currently resolves, and a small
+    /// entry is kept for each object in the pack.
+    pub max_resolved_base_bytes: Option<u64>,
 }
 
 impl pack::Bundle {
@@ -56,7 +67,8 @@ impl pack::Bundle {
             buffered_pack,
             options.iteration_mode,
             pack::data::iter::CompressedBytesMode::CRC32,
-        )?;
+        )?
+        .with_limits(options.limits);
         let pack_kind = pack_entries_iter.kind();
         let (outcome, data_path, index_path) =
             pack::Bundle::inner_write(directory, progress, options, data_file, data_path, pack_entries_iter)?;
@@ -100,7 +112,8 @@ impl pack::Bundle {
             buffered_pack,
             options.iteration_mode,
             pack::data::iter::CompressedBytesMode::CRC32,
-        )?;
+        )?
+        .with_limits(options.limits);
         let pack_kind = pack_entries_iter.kind();
         let num_objects = pack_entries_iter.size_hint().0;
         let pack_entries_iter =
@@ -124,6 +137,8 @@ impl pack::Bundle {
             thread_limit,
             iteration_mode: _,
             index_kind,
+            limits,
+            max_resolved_base_bytes,
         }: Options,
         data_file: Arc<parking_lot::Mutex<NamedTempFile>>,
         data_path: PathBuf,
@@ -140,6 +155,8 @@ impl pack::Bundle {
                     move || new_pack_file_resolver(data_path),
                     pack_entries_iter,
                     thread_limit,
+                    limits,
+                    max_resolved_base_bytes,
                     indexing_progress,
                     &mut index_file,
                 )?;
@@ -168,6 +185,8 @@ impl pack::Bundle {
                     move || new_pack_file_resolver(data_path),
                     pack_entries_iter,
                     thread_limit,
+                    limits,
+                    max_resolved_base_bytes,
                     indexing_progress,
                     io::sink(),
                 )?,
@@ -12,6 +12,13 @@ pub enum Error {
     ZlibInflate(#[from] crate::zlib::Error),
     #[error("A delta chain could not be applied as the ref base with id {0} could not be found")]
     DeltaBaseUnresolved(owned::Id),
+    #[error("The delta chain starting at pack offset {pack_offset} is cyclic")]
+    CyclicDeltaChain { pack_offset: u64 },
+    #[error("The entry at pack offset {pack_offset} exceeds a limit")]
+    Limit {
+        source: crate::limits::Error,
+        pack_offset: u64,
+    },
 }
 
 #[derive(Debug)]
@@ -41,6 +48,15 @@ pub struct Outcome {
     pub object_size: u64,
 }
 
+/// The kind and size of an object as obtained from headers only, see [`File::decode_header()`].
+#[derive(Debug, PartialEq, Eq, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeaderOutcome {
+    pub kind: object::Kind,
+    pub num_deltas: u32,
+    pub object_size: u64,
+}
+
 impl Outcome {
     pub fn default_from_kind(kind: object::Kind) -> Self {
         Self {
@@ -126,6 +142,7 @@ impl File {
         use crate::pack::data::header::Header::*;
         match entry.header {
             Tree | Blob | Commit | Tag => {
+                self.check_object_size(&entry, entry.decompressed_size)?;
                 out.resize(
                     entry
                         .decompressed_size
@@ -145,6 +162,59 @@ impl File {
         }
     }
 
+    /// Obtain the kind and size of the object of `entry` without resolving any delta, by following its delta chain
+    /// through entry headers and decompressing only the header of the first delta.
+    /// `resolve` returns the pack offset of the base of ref deltas, which have to be in this pack.
+    pub fn decode_header(
+        &self,
+        entry: pack::data::Entry,
+        resolve: impl Fn(borrowed::Id<'_>) -> Option<u64>,
+    ) -> Result<HeaderOutcome, Error> {
+        use pack::data::Header;
+        let object_size = if entry.header.is_delta() {
+            // Two sizes of at most 10 bytes each, but deltas may be even smaller than that.
+            let mut buf = [0u8; 20];
+            let len = buf.len().min(entry.decompressed_size as usize);
+            self.decompress_entry_from_data_offset(entry.data_offset, &mut buf[..len])?;
+            let (_base_size, consumed) = delta_header_size_ofs(&buf[..len]);
+            delta_header_size_ofs(&buf[consumed..len]).0
+        } else {
+            entry.decompressed_size
+        };
+
+        let mut cursor = entry.clone();
+        let mut num_deltas = 0;
+        loop {
+            cursor = match cursor.header {
+                Header::Tree | Header::Blob | Header::Commit | Header::Tag => {
+                    return Ok(HeaderOutcome {
+                        kind: cursor.header.to_kind().expect("a non-delta entry"),
+                        num_deltas,
+                        object_size,
+                    })
+                }
+                Header::OfsDelta { base_distance } => self.entry(cursor.base_pack_offset(base_distance)),
+                Header::RefDelta { base_id } => {
+                    self.entry(resolve(base_id.to_borrowed()).ok_or(Error::DeltaBaseUnresolved(base_id))?)
+                }
+            };
+            num_deltas += 1;
+            // Ref deltas could point to each other in broken packs.
+            if num_deltas > self.num_objects() {
+                return Err(Error::CyclicDeltaChain {
+                    pack_offset: entry.pack_offset(),
+                });
+            }
+        }
+    }
+
+    fn check_object_size(&self, entry: &pack::data::Entry, size: u64) -> Result<(), Error> {
+        self.limits.check_object_size(size).map_err(|err| Error::Limit {
+            source: err,
+            pack_offset: entry.pack_offset(),
+        })
+    }
+
     /// resolve: technically, this shoudln't ever be required as stored local packs don't refer to objects by id
     /// that are outside of the pack. Unless, of course, the ref refers to an object within this pack, which means
     /// it's very, very large as 20bytes are smaller than the corresponding MSB encoded number
@@ -176,6 +246,13 @@ impl File {
                 }
                 break;
             }
+            self.check_object_size(&first_entry, cursor.decompressed_size)?;
+            self.limits
+                .check_delta_chain_length(chain.len() as u32 + 1)
+                .map_err(|err| Error::Limit {
+                    source: err,
+                    pack_offset: first_entry.pack_offset(),
+                })?;
             total_delta_data_size += cursor.decompressed_size;
             let decompressed_size = cursor
                 .decompressed_size
@@ -251,6 +328,7 @@ impl File {
                 let (result_size, offset) = delta_header_size_ofs(&instructions[offset..]);
                 bytes_consumed_by_header += offset;
                 biggest_result_size = biggest_result_size.max(result_size);
+                self.check_object_size(&first_entry, biggest_result_size)?;
                 delta.result_size = result_size.try_into().expect("result size fits into usize");
 
                 // the absolute location into the instructions buffer, so we keep track of the end point of the last
@@ -1,25 +1,28 @@
-use crate::pack::data;
+use crate::pack::{self, data};
 use filebuffer::FileBuffer;
 use git_object::SHA1_SIZE;
-use std::{convert::TryFrom, convert::TryInto, path::Path};
+use std::{
+    convert::TryFrom,
+    convert::TryInto,
+    path::{Path, PathBuf},
+};
 
 /// Instantiation
 impl data::File {
     pub fn at(path: impl AsRef<Path>) -> Result<data::File, data::parse::Error> {
         data::File::try_from(path.as_ref())
     }
-}
 
-impl TryFrom<&Path> for data::File {
-    type Error = data::parse::Error;
+    /// Create a pack from `data` held in memory, like a `Vec<u8>` or any other byte source, without touching the disk.
+    ///
+    /// Its [`path()`][data::File::path()] will be empty.
+    pub fn from_data(data: impl AsRef<[u8]> + Send + Sync + 'static) -> Result<data::File, data::parse::Error> {
+        Self::from_buffer(pack::Buffer::new(data), PathBuf::new())
+    }
 
-    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+    fn from_buffer(data: pack::Buffer, path: PathBuf) -> Result<data::File, data::parse::Error> {
         use data::parse::N32_SIZE;
 
-        let data = FileBuffer::open(path).map_err(|e| data::parse::Error::Io {
-            source: e,
-            path: path.to_owned(),
-        })?;
         let pack_len = data.len();
         if pack_len < N32_SIZE * 3 + SHA1_SIZE {
             return Err(data::parse::Error::Corrupt(format!(
@@ -31,9 +34,22 @@ impl TryFrom<&Path> for data::File {
             data::parse::header(&data[..12].try_into().expect("enough data after previous check"))?;
         Ok(data::File {
             data,
-            path: path.to_owned(),
+            path,
             kind,
             num_objects,
+            limits: Default::default(),
         })
     }
 }
+
+impl TryFrom<&Path> for data::File {
+    type Error = data::parse::Error;
+
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        let data = FileBuffer::open(path).map_err(|e| data::parse::Error::Io {
+            source: e,
+            path: path.to_owned(),
+        })?;
+        Self::from_buffer(pack::Buffer::new(data), path.to_owned())
+    }
+}
@@ -12,6 +12,11 @@ pub enum Error {
     PackParse(#[from] pack::data::parse::Error),
     #[error("pack checksum in trailer was {expected}, but actual checksum was {actual}")]
     ChecksumMismatch { expected: owned::Id, actual: owned::Id },
+    #[error("The entry at pack offset {pack_offset} exceeds a limit")]
+    Limit {
+        source: crate::limits::Error,
+        pack_offset: u64,
+    },
 }
 
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
@@ -49,6 +54,7 @@ pub struct Iter<R> {
     mode: Mode,
     compressed: CompressedBytesMode,
     compressed_buf: Option<Vec<u8>>,
+    limits: crate::Limits,
 }
 
 #[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
@@ -108,6 +114,13 @@ where
         self.mode
     }
 
+    /// Fail with an error if an entry declares or decompresses to more bytes than allowed by `limits`.
+    /// This protects against packs received from untrusted sources.
+    pub fn with_limits(mut self, limits: crate::Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     /// Note that `read` is expected at the beginning of a valid pack file with header and trailer
     /// If `verify` is true, we will assert the SHA1 is actually correct before returning the last entry.
     /// Otherwise bit there is a chance that some kinds of bitrot or inconsistencies will not be detected.
@@ -138,6 +151,7 @@ where
             },
             mode: trailer,
             compressed_buf: None,
+            limits: Default::default(),
         })
     }
 
@@ -161,6 +175,14 @@ where
             None => pack::data::Entry::from_read(&mut self.read, self.offset),
         }
         .map_err(Error::from)?;
+        let pack_offset = self.offset;
+        let limit_error = |err| Error::Limit {
+            source: err,
+            pack_offset,
+        };
+        self.limits
+            .check_object_size(entry.decompressed_size)
+            .map_err(limit_error)?;
 
         // Decompress object to learn it's compressed bytes
         let mut decompressor = self.decompressor.take().unwrap_or_default();
@@ -178,14 +200,21 @@ where
             decompressor,
         };
 
-        let bytes_copied = io::copy(&mut decompressed_reader, &mut io::sink())?;
+        let bytes_copied = match self.limits.max_object_size {
+            // Stop right after the limit to not waste time on streams decompressing to more than they declare
+            Some(limit) => io::copy(
+                &mut io::Read::take(&mut decompressed_reader, limit.saturating_add(1)),
+                &mut io::sink(),
+            )?,
+            None => io::copy(&mut decompressed_reader, &mut io::sink())?,
+        };
+        self.limits.check_object_size(bytes_copied).map_err(limit_error)?;
         debug_assert_eq!(
             bytes_copied, entry.decompressed_size,
             "We should have decompressed {} bytes, but got {} instead",
             entry.decompressed_size, bytes_copied
         );
 
-        let pack_offset = self.offset;
         let compressed_size = decompressed_reader.decompressor.total_in;
         self.offset += entry.header_size() as u64 + compressed_size;
         self.decompressor = Some(decompressed_reader.decompressor);
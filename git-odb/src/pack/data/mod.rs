@@ -1,5 +1,4 @@
 //! data within a pack file
-use filebuffer::FileBuffer;
 use std::{convert::TryInto, path::Path};
 
 pub mod decode;
@@ -24,10 +23,11 @@ pub enum Kind {
 }
 
 pub struct File {
-    data: FileBuffer,
+    data: crate::pack::Buffer,
     path: std::path::PathBuf,
     kind: Kind,
     num_objects: u32,
+    limits: crate::Limits,
 }
 
 impl File {
@@ -48,10 +48,22 @@ impl File {
         self.data.len() - SHA1_SIZE
     }
 
+    /// The path from which the pack was loaded, or an empty path if it was created from in-memory data.
     pub fn path(&self) -> &Path {
         &self.path
     }
 
+    /// The limits applied when decoding entries.
+    pub fn limits(&self) -> crate::Limits {
+        self.limits
+    }
+
+    /// Refuse to decode entries exceeding the given `limits`.
+    pub fn with_limits(mut self, limits: crate::Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
     pub fn entry_slice(&self, slice: EntrySlice) -> Option<&[u8]> {
         let entry_end: usize = slice.end.try_into().expect("end of pack fits into usize");
         let entry_start = slice.start as usize;
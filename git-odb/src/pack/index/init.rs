@@ -1,8 +1,15 @@
-use crate::pack::index::{self, Kind, FAN_LEN, V2_SIGNATURE};
+use crate::pack::{
+    self,
+    index::{self, Kind, FAN_LEN, V2_SIGNATURE},
+};
 use byteorder::{BigEndian, ByteOrder};
 use filebuffer::FileBuffer;
 use git_object::SHA1_SIZE;
-use std::{convert::TryFrom, mem::size_of, path::Path};
+use std::{
+    convert::TryFrom,
+    mem::size_of,
+    path::{Path, PathBuf},
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -25,16 +32,15 @@ impl index::File {
     pub fn at(path: impl AsRef<Path>) -> Result<index::File, Error> {
         Self::try_from(path.as_ref())
     }
-}
 
-impl TryFrom<&Path> for index::File {
-    type Error = Error;
+    /// Create an index from `data` held in memory, like a `Vec<u8>` or any other byte source, without touching the disk.
+    ///
+    /// Its [`path()`][index::File::path()] will be empty.
+    pub fn from_data(data: impl AsRef<[u8]> + Send + Sync + 'static) -> Result<index::File, Error> {
+        Self::from_buffer(pack::Buffer::new(data), PathBuf::new())
+    }
 
-    fn try_from(path: &Path) -> Result<Self, Self::Error> {
-        let data = FileBuffer::open(&path).map_err(|e| Error::Io {
-            source: e,
-            path: path.to_owned(),
-        })?;
+    fn from_buffer(data: pack::Buffer, path: PathBuf) -> Result<index::File, Error> {
         let idx_len = data.len();
         if idx_len < FAN_LEN * N32_SIZE + FOOTER_SIZE {
             return Err(Error::Corrupt {
@@ -70,7 +76,7 @@ impl TryFrom<&Path> for index::File {
         };
         Ok(index::File {
             data,
-            path: path.to_owned(),
+            path,
             kind,
             num_objects,
             version,
@@ -79,6 +85,18 @@ impl TryFrom<&Path> for index::File {
     }
 }
 
+impl TryFrom<&Path> for index::File {
+    type Error = Error;
+
+    fn try_from(path: &Path) -> Result<Self, Self::Error> {
+        let data = FileBuffer::open(&path).map_err(|e| Error::Io {
+            source: e,
+            path: path.to_owned(),
+        })?;
+        Self::from_buffer(pack::Buffer::new(data), path.to_owned())
+    }
+}
+
 fn read_fan(d: &[u8]) -> ([u32; FAN_LEN], usize) {
     let mut fan = [0; FAN_LEN];
     for (c, f) in d.chunks(N32_SIZE).zip(fan.iter_mut()) {
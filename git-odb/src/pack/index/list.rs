@@ -0,0 +1,103 @@
+use crate::pack::{
+    self, index,
+    index::{
+        traverse::chain::{self, BASE_NOT_IN_PACK, NO_BASE},
+        util,
+    },
+};
+use git_features::progress::Progress;
+use git_object::owned;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("The base of the delta at pack offset {pack_offset} was not found in this pack")]
+    BaseNotFound { pack_offset: u64 },
+    #[error("The delta chain starting at pack offset {pack_offset} does not end at a base object")]
+    InvalidDeltaChain { pack_offset: u64 },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Information about the base of an entry that is a delta
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Delta {
+    /// The amount of deltas to apply to a base object to obtain the entry's object, 1 or more.
+    pub chain_length: u32,
+    /// The id of the object this delta is applied to, which may be a delta itself.
+    pub base_id: owned::Id,
+}
+
+/// An entry of a pack with the information `git verify-pack -v` shows for it
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    pub id: owned::Id,
+    /// The kind of the object after resolving all deltas
+    pub kind: git_object::Kind,
+    /// The size of the entry's data after decompression, which is the size of the delta for delta objects
+    pub size: u64,
+    /// The amount of bytes the entry occupies in the pack, including its header
+    pub size_in_pack: u64,
+    /// The offset of the entry's header in the pack
+    pub pack_offset: u64,
+    /// Set if the entry is a delta
+    pub delta: Option<Delta>,
+}
+
+/// Listing of entries
+impl index::File {
+    /// Return information about all entries in `pack` in the order they are stored in, as obtained from entry headers
+    /// without decompressing any object data.
+    pub fn list_entries(&self, pack: &pack::data::File, progress: impl Progress) -> Result<Vec<Entry>, Error> {
+        let mut progress = progress;
+        let entries =
+            util::index_entries_sorted_by_offset_ascending(self, progress.add_child("collecting sorted index"));
+        let bases = chain::bases(self, pack, &entries, progress.add_child("following delta chains"))
+            .ok_or(Error::Interrupted)?;
+        let depths = chain::depths(&bases);
+
+        // The kind of a delta is the one of its base, which is known once all entries of lower depth are handled.
+        // Bases which don't have a lower depth than their delta are part of a cycle.
+        let mut by_depth: Vec<_> = (0..entries.len()).collect();
+        by_depth.sort_by_key(|idx| depths[*idx]);
+        let mut kinds = vec![None; entries.len()];
+        for idx in by_depth {
+            kinds[idx] = match bases[idx] {
+                NO_BASE => pack.entry(entries[idx].pack_offset).header.to_kind(),
+                BASE_NOT_IN_PACK => {
+                    return Err(Error::BaseNotFound {
+                        pack_offset: entries[idx].pack_offset,
+                    })
+                }
+                base if depths[base as usize] + 1 == depths[idx] => kinds[base as usize],
+                _ => None,
+            };
+        }
+
+        let mut out = Vec::with_capacity(entries.len());
+        for (idx, index_entry) in entries.iter().enumerate() {
+            let next_offset = entries
+                .get(idx + 1)
+                .map(|e| e.pack_offset)
+                .unwrap_or(pack.pack_end() as u64);
+            out.push(Entry {
+                id: index_entry.oid,
+                kind: kinds[idx].ok_or(Error::InvalidDeltaChain {
+                    pack_offset: index_entry.pack_offset,
+                })?,
+                size: pack.entry(index_entry.pack_offset).decompressed_size,
+                size_in_pack: next_offset - index_entry.pack_offset,
+                pack_offset: index_entry.pack_offset,
+                delta: match bases[idx] {
+                    NO_BASE => None,
+                    base => Some(Delta {
+                        chain_length: depths[idx],
+                        base_id: entries[base as usize].oid,
+                    }),
+                },
+            });
+        }
+        Ok(out)
+    }
+}
@@ -73,8 +73,6 @@ macro_rules! izip {
     };
 }
 
-use filebuffer::FileBuffer;
-
 #[derive(PartialEq, Eq, Ord, PartialOrd, Debug, Hash, Clone, Copy)]
 #[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
 pub enum Kind {
@@ -100,7 +98,7 @@ impl Kind {
 const FAN_LEN: usize = 256;
 
 pub struct File {
-    pub(crate) data: FileBuffer,
+    pub(crate) data: crate::pack::Buffer,
     path: std::path::PathBuf,
     kind: Kind,
     version: u32,
@@ -112,6 +110,7 @@ impl File {
     pub fn kind(&self) -> Kind {
         self.kind
     }
+    /// The path from which the index was loaded, or an empty path if it was created from in-memory data.
     pub fn path(&self) -> &std::path::Path {
         &self.path
     }
@@ -129,6 +128,7 @@ pub mod init;
 pub(crate) mod access;
 pub use access::Entry;
 
+pub mod list;
 pub mod traverse;
 pub(crate) mod util;
 pub mod verify;
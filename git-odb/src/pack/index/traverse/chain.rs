@@ -0,0 +1,110 @@
+use crate::pack::{self, index};
+use git_features::{
+    interrupt::is_triggered,
+    progress::{self, Progress},
+};
+use std::time::Instant;
+
+/// The base of entries which are not a delta.
+pub(crate) const NO_BASE: u32 = u32::MAX;
+/// The base of deltas whose base is not contained in the pack, as in thin packs.
+pub(crate) const BASE_NOT_IN_PACK: u32 = u32::MAX - 1;
+
+/// Return the index of the base of each of the `entries` sorted by pack offset, or one of [`NO_BASE`] and
+/// [`BASE_NOT_IN_PACK`], as obtained from pack entry headers only, or `None` if interrupted.
+pub(crate) fn bases(
+    index: &index::File,
+    pack: &pack::data::File,
+    entries: &[index::Entry],
+    mut progress: impl Progress,
+) -> Option<Vec<u32>> {
+    use pack::data::Header;
+    progress.init(Some(entries.len()), progress::count("entries"));
+    let start = Instant::now();
+
+    let index_by_offset = |offset: u64| {
+        entries
+            .binary_search_by_key(&offset, |e| e.pack_offset)
+            .map(|idx| idx as u32)
+            .unwrap_or(BASE_NOT_IN_PACK)
+    };
+    let mut bases = Vec::with_capacity(entries.len());
+    for (idx, entry) in entries.iter().enumerate() {
+        let pack_entry = pack.entry(entry.pack_offset);
+        bases.push(match pack_entry.header {
+            Header::OfsDelta { base_distance } => index_by_offset(pack_entry.base_pack_offset(base_distance)),
+            Header::RefDelta { base_id } => index
+                .lookup(base_id.to_borrowed())
+                .map_or(BASE_NOT_IN_PACK, |base_index| {
+                    index_by_offset(index.pack_offset_at_index(base_index))
+                }),
+            Header::Tree | Header::Blob | Header::Commit | Header::Tag => NO_BASE,
+        });
+        progress.inc();
+        if idx % 10_000 == 0 && is_triggered() {
+            return None;
+        }
+    }
+    progress.show_throughput(start);
+    Some(bases)
+}
+
+/// Return the amount of deltas to apply to obtain the object of each entry from the `bases` of all entries,
+/// looking at each entry only once.
+///
+/// Deltas whose base is not in the pack count as a single delta, and chains ending in a cycle, which exist only in
+/// broken packs, end where the cycle starts.
+pub(crate) fn depths(bases: &[u32]) -> Vec<u32> {
+    const UNKNOWN: u32 = u32::MAX;
+    const IN_PROGRESS: u32 = u32::MAX - 1;
+    let mut depths = vec![UNKNOWN; bases.len()];
+    let mut path = Vec::new();
+    for start in 0..bases.len() {
+        let mut idx = start;
+        let mut depth = loop {
+            match depths[idx] {
+                UNKNOWN => {}
+                IN_PROGRESS => break 0,
+                depth => break depth,
+            }
+            match bases[idx] {
+                NO_BASE => {
+                    depths[idx] = 0;
+                    break 0;
+                }
+                BASE_NOT_IN_PACK => {
+                    depths[idx] = 1;
+                    break 1;
+                }
+                base => {
+                    depths[idx] = IN_PROGRESS;
+                    path.push(idx);
+                    idx = base as usize;
+                }
+            }
+        };
+        for idx in path.drain(..).rev() {
+            depth += 1;
+            depths[idx] = depth;
+        }
+    }
+    depths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depths_follow_bases_in_any_direction() {
+        assert_eq!(
+            depths(&[NO_BASE, 0, 1, 4, NO_BASE, BASE_NOT_IN_PACK, 5]),
+            vec![0, 1, 2, 1, 0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn depths_of_cycles_are_finite() {
+        assert_eq!(depths(&[1, 0, 0]), vec![2, 1, 3]);
+    }
+}
@@ -0,0 +1,255 @@
+use super::{chain, skip_non_fatal_decode_error, traversal_progress, traverse_chunks, Error, Reducer, SafetyCheck};
+use crate::pack::{self, data::decode, index, index::util};
+use git_features::{parallel, progress::Progress};
+use git_object::owned;
+
+/// The sentinel for entries without a base in this pack.
+const NO_BASE: u32 = u32::MAX;
+
+/// A dependency graph between pack entries and their delta bases, built from entry headers only.
+///
+/// It stores a few bytes per entry, and none of the object data.
+struct Dag {
+    /// All entries sorted by pack offset, referred to by index in the fields below.
+    entries: Vec<index::Entry>,
+    /// The index of the entry's base, or `NO_BASE` if it is a base object or a base outside of the pack.
+    bases: Vec<u32>,
+    /// The length of the delta chain of each entry, including links not represented in this graph.
+    depths: Vec<u32>,
+    /// `children[first_child[i]..first_child[i + 1]]` are the indices of all deltas using `i` as base.
+    first_child: Vec<u32>,
+    children: Vec<u32>,
+}
+
+impl Dag {
+    fn from_index<E>(
+        index: &index::File,
+        pack: &pack::data::File,
+        mut progress: impl Progress,
+    ) -> Result<Self, Error<E>>
+    where
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let entries =
+            util::index_entries_sorted_by_offset_ascending(index, progress.add_child("collecting sorted index"));
+        let mut bases = chain::bases(index, pack, &entries, progress.add_child("building delta graph"))
+            .ok_or(Error::Interrupted)?;
+        let depths = chain::depths(&bases);
+
+        // Bases of completed thin packs are appended, so deltas may point forward. Only edges pointing
+        // backwards are kept, which also assures there are no cycles in broken packs.
+        let mut num_children = vec![0u32; entries.len() + 1];
+        for (idx, base) in bases.iter_mut().enumerate() {
+            if (*base as usize) < idx {
+                num_children[*base as usize] += 1;
+            } else {
+                *base = NO_BASE;
+            }
+        }
+
+        let mut first_child = num_children;
+        let mut offset = 0;
+        for count in first_child.iter_mut() {
+            let num = *count;
+            *count = offset;
+            offset += num;
+        }
+        let mut children = vec![0u32; offset as usize];
+        let mut cursor: Vec<u32> = first_child[..entries.len()].to_owned();
+        for (idx, base) in bases.iter().enumerate() {
+            if *base != NO_BASE {
+                let slot = &mut cursor[*base as usize];
+                children[*slot as usize] = idx as u32;
+                *slot += 1;
+            }
+        }
+
+        Ok(Dag {
+            entries,
+            bases,
+            depths,
+            first_child,
+            children,
+        })
+    }
+
+    fn children(&self, idx: u32) -> &[u32] {
+        let idx = idx as usize;
+        &self.children[self.first_child[idx] as usize..self.first_child[idx + 1] as usize]
+    }
+
+    fn roots(&self) -> Vec<u32> {
+        self.bases
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, base)| if *base == NO_BASE { Some(idx as u32) } else { None })
+            .collect()
+    }
+}
+
+struct PathEntry {
+    data_offset: u64,
+    data: Vec<u8>,
+    kind: git_object::Kind,
+    compressed_size: usize,
+    remaining_children: usize,
+}
+
+/// A cache holding the decoded bases along the path currently traversed in the delta graph, each of which is
+/// dropped once all of its children were decoded.
+/// Anything else is delegated to the `inner` cache.
+struct PathCache<C> {
+    path: Vec<PathEntry>,
+    inner: C,
+}
+
+impl<C> PathCache<C> {
+    fn keep(
+        &mut self,
+        data_offset: u64,
+        data: &[u8],
+        kind: git_object::Kind,
+        compressed_size: usize,
+        num_children: usize,
+    ) {
+        self.path.push(PathEntry {
+            data_offset,
+            data: data.to_owned(),
+            kind,
+            compressed_size,
+            remaining_children: num_children,
+        })
+    }
+
+    fn child_done(&mut self, base_data_offset: u64) {
+        if let Some(pos) = self.path.iter().rposition(|e| e.data_offset == base_data_offset) {
+            let entry = &mut self.path[pos];
+            entry.remaining_children -= 1;
+            if entry.remaining_children == 0 {
+                self.path.remove(pos);
+            }
+        }
+    }
+}
+
+impl<C> pack::cache::DecodeEntry for PathCache<C>
+where
+    C: pack::cache::DecodeEntry,
+{
+    fn put(&mut self, offset: u64, data: &[u8], kind: git_object::Kind, compressed_size: usize) {
+        self.inner.put(offset, data, kind, compressed_size)
+    }
+
+    fn get(&mut self, offset: u64, out: &mut Vec<u8>) -> Option<(git_object::Kind, usize)> {
+        match self.path.iter().rev().find(|e| e.data_offset == offset) {
+            Some(e) => {
+                out.resize(e.data.len(), 0);
+                out.copy_from_slice(&e.data);
+                Some((e.kind, e.compressed_size))
+            }
+            None => self.inner.get(offset, out),
+        }
+    }
+}
+
+/// Verify and validate the content of the index file
+impl index::File {
+    /// Like [`traverse_with_lookup()`][index::File::traverse_with_lookup()], but process objects in an order derived
+    /// from a dependency graph of deltas and their bases, built from pack entry headers.
+    ///
+    /// Each thread handles whole delta trees depth first and keeps the decoded bases on its current path until
+    /// all of their deltas are done, so every delta is applied only once without keeping any other objects in memory.
+    pub fn traverse_with_delta_dag<P, C, Processor, E>(
+        &self,
+        check: SafetyCheck,
+        thread_limit: Option<usize>,
+        new_processor: impl Fn() -> Processor + Send + Sync,
+        new_cache: impl Fn() -> C + Send + Sync,
+        mut root: P,
+        pack: &pack::data::File,
+    ) -> Result<(owned::Id, index::traverse::Outcome, P), Error<E>>
+    where
+        P: Progress,
+        C: pack::cache::DecodeEntry,
+        E: std::error::Error + Send + Sync + 'static,
+        Processor: FnMut(
+            git_object::Kind,
+            &[u8],
+            &index::Entry,
+            &mut <<P as Progress>::SubProgress as Progress>::SubProgress,
+        ) -> Result<(), E>,
+    {
+        let (id, res) = self.verify_while_traversing(pack, check, &mut root, |root| {
+            let dag = Dag::from_index(self, pack, root.add_child("delta graph"))?;
+            let roots = dag.roots();
+
+            let (chunk_size, thread_limit, available_cores) =
+                parallel::optimize_chunk_size_and_thread_limit(50, Some(roots.len()), thread_limit, None);
+            let there_are_enough_entries_to_process = || dag.entries.len() > 1000 * available_cores;
+            let reduce_progress = traversal_progress(root, self.num_objects());
+
+            traverse_chunks(
+                &reduce_progress,
+                roots.chunks(chunk_size),
+                thread_limit,
+                there_are_enough_entries_to_process,
+                || PathCache {
+                    path: Vec::new(),
+                    inner: new_cache(),
+                },
+                new_processor,
+                |roots: &[u32], state| -> Result<Vec<decode::Outcome>, Error<_>> {
+                    state.init_progress(None);
+                    let mut stats = Vec::new();
+                    let mut nodes: Vec<(u32, Option<u64>)> = Vec::new();
+                    for root in roots.iter().rev() {
+                        nodes.push((*root, None));
+                        while let Some((idx, base_data_offset)) = nodes.pop() {
+                            let index_entry = &dag.entries[idx as usize];
+                            let result = self.decode_and_process_entry(
+                                check,
+                                pack,
+                                &mut state.cache,
+                                &mut state.buf,
+                                &mut state.progress,
+                                &mut state.header_buf,
+                                index_entry,
+                                &mut state.processor,
+                            );
+                            state.progress.inc();
+                            if let Some(base_data_offset) = base_data_offset {
+                                state.cache.child_done(base_data_offset);
+                            }
+                            let children = dag.children(idx);
+                            let mut stat = match skip_non_fatal_decode_error(check, result, &mut state.progress)? {
+                                Some(stat) => stat,
+                                None => {
+                                    // Children can still be decoded without a cached base.
+                                    nodes.extend(children.iter().rev().map(|child| (*child, None)));
+                                    continue;
+                                }
+                            };
+                            // Deltas are resolved from a cached base, which hides the length of their chain.
+                            stat.num_deltas = dag.depths[idx as usize];
+                            if !children.is_empty() {
+                                let data_offset = pack.entry(index_entry.pack_offset).data_offset;
+                                state.cache.keep(
+                                    data_offset,
+                                    &state.buf,
+                                    stat.kind,
+                                    stat.compressed_size,
+                                    children.len(),
+                                );
+                                nodes.extend(children.iter().rev().map(|child| (*child, Some(data_offset))));
+                            }
+                            stats.push(stat);
+                        }
+                    }
+                    Ok(stats)
+                },
+                Reducer::from_progress(&reduce_progress, pack.data_len(), check),
+            )
+        })?;
+        Ok((id, res, root))
+    }
+}
@@ -46,10 +46,10 @@ impl index::File {
             || -> Result<_, Error<_>> {
                 let sorted_entries =
                     index_entries_sorted_by_offset_ascending(self, root.add_child("collecting sorted index"));
-                let tree = pack::tree::Tree::from_offsets_in_pack(
+                let tree = pack::tree::Tree::from_offsets_in_pack_data(
                     sorted_entries.into_iter().map(EntryWithDefault::from),
                     |e| e.index_entry.pack_offset,
-                    pack.path(),
+                    pack,
                     root.add_child("indexing"),
                     |id| self.lookup(id).map(|idx| self.pack_offset_at_index(idx)),
                 )?;
@@ -61,6 +61,8 @@ impl index::File {
                     root.add_child("Decoding"),
                     thread_limit,
                     pack.pack_end() as u64,
+                    pack.limits(),
+                    None,
                     || (new_processor(), [0u8; 64]),
                     |data,
                      progress,
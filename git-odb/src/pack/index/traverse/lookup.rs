@@ -1,10 +1,6 @@
-use super::{Error, Reducer, SafetyCheck};
+use super::{skip_non_fatal_decode_error, traversal_progress, traverse_chunks, Error, Reducer, SafetyCheck};
 use crate::pack::{self, data::decode, index, index::util};
-use git_features::{
-    interrupt::ResetOnDrop,
-    parallel::{self, in_parallel_if},
-    progress::{self, unit, Progress},
-};
+use git_features::{parallel, progress::Progress};
 use git_object::owned;
 
 /// Verify and validate the content of the index file
@@ -29,87 +25,46 @@ impl index::File {
             &mut <<P as Progress>::SubProgress as Progress>::SubProgress,
         ) -> Result<(), E>,
     {
-        let _reset_interrupt = ResetOnDrop::default();
-        let (verify_result, traversal_result) = parallel::join(
-            {
-                let pack_progress = root.add_child("SHA1 of pack");
-                let index_progress = root.add_child("SHA1 of index");
-                move || {
-                    let res = self.possibly_verify(pack, check, pack_progress, index_progress);
-                    if res.is_err() {
-                        git_features::interrupt::trigger();
-                    }
-                    res
-                }
-            },
-            || {
-                let index_entries =
-                    util::index_entries_sorted_by_offset_ascending(self, root.add_child("collecting sorted index"));
+        let (id, res) = self.verify_while_traversing(pack, check, &mut root, |root| {
+            let index_entries =
+                util::index_entries_sorted_by_offset_ascending(self, root.add_child("collecting sorted index"));
 
-                let (chunk_size, thread_limit, available_cores) =
-                    parallel::optimize_chunk_size_and_thread_limit(1000, Some(index_entries.len()), thread_limit, None);
-                let there_are_enough_entries_to_process = || index_entries.len() > chunk_size * available_cores;
-                let input_chunks = index_entries.chunks(chunk_size.max(chunk_size));
-                let reduce_progress = parking_lot::Mutex::new({
-                    let mut p = root.add_child("Traversing");
-                    p.init(Some(self.num_objects() as usize), progress::count("objects"));
-                    p
-                });
-                let state_per_thread = |index| {
-                    (
-                        new_cache(),
-                        new_processor(),
-                        Vec::with_capacity(2048), // decode buffer
-                        reduce_progress.lock().add_child(format!("thread {}", index)), // per thread progress
-                    )
-                };
+            let (chunk_size, thread_limit, available_cores) =
+                parallel::optimize_chunk_size_and_thread_limit(1000, Some(index_entries.len()), thread_limit, None);
+            let there_are_enough_entries_to_process = || index_entries.len() > chunk_size * available_cores;
+            let reduce_progress = traversal_progress(root, self.num_objects());
 
-                in_parallel_if(
-                    there_are_enough_entries_to_process,
-                    input_chunks,
-                    thread_limit,
-                    state_per_thread,
-                    |entries: &[index::Entry],
-                     (cache, ref mut processor, buf, progress)|
-                     -> Result<Vec<decode::Outcome>, Error<_>> {
-                        progress.init(
-                            Some(entries.len()),
-                            Some(unit::dynamic(unit::Human::new(
-                                unit::human::Formatter::new(),
-                                "objects",
-                            ))),
+            traverse_chunks(
+                &reduce_progress,
+                index_entries.chunks(chunk_size),
+                thread_limit,
+                there_are_enough_entries_to_process,
+                new_cache,
+                new_processor,
+                |entries: &[index::Entry], state| -> Result<Vec<decode::Outcome>, Error<_>> {
+                    state.init_progress(Some(entries.len()));
+                    let mut stats = Vec::with_capacity(entries.len());
+                    for index_entry in entries.iter() {
+                        let result = self.decode_and_process_entry(
+                            check,
+                            pack,
+                            &mut state.cache,
+                            &mut state.buf,
+                            &mut state.progress,
+                            &mut state.header_buf,
+                            index_entry,
+                            &mut state.processor,
                         );
-                        let mut stats = Vec::with_capacity(entries.len());
-                        let mut header_buf = [0u8; 64];
-                        for index_entry in entries.iter() {
-                            let result = self.decode_and_process_entry(
-                                check,
-                                pack,
-                                cache,
-                                buf,
-                                progress,
-                                &mut header_buf,
-                                index_entry,
-                                processor,
-                            );
-                            progress.inc();
-                            let stat = match result {
-                                Err(err @ Error::PackDecode { .. }) if !check.fatal_decode_error() => {
-                                    progress.info(format!("Ignoring decode error: {}", err));
-                                    continue;
-                                }
-                                res => res,
-                            }?;
+                        state.progress.inc();
+                        if let Some(stat) = skip_non_fatal_decode_error(check, result, &mut state.progress)? {
                             stats.push(stat);
                         }
-                        Ok(stats)
-                    },
-                    Reducer::from_progress(&reduce_progress, pack.data_len(), check),
-                )
-            },
-        );
-        let id = verify_result?;
-        let res = traversal_result?;
+                    }
+                    Ok(stats)
+                },
+                Reducer::from_progress(&reduce_progress, pack.data_len(), check),
+            )
+        })?;
         Ok((id, res, root))
     }
 }
@@ -1,12 +1,17 @@
 use crate::pack::{self, index};
 use git_features::{
-    parallel,
-    progress::{self, Progress},
+    interrupt::ResetOnDrop,
+    parallel::{self, in_parallel_if},
+    progress::{self, unit, Progress},
 };
 use git_object::owned;
 
+pub(crate) mod chain;
+mod dag;
 mod indexed;
 mod lookup;
+mod objects;
+pub use objects::Object;
 mod reduce;
 pub(crate) use reduce::Reducer;
 
@@ -47,10 +52,45 @@ impl index::File {
                 self.traverse_with_lookup(check, thread_limit, new_processor, new_cache, progress, pack)
             }
             Algorithm::DeltaTreeLookup => self.traverse_with_index(check, thread_limit, new_processor, progress, pack),
+            Algorithm::DeltaDagLookup => {
+                self.traverse_with_delta_dag(check, thread_limit, new_processor, new_cache, progress, pack)
+            }
         }
         .map(|(a, b, p)| (a, b, p.into_inner()))
     }
 
+    /// Run `traverse` while verifying the checksums of `pack` and this index in parallel if `check` asks for it,
+    /// and return the index checksum along with the result of the traversal.
+    ///
+    /// A failed verification interrupts the traversal.
+    pub(crate) fn verify_while_traversing<P, T, E>(
+        &self,
+        pack: &pack::data::File,
+        check: SafetyCheck,
+        root: &mut P,
+        traverse: impl FnOnce(&mut P) -> Result<T, Error<E>> + Send,
+    ) -> Result<(owned::Id, T), Error<E>>
+    where
+        P: Progress,
+        T: Send,
+        E: std::error::Error + Send + Sync + 'static,
+    {
+        let _reset_interrupt = ResetOnDrop::default();
+        let pack_progress = root.add_child("SHA1 of pack");
+        let index_progress = root.add_child("SHA1 of index");
+        let (verify_result, traversal_result) = parallel::join(
+            move || {
+                let res = self.possibly_verify(pack, check, pack_progress, index_progress);
+                if res.is_err() {
+                    git_features::interrupt::trigger();
+                }
+                res
+            },
+            || traverse(root),
+        );
+        Ok((verify_result?, traversal_result?))
+    }
+
     pub(crate) fn possibly_verify<E>(
         &self,
         pack: &pack::data::File,
@@ -178,3 +218,91 @@ where
     }
     processor(object_kind, decompressed, &index_entry, progress).map_err(Error::Processor)
 }
+
+/// The state of each thread processing chunks of a traversal in [`traverse_chunks()`].
+pub(crate) struct ThreadState<C, Processor, P> {
+    pub cache: C,
+    pub processor: Processor,
+    /// The buffer to decode objects into.
+    pub buf: Vec<u8>,
+    pub header_buf: [u8; 64],
+    pub progress: P,
+}
+
+impl<C, Processor, P> ThreadState<C, Processor, P>
+where
+    P: Progress,
+{
+    /// Prepare our progress for counting the objects of a new chunk, `num_objects` of them if known.
+    pub fn init_progress(&mut self, num_objects: Option<usize>) {
+        self.progress.init(
+            num_objects,
+            Some(unit::dynamic(unit::Human::new(
+                unit::human::Formatter::new(),
+                "objects",
+            ))),
+        );
+    }
+}
+
+/// Create the progress shared by all threads of a traversal of `num_objects` as child of `root`.
+pub(crate) fn traversal_progress<P: Progress>(root: &mut P, num_objects: u32) -> parking_lot::Mutex<P::SubProgress> {
+    let mut progress = root.add_child("Traversing");
+    progress.init(Some(num_objects as usize), progress::count("objects"));
+    parking_lot::Mutex::new(progress)
+}
+
+/// Pass each of the `chunks` to `consume` on up to `thread_limit` threads if `in_parallel()` agrees, along with the
+/// [state][ThreadState] of the thread it runs on, and feed all results into `reducer`.
+///
+/// The progress of each thread is a child of `reduce_progress`, as obtained by [`traversal_progress()`].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn traverse_chunks<Chunk, P, C, Processor, O, R>(
+    reduce_progress: &parking_lot::Mutex<P>,
+    chunks: impl Iterator<Item = Chunk> + Send,
+    thread_limit: Option<usize>,
+    in_parallel: impl FnOnce() -> bool,
+    new_cache: impl Fn() -> C + Send + Sync,
+    new_processor: impl Fn() -> Processor + Send + Sync,
+    consume: impl Fn(Chunk, &mut ThreadState<C, Processor, P::SubProgress>) -> O + Send + Sync,
+    reducer: R,
+) -> Result<R::Output, R::Error>
+where
+    Chunk: Send,
+    P: Progress,
+    O: Send,
+    R: parallel::Reducer<Input = O>,
+{
+    in_parallel_if(
+        in_parallel,
+        chunks,
+        thread_limit,
+        |index| ThreadState {
+            cache: new_cache(),
+            processor: new_processor(),
+            buf: Vec::with_capacity(2048),
+            header_buf: [0u8; 64],
+            progress: reduce_progress.lock().add_child(format!("thread {}", index)),
+        },
+        consume,
+        reducer,
+    )
+}
+
+/// Return `None` instead of decode errors which `check` doesn't consider fatal, after logging them to `progress`.
+pub(crate) fn skip_non_fatal_decode_error<T, E>(
+    check: SafetyCheck,
+    result: Result<T, Error<E>>,
+    progress: &mut impl Progress,
+) -> Result<Option<T>, Error<E>>
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    match result {
+        Err(err @ Error::PackDecode { .. }) if !check.fatal_decode_error() => {
+            progress.info(format!("Ignoring decode error: {}", err));
+            Ok(None)
+        }
+        res => res.map(Some),
+    }
+}
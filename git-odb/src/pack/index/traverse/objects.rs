@@ -0,0 +1,159 @@
+use super::{chain, skip_non_fatal_decode_error, traversal_progress, traverse_chunks, Error, SafetyCheck};
+use crate::pack::{self, index, index::util};
+use git_features::{
+    interrupt::is_triggered,
+    parallel,
+    progress::{self, Progress},
+};
+use git_object::owned;
+
+/// A fully decoded object as passed to the processor of [`index::File::traverse_objects()`].
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Copy)]
+pub struct Object<'a> {
+    /// The id of the object as stored in the index file
+    pub id: owned::Id,
+    /// The kind of the object after resolving all of its deltas
+    pub kind: git_object::Kind,
+    /// The decompressed and delta-resolved object data
+    pub data: &'a [u8],
+    /// The offset of the object's entry in the pack data file
+    pub pack_offset: u64,
+    /// The amount of deltas between this object and its base, 0 if the object is a base itself
+    pub delta_depth: u32,
+}
+
+/// Traverse all objects in parallel, reducing their processing results with a user defined reducer
+impl index::File {
+    /// Decode all objects in `pack` in parallel, in order of their pack offset, and pass each one to a processor created
+    /// by `new_processor()` for each thread.
+    ///
+    /// The results produced by the processor for each chunk of objects are fed into `reducer`, whose output is returned
+    /// along with the index file checksum once all objects were seen.
+    /// Decode errors are skipped if `check` doesn't consider them fatal.
+    #[allow(clippy::too_many_arguments)]
+    pub fn traverse_objects<P, C, Processor, T, E, R>(
+        &self,
+        pack: &pack::data::File,
+        progress: Option<P>,
+        thread_limit: Option<usize>,
+        check: SafetyCheck,
+        new_processor: impl Fn() -> Processor + Send + Sync,
+        new_cache: impl Fn() -> C + Send + Sync,
+        reducer: R,
+    ) -> Result<(owned::Id, R::Output, Option<P>), Error<E>>
+    where
+        P: Progress,
+        C: pack::cache::DecodeEntry,
+        T: Send,
+        E: std::error::Error + Send + Sync + 'static,
+        R: parallel::Reducer<Input = Vec<T>, Error = E> + Send,
+        R::Output: Send,
+        Processor: FnMut(
+            Object<'_>,
+            &mut <<progress::DoOrDiscard<P> as Progress>::SubProgress as Progress>::SubProgress,
+        ) -> Result<T, E>,
+    {
+        let mut root = progress::DoOrDiscard::from(progress);
+        let (id, res) = self.verify_while_traversing(pack, check, &mut root, |root| {
+            let index_entries =
+                util::index_entries_sorted_by_offset_ascending(self, root.add_child("collecting sorted index"));
+            let delta_depths = chain::depths(
+                &chain::bases(self, pack, &index_entries, root.add_child("following delta chains"))
+                    .ok_or(Error::Interrupted)?,
+            );
+
+            let (chunk_size, thread_limit, available_cores) =
+                parallel::optimize_chunk_size_and_thread_limit(1000, Some(index_entries.len()), thread_limit, None);
+            let there_are_enough_entries_to_process = || index_entries.len() > chunk_size * available_cores;
+            let reduce_progress = traversal_progress(root, self.num_objects());
+
+            traverse_chunks(
+                &reduce_progress,
+                index_entries.chunks(chunk_size).zip(delta_depths.chunks(chunk_size)),
+                thread_limit,
+                there_are_enough_entries_to_process,
+                new_cache,
+                new_processor,
+                |(entries, delta_depths): (&[index::Entry], &[u32]), state| -> Result<Vec<T>, Error<E>> {
+                    state.init_progress(Some(entries.len()));
+                    let mut out = Vec::with_capacity(entries.len());
+                    for (index_entry, delta_depth) in entries.iter().zip(delta_depths) {
+                        let processor = &mut state.processor;
+                        let result = self.decode_and_process_entry(
+                            check,
+                            pack,
+                            &mut state.cache,
+                            &mut state.buf,
+                            &mut state.progress,
+                            &mut state.header_buf,
+                            index_entry,
+                            &mut |kind, data, index_entry, progress| {
+                                out.push(processor(
+                                    Object {
+                                        id: index_entry.oid,
+                                        kind,
+                                        data,
+                                        pack_offset: index_entry.pack_offset,
+                                        delta_depth: *delta_depth,
+                                    },
+                                    progress,
+                                )?);
+                                Ok(())
+                            },
+                        );
+                        state.progress.inc();
+                        skip_non_fatal_decode_error(check, result, &mut state.progress)?;
+                    }
+                    Ok(out)
+                },
+                Reducer {
+                    progress: &reduce_progress,
+                    check,
+                    entries_seen: 0,
+                    inner: reducer,
+                },
+            )
+        })?;
+        Ok((id, res, root.into_inner()))
+    }
+}
+
+struct Reducer<'a, P, R> {
+    progress: &'a parking_lot::Mutex<P>,
+    check: SafetyCheck,
+    entries_seen: usize,
+    inner: R,
+}
+
+impl<'a, P, R, T, E> parallel::Reducer for Reducer<'a, P, R>
+where
+    P: Progress,
+    R: parallel::Reducer<Input = Vec<T>, Error = E>,
+    E: std::error::Error + Send + Sync + 'static,
+{
+    type Input = Result<Vec<T>, Error<E>>;
+    type Output = R::Output;
+    type Error = Error<E>;
+
+    fn feed(&mut self, input: Self::Input) -> Result<(), Self::Error> {
+        let chunk = match input {
+            Err(err @ Error::PackDecode { .. }) if !self.check.fatal_decode_error() => {
+                self.progress.lock().info(format!("Ignoring decode error: {}", err));
+                return Ok(());
+            }
+            res => res,
+        }?;
+        self.entries_seen += chunk.len();
+        self.inner.feed(chunk).map_err(Error::Processor)?;
+        self.progress.lock().set(self.entries_seen);
+
+        if is_triggered() {
+            return Err(Error::Interrupted);
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        self.inner.finalize().map_err(Error::Processor)
+    }
+}
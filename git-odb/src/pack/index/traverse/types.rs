@@ -99,6 +99,12 @@ pub enum Algorithm {
     ///
     /// This option may be well suited for big packs in memory-starved system that support memory mapping.
     Lookup,
+    /// Like `Lookup`, but first build a graph of deltas and their bases from the entry headers referenced by the index,
+    /// without storing any objects, and use it to process each delta right after its base.
+    ///
+    /// Only the bases along the currently traversed delta chain are kept in memory, which allows each delta to be
+    /// applied only once while using much less memory than `DeltaTreeLookup`.
+    DeltaDagLookup,
 }
 
 impl Default for Algorithm {
@@ -3,7 +3,7 @@ use git_features::progress::{self, Progress};
 use git_object::{
     borrowed,
     bstr::{BString, ByteSlice},
-    owned, SHA1_SIZE,
+    fsck, owned, SHA1_SIZE,
 };
 
 #[derive(thiserror::Error, Debug)]
@@ -25,10 +25,16 @@ pub enum Error {
     },
     #[error(transparent)]
     ObjectEncode(#[from] std::io::Error),
+    #[error("{kind} object {id} failed consistency checks:\n{}", .findings.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n"))]
+    ObjectFsck {
+        kind: git_object::Kind,
+        id: owned::Id,
+        findings: Vec<fsck::Finding>,
+    },
 }
 
 /// Various ways in which a pack and index can be verified
-#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone)]
 pub enum Mode {
     /// Validate SHA1 and CRC32
     Sha1CRC32,
@@ -36,6 +42,9 @@ pub enum Mode {
     Sha1CRC32Decode,
     /// Validate SHA1 and CRC32, and decode and encode each non-Blob object
     Sha1CRC32DecodeEncode,
+    /// Validate SHA1 and CRC32, decode each non-Blob object and check it for problems with the given [`fsck::Options`],
+    /// failing on findings with error severity.
+    Sha1CRC32DecodeFsck(fsck::Options),
 }
 
 /// Verify and validate the content of the index file
@@ -97,8 +106,9 @@ impl index::File {
                     root.into_inner(),
                     || {
                         let mut encode_buf = Vec::with_capacity(2048);
+                        let mode = mode.clone();
                         move |kind, data, index_entry, progress| {
-                            Self::verify_entry(mode, &mut encode_buf, kind, data, index_entry, progress)
+                            Self::verify_entry(&mode, &mut encode_buf, kind, data, index_entry, progress)
                         }
                     },
                     make_cache,
@@ -118,7 +128,7 @@ impl index::File {
 
     #[allow(clippy::too_many_arguments)]
     pub fn verify_entry<P>(
-        mode: Mode,
+        mode: &Mode,
         encode_buf: &mut Vec<u8>,
         object_kind: git_object::Kind,
         buf: &[u8],
@@ -128,10 +138,26 @@ impl index::File {
     where
         P: Progress,
     {
-        if let Mode::Sha1CRC32Decode | Mode::Sha1CRC32DecodeEncode = mode {
+        if let Mode::Sha1CRC32Decode | Mode::Sha1CRC32DecodeEncode | Mode::Sha1CRC32DecodeFsck(_) = mode {
             use git_object::Kind::*;
             match object_kind {
                 Tree | Commit | Tag => {
+                    // Checks come first as they report typed findings for much of what wouldn't decode.
+                    if let Mode::Sha1CRC32DecodeFsck(options) = mode {
+                        let (errors, others): (Vec<_>, _) = fsck::object(object_kind, buf, options)
+                            .into_iter()
+                            .partition(fsck::Finding::is_error);
+                        for finding in others {
+                            progress.info(format!("{} object {}: {}", object_kind, index_entry.oid, finding));
+                        }
+                        if !errors.is_empty() {
+                            return Err(Error::ObjectFsck {
+                                kind: object_kind,
+                                id: index_entry.oid,
+                                findings: errors,
+                            });
+                        }
+                    }
                     let borrowed_object =
                         borrowed::Object::from_bytes(object_kind, buf).map_err(|err| Error::ObjectDecode {
                             source: err,
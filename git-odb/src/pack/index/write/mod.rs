@@ -40,11 +40,21 @@ impl pack::index::File {
     /// Note that neither in-pack nor out-of-pack Ref Deltas are supported here, these must have been resolved beforehand.
     /// `make_resolver()`:  It will only be called after the iterator stopped returning elements and produces a function that
     /// provides all bytes belonging to an entry.
+    /// `limits` are applied to objects produced when resolving deltas, while the `entries` are expected to be limited
+    /// by the iterator producing them, see [`pack::data::Iter::with_limits()`].
+    ///
+    /// Deltas are resolved depth first, so each thread only keeps the resolved objects along its current delta chain
+    /// in memory, no matter how many deltas share the same base. `max_resolved_base_bytes` caps the memory used by
+    /// these across all threads, see [`Tree::traverse()`]. Besides that, a small entry for each object in the pack is
+    /// kept in memory until all of them are resolved.
+    #[allow(clippy::too_many_arguments)]
     pub fn write_data_iter_to_stream<F, F2>(
         kind: pack::index::Kind,
         make_resolver: F,
         entries: impl Iterator<Item = Result<pack::data::iter::Entry, pack::data::iter::Error>>,
         thread_limit: Option<usize>,
+        limits: crate::Limits,
+        max_resolved_base_bytes: Option<u64>,
         mut root_progress: impl Progress,
         out: impl io::Write,
     ) -> Result<Outcome, Error>
@@ -151,6 +161,8 @@ impl pack::index::File {
                 root_progress.add_child("Decoding"),
                 thread_limit,
                 pack_entries_end,
+                limits,
+                max_resolved_base_bytes,
                 || (),
                 |data,
                  _progress,
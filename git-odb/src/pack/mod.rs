@@ -1,4 +1,6 @@
+mod buffer;
 pub mod bundle;
+pub(crate) use buffer::Buffer;
 pub mod cache;
 pub mod data;
 pub mod index;
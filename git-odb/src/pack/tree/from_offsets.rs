@@ -3,11 +3,7 @@ use git_features::{
     interrupt::is_triggered,
     progress::{self, Progress},
 };
-use std::{
-    fs, io,
-    io::{BufRead, Read},
-    time::Instant,
-};
+use std::{fs, io, io::BufRead, time::Instant};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -32,17 +28,47 @@ impl<T> Tree<T> {
         data_sorted_by_offsets: impl Iterator<Item = T>,
         get_pack_offset: impl Fn(&T) -> PackOffset,
         pack_path: impl AsRef<std::path::Path>,
-        mut progress: impl Progress,
+        progress: impl Progress,
         resolve_in_pack_id: impl Fn(git_object::borrowed::Id<'_>) -> Option<PackOffset>,
     ) -> Result<Self, Error> {
-        let mut r = io::BufReader::with_capacity(
+        let r = io::BufReader::with_capacity(
             8192 * 8, // this value directly corresponds to performance, 8k (default) is about 4x slower than 64k
             fs::File::open(pack_path).map_err(|err| Error::Io {
                 source: err,
                 message: "open pack path",
             })?,
         );
+        Self::from_offsets_in_pack_reader(data_sorted_by_offsets, get_pack_offset, r, progress, resolve_in_pack_id)
+    }
+
+    /// Like [`from_offsets_in_pack()`][Tree::from_offsets_in_pack()], but reads entry headers from the already opened
+    /// `pack`, which also works if it doesn't exist on disk.
+    pub fn from_offsets_in_pack_data(
+        data_sorted_by_offsets: impl Iterator<Item = T>,
+        get_pack_offset: impl Fn(&T) -> PackOffset,
+        pack: &pack::data::File,
+        progress: impl Progress,
+        resolve_in_pack_id: impl Fn(git_object::borrowed::Id<'_>) -> Option<PackOffset>,
+    ) -> Result<Self, Error> {
+        let pack_data = pack
+            .entry_slice(0..pack.data_len() as u64)
+            .expect("the entire pack is always in bounds");
+        Self::from_offsets_in_pack_reader(
+            data_sorted_by_offsets,
+            get_pack_offset,
+            pack_data,
+            progress,
+            resolve_in_pack_id,
+        )
+    }
 
+    fn from_offsets_in_pack_reader(
+        data_sorted_by_offsets: impl Iterator<Item = T>,
+        get_pack_offset: impl Fn(&T) -> PackOffset,
+        mut r: impl BufRead,
+        mut progress: impl Progress,
+        resolve_in_pack_id: impl Fn(git_object::borrowed::Id<'_>) -> Option<PackOffset>,
+    ) -> Result<Self, Error> {
         let anticpiated_num_objects = if let Some(num_objects) = data_sorted_by_offsets.size_hint().1 {
             progress.init(Some(num_objects), progress::count("objects"));
             num_objects
@@ -106,7 +132,7 @@ impl<T> Tree<T> {
     }
 
     fn advance_cursor_to_pack_offset(
-        r: &mut io::BufReader<fs::File>,
+        r: &mut impl BufRead,
         pack_offset: u64,
         previous_offset: u64,
     ) -> Result<(), Error> {
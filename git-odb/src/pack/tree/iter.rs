@@ -45,7 +45,7 @@ impl<T> Tree<T> {
     /// alias multiple nodes in the tree.
     /// It's safe for multiple threads to hold different chunks, as they are guaranteed to be non-overlapping and unique.
     /// If the tree is accessed after iteration, it will panic as no mutation is allowed anymore, nor is
-    unsafe fn from_node_take_entry(&self, index: usize) -> (T, Vec<usize>)
+    unsafe fn from_node_take_entry(&self, index: usize) -> (T, Vec<u32>)
     where
         T: Default,
     {
@@ -58,7 +58,7 @@ impl<T> Tree<T> {
 
     #[allow(unsafe_code)]
     /// SAFETY: As `take_entry(…)` - but this one only takes if the data of Node is a root
-    unsafe fn from_iter_take_entry_if_root(&self, index: usize) -> Option<(T, Vec<usize>)>
+    unsafe fn from_iter_take_entry_if_root(&self, index: usize) -> Option<(T, Vec<u32>)>
     where
         T: Default,
     {
@@ -89,7 +89,7 @@ impl<T> Tree<T> {
 pub struct Node<'a, T> {
     tree: &'a Tree<T>,
     index: usize,
-    children: Vec<usize>,
+    children: Vec<u32>,
     pub data: T,
 }
 
@@ -121,6 +121,7 @@ where
         };
         let Self { tree, children, .. } = self;
         children.into_iter().map(move |index| {
+            let index = index as usize;
             // SAFETY: The index is valid as it was controlled by `add_child(…)`, then see `take_entry(…)`
             #[allow(unsafe_code)]
             let (data, children) = unsafe { tree.from_node_take_entry(index) };
@@ -1,4 +1,4 @@
-use std::cell::UnsafeCell;
+use std::{cell::UnsafeCell, convert::TryInto};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -11,6 +11,8 @@ pub enum Error {
         delta_pack_offset: u64,
         base_pack_offset: u64,
     },
+    #[error("A tree cannot hold more than {} items", u32::MAX)]
+    InvariantTooManyItems,
 }
 
 mod iter;
@@ -23,7 +25,8 @@ pub struct Item<T> {
     pub offset: u64,
     is_root: bool,
     pub data: T,
-    children: Vec<usize>,
+    /// Indices into the items of the tree, kept small as there is one item per object in the pack.
+    children: Vec<u32>,
 }
 /// A tree that allows one-time iteration over all nodes and their children, consuming it in the process,
 /// while being shareable among threads without a lock.
@@ -93,7 +96,7 @@ impl<T> Tree<T> {
                 base_pack_offset: base_offset,
             }
         })?;
-        let child_index = items.len();
+        let child_index = items.len().try_into().map_err(|_| Error::InvariantTooManyItems)?;
         items[base_index].children.push(child_index);
         items.push(Item {
             is_root: false,
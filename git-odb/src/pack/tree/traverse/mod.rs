@@ -21,6 +21,11 @@ pub enum Error {
     },
     #[error("The resolver failed to obtain the pack entry bytes for the entry at {pack_offset}")]
     ResolveFailed { pack_offset: u64 },
+    #[error("The entry at pack offset {pack_offset} exceeds a limit")]
+    Limit {
+        source: crate::limits::Error,
+        pack_offset: u64,
+    },
     #[error("One of the object inspectors failed")]
     Inspect(#[from] Box<dyn std::error::Error + Send + Sync>),
     #[error("Interrupted")]
@@ -39,6 +44,11 @@ impl<T> Tree<T>
 where
     T: Default + Send,
 {
+    /// Resolve all deltas and call `inspect_object` for each object in the tree.
+    ///
+    /// `max_resolved_base_bytes` caps the bytes of resolved objects all threads keep in memory as bases for deltas,
+    /// at the cost of resolving evicted bases again. Note that the base and the object currently resolved are always
+    /// kept, even if they exceed it.
     #[allow(clippy::too_many_arguments)]
     pub fn traverse<F, P, MBFN, S, E>(
         mut self,
@@ -48,6 +58,8 @@ where
         size_progress: P,
         thread_limit: Option<usize>,
         pack_entries_end: u64,
+        limits: crate::Limits,
+        max_resolved_base_bytes: Option<u64>,
         new_thread_state: impl Fn() -> S + Send + Sync,
         inspect_object: MBFN,
     ) -> Result<Vec<Item<T>>, Error>
@@ -58,7 +70,9 @@ where
         E: std::error::Error + Send + Sync + 'static,
     {
         self.pack_entries_end = Some(pack_entries_end);
-        let (chunk_size, thread_limit, _) = parallel::optimize_chunk_size_and_thread_limit(1, None, thread_limit, None);
+        let (chunk_size, thread_limit, num_threads) =
+            parallel::optimize_chunk_size_and_thread_limit(1, None, thread_limit, None);
+        let max_resolved_base_bytes_per_thread = max_resolved_base_bytes.map(|bytes| bytes / num_threads as u64);
         let object_progress = parking_lot::Mutex::new(object_progress);
 
         // SAFETY: We are owning 'self', and it's the UnsafeCell which we are supposed to use requiring unsafe on every access now.
@@ -75,7 +89,16 @@ where
                     new_thread_state(),
                 )
             },
-            |root_nodes, state| resolve::deltas(root_nodes, state, &resolve, &inspect_object),
+            |root_nodes, state| {
+                resolve::deltas(
+                    root_nodes,
+                    state,
+                    &resolve,
+                    limits,
+                    max_resolved_base_bytes_per_thread,
+                    &inspect_object,
+                )
+            },
             Reducer::new(num_objects, &object_progress, size_progress),
         )?;
         Ok(self.into_items())
@@ -3,13 +3,43 @@ use crate::{
     zlib,
 };
 use git_features::progress::{unit, Progress};
-use std::{cell::RefCell, collections::BTreeMap};
+use std::cell::RefCell;
 
+/// The fully resolved object of the node on one level of the delta chain currently traversed.
+struct Level {
+    header: pack::data::Header,
+    bytes: Vec<u8>,
+    /// The pack entry of the node, to resolve it again after it was evicted.
+    slice: EntrySlice,
+    is_resolved: bool,
+}
+
+impl Default for Level {
+    fn default() -> Self {
+        Level {
+            header: pack::data::Header::Blob,
+            bytes: Vec::new(),
+            slice: 0..0,
+            is_resolved: false,
+        }
+    }
+}
+
+impl Level {
+    fn evict(&mut self) {
+        self.bytes = Vec::new();
+        self.is_resolved = false;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn deltas<T, F, P, MBFN, S, E>(
     nodes: Vec<pack::tree::Node<'_, T>>,
     (bytes_buf, ref mut progress, state): &mut (Vec<u8>, P, S),
     resolve: F,
-    modify_base: MBFN,
+    limits: crate::Limits,
+    max_resolved_base_bytes: Option<u64>,
+    inspect_object: MBFN,
 ) -> Result<(usize, u64), Error>
 where
     F: for<'r> Fn(EntrySlice, &'r mut Vec<u8>) -> Option<()> + Send + Sync,
@@ -18,7 +48,6 @@ where
     T: Default,
     E: std::error::Error + Send + Sync + 'static,
 {
-    let mut decompressed_bytes_by_pack_offset = BTreeMap::new();
     let bytes_buf = RefCell::new(bytes_buf);
     let mut num_objects = 0;
     let mut decompressed_bytes: u64 = 0;
@@ -29,12 +58,17 @@ where
             pack_offset: slice.start,
         })?;
         let entry = pack::data::Entry::from_bytes(&bytes_buf, slice.start);
+        limits
+            .check_object_size(entry.decompressed_size)
+            .map_err(|err| Error::Limit {
+                source: err,
+                pack_offset: slice.start,
+            })?;
         let compressed = &bytes_buf[entry.header_size() as usize..];
         let decompressed_len = entry.decompressed_size as usize;
         Ok((entry, slice.end, decompress_all_at_once(compressed, decompressed_len)?))
     };
 
-    // Traverse the tree breadth first and loose the data produced for the base as it won't be needed anymore.
     progress.init(
         None,
         Some(unit::dynamic(unit::Human::new(
@@ -43,62 +77,156 @@ where
         ))),
     );
 
-    // each node is a base, and its children always start out as deltas which become a base after applying them.
-    // These will be pushed onto our stack until all are processed
+    // Traverse each tree depth first, resolving deltas only once they are visited. That way we only hold one
+    // fully resolved object per level of the current path, instead of all fully resolved children of each base.
+    // The buffer for the resolved object of level N is the base for all nodes at level N + 1. As all nodes
+    // visited between a node and its next sibling are its descendants, a base is never overwritten before all
+    // of its children are done.
+    // If these exceed `max_resolved_base_bytes`, bases are evicted starting with the ones closest to the root, as
+    // they are needed last, and resolved again from the closest base still in memory once one of their children
+    // is visited.
     let root_level = 0;
+    let mut levels: Vec<Level> = Vec::new();
     let mut nodes: Vec<_> = nodes.into_iter().map(|n| (root_level, n)).collect();
-    while let Some((level, mut base)) = nodes.pop() {
-        let (base_entry, entry_end, base_bytes) = if level == root_level {
-            decompress_from_resolver(base.entry_slice())?
+    while let Some((level, mut node)) = nodes.pop() {
+        let level_index = level as usize;
+        if levels.len() <= level_index {
+            levels.resize_with(level_index + 1, Level::default);
+        }
+        levels[level_index].slice = node.entry_slice();
+        let (mut entry, entry_end, bytes) = decompress_from_resolver(node.entry_slice())?;
+        if level == root_level {
+            let resolved = &mut levels[level_index];
+            resolved.header = entry.header;
+            resolved.bytes = bytes;
+            resolved.is_resolved = true;
         } else {
-            decompressed_bytes_by_pack_offset
-                .remove(&base.offset())
-                .expect("we store the resolved delta buffer when done")
-        };
+            let base_index = level_index - 1;
+            if !levels[base_index].is_resolved {
+                resolve_again(
+                    &mut levels,
+                    base_index,
+                    &decompress_from_resolver,
+                    limits,
+                    max_resolved_base_bytes,
+                )?;
+            }
+            // FIXME: this actually invalidates the "pack_offset()" computation, which is not obvious to consumers
+            // at all
+            entry.header = apply_delta_to_base(&mut levels, level_index, &bytes, limits, node.offset())?;
+        }
+        if let Some(max_bytes) = max_resolved_base_bytes {
+            evict_until_within(&mut levels, level_index, max_bytes);
+        }
 
-        modify_base(
-            &mut base.data,
+        let decompressed = &levels[level_index].bytes;
+        inspect_object(
+            &mut node.data,
             progress,
             Context {
-                entry: &base_entry,
+                entry: &entry,
                 entry_end,
-                decompressed: &base_bytes,
+                decompressed,
                 state,
                 level,
             },
         )
         .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)?;
         num_objects += 1;
-        decompressed_bytes += base_bytes.len() as u64;
+        decompressed_bytes += decompressed.len() as u64;
         progress.inc();
-        for child in base.store_changes_then_into_child_iter() {
-            let (mut child_entry, entry_end, delta_bytes) = decompress_from_resolver(child.entry_slice())?;
-            let (base_size, consumed) = pack::data::decode::delta_header_size_ofs(&delta_bytes);
-            let mut header_ofs = consumed;
-            assert_eq!(
-                base_bytes.len(),
-                base_size as usize,
-                "recorded base size in delta does not match"
-            );
-            let (result_size, consumed) = pack::data::decode::delta_header_size_ofs(&delta_bytes[consumed..]);
-            header_ofs += consumed;
 
-            let mut fully_resolved_delta_bytes = bytes_buf.borrow_mut();
-            fully_resolved_delta_bytes.resize(result_size as usize, 0);
-            pack::data::decode::apply_delta(&base_bytes, &mut fully_resolved_delta_bytes, &delta_bytes[header_ofs..]);
+        let child_level = level + 1;
+        nodes.extend(
+            node.store_changes_then_into_child_iter()
+                .map(|child| (child_level, child)),
+        );
+    }
 
-            // FIXME: this actually invalidates the "pack_offset()" computation, which is not obvious to consumers
-            // at all
-            child_entry.header = base_entry.header;
-            decompressed_bytes_by_pack_offset.insert(
-                child.offset(),
-                (child_entry, entry_end, fully_resolved_delta_bytes.to_owned()),
-            );
-            nodes.push((level + 1, child));
+    Ok((num_objects, decompressed_bytes))
+}
+
+/// Apply `delta_bytes` of the node at `level_index` to the resolved base one level above it, and return the header
+/// of the base object.
+fn apply_delta_to_base(
+    levels: &mut [Level],
+    level_index: usize,
+    delta_bytes: &[u8],
+    limits: crate::Limits,
+    pack_offset: u64,
+) -> Result<pack::data::Header, Error> {
+    let (bases, current) = levels.split_at_mut(level_index);
+    let base = &bases[level_index - 1];
+    let resolved = &mut current[0];
+
+    let (base_size, consumed) = pack::data::decode::delta_header_size_ofs(delta_bytes);
+    let mut header_ofs = consumed;
+    assert_eq!(
+        base.bytes.len(),
+        base_size as usize,
+        "recorded base size in delta does not match"
+    );
+    let (result_size, consumed) = pack::data::decode::delta_header_size_ofs(&delta_bytes[consumed..]);
+    header_ofs += consumed;
+    limits
+        .check_object_size(result_size)
+        .and_then(|_| limits.check_delta_chain_length(level_index as u32))
+        .map_err(|err| Error::Limit {
+            source: err,
+            pack_offset,
+        })?;
+
+    resolved.bytes.resize(result_size as usize, 0);
+    pack::data::decode::apply_delta(&base.bytes, &mut resolved.bytes, &delta_bytes[header_ofs..]);
+    resolved.header = base.header;
+    resolved.is_resolved = true;
+    Ok(base.header)
+}
+
+/// Resolve the evicted object at `level_index` again, starting at the closest base above it which is still resolved,
+/// or at the root of the current delta chain.
+fn resolve_again(
+    levels: &mut [Level],
+    level_index: usize,
+    decompress: &dyn Fn(EntrySlice) -> Result<(pack::data::Entry, u64, Vec<u8>), Error>,
+    limits: crate::Limits,
+    max_resolved_base_bytes: Option<u64>,
+) -> Result<(), Error> {
+    let first_unresolved = (0..level_index)
+        .rev()
+        .find(|index| levels[*index].is_resolved)
+        .map_or(0, |index| index + 1);
+    for index in first_unresolved..=level_index {
+        let (entry, _, bytes) = decompress(levels[index].slice.clone())?;
+        if index == 0 {
+            let resolved = &mut levels[index];
+            resolved.header = entry.header;
+            resolved.bytes = bytes;
+            resolved.is_resolved = true;
+        } else {
+            let pack_offset = levels[index].slice.start;
+            apply_delta_to_base(levels, index, &bytes, limits, pack_offset)?;
+        }
+        if let Some(max_bytes) = max_resolved_base_bytes {
+            evict_until_within(levels, index, max_bytes);
         }
     }
+    Ok(())
+}
 
-    Ok((num_objects, decompressed_bytes))
+/// Evict resolved objects other than the one at `keep_index` until their bytes fit into `max_bytes`.
+///
+/// Objects below `keep_index` belong to previously traversed delta chains and go first, followed by the bases above
+/// it starting at the root, as these are needed last.
+fn evict_until_within(levels: &mut [Level], keep_index: usize, max_bytes: u64) {
+    let mut total_bytes: u64 = levels.iter().map(|level| level.bytes.capacity() as u64).sum();
+    for index in (keep_index + 1..levels.len()).chain(0..keep_index) {
+        if total_bytes <= max_bytes {
+            break;
+        }
+        total_bytes -= levels[index].bytes.capacity() as u64;
+        levels[index].evict();
+    }
 }
 
 fn decompress_all_at_once(b: &[u8], decompressed_len: usize) -> Result<Vec<u8>, Error> {
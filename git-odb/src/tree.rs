@@ -0,0 +1,231 @@
+//! Edit trees by path and write the trees that changed into an object database.
+use git_object::{
+    borrowed::{self, TreeIter},
+    bstr::{BString, ByteSlice},
+    find, owned, HashKind, Kind, TreeMode,
+};
+use std::{cmp::Ordering, collections::BTreeMap};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error<E>
+where
+    E: std::error::Error + 'static,
+{
+    #[error(transparent)]
+    Find(#[from] find::Error<E>),
+    #[error("The path '{path}' is empty or has components which are empty, '.' or '..'")]
+    InvalidPath { path: BString },
+}
+
+/// Edits a tree by adding, replacing and removing entries at paths, creating and removing subtrees as needed.
+///
+/// Trees on the way to an edited path are obtained as they are needed, and only these trees are written by
+/// [`write()`][Editor::write()].
+pub struct Editor<Find> {
+    find: Find,
+    root: Tree,
+    buf: Vec<u8>,
+}
+
+/// A tree that is edited, along with its subtrees that are edited as well.
+struct Tree {
+    tree: owned::Tree,
+    /// Edited subtrees by file name, whose entries in `tree` have an outdated id until the tree is written.
+    children: BTreeMap<BString, Tree>,
+}
+
+impl<Find, E> Editor<Find>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    E: std::error::Error + 'static,
+{
+    /// Edit the tree `root`, or an empty tree if it is `None`, obtaining trees with [`find`][git_object::find].
+    pub fn new(root: Option<owned::Id>, mut find: Find) -> Result<Self, Error<E>> {
+        let mut buf = Vec::new();
+        let root = match root {
+            Some(oid) => Tree::load(&mut find, oid, &mut buf)?,
+            None => Tree::default(),
+        };
+        Ok(Editor { find, root, buf })
+    }
+
+    /// Add an entry with `mode` and `oid` at `path`, a `/` separated path relative to the root tree, or replace the
+    /// entry that is already there.
+    ///
+    /// Missing trees leading to `path` are created, and entries in their way which aren't trees are replaced by trees.
+    pub fn upsert(&mut self, path: impl AsRef<[u8]>, mode: TreeMode, oid: owned::Id) -> Result<&mut Self, Error<E>> {
+        let components = components(path.as_ref())?;
+        let (name, parents) = components.split_last().expect("at least one component");
+        let mut tree = &mut self.root;
+        for parent in parents {
+            tree = tree.child(parent, &mut self.find, &mut self.buf)?;
+        }
+        tree.children.remove(name.as_bstr());
+        match tree.position(name) {
+            Some(idx) => {
+                let entry = &mut tree.tree.entries[idx];
+                entry.mode = mode;
+                entry.oid = oid;
+            }
+            None => tree.tree.entries.push(owned::tree::Entry {
+                mode,
+                filename: name.as_bstr().to_owned(),
+                oid,
+            }),
+        }
+        Ok(self)
+    }
+
+    /// Remove the entry at `path`, a `/` separated path relative to the root tree, along with all trees that become
+    /// empty by removing it, except for the root tree.
+    ///
+    /// Nothing happens if there is no such entry.
+    pub fn remove(&mut self, path: impl AsRef<[u8]>) -> Result<&mut Self, Error<E>> {
+        let components = components(path.as_ref())?;
+        self.root.remove(&components, &mut self.find, &mut self.buf)?;
+        Ok(self)
+    }
+
+    /// Write all edited trees to `out` using `hash`, and return the id of the root tree.
+    ///
+    /// The editor can still be used afterwards, to write further edits.
+    pub fn write<W: crate::Write>(&mut self, out: &W, hash: HashKind) -> Result<owned::Id, W::Error> {
+        self.root.write(out, hash, &mut self.buf)
+    }
+}
+
+impl Default for Tree {
+    fn default() -> Self {
+        Tree {
+            tree: owned::Tree { entries: Vec::new() },
+            children: BTreeMap::new(),
+        }
+    }
+}
+
+impl Tree {
+    fn load<Find, E>(find: &mut Find, oid: owned::Id, buf: &mut Vec<u8>) -> Result<Self, Error<E>>
+    where
+        Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+        E: std::error::Error + 'static,
+    {
+        find::load(find, oid, buf)?;
+        let entries = TreeIter::from_bytes(buf)
+            .map(|entry| entry.map(Into::into))
+            .collect::<Result<_, _>>()
+            .map_err(|err| find::Error::Decode { source: err, oid })?;
+        Ok(Tree {
+            tree: owned::Tree { entries },
+            children: BTreeMap::new(),
+        })
+    }
+
+    fn position(&self, name: &[u8]) -> Option<usize> {
+        self.tree.entries.iter().position(|entry| entry.filename == name)
+    }
+
+    /// Return the edited subtree `name`, which is obtained from `find` or created if needed.
+    fn child<Find, E>(&mut self, name: &[u8], find: &mut Find, buf: &mut Vec<u8>) -> Result<&mut Tree, Error<E>>
+    where
+        Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+        E: std::error::Error + 'static,
+    {
+        if !self.children.contains_key(name.as_bstr()) {
+            let child = match self.position(name) {
+                Some(idx) if self.tree.entries[idx].mode == TreeMode::Tree => {
+                    Tree::load(find, self.tree.entries[idx].oid, buf)?
+                }
+                Some(idx) => {
+                    self.tree.entries[idx].mode = TreeMode::Tree;
+                    Tree::default()
+                }
+                None => {
+                    self.tree.entries.push(owned::tree::Entry {
+                        mode: TreeMode::Tree,
+                        filename: name.as_bstr().to_owned(),
+                        oid: owned::Id::null(),
+                    });
+                    Tree::default()
+                }
+            };
+            self.children.insert(name.as_bstr().to_owned(), child);
+        }
+        Ok(self.children.get_mut(name.as_bstr()).expect("present or just inserted"))
+    }
+
+    /// Remove the entry at `components` and return true if there was one.
+    fn remove<Find, E>(&mut self, components: &[&[u8]], find: &mut Find, buf: &mut Vec<u8>) -> Result<bool, Error<E>>
+    where
+        Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+        E: std::error::Error + 'static,
+    {
+        let (name, rest) = components.split_first().expect("at least one component");
+        let idx = match self.position(name) {
+            Some(idx) => idx,
+            None => return Ok(false),
+        };
+        if !rest.is_empty() {
+            if self.tree.entries[idx].mode != TreeMode::Tree {
+                return Ok(false);
+            }
+            let removed = match self.children.get_mut(name.as_bstr()) {
+                Some(child) => child.remove(rest, find, buf)?,
+                None => {
+                    let mut child = Tree::load(find, self.tree.entries[idx].oid, buf)?;
+                    let removed = child.remove(rest, find, buf)?;
+                    if removed {
+                        self.children.insert(name.as_bstr().to_owned(), child);
+                    }
+                    removed
+                }
+            };
+            if !removed || !self.children[name.as_bstr()].tree.entries.is_empty() {
+                return Ok(removed);
+            }
+        }
+        self.tree.entries.remove(idx);
+        self.children.remove(name.as_bstr());
+        Ok(true)
+    }
+
+    fn write<W: crate::Write>(&mut self, out: &W, hash: HashKind, buf: &mut Vec<u8>) -> Result<owned::Id, W::Error> {
+        for (name, child) in self.children.iter_mut() {
+            let oid = child.write(out, hash, buf)?;
+            self.tree
+                .entries
+                .iter_mut()
+                .find(|entry| entry.filename == *name)
+                .expect("edited subtrees have an entry")
+                .oid = oid;
+        }
+        self.tree.entries.sort_by(compare);
+        buf.clear();
+        self.tree.write_to(&mut *buf)?;
+        out.write_buf(Kind::Tree, buf, hash)
+    }
+}
+
+/// Split `path` into its components, which must be valid file names.
+fn components<E>(path: &[u8]) -> Result<Vec<&[u8]>, Error<E>>
+where
+    E: std::error::Error + 'static,
+{
+    let components: Vec<_> = path.split_str("/").collect();
+    if components
+        .iter()
+        .any(|component| component.is_empty() || *component == b"." || *component == b"..")
+    {
+        return Err(Error::InvalidPath { path: path.into() });
+    }
+    Ok(components)
+}
+
+/// Compare entries the way trees sort them.
+fn compare(lhs: &owned::tree::Entry, rhs: &owned::tree::Entry) -> Ordering {
+    git_object::tree::compare_names(
+        &lhs.filename,
+        lhs.mode == TreeMode::Tree,
+        &rhs.filename,
+        rhs.mode == TreeMode::Tree,
+    )
+}
@@ -27,6 +27,16 @@ impl Default for Deflate {
 }
 
 impl Deflate {
+    fn with_level(level: u8) -> Self {
+        let mut inner = CompressorOxide::default();
+        inner.set_compression_level_raw(level);
+        Deflate {
+            inner,
+            total_in: 0,
+            total_out: 0,
+        }
+    }
+
     fn compress(&mut self, input: &[u8], output: &mut [u8], flush: MZFlush) -> Result<Status, Error> {
         let res = deflate::stream::deflate(&mut self.inner, input, output, flush);
         self.total_in += res.bytes_consumed as u64;
@@ -65,6 +75,15 @@ where
         }
     }
 
+    /// Create a new instance writing to `inner` which compresses with the given zlib `level` from 0 (none) to 9 (best).
+    pub fn with_level(inner: W, level: u8) -> DeflateWriter<W> {
+        DeflateWriter {
+            compressor: Deflate::with_level(level),
+            inner,
+            buf: [0; BUF_SIZE],
+        }
+    }
+
     pub fn reset(&mut self) {
         self.compressor.inner.reset();
     }
@@ -15,6 +15,18 @@ mod deflate_stream {
         assert_deflate_buffer(out, b"hello")
     }
 
+    #[test]
+    fn compression_level_zero_stores_data() -> Result<(), Box<dyn std::error::Error>> {
+        let bytes =
+            include_bytes!("../../../../tests/fixtures/packs/pack-11fdfa9e156ab73caae3b6da867192221f2089c2.pack");
+        let mut w = DeflateWriter::with_level(Vec::new(), 0);
+        w.write_all(bytes)?;
+        w.flush()?;
+
+        assert!(w.inner.len() > bytes.len(), "stored blocks add a little overhead");
+        assert_deflate_buffer(w.inner, bytes)
+    }
+
     fn assert_deflate_buffer(out: Vec<u8>, expected: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
         let mut actual = Vec::new();
         InflateReader::from_read(out.as_slice()).read_to_end(&mut actual)?;
@@ -70,3 +70,144 @@ fn no_alternate_in_first_objects_dir() -> crate::Result {
     assert!(alternate::resolve(tmp.path())?.is_empty());
     Ok(())
 }
+
+#[test]
+fn additional_alternates_are_resolved_before_the_alternates_file() -> crate::Result {
+    let tmp = tempdir::TempDir::new("alternates")?;
+    let (from, to) = alternate(tmp.path().join("a"), tmp.path().join("b"))?;
+    let additional = tmp.path().join("c");
+    fs::create_dir_all(&additional)?;
+
+    let alternates = alternate::resolve_with_additional(&from, vec![additional.clone(), to.clone()])?;
+    assert_eq!(
+        alternates.into_iter().map(|db| db.loose.path).collect::<Vec<_>>(),
+        vec![additional, to],
+        "duplicates are ignored and the order is the one of a depth-first traversal"
+    );
+    Ok(())
+}
+
+#[test]
+fn alternates_are_resolved_in_the_order_git_searches_them() -> crate::Result {
+    let tmp = tempdir::TempDir::new("alternates")?;
+    let dir = |name: &str| tmp.path().join(name);
+    fs::create_dir_all(dir("a").join("info"))?;
+    fs::write(
+        dir("a").join("info").join("alternates"),
+        format!("{}\n{}\n", dir("b").display(), dir("c").display()),
+    )?;
+    alternate(dir("d"), dir("e"))?;
+    for name in &["b", "c", "f"] {
+        fs::create_dir_all(dir(name))?;
+    }
+
+    let alternates = alternate::resolve_with_additional(dir("a"), vec![dir("d"), dir("f")])?;
+    assert_eq!(
+        alternates.into_iter().map(|db| db.loose.path).collect::<Vec<_>>(),
+        vec![dir("e"), dir("f"), dir("b"), dir("c")],
+        "additional directories come first with their own alternates in their place, followed by the ones in the file"
+    );
+    Ok(())
+}
+
+#[test]
+fn additional_alternates_without_alternates_file() -> crate::Result {
+    let tmp = tempdir::TempDir::new("alternates")?;
+    let additional = tmp.path().join("shared");
+    fs::create_dir_all(&additional)?;
+
+    let alternates = alternate::resolve_with_additional(tmp.path(), Some(additional.clone()))?;
+    assert_eq!(alternates.len(), 1);
+    assert_eq!(alternates[0].loose.path, additional);
+    Ok(())
+}
+
+#[test]
+fn append_creates_the_alternates_file_and_skips_duplicates() -> crate::Result {
+    let tmp = tempdir::TempDir::new("alternates")?;
+    let from = tmp.path().join("a");
+    let shared = tmp.path().join("shared");
+    let quoted = Path::new("#needs-quoting");
+    fs::create_dir_all(from.join(quoted))?;
+    fs::create_dir_all(&shared)?;
+
+    assert!(alternate::append(&from, &shared)?, "the alternate was added");
+    assert!(!alternate::append(&from, &shared)?, "it is not added twice");
+    assert!(alternate::append(&from, quoted)?);
+    assert!(
+        fs::read_to_string(from.join("info").join("alternates"))?.ends_with("needs-quoting\"\n"),
+        "paths which would be read back as comment are quoted"
+    );
+
+    let alternates = alternate::resolve(&from)?;
+    assert_eq!(
+        alternates.into_iter().map(|db| db.loose.path).collect::<Vec<_>>(),
+        vec![shared, from.join(quoted)],
+        "alternates are used in the order they were appended"
+    );
+    Ok(())
+}
+
+#[test]
+fn relative_alternates_from_the_environment_are_relative_to_the_current_directory() -> crate::Result {
+    let tmp = tempdir::TempDir::new("alternates")?;
+    let current_dir = tmp.path().join("cwd");
+    let relative = Path::new("shared").join("objects");
+    fs::create_dir_all(current_dir.join(&relative))?;
+    let objects = tmp.path().join("repo").join("objects");
+    fs::create_dir_all(&objects)?;
+
+    let alternates =
+        alternate::resolve_with_environment_values(&objects, None, Some(relative.clone().into()), &current_dir)?;
+    assert_eq!(alternates.len(), 1);
+    assert_eq!(
+        alternates[0].loose.path,
+        current_dir.join(relative),
+        "unlike paths in the alternates file, they are not relative to the objects directory"
+    );
+    Ok(())
+}
+
+#[test]
+fn the_object_directory_from_the_environment_overrides_the_given_one() -> crate::Result {
+    let tmp = tempdir::TempDir::new("alternates")?;
+    let (from, to) = alternate(tmp.path().join("a"), tmp.path().join("b"))?;
+
+    let alternates = alternate::resolve_with_environment_values(
+        tmp.path().join("does-not-matter"),
+        Some(from.into()),
+        None,
+        tmp.path(),
+    )?;
+    assert_eq!(
+        alternates.into_iter().map(|db| db.loose.path).collect::<Vec<_>>(),
+        vec![to]
+    );
+    Ok(())
+}
+
+#[test]
+#[cfg(not(windows))]
+fn environment_entries_are_quoted_if_they_would_be_misread() -> crate::Result {
+    use git_odb::alternate::parse;
+    for path in &[
+        "plain/path",
+        "with:separator",
+        "\"leading-quote",
+        "with:\ttab\\and\u{e4}",
+    ] {
+        let entry = alternate::environment_entry(path)?;
+        assert_eq!(
+            entry.to_str().expect("ascii").starts_with('"'),
+            *path != "plain/path",
+            "only paths that need it are quoted"
+        );
+        let value = format!("first:{}:last", entry.to_str().expect("ascii"));
+        assert_eq!(
+            parse::environment_value(value.as_bytes())?,
+            vec![PathBuf::from("first"), PathBuf::from(path), PathBuf::from("last")],
+            "it can be read back between other entries"
+        );
+    }
+    Ok(())
+}
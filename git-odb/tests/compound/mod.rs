@@ -0,0 +1,238 @@
+mod quarantine {
+    use crate::{fixture_path, hex_to_id, pack::SMALL_PACK};
+    use git_features::progress;
+    use git_object::{HashKind, Kind};
+    use git_odb::{compound, pack, Write};
+    use std::fs;
+
+    fn db_in(dir: &tempfile::TempDir) -> Result<compound::Db, Box<dyn std::error::Error>> {
+        Ok(compound::Db::at(dir.path())?)
+    }
+
+    #[test]
+    fn loose_objects_are_only_visible_after_commit() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let db = db_in(&dir)?;
+        let existing = db.write_buf(Kind::Blob, b"existing", HashKind::Sha1)?;
+
+        let quarantine = db.quarantine()?;
+        assert!(quarantine
+            .path()
+            .file_name()
+            .and_then(|n| n.to_str())
+            .expect("valid name")
+            .starts_with("incoming-"));
+        let received = quarantine.db().write_buf(Kind::Blob, b"received", HashKind::Sha1)?;
+        let duplicate = quarantine.db().write_buf(Kind::Blob, b"existing", HashKind::Sha1)?;
+        assert_eq!(duplicate, existing);
+
+        let mut buf = Vec::new();
+        assert!(
+            quarantine.db().locate(existing.to_borrowed(), &mut buf).is_some(),
+            "the main objects directory is an alternate of the quarantine"
+        );
+        assert!(quarantine.db().locate(received.to_borrowed(), &mut buf).is_some());
+        assert!(
+            db.locate(received.to_borrowed(), &mut buf).is_none(),
+            "the main objects directory doesn't see quarantined objects"
+        );
+
+        let quarantine_path = quarantine.path().to_owned();
+        quarantine.commit()?;
+        assert!(!quarantine_path.exists(), "the quarantine directory is removed");
+        assert!(db.locate(received.to_borrowed(), &mut buf).is_some());
+        assert!(db.locate(existing.to_borrowed(), &mut buf).is_some());
+        assert!(
+            !dir.path().join("info").join("alternates").exists(),
+            "the alternates file of the quarantine isn't migrated"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn iterating_the_main_db_does_not_list_quarantined_objects() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let db = db_in(&dir)?;
+        let existing = db.write_buf(Kind::Blob, b"existing", HashKind::Sha1)?;
+        let quarantine = db.quarantine()?;
+        let received = quarantine.db().write_buf(Kind::Blob, b"received", HashKind::Sha1)?;
+
+        assert_eq!(
+            db.loose.iter().collect::<Result<Vec<_>, _>>()?,
+            vec![existing],
+            "objects in the quarantine directory are not part of the main objects directory"
+        );
+        assert_eq!(
+            quarantine.db().loose.iter().collect::<Result<Vec<_>, _>>()?,
+            vec![received]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rollback_discards_all_received_objects() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let db = db_in(&dir)?;
+        let quarantine = db.quarantine()?;
+        let received = quarantine.db().write_buf(Kind::Blob, b"received", HashKind::Sha1)?;
+        let quarantine_path = quarantine.path().to_owned();
+
+        quarantine.rollback()?;
+        assert!(!quarantine_path.exists());
+        assert!(db.locate(received.to_borrowed(), &mut Vec::new()).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn received_packs_are_migrated_into_the_pack_directory() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let db = db_in(&dir)?;
+        let mut quarantine = db.quarantine()?;
+        pack::Bundle::write_to_directory_eagerly(
+            fs::File::open(fixture_path(SMALL_PACK))?,
+            None,
+            Some(quarantine.pack_directory()),
+            progress::Discard,
+            pack::bundle::write::Options {
+                thread_limit: None,
+                iteration_mode: pack::data::iter::Mode::Verify,
+                index_kind: pack::index::Kind::V2,
+                limits: Default::default(),
+                max_resolved_base_bytes: None,
+            },
+        )?;
+        quarantine.reload()?;
+
+        let id = hex_to_id("bd46bb3f5bb4ca5431770c4fde0735fb89d382f3");
+        let mut buf = Vec::new();
+        assert!(quarantine.db().locate(id.to_borrowed(), &mut buf).is_some());
+        assert!(
+            quarantine
+                .environment()?
+                .iter()
+                .any(|(name, value)| *name == "GIT_OBJECT_DIRECTORY" && value == quarantine.path().as_os_str()),
+            "hooks are pointed to the quarantine directory"
+        );
+
+        quarantine.commit()?;
+        assert_eq!(fs::read_dir(dir.path().join("pack"))?.count(), 2, "pack and index");
+        assert!(db_in(&dir)?.locate(id.to_borrowed(), &mut buf).is_some());
+        Ok(())
+    }
+}
+
+mod local_clone {
+    use crate::{fixture_path, hex_to_id, pack::SMALL_PACK};
+    use git_features::progress;
+    use git_object::{owned, HashKind, Kind};
+    use git_odb::{compound, compound::local_clone::Mode, pack, Write};
+    use std::fs;
+
+    fn source_db(dir: &tempfile::TempDir) -> Result<(compound::Db, owned::Id), Box<dyn std::error::Error>> {
+        let pack_directory = dir.path().join("pack");
+        fs::create_dir(&pack_directory)?;
+        pack::Bundle::write_to_directory_eagerly(
+            fs::File::open(fixture_path(SMALL_PACK))?,
+            None,
+            Some(pack_directory),
+            progress::Discard,
+            pack::bundle::write::Options {
+                thread_limit: None,
+                iteration_mode: pack::data::iter::Mode::Verify,
+                index_kind: pack::index::Kind::V2,
+                limits: Default::default(),
+                max_resolved_base_bytes: None,
+            },
+        )?;
+        let db = compound::Db::at(dir.path())?;
+        let loose_id = db.write_buf(Kind::Blob, b"loose", HashKind::Sha1)?;
+        Ok((db, loose_id))
+    }
+
+    #[test]
+    fn all_modes_make_all_objects_available() -> Result<(), Box<dyn std::error::Error>> {
+        let source = tempfile::tempdir()?;
+        let (db, loose_id) = source_db(&source)?;
+        let packed_id = hex_to_id("bd46bb3f5bb4ca5431770c4fde0735fb89d382f3");
+
+        for mode in &[Mode::Link, Mode::Copy, Mode::Reference] {
+            let destination = tempfile::tempdir()?;
+            let objects_directory = destination.path().join("objects");
+            let outcome = db.clone_local(&objects_directory, *mode)?;
+            match mode {
+                Mode::Link => {
+                    assert_eq!(
+                        outcome.files_linked, 3,
+                        "pack, index and loose object are on the same filesystem"
+                    );
+                    assert_eq!(outcome.files_copied, 0);
+                    assert!(outcome.alternates.is_empty());
+                }
+                Mode::Copy => {
+                    assert_eq!(outcome.files_copied, 3);
+                    assert!(outcome.bytes_copied > 0);
+                    assert!(outcome.alternates.is_empty());
+                }
+                Mode::Reference => {
+                    assert_eq!(outcome.files_linked + outcome.files_copied, 0);
+                    assert_eq!(outcome.alternates, vec![source.path().canonicalize()?]);
+                    assert!(!objects_directory.join("pack").exists());
+                }
+            }
+
+            let clone = compound::Db::at(&objects_directory)?;
+            let mut buf = Vec::new();
+            assert!(clone.locate(loose_id.to_borrowed(), &mut buf).is_some(), "{:?}", mode);
+            assert!(clone.locate(packed_id.to_borrowed(), &mut buf).is_some(), "{:?}", mode);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn alternates_of_the_source_are_registered_with_absolute_paths() -> Result<(), Box<dyn std::error::Error>> {
+        let alternate = tempfile::tempdir()?;
+        let (_, alternate_id) = source_db(&alternate)?;
+        let source = tempfile::tempdir()?;
+        fs::create_dir(source.path().join("info"))?;
+        fs::write(
+            source.path().join("info").join("alternates"),
+            format!("{}\n", alternate.path().display()),
+        )?;
+        let db = compound::Db::at(source.path())?;
+
+        let destination = tempfile::tempdir()?;
+        let outcome = db.clone_local(destination.path(), Mode::Link)?;
+        assert_eq!(outcome.alternates, vec![alternate.path().canonicalize()?]);
+        assert!(compound::Db::at(destination.path())?
+            .locate(alternate_id.to_borrowed(), &mut Vec::new())
+            .is_some());
+        Ok(())
+    }
+}
+
+mod init {
+    use crate::{fixture_path, pack::SMALL_PACK};
+    use git_odb::compound;
+    use std::fs;
+
+    #[test]
+    fn all_pack_indices_in_the_pack_directory_are_loaded() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let pack_directory = dir.path().join("pack");
+        fs::create_dir(&pack_directory)?;
+        let pack = fixture_path(SMALL_PACK);
+        for name in &["pack-named-like-git", "named-after-hash"] {
+            for extension in &["pack", "idx"] {
+                fs::copy(
+                    pack.with_extension(extension),
+                    pack_directory.join(name).with_extension(extension),
+                )?;
+            }
+        }
+        fs::write(pack_directory.join("unrelated.keep"), b"")?;
+
+        let db = compound::Db::at(dir.path())?;
+        assert_eq!(db.packs.len(), 2, "the name of packs doesn't matter, just like in git");
+        Ok(())
+    }
+}
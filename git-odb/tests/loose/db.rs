@@ -25,6 +25,15 @@ fn iter() {
     oids.sort();
     assert_eq!(oids, object_ids())
 }
+#[test]
+fn contains() {
+    let db = ldb();
+    for id in object_ids() {
+        assert!(db.contains(id.to_borrowed()), "{} is present", id);
+    }
+    assert!(!db.contains(hex_to_id("37d4e6c5c48ba0d245164c4e10d5f41140cab989").to_borrowed()));
+}
+
 pub fn locate_oid(id: owned::Id) -> loose::Object {
     ldb()
         .locate(id.to_borrowed())
@@ -62,6 +71,107 @@ mod write {
         }
         Ok(())
     }
+
+    #[test]
+    fn write_with_options() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let options = loose::db::write::Options {
+            compression_level: Some(0),
+            fsync: true,
+            shared_repository: "group".parse()?,
+        };
+        let db = loose::Db::at(dir.path()).with_write_options(options);
+        let default_db = loose::Db::at(dir.path().join("default"));
+        std::fs::create_dir(&default_db.path)?;
+
+        let data = vec![b'x'; 4096];
+        let id = db.write_buf(git_object::Kind::Blob, &data, HashKind::Sha1)?;
+        assert_eq!(default_db.write_buf(git_object::Kind::Blob, &data, HashKind::Sha1)?, id);
+
+        let path = |db: &loose::Db| {
+            db.path
+                .join(&id.to_sha1_hex_string()[..2])
+                .join(&id.to_sha1_hex_string()[2..])
+        };
+        assert!(
+            std::fs::metadata(path(&db))?.len() > std::fs::metadata(path(&default_db))?.len(),
+            "the uncompressed object is bigger"
+        );
+        assert_eq!(
+            db.locate(id.to_borrowed())
+                .expect("id present")?
+                .decode()?
+                .as_blob()
+                .expect("blob")
+                .data,
+            &data[..]
+        );
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |p: std::path::PathBuf| std::fs::metadata(p).map(|m| m.permissions().mode() & 0o7777);
+            let default_mode = |p: std::path::PathBuf| mode(p).map(|mode| mode & !0o222);
+            assert_eq!(
+                mode(path(&default_db))? & 0o222,
+                0,
+                "objects are read-only without a shared repository as well"
+            );
+            assert_eq!(
+                mode(path(&db))?,
+                default_mode(path(&default_db))? | 0o440,
+                "read permissions for the group are added to the ones of objects, which stay read-only"
+            );
+            let dir_mode = mode(path(&db).parent().expect("parent").to_owned())?;
+            assert_eq!(
+                dir_mode & 0o2770,
+                0o2770,
+                "the group can write and inherits the directory"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn write_with_invalid_compression_level() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let db = loose::Db::at(dir.path()).with_write_options(loose::db::write::Options {
+            compression_level: Some(10),
+            ..Default::default()
+        });
+        assert!(matches!(
+            db.write_buf(git_object::Kind::Blob, b"data", HashKind::Sha1),
+            Err(loose::db::write::Error::CompressionLevel(10))
+        ));
+    }
+
+    #[test]
+    fn shared_repository_permissions_are_added_like_git_does() {
+        use loose::db::write::SharedRepository;
+        assert_eq!(SharedRepository::Umask.file_mode(0o600), 0o400);
+        assert_eq!(SharedRepository::Group.file_mode(0o600), 0o440);
+        assert_eq!(SharedRepository::All.file_mode(0o644), 0o444);
+        assert_eq!(SharedRepository::Group.directory_mode(0o755), Some(0o2775));
+        assert_eq!(SharedRepository::Mode(0o640).file_mode(0o644), 0o440);
+        assert_eq!(SharedRepository::Mode(0o640).directory_mode(0o755), Some(0o2750));
+    }
+
+    #[test]
+    fn shared_repository_from_str() {
+        use loose::db::write::SharedRepository;
+        for (input, expected) in &[
+            ("false", SharedRepository::Umask),
+            ("umask", SharedRepository::Umask),
+            ("true", SharedRepository::Group),
+            ("group", SharedRepository::Group),
+            ("everybody", SharedRepository::All),
+            ("0640", SharedRepository::Mode(0o640)),
+        ] {
+            assert_eq!(input.parse::<SharedRepository>().expect("valid"), *expected);
+        }
+        assert!("0999".parse::<SharedRepository>().is_err());
+        assert!("nobody".parse::<SharedRepository>().is_err());
+    }
 }
 
 mod locate {
@@ -137,6 +247,25 @@ cjHJZXWmV4CcRfmLsXzU8s2cR9A0DBvOxhPD1TlKC2JhBFXigjuL9U4Rbq9tdegB
         Ok(())
     }
 
+    #[test]
+    fn limits_refuse_objects_which_are_too_large() {
+        let db = ldb().with_limits(git_odb::Limits {
+            max_object_size: Some(1023),
+            ..Default::default()
+        });
+        let id = hex_to_id("722fe60ad4f0276d5a8121970b5bb9dccdad4ef9");
+        assert!(matches!(
+            db.locate(id.to_borrowed()).expect("id present"),
+            Err(loose::db::locate::Error::Limit {
+                source: git_odb::limits::Error::ObjectTooLarge {
+                    size: 1024,
+                    limit: 1023
+                },
+                ..
+            })
+        ));
+    }
+
     #[test]
     fn blob_stream() -> Result<(), Box<dyn std::error::Error>> {
         let mut o = locate("37d4e6c5c48ba0d245164c4e10d5f41140cab980");
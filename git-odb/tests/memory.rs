@@ -0,0 +1,182 @@
+//! Tests measuring memory, which need a binary of their own as they track all allocations of the process.
+use git_features::{hash::Sha1, progress};
+use git_odb::pack;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    io,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+#[allow(unsafe_code)]
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::SeqCst) + layout.size();
+            PEAK.fetch_max(current, Ordering::SeqCst);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::SeqCst);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: PeakAlloc = PeakAlloc;
+
+/// Run `f` and return its result along with the peak of memory it allocated on top of what was allocated before.
+fn peak_bytes_of<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = CURRENT.load(Ordering::SeqCst);
+    PEAK.store(before, Ordering::SeqCst);
+    let res = f();
+    (res, PEAK.load(Ordering::SeqCst) - before)
+}
+
+const OBJECT_SIZE: usize = 1024 * 1024;
+const CHAIN_LENGTH: usize = 24;
+
+fn varint(mut n: usize, out: &mut Vec<u8>) {
+    while n >= 0x80 {
+        out.push(0x80 | (n & 0x7f) as u8);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+/// A delta turning a base of `base_size` bytes into the same bytes followed by `suffix`.
+fn append_delta(base_size: usize, suffix: &[u8; 8]) -> Vec<u8> {
+    let mut delta = Vec::new();
+    varint(base_size, &mut delta);
+    varint(base_size + suffix.len(), &mut delta);
+    let mut copy_from_start = 0x80u8;
+    let mut size_bytes = Vec::new();
+    for index in 0..3 {
+        let byte = (base_size >> (8 * index)) as u8;
+        if byte != 0 {
+            copy_from_start |= 0x10 << index;
+            size_bytes.push(byte);
+        }
+    }
+    delta.push(copy_from_start);
+    delta.extend(size_bytes);
+    delta.push(suffix.len() as u8);
+    delta.extend(suffix);
+    delta
+}
+
+fn write_entry(pack: &mut Vec<u8>, kind: u8, data: &[u8], base_offset: Option<usize>) -> usize {
+    let offset = pack.len();
+    let mut size = data.len();
+    let mut byte = (kind << 4) | (size & 0x0f) as u8;
+    size >>= 4;
+    while size != 0 {
+        pack.push(byte | 0x80);
+        byte = (size & 0x7f) as u8;
+        size >>= 7;
+    }
+    pack.push(byte);
+    if let Some(base_offset) = base_offset {
+        let mut distance = offset - base_offset;
+        let mut encoded = vec![(distance & 0x7f) as u8];
+        distance >>= 7;
+        while distance != 0 {
+            distance -= 1;
+            encoded.insert(0, 0x80 | (distance & 0x7f) as u8);
+            distance >>= 7;
+        }
+        pack.extend(encoded);
+    }
+    pack.extend(miniz_oxide::deflate::compress_to_vec_zlib(data, 1));
+    offset
+}
+
+/// A pack with a blob of `OBJECT_SIZE` bytes at the root of a delta chain of `CHAIN_LENGTH`, where each delta also has
+/// a sibling. Siblings come first in the pack and are thus resolved last, after their base was needed for the whole
+/// chain below it.
+fn pack_with_long_delta_chain_of_big_objects() -> Vec<u8> {
+    const BLOB: u8 = 3;
+    const OFS_DELTA: u8 = 6;
+    let mut pack = b"PACK".to_vec();
+    pack.extend(&2u32.to_be_bytes());
+    pack.extend(&(1 + 2 * CHAIN_LENGTH as u32).to_be_bytes());
+
+    let blob: Vec<u8> = (0..OBJECT_SIZE).map(|i| (i % 251) as u8).collect();
+    let mut base_offset = write_entry(&mut pack, BLOB, &blob, None);
+    for level in 0..CHAIN_LENGTH {
+        let base_size = OBJECT_SIZE + level * 8;
+        write_entry(
+            &mut pack,
+            OFS_DELTA,
+            &append_delta(base_size, b"sibling!"),
+            Some(base_offset),
+        );
+        base_offset = write_entry(
+            &mut pack,
+            OFS_DELTA,
+            &append_delta(base_size, &(level as u64).to_be_bytes()),
+            Some(base_offset),
+        );
+    }
+    let mut hash = Sha1::default();
+    hash.update(&pack);
+    pack.extend(&hash.digest());
+    pack
+}
+
+fn write_index(pack: &[u8], max_resolved_base_bytes: Option<u64>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut index = Vec::new();
+    pack::index::File::write_data_iter_to_stream(
+        pack::index::Kind::V2,
+        || {
+            Ok(|slice: pack::data::EntrySlice, out: &mut Vec<u8>| {
+                pack.get(slice.start as usize..slice.end as usize)
+                    .map(|entry| out.copy_from_slice(entry))
+            })
+        },
+        pack::data::Iter::new_from_header(
+            io::BufReader::new(pack),
+            pack::data::iter::Mode::Verify,
+            pack::data::iter::CompressedBytesMode::CRC32,
+        )?,
+        None,
+        Default::default(),
+        max_resolved_base_bytes,
+        progress::Discard,
+        &mut index,
+    )?;
+    Ok(index)
+}
+
+#[test]
+fn indexing_keeps_resolved_bases_within_the_configured_bytes() -> Result<(), Box<dyn std::error::Error>> {
+    let pack = pack_with_long_delta_chain_of_big_objects();
+
+    let (unbounded_index, unbounded_peak) = peak_bytes_of(|| write_index(&pack, None));
+    assert!(
+        unbounded_peak > CHAIN_LENGTH * OBJECT_SIZE,
+        "all bases of the chain are kept by default, peak was {}",
+        unbounded_peak
+    );
+
+    let max_resolved_base_bytes = 4 * OBJECT_SIZE;
+    let (bounded_index, bounded_peak) = peak_bytes_of(|| write_index(&pack, Some(max_resolved_base_bytes as u64)));
+    let object_being_resolved_and_its_base_and_delta = 3 * OBJECT_SIZE;
+    assert!(
+        bounded_peak < max_resolved_base_bytes + object_being_resolved_and_its_base_and_delta,
+        "peak was {}",
+        bounded_peak
+    );
+    assert_eq!(
+        bounded_index?, unbounded_index?,
+        "evicted bases are resolved again to produce the same index"
+    );
+    Ok(())
+}
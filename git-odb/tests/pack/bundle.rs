@@ -33,6 +33,38 @@ mod locate {
             }
             Ok(())
         }
+
+        #[test]
+        fn all_from_memory() -> Result<(), Box<dyn std::error::Error>> {
+            for (index_path, data_path) in PACKS_AND_INDICES {
+                let bundle = pack::Bundle {
+                    index: pack::index::File::from_data(std::fs::read(fixture_path(index_path))?)?,
+                    pack: pack::data::File::from_data(std::fs::read(fixture_path(data_path))?)?,
+                };
+                assert_eq!(bundle.pack.path(), std::path::Path::new(""));
+                assert_eq!(bundle.index.path(), std::path::Path::new(""));
+
+                let mut buf = Vec::new();
+                for entry in bundle.index.iter() {
+                    let obj = bundle
+                        .locate(entry.oid.to_borrowed(), &mut buf, &mut pack::cache::DecodeEntryNoop)
+                        .expect("id present")?;
+                    obj.verify_checksum(entry.oid.to_borrowed())?;
+                }
+                for algorithm in &[
+                    pack::index::traverse::Algorithm::Lookup,
+                    pack::index::traverse::Algorithm::DeltaTreeLookup,
+                ] {
+                    bundle.index.verify_integrity(
+                        Some((&bundle.pack, pack::index::verify::Mode::Sha1CRC32Decode, *algorithm)),
+                        None,
+                        git_features::progress::Discard.into(),
+                        || pack::cache::DecodeEntryNoop,
+                    )?;
+                }
+            }
+            Ok(())
+        }
     }
 
     #[test]
@@ -130,6 +162,37 @@ mod write_to_directory {
         Ok(())
     }
 
+    #[test]
+    fn delta_chain_limits_apply_while_resolving() -> Result<(), Box<dyn std::error::Error>> {
+        let err = pack::Bundle::write_to_directory_eagerly(
+            fs::File::open(fixture_path(SMALL_PACK))?,
+            None,
+            None::<&Path>,
+            progress::Discard,
+            bundle::write::Options {
+                thread_limit: None,
+                iteration_mode: pack::data::iter::Mode::Verify,
+                index_kind: pack::index::Kind::V2,
+                limits: git_odb::Limits {
+                    max_delta_chain_length: Some(1),
+                    ..Default::default()
+                },
+                max_resolved_base_bytes: None,
+            },
+        )
+        .expect_err("the pack contains a delta chain of length 2");
+        assert!(matches!(
+            err,
+            bundle::write::Error::IndexWrite(pack::index::write::Error::TreeTraversal(
+                pack::tree::traverse::Error::Limit {
+                    source: git_odb::limits::Error::DeltaChainTooLong { length: 2, limit: 1 },
+                    ..
+                }
+            ))
+        ));
+        Ok(())
+    }
+
     fn file_name(entry: &fs::DirEntry) -> String {
         entry.path().file_name().unwrap().to_str().unwrap().to_owned()
     }
@@ -148,6 +211,8 @@ mod write_to_directory {
                 thread_limit: None,
                 iteration_mode: pack::data::iter::Mode::Verify,
                 index_kind: pack::index::Kind::V2,
+                limits: Default::default(),
+                max_resolved_base_bytes: None,
             },
         )
         .map_err(Into::into)
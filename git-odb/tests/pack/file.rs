@@ -40,7 +40,10 @@ mod decode_entry {
     use crate::{fixture_path, fixup, pack::file::pack_at, pack::SMALL_PACK};
     use bstr::ByteSlice;
     use git_object::borrowed;
-    use git_odb::pack::{cache, data::decode::ResolvedBase};
+    use git_odb::{
+        pack,
+        pack::{cache, data::decode::ResolvedBase},
+    };
 
     fn content_of(path: &str) -> Vec<u8> {
         fixup(std::fs::read(fixture_path(path)).expect("valid fixture"))
@@ -79,6 +82,54 @@ mod decode_entry {
         );
     }
 
+    #[test]
+    fn limits_turn_into_errors() {
+        fn resolve_with_panic(_oid: borrowed::Id, _out: &mut Vec<u8>) -> Option<ResolvedBase> {
+            panic!("should not want to resolve an id here")
+        }
+        let decode_with_limits = |offset: u64, limits: git_odb::Limits| {
+            let p = pack_at(SMALL_PACK).with_limits(limits);
+            p.decode_entry(
+                p.entry(offset),
+                &mut Vec::new(),
+                resolve_with_panic,
+                &mut cache::DecodeEntryNoop,
+            )
+        };
+
+        let err = decode_with_limits(
+            3033,
+            git_odb::Limits {
+                max_delta_chain_length: Some(1),
+                ..Default::default()
+            },
+        )
+        .expect_err("the chain has two links");
+        assert!(matches!(
+            err,
+            pack::data::decode::Error::Limit {
+                source: git_odb::limits::Error::DeltaChainTooLong { length: 2, limit: 1 },
+                pack_offset: 3033
+            }
+        ));
+
+        let too_small = git_odb::Limits {
+            max_object_size: Some(186),
+            ..Default::default()
+        };
+        assert!(matches!(
+            decode_with_limits(1968, too_small).expect_err("the commit is 187 bytes"),
+            pack::data::decode::Error::Limit {
+                source: git_odb::limits::Error::ObjectTooLarge { size: 187, limit: 186 },
+                ..
+            }
+        ));
+        assert!(
+            decode_with_limits(3033, too_small).is_err(),
+            "limits also apply to bases and results of deltas"
+        );
+    }
+
     fn decode_entry_at_offset(offset: u64) -> Vec<u8> {
         fn resolve_with_panic(_oid: borrowed::Id, _out: &mut Vec<u8>) -> Option<ResolvedBase> {
             panic!("should not want to resolve an id here")
@@ -93,6 +144,39 @@ mod decode_entry {
     }
 }
 
+mod decode_header {
+    use crate::{pack::file::pack_at, pack::SMALL_PACK};
+    use git_odb::pack::{self, cache, data::decode::ResolvedBase};
+
+    #[test]
+    fn matches_decoded_entries_without_resolving_deltas() -> Result<(), Box<dyn std::error::Error>> {
+        let p = pack_at(SMALL_PACK);
+        let mut num_deltas = 0;
+        for entry in p.streaming_iter()? {
+            let entry = p.entry(entry?.pack_offset);
+            let header = p.decode_header(entry.clone(), |_id| panic!("no ref deltas in this pack"))?;
+            let mut buf = Vec::new();
+            let outcome = p.decode_entry(
+                entry,
+                &mut buf,
+                |_id, _out| -> Option<ResolvedBase> { panic!("no ref deltas in this pack") },
+                &mut cache::DecodeEntryNoop,
+            )?;
+            assert_eq!(
+                header,
+                pack::data::decode::HeaderOutcome {
+                    kind: outcome.kind,
+                    num_deltas: outcome.num_deltas,
+                    object_size: buf.len() as u64,
+                }
+            );
+            num_deltas += header.num_deltas;
+        }
+        assert!(num_deltas > 0, "the pack contains deltas");
+        Ok(())
+    }
+}
+
 mod decompress_entry {
     use crate::{pack::file::pack_at, pack::SMALL_PACK};
     use git_object::bstr::ByteSlice;
@@ -89,7 +89,16 @@ mod method {
                                     .map(|slice| out.copy_from_slice(slice))
                             }
                         };
-                        assert_index_write(mode, compressed, index_path, data_path, resolve)?;
+                        for max_resolved_base_bytes in &[None, Some(0)] {
+                            assert_index_write(
+                                mode,
+                                compressed,
+                                index_path,
+                                data_path,
+                                *max_resolved_base_bytes,
+                                &resolve,
+                            )?;
+                        }
                     }
                 }
             }
@@ -101,6 +110,7 @@ mod method {
             compressed: &iter::CompressedBytesMode,
             index_path: &&str,
             data_path: &&str,
+            max_resolved_base_bytes: Option<u64>,
             resolve: F,
         ) -> Result<(), Box<dyn std::error::Error>>
         where
@@ -120,6 +130,8 @@ mod method {
                 || Ok(resolve),
                 pack_iter,
                 None,
+                Default::default(),
+                max_resolved_base_bytes,
                 progress::Discard,
                 &mut actual,
             )?;
@@ -189,13 +201,52 @@ use git_odb::pack::cache::DecodeEntryNoop;
 static ALGOS: &[index::traverse::Algorithm] = &[
     index::traverse::Algorithm::Lookup,
     index::traverse::Algorithm::DeltaTreeLookup,
+    index::traverse::Algorithm::DeltaDagLookup,
 ];
 
-static MODES: &[index::verify::Mode] = &[
-    index::verify::Mode::Sha1CRC32,
-    index::verify::Mode::Sha1CRC32Decode,
-    index::verify::Mode::Sha1CRC32DecodeEncode,
-];
+fn modes() -> Vec<index::verify::Mode> {
+    vec![
+        index::verify::Mode::Sha1CRC32,
+        index::verify::Mode::Sha1CRC32Decode,
+        index::verify::Mode::Sha1CRC32DecodeEncode,
+        index::verify::Mode::Sha1CRC32DecodeFsck(Default::default()),
+    ]
+}
+
+#[test]
+fn verify_entry_checks_objects_with_the_given_fsck_options() {
+    use git_features::progress::Discard;
+    use git_object::fsck;
+    let tree = {
+        let mut tree = b"100644 .git\0".to_vec();
+        tree.extend_from_slice(&[1; SHA1_SIZE]);
+        tree
+    };
+    let entry = index::Entry {
+        oid: hex_to_id("0101010101010101010101010101010101010101"),
+        pack_offset: 12,
+        crc32: None,
+    };
+    let verify = |options: fsck::Options| {
+        index::File::verify_entry(
+            &index::verify::Mode::Sha1CRC32DecodeFsck(options),
+            &mut Vec::new(),
+            object::Kind::Tree,
+            &tree,
+            &entry,
+            &mut Discard,
+        )
+    };
+    assert!(verify(fsck::Options::default()).is_ok(), "'.git' entries are warnings");
+    assert!(matches!(
+        verify(fsck::Options::strict()),
+        Err(index::verify::Error::ObjectFsck { findings, .. }) if findings[0].check == fsck::Check::HasDotgit
+    ));
+    assert!(
+        verify(fsck::Options::strict().with_severity(fsck::Check::HasDotgit, fsck::Severity::Ignore)).is_ok(),
+        "configured severities take precedence over strictness"
+    );
+}
 
 #[test]
 fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
@@ -288,10 +339,12 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
         assert_eq!(pack.kind(), pack::data::Kind::V2);
         assert_eq!(pack.num_objects(), idx.num_objects());
         for algo in ALGOS {
-            for mode in MODES {
+            for mode in modes() {
                 assert_eq!(
-                    idx.verify_integrity(Some((&pack, *mode, *algo)), None, Discard.into(), || DecodeEntryNoop)
-                        .map(|(a, b, _)| (a, b))?,
+                    idx.verify_integrity(Some((&pack, mode.clone(), *algo)), None, Discard.into(), || {
+                        DecodeEntryNoop
+                    })
+                    .map(|(a, b, _)| (a, b))?,
                     (idx.index_checksum(), Some(stats.to_owned())),
                     "{:?} -> {:?}",
                     algo,
@@ -356,6 +409,110 @@ fn pack_lookup() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[test]
+fn traverse_objects() -> Result<(), Box<dyn std::error::Error>> {
+    struct Collect(Vec<(object::Kind, u32, usize, u64)>);
+    impl git_features::parallel::Reducer for Collect {
+        type Input = Vec<(object::Kind, u32, usize, u64)>;
+        type Output = Vec<(object::Kind, u32, usize, u64)>;
+        type Error = std::io::Error;
+
+        fn feed(&mut self, input: Self::Input) -> Result<(), Self::Error> {
+            self.0.extend(input);
+            Ok(())
+        }
+
+        fn finalize(self) -> Result<Self::Output, Self::Error> {
+            Ok(self.0)
+        }
+    }
+
+    let idx = index::File::at(fixture_path(INDEX_V2))?;
+    let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2))?;
+    let (id, objects, _) = idx.traverse_objects(
+        &pack,
+        Some(Discard),
+        None,
+        index::traverse::SafetyCheck::All,
+        || {
+            |object: index::traverse::Object<'_>, _progress: &mut _| {
+                Ok::<_, std::io::Error>((object.kind, object.delta_depth, object.data.len(), object.pack_offset))
+            }
+        },
+        || DecodeEntryNoop,
+        Collect(Vec::new()),
+    )?;
+    assert_eq!(id, idx.index_checksum());
+    assert_eq!(objects.len(), idx.num_objects() as usize);
+
+    let mut objects_per_chain_length = std::collections::BTreeMap::new();
+    for (_, depth, _, _) in &objects {
+        *objects_per_chain_length.entry(*depth).or_insert(0) += 1;
+    }
+    assert_eq!(
+        objects_per_chain_length,
+        b_tree_map! {0 => 18, 1 => 4, 2 => 3, 3 => 1, 4 => 2, 5 => 1, 6 => 1},
+        "delta depths match the chain lengths seen when verifying"
+    );
+    assert_eq!(objects.iter().map(|o| o.2).sum::<usize>(), 288658);
+    assert_eq!(
+        objects.iter().filter(|o| o.0 == object::Kind::Commit).count(),
+        10,
+        "kinds are those of the resolved objects"
+    );
+    let mut offsets: Vec<_> = objects.iter().map(|o| o.3).collect();
+    offsets.sort_unstable();
+    assert_eq!(offsets, idx.sorted_offsets());
+    Ok(())
+}
+
+#[test]
+fn list_entries() -> Result<(), Box<dyn std::error::Error>> {
+    let idx = index::File::at(fixture_path(INDEX_V2))?;
+    let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2))?;
+    let entries = idx.list_entries(&pack, Discard)?;
+    assert_eq!(entries.len(), idx.num_objects() as usize);
+
+    // as seen in the output of `git verify-pack -v`
+    assert_eq!(
+        entries[0],
+        index::list::Entry {
+            id: hex_to_id("501b297447a8255d3533c6858bb692575cdefaa0"),
+            kind: object::Kind::Commit,
+            size: 225,
+            size_in_pack: 153,
+            pack_offset: 12,
+            delta: None
+        }
+    );
+    assert_eq!(
+        entries[entries.len() - 1],
+        index::list::Entry {
+            id: hex_to_id("18bd3fc20b0565f94bce0a3e94b6a83b26b88627"),
+            kind: object::Kind::Tree,
+            size: 184,
+            size_in_pack: 198,
+            pack_offset: 51657,
+            delta: Some(index::list::Delta {
+                chain_length: 6,
+                base_id: hex_to_id("3ab660ad62dd7c8c8bd637aa9bc1c2843a8439fe")
+            })
+        }
+    );
+
+    let mut objects_per_chain_length = std::collections::BTreeMap::new();
+    for entry in &entries {
+        *objects_per_chain_length
+            .entry(entry.delta.map_or(0, |d| d.chain_length))
+            .or_insert(0) += 1;
+    }
+    assert_eq!(
+        objects_per_chain_length,
+        b_tree_map! {0 => 18, 1 => 4, 2 => 3, 3 => 1, 4 => 2, 5 => 1, 6 => 1}
+    );
+    Ok(())
+}
+
 #[test]
 fn iter() -> Result<(), Box<dyn std::error::Error>> {
     for (path, kind, num_objects, version, index_checksum, pack_checksum) in &[
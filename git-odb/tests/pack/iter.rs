@@ -94,6 +94,37 @@ mod new_from_header {
         Ok(())
     }
 
+    #[test]
+    fn limits_stop_the_iteration_with_an_error() -> Result<(), Box<dyn std::error::Error>> {
+        let iter = pack::data::Iter::new_from_header(
+            std::io::BufReader::new(fs::File::open(fixture_path(SMALL_PACK))?),
+            Mode::Verify,
+            CompressedBytesMode::Keep,
+        )?
+        .with_limits(git_odb::Limits {
+            max_object_size: Some(100),
+            ..Default::default()
+        });
+        let results = iter.collect::<Vec<_>>();
+        let err = results
+            .last()
+            .expect("at least one result")
+            .as_ref()
+            .expect_err("an object is larger than the limit");
+        assert!(matches!(
+            err,
+            pack::data::iter::Error::Limit {
+                source: git_odb::limits::Error::ObjectTooLarge { limit: 100, .. },
+                ..
+            }
+        ));
+        assert!(
+            results.len() < 42,
+            "the iteration stops at the first error instead of processing all objects"
+        );
+        Ok(())
+    }
+
     #[test]
     fn restore_missing_trailer() -> Result<(), Box<dyn std::error::Error>> {
         let pack = fs::read(fixture_path(SMALL_PACK))?;
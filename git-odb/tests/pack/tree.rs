@@ -28,6 +28,65 @@ mod method {
             Ok(())
         }
     }
+
+    mod traverse {
+        use crate::{
+            fixture_path,
+            pack::{INDEX_V2, PACK_FOR_INDEX_V2},
+        };
+        use common_macros::b_tree_map;
+        use filebuffer::FileBuffer;
+        use git_features::progress::Discard;
+        use git_odb::pack;
+        use std::convert::Infallible;
+
+        #[test]
+        fn resolves_all_deltas_depth_first() -> Result<(), Box<dyn std::error::Error>> {
+            let idx = pack::index::File::at(fixture_path(INDEX_V2))?;
+            let pack = pack::data::File::at(fixture_path(PACK_FOR_INDEX_V2))?;
+            let sorted_offsets = idx.sorted_offsets();
+            let tree = pack::tree::Tree::from_offsets_in_pack(
+                sorted_offsets.iter().map(|ofs| (*ofs, None::<(u16, usize)>)),
+                |(ofs, _)| *ofs,
+                fixture_path(PACK_FOR_INDEX_V2),
+                Discard,
+                |id| idx.lookup(id).map(|index| idx.pack_offset_at_index(index)),
+            )?;
+            let buf = FileBuffer::open(fixture_path(PACK_FOR_INDEX_V2))?;
+            let items = tree.traverse(
+                || true,
+                |slice, out| {
+                    buf.get(slice.start as usize..slice.end as usize)
+                        .map(|entry| out.copy_from_slice(entry))
+                },
+                Discard,
+                Discard,
+                Some(2),
+                (pack.data_len() - git_object::SHA1_SIZE) as u64,
+                Default::default(),
+                None,
+                || (),
+                |(_, data), _progress, ctx| -> Result<(), Infallible> {
+                    *data = Some((ctx.level, ctx.decompressed.len()));
+                    Ok(())
+                },
+            )?;
+
+            let mut objects_per_level = std::collections::BTreeMap::new();
+            let mut total_object_size = 0;
+            for item in items {
+                let (level, size) = item.data.1.expect("every item was visited");
+                *objects_per_level.entry(level).or_insert(0) += 1;
+                total_object_size += size;
+            }
+            assert_eq!(
+                objects_per_level,
+                b_tree_map! {0 => 18, 1 => 4, 2 => 3, 3 => 1, 4 => 2, 5 => 1, 6 => 1}
+            );
+            assert_eq!(total_object_size, 288658);
+            Ok(())
+        }
+    }
 }
 
 struct TreeItem<D> {
@@ -0,0 +1,140 @@
+use crate::hex_to_id;
+use git_object::{borrowed, find, owned, HashKind, Kind, TreeMode};
+use git_odb::{tree, Write};
+use std::{cell::RefCell, collections::HashMap, convert::Infallible, io};
+
+/// An object database in memory which counts the objects written to it.
+#[derive(Default)]
+struct Db {
+    objects: RefCell<HashMap<owned::Id, Vec<u8>>>,
+    writes: RefCell<usize>,
+}
+
+impl Write for Db {
+    type Error = io::Error;
+
+    fn write_stream(&self, kind: Kind, size: u64, mut from: impl io::Read, hash: HashKind) -> io::Result<owned::Id> {
+        let mut buf = Vec::new();
+        from.read_to_end(&mut buf)?;
+        let oid = git_odb::sink().write_stream(kind, size, buf.as_slice(), hash)?;
+        self.objects.borrow_mut().insert(oid, buf);
+        *self.writes.borrow_mut() += 1;
+        Ok(oid)
+    }
+}
+
+type Find<'a> = Box<dyn FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), Infallible>> + 'a>;
+
+impl Db {
+    fn editor(&self, root: Option<owned::Id>) -> tree::Editor<Find<'_>> {
+        tree::Editor::new(
+            root,
+            Box::new(move |oid: borrowed::Id<'_>, buf: &mut Vec<u8>| {
+                buf.extend_from_slice(self.objects.borrow().get(&owned::Id::from(oid))?);
+                Some(Ok(()))
+            }) as Find<'_>,
+        )
+        .expect("root tree to be present")
+    }
+
+    /// Write a tree with nested subtrees and entries whose names sort differently if they are trees.
+    fn base(&self) -> owned::Id {
+        let mut editor = self.editor(None);
+        editor
+            .upsert("d/e/f", TreeMode::BlobExecutable, id(3))
+            .unwrap()
+            .upsert("d/b", TreeMode::Blob, id(2))
+            .unwrap()
+            .upsert("d.txt", TreeMode::Blob, id(1))
+            .unwrap()
+            .upsert("a", TreeMode::Blob, id(1))
+            .unwrap();
+        editor.write(self, HashKind::Sha1).expect("in-memory write")
+    }
+}
+
+fn id(n: u8) -> owned::Id {
+    owned::Id::from([n * 0x11; 20])
+}
+
+#[test]
+fn trees_are_built_in_git_order_from_nothing() {
+    let db = Db::default();
+    assert_eq!(db.base(), hex_to_id("681cb02e31b707c2a2a870f27329109bc93bf9b9"));
+    assert_eq!(*db.writes.borrow(), 3, "one write per tree");
+
+    assert_eq!(
+        db.editor(None).write(&db, HashKind::Sha1).unwrap(),
+        hex_to_id("4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+        "the empty tree"
+    );
+}
+
+#[test]
+fn removals_remove_empty_trees_and_upserts_replace_non_trees() {
+    let db = Db::default();
+    let base = db.base();
+    *db.writes.borrow_mut() = 0;
+
+    let mut editor = db.editor(Some(base));
+    editor
+        .remove("d/e/f")
+        .unwrap()
+        .remove("d/missing/x")
+        .unwrap()
+        .remove("a/missing")
+        .unwrap()
+        .upsert("a/x", TreeMode::Blob, id(3))
+        .unwrap();
+    assert_eq!(
+        editor.write(&db, HashKind::Sha1).unwrap(),
+        hex_to_id("a23a56810efa8debf318f643b4f1525312b270bd")
+    );
+    assert_eq!(*db.writes.borrow(), 3, "only the root tree, 'a' and 'd' are written");
+
+    editor
+        .remove("a")
+        .unwrap()
+        .remove("d")
+        .unwrap()
+        .remove("d.txt")
+        .unwrap();
+    assert_eq!(
+        editor.write(&db, HashKind::Sha1).unwrap(),
+        hex_to_id("4b825dc642cb6eb9a060e54bf8d69288fbee4904"),
+        "the root tree remains even if it's empty"
+    );
+}
+
+#[test]
+fn upserting_a_tree_replaces_all_edits_within_it() {
+    let db = Db::default();
+    let base = db.base();
+    let mut editor = db.editor(Some(base));
+    let d = hex_to_id("54650053e72ab105d506b142f84045e0b6a4c3d2");
+    editor
+        .upsert("d/e/g", TreeMode::Blob, id(1))
+        .unwrap()
+        .upsert("d", TreeMode::Tree, d)
+        .unwrap();
+    assert_eq!(editor.write(&db, HashKind::Sha1).unwrap(), base);
+}
+
+#[test]
+fn invalid_paths_and_missing_trees_are_errors() {
+    let db = Db::default();
+    let mut editor = db.editor(None);
+    for path in &["", "a//b", "a/", "./a", "a/../b"] {
+        assert!(matches!(editor.remove(path), Err(tree::Error::InvalidPath { .. })));
+    }
+    let missing = id(9);
+    editor.upsert("a", TreeMode::Tree, missing).unwrap();
+    assert!(matches!(
+        editor.upsert("a/b", TreeMode::Blob, id(1)),
+        Err(tree::Error::Find(find::Error::NotFound { oid })) if oid == missing
+    ));
+    assert!(matches!(
+        tree::Editor::new(Some(missing), |_oid, _buf| -> Option<Result<(), Infallible>> { None }),
+        Err(tree::Error::Find(find::Error::NotFound { oid })) if oid == missing
+    ));
+}
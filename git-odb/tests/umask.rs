@@ -0,0 +1,26 @@
+//! Tests of object permissions, which need a binary of their own as they change the umask of the process.
+#[cfg(unix)]
+#[test]
+fn objects_are_readable_by_everyone_the_umask_permits() -> Result<(), Box<dyn std::error::Error>> {
+    use git_object::HashKind;
+    use git_odb::{loose, Write};
+    use std::os::unix::fs::PermissionsExt;
+
+    #[allow(unsafe_code)]
+    unsafe {
+        libc::umask(0o022);
+    }
+    let dir = tempfile::tempdir()?;
+    let db = loose::Db::at(dir.path());
+    let id = db.write_buf(git_object::Kind::Blob, b"data", HashKind::Sha1)?;
+    let hex = id.to_sha1_hex_string();
+    let mode = std::fs::metadata(dir.path().join(&hex[..2]).join(&hex[2..]))?
+        .permissions()
+        .mode();
+    assert_eq!(
+        mode & 0o7777,
+        0o444,
+        "like git, objects are created with 0444 minus the umask"
+    );
+    Ok(())
+}
@@ -0,0 +1,90 @@
+//! Resolve references stored in files of a git directory, loose or packed.
+use quick_error::quick_error;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The id of an object as stored in a reference, a SHA-1 hash.
+pub type Id = [u8; 20];
+
+quick_error! {
+    #[derive(Debug)]
+    pub enum Error {
+        Io(err: io::Error, path: PathBuf) {
+            display("Could not read reference file at '{}'", path.display())
+            source(err)
+        }
+        InvalidTarget(name: String) {
+            display("The reference '{}' points to an invalid name", name)
+        }
+        InvalidId(name: String) {
+            display("The reference '{}' does not contain an object id", name)
+        }
+        TooDeep(name: String) {
+            display("The references starting at '{}' are nested too deeply", name)
+        }
+    }
+}
+
+/// The maximum amount of symbolic references followed, like git does.
+const MAX_SYMBOLIC_REFS: usize = 5;
+
+/// Return the id of the object the reference `name` in `git_dir` points to, following symbolic references like `HEAD`,
+/// or `None` if it or a reference it points to doesn't exist, like an unborn branch.
+///
+/// Loose references take precedence over those in the `packed-refs` file.
+pub fn peel_to_id(git_dir: impl AsRef<Path>, name: &str) -> Result<Option<Id>, Error> {
+    let git_dir = git_dir.as_ref();
+    let mut current = name.to_owned();
+    for _ in 0..MAX_SYMBOLIC_REFS {
+        let path = git_dir.join(&current);
+        let content = match fs::read(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound || path.is_dir() => return packed(git_dir, &current),
+            Err(err) => return Err(Error::Io(err, path)),
+        };
+        let content = content.trim_ascii_end();
+        match content.strip_prefix(b"ref: ") {
+            Some(target) => {
+                current = std::str::from_utf8(target)
+                    .ok()
+                    .map(str::trim)
+                    .filter(|target| is_valid_target(target))
+                    .ok_or_else(|| Error::InvalidTarget(current.clone()))?
+                    .to_owned()
+            }
+            None => return parse_id(content).map(Some).ok_or(Error::InvalidId(current)),
+        }
+    }
+    Err(Error::TooDeep(name.to_owned()))
+}
+
+/// Return the id of the reference `name` in the `packed-refs` file of `git_dir`, or `None` if it isn't there.
+pub fn packed(git_dir: impl AsRef<Path>, name: &str) -> Result<Option<Id>, Error> {
+    let path = git_dir.as_ref().join("packed-refs");
+    let packed = match fs::read(&path) {
+        Ok(packed) => packed,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(Error::Io(err, path)),
+    };
+    for line in packed.split(|b| *b == b'\n') {
+        if line.len() > 41 && line[40] == b' ' && line[41..].trim_ascii_end() == name.as_bytes() {
+            return parse_id(&line[..40])
+                .map(Some)
+                .ok_or_else(|| Error::InvalidId(name.to_owned()));
+        }
+    }
+    Ok(None)
+}
+
+/// Symbolic references may only point to `HEAD` or valid names below `refs/`, which also keeps them within the git
+/// directory.
+fn is_valid_target(name: &str) -> bool {
+    name == "HEAD" || (name.starts_with("refs/") && crate::validated::name(name.into()).is_ok())
+}
+
+fn parse_id(hex: &[u8]) -> Option<Id> {
+    let mut id = Id::default();
+    hex::decode_to_slice(hex, &mut id).ok().map(|_| id)
+}
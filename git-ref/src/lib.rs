@@ -1,4 +1,5 @@
 #![forbid(unsafe_code)]
 #![deny(rust_2018_idioms)]
 
+pub mod file;
 pub mod validated;
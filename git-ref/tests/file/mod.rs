@@ -0,0 +1,90 @@
+mod peel_to_id {
+    use git_ref::file;
+    use std::fs;
+
+    const ID: &str = "f1cd3cc7bc63a4a2b357a475a58ad49b40355470";
+    const OTHER_ID: &str = "a9d4d7d0d4e5d0ba7be5d84b5c3b1c5a5d0b0d8b";
+
+    fn id(hex: &str) -> file::Id {
+        let mut id = file::Id::default();
+        hex::decode_to_slice(hex, &mut id).expect("valid hex");
+        id
+    }
+
+    fn git_dir() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("temp dir");
+        fs::create_dir_all(dir.path().join("refs/heads")).expect("refs dir");
+        fs::write(dir.path().join("HEAD"), "ref: refs/heads/main\n").expect("write HEAD");
+        dir
+    }
+
+    #[test]
+    fn symbolic_refs_are_followed_to_loose_refs() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = git_dir();
+        fs::write(dir.path().join("refs/heads/main"), format!("{}\n", ID))?;
+        assert_eq!(file::peel_to_id(dir.path(), "HEAD")?, Some(id(ID)));
+        Ok(())
+    }
+
+    #[test]
+    fn symbolic_refs_are_followed_to_packed_refs_and_loose_refs_take_precedence(
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = git_dir();
+        fs::write(
+            dir.path().join("packed-refs"),
+            format!(
+                "# pack-refs with: peeled fully-peeled sorted \n{} refs/heads/main\n{} refs/tags/v1\n",
+                ID, OTHER_ID
+            ),
+        )?;
+        assert_eq!(file::peel_to_id(dir.path(), "HEAD")?, Some(id(ID)));
+        assert_eq!(file::peel_to_id(dir.path(), "refs/tags/v1")?, Some(id(OTHER_ID)));
+
+        fs::write(dir.path().join("refs/heads/main"), format!("{}\n", OTHER_ID))?;
+        assert_eq!(file::peel_to_id(dir.path(), "HEAD")?, Some(id(OTHER_ID)));
+        Ok(())
+    }
+
+    #[test]
+    fn unborn_branches_yield_none() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = git_dir();
+        assert_eq!(file::peel_to_id(dir.path(), "HEAD")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn cycles_of_symbolic_refs_are_an_error() {
+        let dir = git_dir();
+        fs::write(dir.path().join("refs/heads/main"), "ref: HEAD\n").expect("write ref");
+        assert!(matches!(
+            file::peel_to_id(dir.path(), "HEAD"),
+            Err(file::Error::TooDeep(name)) if name == "HEAD"
+        ));
+    }
+
+    #[test]
+    fn refs_without_an_id_are_an_error() {
+        let dir = git_dir();
+        fs::write(dir.path().join("refs/heads/main"), "garbage\n").expect("write ref");
+        assert!(matches!(
+            file::peel_to_id(dir.path(), "HEAD"),
+            Err(file::Error::InvalidId(name)) if name == "refs/heads/main"
+        ));
+    }
+
+    #[test]
+    fn symbolic_refs_to_names_outside_of_refs_are_an_error() {
+        let dir = git_dir();
+        for target in &["/etc/passwd", "../../outside", "refs/../../outside", "config"] {
+            fs::write(dir.path().join("refs/heads/main"), format!("ref: {}\n", target)).expect("write ref");
+            assert!(
+                matches!(
+                    file::peel_to_id(dir.path(), "HEAD"),
+                    Err(file::Error::InvalidTarget(name)) if name == "refs/heads/main"
+                ),
+                "{} is rejected",
+                target
+            );
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use git_object::bstr::{BString, ByteSlice};
+
+/// Decode the first block in `data` armored with `label`, like `PGP SIGNATURE`, and return its data along with what
+/// follows the block, or `None` if there is no such block or it is malformed.
+///
+/// Armor headers and the checksum of OpenPGP armor are supported, and the checksum is verified if present.
+pub(crate) fn decode<'a>(data: &'a [u8], label: &str) -> Option<(Vec<u8>, &'a [u8])> {
+    let begin = format!("-----BEGIN {}-----", label);
+    let end = format!("-----END {}-----", label);
+    let mut lines = Lines(data);
+    lines.find(|line| line.trim_ascii_end() == begin.as_bytes())?;
+
+    let mut body = Vec::new();
+    loop {
+        let line = lines.next()?;
+        if line.trim_ascii_end() == end.as_bytes() {
+            break;
+        }
+        body.push(line.trim_ascii());
+    }
+    let rest = lines.0;
+    // Headers are separated from the data by an empty line, which is only present in OpenPGP armor.
+    if let Some(empty) = body.iter().position(|line| line.is_empty()) {
+        body.drain(..=empty);
+    }
+    let checksum = match body.last() {
+        Some(line) if line.len() == 5 && line[0] == b'=' => {
+            let checksum = base64::decode(&line[1..]).ok()?;
+            body.pop();
+            Some(checksum)
+        }
+        _ => None,
+    };
+    let decoded = base64::decode(body.concat()).ok()?;
+    if let Some(checksum) = checksum {
+        let crc = crc24(&decoded).to_be_bytes();
+        if checksum != crc[1..] {
+            return None;
+        }
+    }
+    Some((decoded, rest))
+}
+
+/// The kinds of armor, which differ in their line length and whether they have a checksum.
+#[derive(Clone, Copy)]
+pub(crate) enum Kind {
+    OpenPgp,
+    Ssh,
+}
+
+/// Encode `data` in a block armored with `label`, like `PGP SIGNATURE`, the way `gpg` or `ssh-keygen` do.
+pub(crate) fn encode(data: &[u8], label: &str, kind: Kind) -> BString {
+    let (line_len, checksum) = match kind {
+        Kind::OpenPgp => (64, Some(crc24(data))),
+        Kind::Ssh => (70, None),
+    };
+    let mut out = BString::from(format!("-----BEGIN {}-----\n", label));
+    if checksum.is_some() {
+        out.push(b'\n');
+    }
+    let encoded = base64::encode(data);
+    for line in encoded.as_bytes().chunks(line_len) {
+        out.extend_from_slice(line);
+        out.push(b'\n');
+    }
+    if let Some(checksum) = checksum {
+        out.push(b'=');
+        out.extend_from_slice(base64::encode(&checksum.to_be_bytes()[1..]).as_bytes());
+        out.push(b'\n');
+    }
+    out.extend_from_slice(format!("-----END {}-----\n", label).as_bytes());
+    out
+}
+
+/// Lines including their terminator, which keeps the data that is left to iterate.
+struct Lines<'a>(&'a [u8]);
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let end = self.0.find_byte(b'\n').map_or(self.0.len(), |pos| pos + 1);
+        let (line, rest) = self.0.split_at(end);
+        self.0 = rest;
+        Some(line)
+    }
+}
+
+/// The checksum of OpenPGP armor, as defined in RFC 4880 section 6.1.
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc = 0xB7_04CEu32;
+    for byte in data {
+        crc ^= u32::from(*byte) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x100_0000 != 0 {
+                crc ^= 0x186_4CFB;
+            }
+        }
+    }
+    crc & 0xFF_FFFF
+}
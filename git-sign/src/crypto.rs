@@ -0,0 +1,66 @@
+//! Signature verification primitives shared by OpenPGP and SSH, which return false if the key is invalid as well.
+use p256::ecdsa::signature::hazmat::PrehashVerifier;
+use std::convert::TryInto;
+
+/// Verify the PKCS #1 v1.5 `signature` of the `digest` which was hashed as `scheme` describes, with the RSA key of
+/// modulus `n` and exponent `e`.
+pub fn rsa(n: &[u8], e: &[u8], scheme: rsa::Pkcs1v15Sign, digest: &[u8], signature: &[u8]) -> bool {
+    let key = match rsa::RsaPublicKey::new(rsa::BigUint::from_bytes_be(n), rsa::BigUint::from_bytes_be(e)) {
+        Ok(key) => key,
+        Err(_) => return false,
+    };
+    let signature = match left_pad(signature, rsa::traits::PublicKeyParts::size(&key)) {
+        Some(signature) => signature,
+        None => return false,
+    };
+    key.verify(scheme, digest, &signature).is_ok()
+}
+
+/// Verify the Ed25519 signature made of `r` and `s` of `message` with the public key `point`.
+pub fn ed25519(point: &[u8; 32], message: &[u8], r: &[u8], s: &[u8]) -> bool {
+    let (r, s) = match (left_pad(r, 32), left_pad(s, 32)) {
+        (Some(r), Some(s)) => (r, s),
+        _ => return false,
+    };
+    let mut signature = [0; 64];
+    signature[..32].copy_from_slice(&r);
+    signature[32..].copy_from_slice(&s);
+    match ed25519_dalek::VerifyingKey::from_bytes(point) {
+        Ok(key) => key
+            .verify_strict(message, &ed25519_dalek::Signature::from_bytes(&signature))
+            .is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Verify the ECDSA signature made of `r` and `s` of the `digest` of a message with the SEC1 encoded public key `point`
+/// on NIST P-256.
+pub fn p256(point: &[u8], digest: &[u8], r: &[u8], s: &[u8]) -> bool {
+    let key = match p256::ecdsa::VerifyingKey::from_sec1_bytes(point) {
+        Ok(key) => key,
+        Err(_) => return false,
+    };
+    let (r, s) = match (left_pad(r, 32), left_pad(s, 32)) {
+        (Some(r), Some(s)) => (r, s),
+        _ => return false,
+    };
+    let r: [u8; 32] = r.as_slice().try_into().expect("padded");
+    let s: [u8; 32] = s.as_slice().try_into().expect("padded");
+    match p256::ecdsa::Signature::from_scalars(r, s) {
+        Ok(signature) => key.verify_prehash(digest, &signature).is_ok(),
+        Err(_) => false,
+    }
+}
+
+/// Return the big-endian integer `bytes` padded with leading zeroes to `len`, or `None` if it is too long, after
+/// stripping its own leading zeroes.
+pub fn left_pad(bytes: &[u8], len: usize) -> Option<Vec<u8>> {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    let bytes = &bytes[start..];
+    if bytes.len() > len {
+        return None;
+    }
+    let mut padded = vec![0; len - bytes.len()];
+    padded.extend_from_slice(bytes);
+    Some(padded)
+}
@@ -0,0 +1,132 @@
+#![forbid(unsafe_code)]
+#![deny(rust_2018_idioms)]
+//! Verify the OpenPGP and SSH signatures of commits and tags against trusted keys, and sign them.
+
+use git_object::{
+    bstr::{BString, ByteSlice},
+    signed::Signed,
+};
+
+mod armor;
+mod crypto;
+pub mod openpgp;
+pub mod sign;
+pub mod ssh;
+
+/// The kinds of signatures that can be verified.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Kind {
+    /// A signature made by `gpg` or another OpenPGP implementation.
+    OpenPgp,
+    /// A signature made by `ssh-keygen -Y sign`.
+    Ssh,
+}
+
+impl Kind {
+    /// Determine the kind of the armored `signature` by its first line, or return `None` if it is of no known kind.
+    pub fn from_signature(signature: &[u8]) -> Option<Self> {
+        let first_line = signature.lines().next()?.trim_ascii_end();
+        match first_line {
+            b"-----BEGIN PGP SIGNATURE-----" => Some(Kind::OpenPgp),
+            b"-----BEGIN SSH SIGNATURE-----" => Some(Kind::Ssh),
+            _ => None,
+        }
+    }
+}
+
+/// The keys whose signatures are trusted.
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+pub struct Trust {
+    /// The keys trusted to make OpenPGP signatures.
+    pub keyring: openpgp::Keyring,
+    /// The keys trusted to make SSH signatures.
+    pub allowed_signers: ssh::AllowedSigners,
+}
+
+impl Trust {
+    /// Trust the OpenPGP keys in `keyring`.
+    pub fn with_keyring(mut self, keyring: openpgp::Keyring) -> Self {
+        self.keyring = keyring;
+        self
+    }
+
+    /// Trust the SSH keys in `allowed_signers`.
+    pub fn with_allowed_signers(mut self, allowed_signers: ssh::AllowedSigners) -> Self {
+        self.allowed_signers = allowed_signers;
+        self
+    }
+}
+
+/// The signer of a verified signature.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+pub struct Signer {
+    /// The kind of the signature.
+    pub kind: Kind,
+    /// The user id of the OpenPGP key, or the principals of the SSH key in the allowed signers file.
+    ///
+    /// It is empty if the OpenPGP key has no user id.
+    pub identity: BString,
+    /// The fingerprint of the key, in hexadecimal for OpenPGP keys and as `SHA256:<base64>` for SSH keys.
+    pub fingerprint: String,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("The object is not signed")]
+    Unsigned,
+    #[error("The signed object could not be decoded")]
+    Decode(#[from] git_object::borrowed::Error),
+    #[error("The signature is neither an OpenPGP nor an SSH signature")]
+    UnknownKind,
+    #[error(transparent)]
+    OpenPgp(#[from] openpgp::Error),
+    #[error(transparent)]
+    Ssh(#[from] ssh::Error),
+}
+
+/// Verify the armored `signature` of `data` against the keys in `trust`, and return who made it.
+///
+/// `time` is the time in seconds since the unix epoch at which the signature was made, if it is known, and SSH keys
+/// must be valid at that time.
+pub fn verify(data: &[u8], signature: &[u8], time: Option<u32>, trust: &Trust) -> Result<Signer, Error> {
+    match Kind::from_signature(signature).ok_or(Error::UnknownKind)? {
+        Kind::OpenPgp => {
+            let key = openpgp::verify(data, signature, &trust.keyring)?;
+            Ok(Signer {
+                kind: Kind::OpenPgp,
+                identity: key.user_id.clone().unwrap_or_default(),
+                fingerprint: key.fingerprint.clone(),
+            })
+        }
+        Kind::Ssh => {
+            let (entry, fingerprint) = ssh::verify(data, signature, &trust.allowed_signers, time.map(i64::from))?;
+            Ok(Signer {
+                kind: Kind::Ssh,
+                identity: entry.principals.clone(),
+                fingerprint,
+            })
+        }
+    }
+}
+
+/// Verify the signature of the serialized commit `data` against the keys in `trust`, like `git verify-commit` does,
+/// and return who made it.
+///
+/// The commit time is used as the time at which the signature was made.
+pub fn verify_commit(data: &[u8], trust: &Trust) -> Result<Signer, Error> {
+    let Signed { payload, signature } = git_object::signed::commit(data).ok_or(Error::Unsigned)?;
+    let time = git_object::borrowed::Commit::from_bytes(&payload)?.committer.time.time;
+    verify(&payload, &signature, Some(time), trust)
+}
+
+/// Verify the signature of the serialized tag `data` against the keys in `trust`, like `git verify-tag` does, and
+/// return who made it.
+///
+/// The time of the tagger, if there is one, is used as the time at which the signature was made.
+pub fn verify_tag(data: &[u8], trust: &Trust) -> Result<Signer, Error> {
+    let Signed { payload, signature } = git_object::signed::tag(data).ok_or(Error::Unsigned)?;
+    let time = git_object::borrowed::Tag::from_bytes(&payload)?
+        .signature
+        .map(|tagger| tagger.time.time);
+    verify(&payload, &signature, time, trust)
+}
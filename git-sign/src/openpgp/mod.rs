@@ -0,0 +1,469 @@
+//! Verify OpenPGP signatures like the ones `gpg` makes against the keys in a keyring, and make them with secret keys.
+use crate::{armor, crypto};
+use git_object::bstr::{BString, ByteSlice};
+use packet::{algorithm, kind, tag, Packets, PublicKey, Reader};
+use sha2::Digest;
+
+mod packet;
+
+mod sign;
+pub use sign::SecretKey;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("The OpenPGP data is malformed: {message}")]
+    Malformed { message: &'static str },
+    #[error("OpenPGP signatures of version {version} are not supported")]
+    UnsupportedVersion { version: u8 },
+    #[error("Signatures of type {kind:#04x} are not supported, only signatures of binary data are")]
+    UnsupportedSignatureType { kind: u8 },
+    #[error("The hash algorithm with id {id} is not supported")]
+    UnsupportedHashAlgorithm { id: u8 },
+    #[error("The public key algorithm with id {id} is not supported")]
+    UnsupportedKeyAlgorithm { id: u8 },
+    #[error("The signature was made by the key {key_id} which isn't in the keyring")]
+    UnknownKey { key_id: String },
+    #[error("The signature doesn't match the signed data")]
+    BadSignature,
+    #[error("The key {fingerprint} which made the signature is revoked")]
+    RevokedKey { fingerprint: String },
+    #[error("The key {fingerprint} which made the signature had expired when it was made")]
+    ExpiredKey { fingerprint: String },
+    #[error("The key {fingerprint} which made the signature may not be used for signing")]
+    KeyCannotSign { fingerprint: String },
+    #[error("Encrypted secret keys are not supported")]
+    EncryptedKey,
+    #[error("There is no secret key that can sign")]
+    NoSecretKey,
+    #[error("There is no secret key matching '{id}' that can sign")]
+    NoMatchingSecretKey { id: String },
+}
+
+/// A public key or subkey which can verify signatures.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Key {
+    /// The fingerprint of the key in upper-case hexadecimal.
+    pub fingerprint: String,
+    /// The first user id of the primary key, which is also the primary key of subkeys, if it has one.
+    pub user_id: Option<BString>,
+    public: PublicKey,
+    /// The time at which the key expires in seconds since the unix epoch, if it does.
+    expires: Option<u64>,
+    can_sign: bool,
+    is_revoked: bool,
+}
+
+/// A set of trusted version 4 public keys.
+///
+/// Primary keys are only trusted along with a valid self-signature, and subkeys only with a valid binding signature of
+/// their primary key. The latest of these signatures says when a key expires and whether it may sign, and subkeys that
+/// may sign must also be bound to their primary key by a signature of their own. Keys with a valid revocation signature
+/// are kept, but none of their signatures verify.
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+pub struct Keyring {
+    keys: Vec<Key>,
+}
+
+impl Keyring {
+    /// Read all keys in `data`, which is binary or armored like the output of `gpg --export [--armor]`.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let mut keyring = Keyring::default();
+        keyring.add(data)?;
+        Ok(keyring)
+    }
+
+    /// Add all keys in `data`, which is binary or armored like the output of `gpg --export [--armor]`.
+    pub fn add(&mut self, data: &[u8]) -> Result<&mut Self, Error> {
+        const ARMOR_LABEL: &str = "PGP PUBLIC KEY BLOCK";
+        if data.find("-----BEGIN ").is_none() {
+            self.add_packets(data)?;
+            return Ok(self);
+        }
+        let mut rest = data;
+        while rest.find(ARMOR_LABEL).is_some() {
+            let (packets, remainder) = armor::decode(rest, ARMOR_LABEL).ok_or(Error::Malformed {
+                message: "invalid public key block armor",
+            })?;
+            self.add_packets(&packets)?;
+            rest = remainder;
+        }
+        Ok(self)
+    }
+
+    /// The keys and subkeys in the keyring.
+    pub fn keys(&self) -> &[Key] {
+        &self.keys
+    }
+
+    fn add_packets(&mut self, data: &[u8]) -> Result<(), Error> {
+        /// The packet the signatures that follow belong to.
+        enum Last {
+            PrimaryKey,
+            UserId,
+            Subkey,
+            Other,
+        }
+        let mut certificate: Option<Certificate<'_>> = None;
+        let mut last = Last::Other;
+        for packet in Packets(data) {
+            let (tag, body) = packet?;
+            match tag {
+                tag::PUBLIC_KEY => {
+                    if let Some(certificate) = certificate.take() {
+                        certificate.add_keys_to(&mut self.keys);
+                    }
+                    certificate = Component::from_bytes(body)?.map(|primary| Certificate {
+                        primary,
+                        user_ids: Vec::new(),
+                        subkeys: Vec::new(),
+                    });
+                    last = Last::PrimaryKey;
+                }
+                tag::PUBLIC_SUBKEY => {
+                    last = Last::Other;
+                    if let (Some(certificate), Some(subkey)) = (certificate.as_mut(), Component::from_bytes(body)?) {
+                        certificate.subkeys.push(subkey);
+                        last = Last::Subkey;
+                    }
+                }
+                tag::USER_ID => {
+                    if let Some(certificate) = certificate.as_mut() {
+                        certificate.user_ids.push((body, Vec::new()));
+                    }
+                    last = Last::UserId;
+                }
+                tag::USER_ATTRIBUTE => last = Last::Other,
+                tag::SIGNATURE => {
+                    // Signatures that can't be parsed can't make a key valid, and other implementations may understand them.
+                    let (certificate, signature) = match (certificate.as_mut(), packet::Signature::from_bytes(body)) {
+                        (Some(certificate), Ok(signature)) => (certificate, signature),
+                        _ => continue,
+                    };
+                    let signatures = match last {
+                        Last::PrimaryKey => Some(&mut certificate.primary.signatures),
+                        Last::UserId => certificate.user_ids.last_mut().map(|(_, signatures)| signatures),
+                        Last::Subkey => certificate.subkeys.last_mut().map(|subkey| &mut subkey.signatures),
+                        Last::Other => None,
+                    };
+                    if let Some(signatures) = signatures {
+                        signatures.push(signature);
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(certificate) = certificate {
+            certificate.add_keys_to(&mut self.keys);
+        }
+        Ok(())
+    }
+}
+
+/// A key or subkey along with the signatures that follow it.
+struct Component<'a> {
+    /// The key as it is hashed by signatures over it.
+    hashed: Vec<u8>,
+    fingerprint: Vec<u8>,
+    public: PublicKey,
+    /// The time at which the key was created in seconds since the unix epoch.
+    created: u32,
+    signatures: Vec<packet::Signature<'a>>,
+}
+
+impl<'a> Component<'a> {
+    /// Parse the body of a key packet, or return `None` if it's not a version 4 key.
+    fn from_bytes(body: &[u8]) -> Result<Option<Self>, Error> {
+        let (public, len) = match packet::public_key(body)? {
+            Some(public) => public,
+            None => return Ok(None),
+        };
+        Ok(Some(Component {
+            hashed: hashed_key(&body[..len]),
+            fingerprint: fingerprint(&body[..len]),
+            public,
+            created: Reader(&body[1..]).u32()?,
+            signatures: Vec::new(),
+        }))
+    }
+
+    /// The time at which this key expires according to `signature`, if it does.
+    fn expires(&self, signature: &packet::Signature<'_>) -> Option<u64> {
+        signature
+            .key_expiration
+            .filter(|seconds| *seconds != 0)
+            .map(|seconds| u64::from(self.created) + u64::from(seconds))
+    }
+}
+
+/// A primary key along with its user ids and subkeys.
+struct Certificate<'a> {
+    primary: Component<'a>,
+    user_ids: Vec<(&'a [u8], Vec<packet::Signature<'a>>)>,
+    subkeys: Vec<Component<'a>>,
+}
+
+impl<'a> Certificate<'a> {
+    /// Add the primary key to `keys` if it has a valid self-signature, along with all subkeys that are bound to it.
+    fn add_keys_to(self, keys: &mut Vec<Key>) {
+        let primary = &self.primary;
+        let is_valid = |key: &PublicKey, signature: &packet::Signature<'_>, parts: &[&[u8]]| {
+            matches!(check(key, signature, parts), Ok(true))
+        };
+
+        let mut user_id = None;
+        let mut self_signature: Option<&packet::Signature<'_>> = None;
+        let certifications = self.user_ids.iter().flat_map(|(id, signatures)| {
+            signatures
+                .iter()
+                .filter(|signature| {
+                    matches!(
+                        signature.kind,
+                        kind::GENERIC_CERTIFICATION..=kind::POSITIVE_CERTIFICATION
+                    )
+                })
+                .map(move |signature| (Some(*id), signature))
+        });
+        let direct = primary
+            .signatures
+            .iter()
+            .filter(|signature| signature.kind == kind::DIRECT_KEY)
+            .map(|signature| (None, signature));
+        for (id, signature) in certifications.chain(direct) {
+            let is_self_signature = match id {
+                Some(id) => is_valid(
+                    &primary.public,
+                    signature,
+                    &[&primary.hashed, &[0xb4], &(id.len() as u32).to_be_bytes(), id],
+                ),
+                None => is_valid(&primary.public, signature, &[&primary.hashed]),
+            };
+            if !is_self_signature {
+                continue;
+            }
+            if user_id.is_none() {
+                user_id = id.map(|id| id.as_bstr().to_owned());
+            }
+            if self_signature.is_none_or(|latest| latest.created <= signature.created) {
+                self_signature = Some(signature);
+            }
+        }
+        let self_signature = match self_signature {
+            Some(signature) => signature,
+            None => return,
+        };
+        let is_revoked = primary.signatures.iter().any(|signature| {
+            signature.kind == kind::KEY_REVOCATION && is_valid(&primary.public, signature, &[&primary.hashed])
+        });
+        let expires = primary.expires(self_signature);
+        keys.push(Key {
+            fingerprint: to_hex(&primary.fingerprint),
+            user_id: user_id.clone(),
+            public: primary.public.clone(),
+            expires,
+            can_sign: self_signature.key_flags.is_none_or(|flags| flags & 0x02 != 0),
+            is_revoked,
+        });
+
+        for subkey in &self.subkeys {
+            let parts: &[&[u8]] = &[&primary.hashed, &subkey.hashed];
+            let binding = subkey
+                .signatures
+                .iter()
+                .filter(|signature| {
+                    signature.kind == kind::SUBKEY_BINDING && is_valid(&primary.public, signature, parts)
+                })
+                .max_by_key(|signature| signature.created);
+            let binding = match binding {
+                Some(signature) => signature,
+                None => continue,
+            };
+            let is_cross_signed = binding
+                .embedded
+                .and_then(|body| packet::Signature::from_bytes(body).ok())
+                .is_some_and(|signature| {
+                    signature.kind == kind::PRIMARY_KEY_BINDING && is_valid(&subkey.public, &signature, parts)
+                });
+            let is_revoked = is_revoked
+                || subkey.signatures.iter().any(|signature| {
+                    signature.kind == kind::SUBKEY_REVOCATION && is_valid(&primary.public, signature, parts)
+                });
+            let expires = match (expires, subkey.expires(binding)) {
+                (Some(primary), Some(subkey)) => Some(primary.min(subkey)),
+                (primary, subkey) => primary.or(subkey),
+            };
+            keys.push(Key {
+                fingerprint: to_hex(&subkey.fingerprint),
+                user_id: user_id.clone(),
+                public: subkey.public.clone(),
+                expires,
+                can_sign: is_cross_signed && binding.key_flags.is_none_or(|flags| flags & 0x02 != 0),
+                is_revoked,
+            });
+        }
+    }
+}
+
+/// Verify the armored OpenPGP `signature` of `data`, which must have been made by a key in `keyring`, and return
+/// that key.
+pub fn verify<'a>(data: &[u8], signature: &[u8], keyring: &'a Keyring) -> Result<&'a Key, Error> {
+    let (packets, _) = armor::decode(signature, "PGP SIGNATURE").ok_or(Error::Malformed {
+        message: "invalid signature armor",
+    })?;
+    let body = Packets(&packets)
+        .find_map(|packet| match packet {
+            Ok((tag::SIGNATURE, body)) => Some(Ok(body)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .ok_or(Error::Malformed {
+            message: "no signature packet",
+        })??;
+    let signature = packet::Signature::from_bytes(body)?;
+    if signature.kind != kind::BINARY {
+        return Err(Error::UnsupportedSignatureType { kind: signature.kind });
+    }
+
+    let key = match (signature.issuer_fingerprint, signature.issuer) {
+        (Some(fingerprint), _) => {
+            let fingerprint = to_hex(fingerprint);
+            keyring.keys.iter().find(|key| key.fingerprint == fingerprint)
+        }
+        (None, Some(key_id)) => {
+            let key_id = to_hex(key_id);
+            keyring.keys.iter().find(|key| key.fingerprint.ends_with(&key_id))
+        }
+        (None, None) => {
+            return Err(Error::Malformed {
+                message: "the signature doesn't name its issuer",
+            })
+        }
+    }
+    .ok_or_else(|| Error::UnknownKey {
+        key_id: to_hex(
+            signature
+                .issuer
+                .expect("the key id is derived from the fingerprint if needed"),
+        ),
+    })?;
+
+    if !check(&key.public, &signature, &[data])? {
+        return Err(Error::BadSignature);
+    }
+    let fingerprint = || key.fingerprint.clone();
+    if key.is_revoked {
+        return Err(Error::RevokedKey {
+            fingerprint: fingerprint(),
+        });
+    }
+    if !key.can_sign {
+        return Err(Error::KeyCannotSign {
+            fingerprint: fingerprint(),
+        });
+    }
+    if let Some(expires) = key.expires {
+        if signature.created.is_none_or(|created| u64::from(created) >= expires) {
+            return Err(Error::ExpiredKey {
+                fingerprint: fingerprint(),
+            });
+        }
+    }
+    Ok(key)
+}
+
+/// Return true if `signature` was made by `key` over the concatenation of `parts`.
+fn check(key: &PublicKey, signature: &packet::Signature<'_>, parts: &[&[u8]]) -> Result<bool, Error> {
+    let hash = Hash::from_id(signature.hash_algorithm)?;
+    let trailer_len = (signature.hashed.len() as u32).to_be_bytes();
+    let mut hashed = parts.to_vec();
+    hashed.extend_from_slice(&[signature.hashed, &[4, 0xff], &trailer_len]);
+    let digest = hash.digest(&hashed);
+    if digest[..2] != signature.hash_prefix {
+        return Ok(false);
+    }
+
+    let mut values = Reader(signature.values);
+    Ok(match (key, signature.algorithm) {
+        (PublicKey::Rsa { n, e }, algorithm::RSA) | (PublicKey::Rsa { n, e }, algorithm::RSA_SIGN_ONLY) => {
+            crypto::rsa(n, e, hash.pkcs1v15(), &digest, values.mpi()?)
+        }
+        (PublicKey::EcdsaP256 { point }, algorithm::ECDSA) => {
+            crypto::p256(point, &digest, values.mpi()?, values.mpi()?)
+        }
+        (PublicKey::Ed25519 { point }, algorithm::EDDSA_LEGACY) => {
+            crypto::ed25519(point, &digest, values.mpi()?, values.mpi()?)
+        }
+        (PublicKey::Ed25519 { point }, algorithm::ED25519) => {
+            let signature = values.take(64)?;
+            crypto::ed25519(point, &digest, &signature[..32], &signature[32..])
+        }
+        (PublicKey::Unsupported { algorithm }, _) => return Err(Error::UnsupportedKeyAlgorithm { id: *algorithm }),
+        (_, id) => return Err(Error::UnsupportedKeyAlgorithm { id }),
+    })
+}
+
+/// The hash algorithms of signatures that are supported.
+#[derive(Clone, Copy)]
+enum Hash {
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl Hash {
+    fn from_id(id: u8) -> Result<Self, Error> {
+        Ok(match id {
+            2 => Hash::Sha1,
+            8 => Hash::Sha256,
+            9 => Hash::Sha384,
+            10 => Hash::Sha512,
+            11 => Hash::Sha224,
+            _ => return Err(Error::UnsupportedHashAlgorithm { id }),
+        })
+    }
+
+    fn digest(self, parts: &[&[u8]]) -> Vec<u8> {
+        fn digest<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut hasher = D::new();
+            for part in parts {
+                hasher.update(part);
+            }
+            hasher.finalize().to_vec()
+        }
+        match self {
+            Hash::Sha1 => digest::<sha1::Sha1>(parts),
+            Hash::Sha224 => digest::<sha2::Sha224>(parts),
+            Hash::Sha256 => digest::<sha2::Sha256>(parts),
+            Hash::Sha384 => digest::<sha2::Sha384>(parts),
+            Hash::Sha512 => digest::<sha2::Sha512>(parts),
+        }
+    }
+
+    fn pkcs1v15(self) -> rsa::Pkcs1v15Sign {
+        match self {
+            Hash::Sha1 => rsa::Pkcs1v15Sign::new::<sha1::Sha1>(),
+            Hash::Sha224 => rsa::Pkcs1v15Sign::new::<sha2::Sha224>(),
+            Hash::Sha256 => rsa::Pkcs1v15Sign::new::<sha2::Sha256>(),
+            Hash::Sha384 => rsa::Pkcs1v15Sign::new::<sha2::Sha384>(),
+            Hash::Sha512 => rsa::Pkcs1v15Sign::new::<sha2::Sha512>(),
+        }
+    }
+}
+
+/// The version 4 public key packet `body` as it is hashed for fingerprints and signatures over keys.
+fn hashed_key(body: &[u8]) -> Vec<u8> {
+    let mut hashed = vec![0x99];
+    hashed.extend_from_slice(&(body.len() as u16).to_be_bytes());
+    hashed.extend_from_slice(body);
+    hashed
+}
+
+/// The fingerprint of the version 4 public key packet `body`.
+fn fingerprint(body: &[u8]) -> Vec<u8> {
+    Hash::Sha1.digest(&[&hashed_key(body)])
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
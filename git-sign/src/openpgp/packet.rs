@@ -0,0 +1,304 @@
+//! Parsing of the OpenPGP packets needed to verify signatures, as defined in RFC 4880.
+use super::Error;
+use std::convert::TryInto;
+
+/// The tags of packets that are of interest.
+pub mod tag {
+    pub const SIGNATURE: u8 = 2;
+    pub const SECRET_KEY: u8 = 5;
+    pub const PUBLIC_KEY: u8 = 6;
+    pub const SECRET_SUBKEY: u8 = 7;
+    pub const USER_ID: u8 = 13;
+    pub const PUBLIC_SUBKEY: u8 = 14;
+    pub const USER_ATTRIBUTE: u8 = 17;
+}
+
+/// The types of signatures that are of interest.
+pub mod kind {
+    pub const BINARY: u8 = 0x00;
+    pub const GENERIC_CERTIFICATION: u8 = 0x10;
+    pub const POSITIVE_CERTIFICATION: u8 = 0x13;
+    pub const SUBKEY_BINDING: u8 = 0x18;
+    pub const PRIMARY_KEY_BINDING: u8 = 0x19;
+    pub const DIRECT_KEY: u8 = 0x1F;
+    pub const KEY_REVOCATION: u8 = 0x20;
+    pub const SUBKEY_REVOCATION: u8 = 0x28;
+}
+
+/// The types of signature subpackets that are of interest.
+pub mod subpacket {
+    pub const CREATION_TIME: u8 = 2;
+    pub const KEY_EXPIRATION_TIME: u8 = 9;
+    pub const ISSUER: u8 = 16;
+    pub const KEY_FLAGS: u8 = 27;
+    pub const EMBEDDED_SIGNATURE: u8 = 32;
+    pub const ISSUER_FINGERPRINT: u8 = 33;
+}
+
+/// The algorithm ids of the supported public key algorithms.
+pub mod algorithm {
+    pub const RSA: u8 = 1;
+    pub const RSA_SIGN_ONLY: u8 = 3;
+    pub const ECDSA: u8 = 19;
+    /// The EdDSA algorithm of the draft for RFC 4880bis that gnupg uses for Ed25519 keys.
+    pub const EDDSA_LEGACY: u8 = 22;
+    pub const ED25519: u8 = 27;
+}
+
+const OID_NIST_P256: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+const OID_ED25519: &[u8] = &[0x2B, 0x06, 0x01, 0x04, 0x01, 0xDA, 0x47, 0x0F, 0x01];
+
+fn malformed(message: &'static str) -> Error {
+    Error::Malformed { message }
+}
+
+/// An iterator over the tags and bodies of the packets in binary OpenPGP data.
+pub struct Packets<'a>(pub &'a [u8]);
+
+impl<'a> Iterator for Packets<'a> {
+    type Item = Result<(u8, &'a [u8]), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let res = self.parse_packet();
+        if res.is_err() {
+            self.0 = &[];
+        }
+        Some(res)
+    }
+}
+
+impl<'a> Packets<'a> {
+    fn parse_packet(&mut self) -> Result<(u8, &'a [u8]), Error> {
+        let mut data = Reader(self.0);
+        let header = data.u8()?;
+        if header & 0x80 == 0 {
+            return Err(malformed("packet header without its most significant bit set"));
+        }
+        let (tag, len) = if header & 0x40 == 0 {
+            let len = match header & 0x03 {
+                0 => usize::from(data.u8()?),
+                1 => usize::from(data.u16()?),
+                2 => data.u32()? as usize,
+                _ => data.0.len(),
+            };
+            ((header >> 2) & 0x0f, len)
+        } else {
+            let len = match data.u8()? {
+                len @ 0..=191 => usize::from(len),
+                len @ 192..=223 => ((usize::from(len) - 192) << 8) + usize::from(data.u8()?) + 192,
+                255 => data.u32()? as usize,
+                _ => return Err(malformed("partial body lengths are not supported")),
+            };
+            (header & 0x3f, len)
+        };
+        let body = data.take(len)?;
+        self.0 = data.0;
+        Ok((tag, body))
+    }
+}
+
+/// A cursor over binary data that fails if there isn't enough of it.
+pub struct Reader<'a>(pub &'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(malformed("unexpected end of data"));
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    pub fn u8(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn u16(&mut self) -> Result<u16, Error> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Read a multiprecision integer and return its big-endian bytes.
+    pub fn mpi(&mut self) -> Result<&'a [u8], Error> {
+        let bits = usize::from(self.u16()?);
+        self.take(bits.div_ceil(8))
+    }
+}
+
+/// The key material of a public key.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum PublicKey {
+    Rsa {
+        n: Vec<u8>,
+        e: Vec<u8>,
+    },
+    /// An uncompressed SEC1 encoded point on NIST P-256.
+    EcdsaP256 {
+        point: Vec<u8>,
+    },
+    Ed25519 {
+        point: [u8; 32],
+    },
+    /// A key of an algorithm or curve that isn't supported.
+    Unsupported {
+        algorithm: u8,
+    },
+}
+
+/// Parse the public part of the body of a version 4 key or subkey packet along with its length, or return `None` if
+/// it is of another version.
+pub fn public_key(body: &[u8]) -> Result<Option<(PublicKey, usize)>, Error> {
+    let mut data = Reader(body);
+    if data.u8()? != 4 {
+        return Ok(None);
+    }
+    let _created = data.u32()?;
+    let algorithm = data.u8()?;
+    let key = match algorithm {
+        algorithm::RSA | algorithm::RSA_SIGN_ONLY => {
+            let n = data.mpi()?.to_vec();
+            let e = data.mpi()?.to_vec();
+            PublicKey::Rsa { n, e }
+        }
+        algorithm::ECDSA | algorithm::EDDSA_LEGACY => {
+            let oid_len = usize::from(data.u8()?);
+            let oid = data.take(oid_len)?;
+            let point = data.mpi()?;
+            match (algorithm, oid) {
+                (algorithm::ECDSA, OID_NIST_P256) => PublicKey::EcdsaP256 { point: point.to_vec() },
+                (algorithm::EDDSA_LEGACY, OID_ED25519) => match point.split_first() {
+                    Some((0x40, point)) if point.len() == 32 => PublicKey::Ed25519 {
+                        point: point.try_into().expect("checked length"),
+                    },
+                    _ => return Err(malformed("an Ed25519 point must be 32 bytes prefixed with 0x40")),
+                },
+                _ => PublicKey::Unsupported { algorithm },
+            }
+        }
+        algorithm::ED25519 => PublicKey::Ed25519 {
+            point: data.take(32)?.try_into().expect("checked length"),
+        },
+        _ => PublicKey::Unsupported { algorithm },
+    };
+    Ok(Some((key, body.len() - data.0.len())))
+}
+
+/// A parsed version 4 signature packet.
+pub struct Signature<'a> {
+    pub kind: u8,
+    pub algorithm: u8,
+    pub hash_algorithm: u8,
+    /// The part of the packet which is hashed along with the signed data.
+    pub hashed: &'a [u8],
+    /// The id of the key which made the signature, if it is known.
+    pub issuer: Option<&'a [u8]>,
+    /// The fingerprint of the version 4 key which made the signature, if it is known.
+    pub issuer_fingerprint: Option<&'a [u8]>,
+    /// The first byte of the flags of the signed key, which says what it may be used for, if it is known.
+    pub key_flags: Option<u8>,
+    /// The time the signature was made at in seconds since the unix epoch, if it is known.
+    pub created: Option<u32>,
+    /// The seconds after its creation at which the signed key expires, with 0 meaning never, if it is known.
+    pub key_expiration: Option<u32>,
+    /// The body of a signature packet contained in this one, like the primary key binding signature of a signing subkey.
+    pub embedded: Option<&'a [u8]>,
+    /// The first two bytes of the hash, to quickly reject signatures of other data.
+    pub hash_prefix: [u8; 2],
+    /// The multiprecision integers or the raw bytes of the signature, depending on the algorithm.
+    pub values: &'a [u8],
+}
+
+impl<'a> Signature<'a> {
+    /// Parse the body of a signature packet.
+    pub fn from_bytes(body: &'a [u8]) -> Result<Self, Error> {
+        let mut data = Reader(body);
+        let version = data.u8()?;
+        if version != 4 {
+            return Err(Error::UnsupportedVersion { version });
+        }
+        let kind = data.u8()?;
+        let algorithm = data.u8()?;
+        let hash_algorithm = data.u8()?;
+        let hashed_len = usize::from(data.u16()?);
+        let hashed_subpackets = data.take(hashed_len)?;
+        let hashed = &body[..body.len() - data.0.len()];
+        let unhashed_len = usize::from(data.u16()?);
+        let unhashed_subpackets = data.take(unhashed_len)?;
+        let hash_prefix = data.take(2)?;
+
+        let mut issuer = None;
+        let mut issuer_fingerprint = None;
+        let mut key_flags = None;
+        let mut created = None;
+        let mut key_expiration = None;
+        let mut embedded = None;
+        let subpackets = Subpackets(hashed_subpackets)
+            .map(|subpacket| subpacket.map(|subpacket| (true, subpacket)))
+            .chain(Subpackets(unhashed_subpackets).map(|subpacket| subpacket.map(|subpacket| (false, subpacket))));
+        for subpacket in subpackets {
+            let (is_hashed, (kind, body)) = subpacket?;
+            match kind {
+                subpacket::ISSUER if body.len() == 8 => issuer = Some(body),
+                subpacket::ISSUER_FINGERPRINT if body.len() == 21 && body[0] == 4 => {
+                    issuer_fingerprint = Some(&body[1..])
+                }
+                subpacket::KEY_FLAGS if is_hashed => key_flags = body.first().copied(),
+                subpacket::CREATION_TIME if is_hashed => created = Some(Reader(body).u32()?),
+                subpacket::KEY_EXPIRATION_TIME if is_hashed => key_expiration = Some(Reader(body).u32()?),
+                subpacket::EMBEDDED_SIGNATURE => embedded = Some(body),
+                _ => {}
+            }
+        }
+        Ok(Signature {
+            kind,
+            algorithm,
+            hash_algorithm,
+            hashed,
+            issuer: issuer.or_else(|| issuer_fingerprint.map(|fingerprint: &[u8]| &fingerprint[12..])),
+            issuer_fingerprint,
+            key_flags,
+            created,
+            key_expiration,
+            embedded,
+            hash_prefix: [hash_prefix[0], hash_prefix[1]],
+            values: data.0,
+        })
+    }
+}
+
+/// An iterator over the types, without the critical bit, and bodies of signature subpackets.
+struct Subpackets<'a>(&'a [u8]);
+
+impl<'a> Iterator for Subpackets<'a> {
+    type Item = Result<(u8, &'a [u8]), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None;
+        }
+        let mut data = Reader(self.0);
+        let res = (|| {
+            let len = match data.u8()? {
+                len @ 0..=191 => usize::from(len),
+                len @ 192..=254 => ((usize::from(len) - 192) << 8) + usize::from(data.u8()?) + 192,
+                255 => data.u32()? as usize,
+            };
+            let body = data.take(len)?;
+            match body.split_first() {
+                Some((kind, body)) => Ok((kind & 0x7f, body)),
+                None => Err(malformed("empty signature subpacket")),
+            }
+        })();
+        self.0 = if res.is_ok() { data.0 } else { &[] };
+        Some(res)
+    }
+}
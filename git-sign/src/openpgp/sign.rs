@@ -0,0 +1,332 @@
+use super::{
+    fingerprint, packet,
+    packet::{algorithm, subpacket, tag, Packets, PublicKey, Reader},
+    to_hex, Error, Hash,
+};
+use crate::{armor, crypto, sign::Sign};
+use git_object::bstr::{BStr, BString, ByteSlice};
+use p256::ecdsa::signature::hazmat::PrehashSigner;
+use std::{
+    convert::TryInto,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The hash algorithm of the signatures that are made.
+const HASH: (Hash, u8) = (Hash::Sha256, 8);
+
+/// An unencrypted version 4 secret key or subkey which signs like `gpg --detach-sign --armor`.
+pub struct SecretKey {
+    fingerprint: Vec<u8>,
+    user_id: Option<BString>,
+    algorithm: u8,
+    secret: Secret,
+}
+
+enum Secret {
+    Rsa(rsa::RsaPrivateKey),
+    P256(p256::ecdsa::SigningKey),
+    Ed25519(ed25519_dalek::SigningKey),
+}
+
+/// A secret key or subkey as it was found in the data, along with what is known about it.
+struct Candidate<'a> {
+    body: &'a [u8],
+    public: PublicKey,
+    public_len: usize,
+    fingerprint: Vec<u8>,
+    /// The index of the primary key of this key, which may be the key itself.
+    primary: usize,
+    user_ids: Vec<&'a [u8]>,
+    can_sign: bool,
+}
+
+impl SecretKey {
+    /// Read the key to sign with from `data`, which is binary or armored like the output of
+    /// `gpg --export-secret-keys [--armor]`.
+    ///
+    /// `id` selects the key like `user.signingKey`, by the fingerprint or key id of a key or subkey, which must be
+    /// suffixed with `!` to use a primary key that has subkeys that can sign, or by a part of the user id of its primary
+    /// key. Otherwise, the first primary key is selected. Of the keys of a selected primary key, the last one that can sign is used.
+    pub fn from_bytes(data: &[u8], id: Option<&str>) -> Result<Self, Error> {
+        const ARMOR_LABEL: &str = "PGP PRIVATE KEY BLOCK";
+        let mut blocks = Vec::new();
+        if data.find("-----BEGIN ").is_none() {
+            blocks.push(data.to_vec());
+        } else {
+            let mut rest = data;
+            while rest.find(ARMOR_LABEL).is_some() {
+                let (block, remainder) = armor::decode(rest, ARMOR_LABEL).ok_or(Error::Malformed {
+                    message: "invalid private key block armor",
+                })?;
+                blocks.push(block);
+                rest = remainder;
+            }
+        }
+        let mut candidates = Vec::new();
+        for block in &blocks {
+            parse_candidates(block, &mut candidates)?;
+        }
+
+        let chosen = match id {
+            Some(id) => select(&candidates, id).ok_or_else(|| Error::NoMatchingSecretKey { id: id.into() })?,
+            None => candidates
+                .first()
+                .and_then(|first| last_signing_key(&candidates, first.primary))
+                .ok_or(Error::NoSecretKey)?,
+        };
+        let candidate = &candidates[chosen];
+        let user_id = candidates[candidate.primary]
+            .user_ids
+            .first()
+            .map(|user_id| user_id.as_bstr().to_owned());
+        let algorithm = candidate.body[5];
+        let mut data = Reader(&candidate.body[candidate.public_len..]);
+        if data.u8()? != 0 {
+            return Err(Error::EncryptedKey);
+        }
+        let secret = match &candidate.public {
+            PublicKey::Rsa { n, e } => {
+                let number = |data: &mut Reader<'_>| data.mpi().map(rsa::BigUint::from_bytes_be);
+                let d = number(&mut data)?;
+                let p = number(&mut data)?;
+                let q = number(&mut data)?;
+                let key = rsa::RsaPrivateKey::from_components(
+                    rsa::BigUint::from_bytes_be(n),
+                    rsa::BigUint::from_bytes_be(e),
+                    d,
+                    vec![p, q],
+                )
+                .map_err(|_| Error::Malformed {
+                    message: "invalid RSA secret key",
+                })?;
+                Secret::Rsa(key)
+            }
+            PublicKey::EcdsaP256 { .. } => {
+                let scalar = crypto::left_pad(data.mpi()?, 32).ok_or(Error::Malformed {
+                    message: "ECDSA NIST P-256 secret keys must be 32 bytes",
+                })?;
+                Secret::P256(
+                    p256::ecdsa::SigningKey::from_slice(&scalar).map_err(|_| Error::Malformed {
+                        message: "invalid ECDSA NIST P-256 secret key",
+                    })?,
+                )
+            }
+            PublicKey::Ed25519 { .. } => {
+                let seed = if algorithm == algorithm::ED25519 {
+                    Some(data.take(32)?.to_vec())
+                } else {
+                    crypto::left_pad(data.mpi()?, 32)
+                }
+                .ok_or(Error::Malformed {
+                    message: "Ed25519 secret keys must be 32 bytes",
+                })?;
+                Secret::Ed25519(ed25519_dalek::SigningKey::from_bytes(
+                    seed.as_slice().try_into().expect("padded"),
+                ))
+            }
+            PublicKey::Unsupported { algorithm } => return Err(Error::UnsupportedKeyAlgorithm { id: *algorithm }),
+        };
+        Ok(SecretKey {
+            fingerprint: candidate.fingerprint.clone(),
+            user_id,
+            algorithm,
+            secret,
+        })
+    }
+
+    /// The fingerprint of the key in upper-case hexadecimal.
+    pub fn fingerprint(&self) -> String {
+        to_hex(&self.fingerprint)
+    }
+
+    /// The first user id of the primary key of this key, if it has one.
+    pub fn user_id(&self) -> Option<&BStr> {
+        self.user_id.as_ref().map(|user_id| user_id.as_bstr())
+    }
+}
+
+fn parse_candidates<'a>(data: &'a [u8], candidates: &mut Vec<Candidate<'a>>) -> Result<(), Error> {
+    let mut primary = None;
+    let mut is_signed = false;
+    for packet in Packets(data) {
+        let (tag, body) = packet?;
+        match tag {
+            tag::SECRET_KEY | tag::SECRET_SUBKEY => {
+                if tag == tag::SECRET_KEY {
+                    primary = None;
+                }
+                let (public, public_len) = match packet::public_key(body)? {
+                    Some(public) => public,
+                    None => continue,
+                };
+                let index = candidates.len();
+                let primary = *primary.get_or_insert(index);
+                candidates.push(Candidate {
+                    body,
+                    fingerprint: fingerprint(&body[..public_len]),
+                    public,
+                    public_len,
+                    primary,
+                    user_ids: Vec::new(),
+                    can_sign: true,
+                });
+                is_signed = false;
+            }
+            tag::USER_ID => {
+                if let Some(primary) = primary {
+                    candidates[primary].user_ids.push(body);
+                }
+            }
+            tag::SIGNATURE if !is_signed && primary.is_some() => {
+                // The first self-signature after a key says what the key may be used for.
+                let signature = packet::Signature::from_bytes(body)?;
+                if let (Some(flags), Some(candidate)) = (signature.key_flags, candidates.last_mut()) {
+                    candidate.can_sign = flags & 0x02 != 0;
+                }
+                is_signed = true;
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Return the index of the key to sign with that `id` selects.
+fn select(candidates: &[Candidate<'_>], id: &str) -> Option<usize> {
+    let (id, is_exact) = match id.strip_suffix('!') {
+        Some(id) => (id, true),
+        None => (id, false),
+    };
+    let hex = id.trim_start_matches("0x").to_ascii_uppercase();
+    let is_hex = hex.len() >= 8 && hex.bytes().all(|b| b.is_ascii_hexdigit());
+    if is_hex {
+        if let Some(index) = candidates
+            .iter()
+            .position(|candidate| to_hex(&candidate.fingerprint).ends_with(&hex))
+        {
+            let candidate = &candidates[index];
+            return if is_exact || candidate.primary != index {
+                Some(index).filter(|_| candidate.can_sign)
+            } else {
+                last_signing_key(candidates, index)
+            };
+        }
+    }
+    let needle = id.to_ascii_lowercase();
+    candidates
+        .iter()
+        .enumerate()
+        .filter(|(index, candidate)| {
+            candidate.primary == *index
+                && candidate
+                    .user_ids
+                    .iter()
+                    .any(|user_id| user_id.to_ascii_lowercase().find(&needle).is_some())
+        })
+        .find_map(|(index, _)| last_signing_key(candidates, index))
+}
+
+/// Return the index of the last key of the primary key at `primary` that can sign.
+fn last_signing_key(candidates: &[Candidate<'_>], primary: usize) -> Option<usize> {
+    candidates
+        .iter()
+        .rposition(|candidate| candidate.primary == primary && candidate.can_sign)
+}
+
+impl Sign for SecretKey {
+    type Error = Error;
+
+    fn sign(&mut self, data: &[u8]) -> Result<BString, Self::Error> {
+        let (hash, hash_id) = HASH;
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |time| time.as_secs() as u32);
+        let mut issuer_fingerprint = vec![4];
+        issuer_fingerprint.extend_from_slice(&self.fingerprint);
+        let mut hashed_subpackets = Vec::new();
+        push_subpacket(
+            &mut hashed_subpackets,
+            subpacket::ISSUER_FINGERPRINT,
+            &issuer_fingerprint,
+        );
+        push_subpacket(&mut hashed_subpackets, subpacket::CREATION_TIME, &created.to_be_bytes());
+        let mut unhashed_subpackets = Vec::new();
+        push_subpacket(
+            &mut unhashed_subpackets,
+            subpacket::ISSUER,
+            &self.fingerprint[self.fingerprint.len() - 8..],
+        );
+
+        let mut body = vec![4, 0x00, self.algorithm, hash_id];
+        body.extend_from_slice(&(hashed_subpackets.len() as u16).to_be_bytes());
+        body.extend_from_slice(&hashed_subpackets);
+        let trailer_len = (body.len() as u32).to_be_bytes();
+        let digest = hash.digest(&[data, &body, &[4, 0xff], &trailer_len]);
+        body.extend_from_slice(&(unhashed_subpackets.len() as u16).to_be_bytes());
+        body.extend_from_slice(&unhashed_subpackets);
+        body.extend_from_slice(&digest[..2]);
+
+        match &self.secret {
+            Secret::Rsa(key) => {
+                let value = key.sign(hash.pkcs1v15(), &digest).map_err(|_| Error::Malformed {
+                    message: "the RSA key cannot sign",
+                })?;
+                push_mpi(&mut body, &value);
+            }
+            Secret::P256(key) => {
+                let value: p256::ecdsa::Signature = key.sign_prehash(&digest).map_err(|_| Error::Malformed {
+                    message: "the ECDSA key cannot sign",
+                })?;
+                let (r, s) = value.split_bytes();
+                push_mpi(&mut body, &r);
+                push_mpi(&mut body, &s);
+            }
+            Secret::Ed25519(key) => {
+                let value = ed25519_dalek::Signer::sign(key, &digest).to_bytes();
+                if self.algorithm == algorithm::ED25519 {
+                    body.extend_from_slice(&value);
+                } else {
+                    push_mpi(&mut body, &value[..32]);
+                    push_mpi(&mut body, &value[32..]);
+                }
+            }
+        }
+
+        let mut packet = vec![0xC0 | tag::SIGNATURE];
+        push_len(&mut packet, body.len());
+        packet.extend_from_slice(&body);
+        Ok(armor::encode(&packet, "PGP SIGNATURE", armor::Kind::OpenPgp))
+    }
+}
+
+fn push_len(out: &mut Vec<u8>, len: usize) {
+    match len {
+        0..=191 => out.push(len as u8),
+        192..=8383 => {
+            let len = len - 192;
+            out.push((len >> 8) as u8 + 192);
+            out.push(len as u8);
+        }
+        _ => {
+            out.push(255);
+            out.extend_from_slice(&(len as u32).to_be_bytes());
+        }
+    }
+}
+
+fn push_subpacket(out: &mut Vec<u8>, kind: u8, body: &[u8]) {
+    push_len(out, body.len() + 1);
+    out.push(kind);
+    out.extend_from_slice(body);
+}
+
+/// Write the big-endian integer `bytes` as multiprecision integer.
+fn push_mpi(out: &mut Vec<u8>, bytes: &[u8]) {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    let bytes = &bytes[start..];
+    let bits = bytes
+        .first()
+        .map_or(0, |first| (bytes.len() - 1) * 8 + (8 - first.leading_zeros() as usize));
+    out.extend_from_slice(&(bits as u16).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
@@ -0,0 +1,77 @@
+//! Sign commits and tags with a pluggable signer, like `git commit -S` and `git tag -s` do.
+use git_object::{bstr::BString, owned};
+use std::io;
+
+pub mod program;
+#[doc(inline)]
+pub use program::Program;
+
+/// Something that makes armored signatures, like an OpenPGP or SSH key or an external program.
+pub trait Sign {
+    type Error: std::error::Error + 'static;
+
+    /// Sign `data` and return the armored signature, which ends with a newline.
+    fn sign(&mut self, data: &[u8]) -> Result<BString, Self::Error>;
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error<E>
+where
+    E: std::error::Error + 'static,
+{
+    #[error("The object to sign could not be serialized")]
+    Io(#[from] io::Error),
+    #[error("The object could not be signed")]
+    Sign(#[source] E),
+    #[error("The signer produced an empty signature")]
+    EmptySignature,
+    #[error("Tags without message cannot be signed")]
+    EmptyMessage,
+}
+
+/// The header of commits which holds their signature.
+const SIGNATURE_HEADER: &str = "gpgsig";
+
+/// Sign `commit` with `signer` and store the signature in its `gpgsig` header, replacing any previous signature.
+///
+/// Written with [`owned::Commit::write_to()`], the commit is signed the way `git commit -S` signs it.
+pub fn commit<S: Sign>(commit: &mut owned::Commit, signer: &mut S) -> Result<(), Error<S::Error>> {
+    commit
+        .extra_headers
+        .retain(|(name, _)| name != SIGNATURE_HEADER && name != "gpgsig-sha256");
+    let mut payload = Vec::new();
+    commit.write_to(&mut payload)?;
+    let mut signature = signer.sign(&payload).map_err(Error::Sign)?;
+    // Lines of multi-line headers are terminated by the header folding, which would add an empty line otherwise.
+    if signature.last() == Some(&b'\n') {
+        signature.pop();
+    }
+    if signature.is_empty() {
+        return Err(Error::EmptySignature);
+    }
+    commit.extra_headers.push((SIGNATURE_HEADER.into(), signature));
+    Ok(())
+}
+
+/// Sign `tag` with `signer` and store the signature in its `pgp_signature` field, replacing any previous signature.
+///
+/// Written with [`owned::Tag::write_to()`], the tag is signed the way `git tag -s` signs it, which is why the
+/// newline that ends the signed message is removed from it, as it separates the message from the signature.
+pub fn tag<S: Sign>(tag: &mut owned::Tag, signer: &mut S) -> Result<(), Error<S::Error>> {
+    tag.pgp_signature = None;
+    if tag.message.iter().all(|b| *b == b'\n') {
+        return Err(Error::EmptyMessage);
+    }
+    if tag.message.last() != Some(&b'\n') {
+        tag.message.push(b'\n');
+    }
+    let mut payload = Vec::new();
+    tag.write_to(&mut payload)?;
+    let signature = signer.sign(&payload).map_err(Error::Sign)?;
+    if signature.is_empty() {
+        return Err(Error::EmptySignature);
+    }
+    tag.message.pop();
+    tag.pgp_signature = Some(signature);
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use crate::{sign::Sign, ssh, Kind};
+use git_object::bstr::{BString, ByteSlice};
+use std::{
+    ffi::OsString,
+    io::{self, Write},
+    process::{Command, Stdio},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Could not run '{}'", program.to_string_lossy())]
+    Io { source: io::Error, program: OsString },
+    #[error("'{}' failed to sign: {stderr}", program.to_string_lossy())]
+    Failed { program: OsString, stderr: BString },
+}
+
+/// An external program that signs, like `gpg` configured by `gpg.program` or `ssh-keygen` configured by
+/// `gpg.ssh.program`, which is called the way git calls it.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Program {
+    /// The program to run, which is looked up in `PATH` unless it is a path.
+    pub program: OsString,
+    /// The kind of signatures the program makes, which determines its arguments.
+    pub kind: Kind,
+    /// The key to sign with like `user.signingKey`, the id of an OpenPGP key or the path to an SSH key file.
+    pub key: OsString,
+}
+
+impl Program {
+    /// Sign with `gpg` using the OpenPGP key with the id, fingerprint or user id `key`.
+    pub fn gpg(key: impl Into<OsString>) -> Self {
+        Program {
+            program: "gpg".into(),
+            kind: Kind::OpenPgp,
+            key: key.into(),
+        }
+    }
+
+    /// Sign with `ssh-keygen` using the SSH key in the file at `key`, whose private key may also be in an agent.
+    pub fn ssh_keygen(key: impl Into<OsString>) -> Self {
+        Program {
+            program: "ssh-keygen".into(),
+            kind: Kind::Ssh,
+            key: key.into(),
+        }
+    }
+
+    /// Run `program` instead of the default one.
+    pub fn with_program(mut self, program: impl Into<OsString>) -> Self {
+        self.program = program.into();
+        self
+    }
+}
+
+impl Sign for Program {
+    type Error = Error;
+
+    fn sign(&mut self, data: &[u8]) -> Result<BString, Self::Error> {
+        let mut cmd = Command::new(&self.program);
+        match self.kind {
+            Kind::OpenPgp => cmd.args(["--status-fd=2", "-bsau"]).arg(&self.key),
+            Kind::Ssh => cmd.args(["-Y", "sign", "-n", ssh::NAMESPACE, "-f"]).arg(&self.key),
+        };
+        let io_err = |source| Error::Io {
+            source,
+            program: self.program.clone(),
+        };
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(io_err)?;
+        let mut stdin = child.stdin.take().expect("piped stdin");
+        let output = std::thread::scope(|scope| {
+            // Write from another thread so the program can't block on writing its output while we write its input.
+            let writer = scope.spawn(move || stdin.write_all(data));
+            let output = child.wait_with_output();
+            // Programs that fail may stop reading early, which is reported by their exit status instead.
+            let _ = writer.join().expect("writing doesn't panic");
+            output
+        })
+        .map_err(io_err)?;
+
+        // Like git, trust gpg's status output more than its exit code.
+        let has_signed = match self.kind {
+            Kind::OpenPgp => output.stderr.find("\n[GNUPG:] SIG_CREATED ").is_some(),
+            Kind::Ssh => output.status.success(),
+        };
+        if !has_signed || output.stdout.is_empty() {
+            return Err(Error::Failed {
+                program: self.program.clone(),
+                stderr: output.stderr.into(),
+            });
+        }
+        Ok(output.stdout.into())
+    }
+}
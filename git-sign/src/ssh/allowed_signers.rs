@@ -0,0 +1,211 @@
+//! Parse the allowed signers files of `ssh-keygen`, as described in the ALLOWED SIGNERS section of its manual.
+use super::{Error, Reader};
+use git_object::bstr::{BStr, BString, ByteSlice};
+
+/// An allowed signer, which is a line of the allowed signers file.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Entry {
+    /// The comma-separated principals of the key, usually email addresses.
+    pub principals: BString,
+    /// The comma-separated patterns of the namespaces the key may sign in, or `None` if it may sign in all of them.
+    pub namespaces: Option<BString>,
+    /// The time in seconds since the unix epoch from which on the key may sign.
+    pub valid_after: Option<i64>,
+    /// The time in seconds since the unix epoch until which the key may sign.
+    pub valid_before: Option<i64>,
+    /// If true, the key is a certificate authority, whose certificates are not supported, so it never matches.
+    pub cert_authority: bool,
+    /// The public key in the SSH wire format.
+    pub key: Vec<u8>,
+}
+
+/// The keys allowed to make signatures, along with their principals.
+#[derive(Default, PartialEq, Eq, Debug, Clone)]
+pub struct AllowedSigners {
+    entries: Vec<Entry>,
+}
+
+impl AllowedSigners {
+    /// Parse the allowed signers file `data`, as configured by `gpg.ssh.allowedSignersFile`.
+    ///
+    /// Times in the `valid-after` and `valid-before` options are interpreted as UTC even without a `Z` suffix.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let mut entries = Vec::new();
+        for (idx, line) in data.lines().enumerate() {
+            let line = line.trim_ascii();
+            if line.is_empty() || line.starts_with(b"#") {
+                continue;
+            }
+            entries.push(parse_entry(line).map_err(|message| Error::AllowedSigners { line: idx + 1, message })?);
+        }
+        Ok(AllowedSigners { entries })
+    }
+
+    /// The entries in the order of the file.
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Find the first entry with the public `key` in the SSH wire format which may sign in `namespace` at `time`
+    /// in seconds since the unix epoch, if it is known.
+    pub fn find(&self, key: &[u8], namespace: &str, time: Option<i64>) -> Option<&Entry> {
+        self.entries.iter().find(|entry| {
+            !entry.cert_authority
+                && entry.key == key
+                && entry
+                    .namespaces
+                    .as_ref()
+                    .is_none_or(|patterns| matches_pattern_list(patterns.as_bstr(), namespace.as_bytes()))
+                && time.is_none_or(|time| {
+                    entry.valid_after.is_none_or(|after| time >= after)
+                        && entry.valid_before.is_none_or(|before| time <= before)
+                })
+        })
+    }
+}
+
+fn parse_entry(line: &[u8]) -> Result<Entry, &'static str> {
+    let mut tokens = Tokens(line);
+    let principals = tokens.next().ok_or("missing principals")?;
+    let mut entry = Entry {
+        principals: unquote(principals).into(),
+        namespaces: None,
+        valid_after: None,
+        valid_before: None,
+        cert_authority: false,
+        key: Vec::new(),
+    };
+    let mut key_type = tokens.next().ok_or("missing key type")?;
+    if !is_key_type(key_type) {
+        parse_options(key_type, &mut entry)?;
+        key_type = tokens.next().ok_or("missing key type")?;
+    }
+    let key = tokens.next().ok_or("missing key")?;
+    entry.key = base64::decode(key).map_err(|_| "the key is not valid base64")?;
+    if Reader(&entry.key).string().ok() != Some(key_type) {
+        return Err("the key is not of the given type");
+    }
+    Ok(entry)
+}
+
+fn is_key_type(token: &[u8]) -> bool {
+    token.starts_with(b"ssh-") || token.starts_with(b"ecdsa-") || token.starts_with(b"sk-")
+}
+
+fn parse_options(options: &[u8], entry: &mut Entry) -> Result<(), &'static str> {
+    for option in split_unquoted(options, b',') {
+        let (name, value) = match option.find_byte(b'=') {
+            Some(pos) => (&option[..pos], Some(unquote(&option[pos + 1..]))),
+            None => (option, None),
+        };
+        match (name.to_ascii_lowercase().as_slice(), value) {
+            (b"cert-authority", None) => entry.cert_authority = true,
+            (b"namespaces", Some(value)) => entry.namespaces = Some(value.into()),
+            (b"valid-after", Some(value)) => entry.valid_after = Some(parse_time(value)?),
+            (b"valid-before", Some(value)) => entry.valid_before = Some(parse_time(value)?),
+            _ => return Err("unsupported option"),
+        }
+    }
+    Ok(())
+}
+
+/// Parse a time of the form `YYYYMMDD[HHMM[SS]][Z]` into seconds since the unix epoch.
+fn parse_time(time: &[u8]) -> Result<i64, &'static str> {
+    const INVALID: &str = "invalid time, expected YYYYMMDD[HHMM[SS]][Z]";
+    let time = time.strip_suffix(b"Z").unwrap_or(time);
+    if !matches!(time.len(), 8 | 12 | 14) || !time.iter().all(u8::is_ascii_digit) {
+        return Err(INVALID);
+    }
+    let number = |range: std::ops::Range<usize>| {
+        time.get(range)
+            .map_or(0, |digits| digits.iter().fold(0, |n, d| n * 10 + i64::from(d - b'0')))
+    };
+    let (year, month, day) = (number(0..4), number(4..6), number(6..8));
+    let (hour, minute, second) = (number(8..10), number(10..12), number(12..14));
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 59 {
+        return Err(INVALID);
+    }
+    Ok(days_from_civil(year, month, day) * 86400 + hour * 3600 + minute * 60 + second)
+}
+
+/// The number of days since the unix epoch of the given date in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Return true if `text` matches the comma-separated `patterns`, where patterns prefixed with `!` exclude matches.
+fn matches_pattern_list(patterns: &BStr, text: &[u8]) -> bool {
+    let mut matched = false;
+    for pattern in patterns.split_str(",") {
+        let (negated, pattern) = match pattern.strip_prefix(b"!") {
+            Some(pattern) => (true, pattern),
+            None => (false, pattern),
+        };
+        if matches_pattern(pattern, text) {
+            if negated {
+                return false;
+            }
+            matched = true;
+        }
+    }
+    matched
+}
+
+/// Return true if `text` matches `pattern`, in which `*` matches any number of bytes and `?` matches a single one.
+fn matches_pattern(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| matches_pattern(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && matches_pattern(rest, &text[1..]),
+        Some((byte, rest)) => text.first() == Some(byte) && matches_pattern(rest, &text[1..]),
+    }
+}
+
+fn unquote(value: &[u8]) -> &[u8] {
+    value
+        .strip_prefix(b"\"")
+        .and_then(|value| value.strip_suffix(b"\""))
+        .unwrap_or(value)
+}
+
+/// Split `data` at each `separator` which isn't within double quotes.
+fn split_unquoted(data: &[u8], separator: u8) -> impl Iterator<Item = &[u8]> {
+    let mut in_quotes = false;
+    data.split(move |byte| {
+        if *byte == b'"' {
+            in_quotes = !in_quotes;
+        }
+        *byte == separator && !in_quotes
+    })
+}
+
+/// An iterator over the whitespace-separated tokens of a line, which may contain whitespace within double quotes.
+struct Tokens<'a>(&'a [u8]);
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let data = self.0.trim_ascii_start();
+        if data.is_empty() {
+            return None;
+        }
+        let mut in_quotes = false;
+        let end = data
+            .iter()
+            .position(|byte| {
+                if *byte == b'"' {
+                    in_quotes = !in_quotes;
+                }
+                byte.is_ascii_whitespace() && !in_quotes
+            })
+            .unwrap_or(data.len());
+        self.0 = &data[end..];
+        Some(&data[..end])
+    }
+}
@@ -0,0 +1,198 @@
+//! Verify SSH signatures like the ones `ssh-keygen -Y sign` makes against the keys of allowed signers, and make them with private keys.
+use crate::{armor, crypto};
+use git_object::bstr::BString;
+use sha2::Digest;
+use std::convert::TryInto;
+
+pub mod allowed_signers;
+#[doc(inline)]
+pub use allowed_signers::AllowedSigners;
+
+mod sign;
+pub use sign::PrivateKey;
+
+/// The namespace of signatures made by git.
+pub const NAMESPACE: &str = "git";
+
+const MAGIC: &[u8] = b"SSHSIG";
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("The SSH signature is malformed: {message}")]
+    Malformed { message: &'static str },
+    #[error("SSH signatures of version {version} are not supported")]
+    UnsupportedVersion { version: u32 },
+    #[error("The algorithm '{name}' is not supported")]
+    UnsupportedAlgorithm { name: BString },
+    #[error("The signature was made for the namespace '{namespace}' instead of '{}'", NAMESPACE)]
+    Namespace { namespace: BString },
+    #[error("The key {fingerprint} is not allowed to make signatures")]
+    NotAllowed { fingerprint: String },
+    #[error("The signature doesn't match the signed data")]
+    BadSignature,
+    #[error("Line {line} of the allowed signers is malformed: {message}")]
+    AllowedSigners { line: usize, message: &'static str },
+    #[error("Encrypted private keys are not supported")]
+    EncryptedKey,
+}
+
+fn malformed(message: &'static str) -> Error {
+    Error::Malformed { message }
+}
+
+/// Verify the armored SSH `signature` of `data`, which must have been made for the `git` namespace by a key of
+/// `allowed_signers` that is valid at `time` in seconds since the unix epoch, if it is known.
+///
+/// Return the entry of the allowed signer along with the fingerprint of its key, like `ssh-keygen` shows it.
+pub fn verify<'a>(
+    data: &[u8],
+    signature: &[u8],
+    allowed_signers: &'a AllowedSigners,
+    time: Option<i64>,
+) -> Result<(&'a allowed_signers::Entry, String), Error> {
+    let (blob, _) = armor::decode(signature, "SSH SIGNATURE").ok_or_else(|| malformed("invalid signature armor"))?;
+    let mut blob = Reader(&blob);
+    if blob.take(MAGIC.len())? != MAGIC {
+        return Err(malformed("the signature doesn't start with SSHSIG"));
+    }
+    let version = blob.u32()?;
+    if version != 1 {
+        return Err(Error::UnsupportedVersion { version });
+    }
+    let public_key = blob.string()?;
+    let namespace = blob.string()?;
+    let reserved = blob.string()?;
+    let hash_algorithm = blob.string()?;
+    let mut signature = Reader(blob.string()?);
+
+    if namespace != NAMESPACE.as_bytes() {
+        return Err(Error::Namespace {
+            namespace: namespace.into(),
+        });
+    }
+    let fingerprint = fingerprint(public_key);
+    let entry = allowed_signers
+        .find(public_key, NAMESPACE, time)
+        .ok_or_else(|| Error::NotAllowed {
+            fingerprint: fingerprint.clone(),
+        })?;
+
+    let digest = match hash_algorithm {
+        b"sha256" => sha2::Sha256::digest(data).to_vec(),
+        b"sha512" => sha2::Sha512::digest(data).to_vec(),
+        name => return Err(Error::UnsupportedAlgorithm { name: name.into() }),
+    };
+    let signed = signed_data(namespace, reserved, hash_algorithm, &digest);
+
+    let mut key = Reader(public_key);
+    let key_type = key.string()?;
+    let signature_type = signature.string()?;
+    let signature = signature.string()?;
+    let is_valid = match (key_type, signature_type) {
+        (b"ssh-ed25519", b"ssh-ed25519") => {
+            let point = key
+                .string()?
+                .try_into()
+                .map_err(|_| malformed("Ed25519 keys must be 32 bytes"))?;
+            signature.len() == 64 && crypto::ed25519(point, &signed, &signature[..32], &signature[32..])
+        }
+        (b"ssh-rsa", b"rsa-sha2-256") | (b"ssh-rsa", b"rsa-sha2-512") => {
+            let e = key.string()?;
+            let n = key.string()?;
+            let (digest, scheme) = if signature_type == b"rsa-sha2-256" {
+                (
+                    sha2::Sha256::digest(&signed).to_vec(),
+                    rsa::Pkcs1v15Sign::new::<sha2::Sha256>(),
+                )
+            } else {
+                (
+                    sha2::Sha512::digest(&signed).to_vec(),
+                    rsa::Pkcs1v15Sign::new::<sha2::Sha512>(),
+                )
+            };
+            crypto::rsa(n, e, scheme, &digest, signature)
+        }
+        (b"ecdsa-sha2-nistp256", b"ecdsa-sha2-nistp256") => {
+            let _curve = key.string()?;
+            let point = key.string()?;
+            let mut signature = Reader(signature);
+            crypto::p256(
+                point,
+                &sha2::Sha256::digest(&signed),
+                signature.string()?,
+                signature.string()?,
+            )
+        }
+        (_, name) => return Err(Error::UnsupportedAlgorithm { name: name.into() }),
+    };
+    if is_valid {
+        Ok((entry, fingerprint))
+    } else {
+        Err(Error::BadSignature)
+    }
+}
+
+/// The data which is actually signed, given the `digest` of the data to sign made with `hash_algorithm`.
+fn signed_data(namespace: &[u8], reserved: &[u8], hash_algorithm: &[u8], digest: &[u8]) -> Vec<u8> {
+    let mut signed = Writer(MAGIC.to_vec());
+    for field in &[namespace, reserved, hash_algorithm, digest] {
+        signed.string(field);
+    }
+    signed.0
+}
+
+/// The fingerprint of the public key `blob` as `ssh-keygen` shows it.
+fn fingerprint(blob: &[u8]) -> String {
+    format!(
+        "SHA256:{}",
+        base64::encode_config(sha2::Sha256::digest(blob), base64::STANDARD_NO_PAD)
+    )
+}
+
+/// A cursor over data in the SSH wire format.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < len {
+            return Err(malformed("unexpected end of data"));
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn string(&mut self) -> Result<&'a [u8], Error> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+}
+
+/// A buffer for data in the SSH wire format.
+#[derive(Default)]
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn string(&mut self, data: &[u8]) {
+        self.0.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        self.0.extend_from_slice(data);
+    }
+
+    /// Write the unsigned big-endian integer `bytes`.
+    fn mpint(&mut self, bytes: &[u8]) {
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        let bytes = &bytes[start..];
+        if bytes.first().is_some_and(|b| b & 0x80 != 0) {
+            let mut positive = vec![0];
+            positive.extend_from_slice(bytes);
+            self.string(&positive);
+        } else {
+            self.string(bytes);
+        }
+    }
+}
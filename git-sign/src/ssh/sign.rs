@@ -0,0 +1,134 @@
+use super::{fingerprint, malformed, signed_data, Error, Reader, Writer, MAGIC, NAMESPACE};
+use crate::{armor, crypto, sign::Sign};
+use git_object::bstr::BString;
+use p256::ecdsa::signature::hazmat::PrehashSigner;
+use sha2::Digest;
+use std::convert::TryInto;
+
+const AUTH_MAGIC: &[u8] = b"openssh-key-v1\0";
+
+/// An unencrypted private key in the format of OpenSSH, as written by `ssh-keygen -N ''`, which signs like
+/// `ssh-keygen -Y sign -n git`.
+pub struct PrivateKey {
+    public: Vec<u8>,
+    secret: Secret,
+}
+
+enum Secret {
+    Ed25519(ed25519_dalek::SigningKey),
+    Rsa(rsa::RsaPrivateKey),
+    P256(p256::ecdsa::SigningKey),
+}
+
+impl PrivateKey {
+    /// Parse the armored private key `data`, which must be an unencrypted Ed25519, RSA or ECDSA NIST P-256 key.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, Error> {
+        let (data, _) =
+            armor::decode(data, "OPENSSH PRIVATE KEY").ok_or_else(|| malformed("invalid private key armor"))?;
+        let mut data = Reader(&data);
+        if data.take(AUTH_MAGIC.len())? != AUTH_MAGIC {
+            return Err(malformed("the private key doesn't start with openssh-key-v1"));
+        }
+        let cipher = data.string()?;
+        let _kdf = data.string()?;
+        let _kdf_options = data.string()?;
+        if cipher != b"none" {
+            return Err(Error::EncryptedKey);
+        }
+        if data.u32()? != 1 {
+            return Err(malformed("only files with a single private key are supported"));
+        }
+        let public = data.string()?.to_vec();
+
+        let mut private = Reader(data.string()?);
+        if private.u32()? != private.u32()? {
+            return Err(malformed("the check integers of the private key differ"));
+        }
+        let secret = match private.string()? {
+            b"ssh-ed25519" => {
+                let _public = private.string()?;
+                let keypair = private.string()?;
+                let seed = keypair
+                    .get(..32)
+                    .ok_or_else(|| malformed("Ed25519 private keys must be 64 bytes"))?;
+                Secret::Ed25519(ed25519_dalek::SigningKey::from_bytes(
+                    seed.try_into().expect("checked length"),
+                ))
+            }
+            b"ssh-rsa" => {
+                let mut number = || private.string().map(rsa::BigUint::from_bytes_be);
+                let (n, e, d, _iqmp, p, q) = (number()?, number()?, number()?, number()?, number()?, number()?);
+                Secret::Rsa(
+                    rsa::RsaPrivateKey::from_components(n, e, d, vec![p, q])
+                        .map_err(|_| malformed("invalid RSA private key"))?,
+                )
+            }
+            b"ecdsa-sha2-nistp256" => {
+                let _curve = private.string()?;
+                let _public = private.string()?;
+                let scalar = crypto::left_pad(private.string()?, 32)
+                    .ok_or_else(|| malformed("ECDSA NIST P-256 private keys must be 32 bytes"))?;
+                Secret::P256(
+                    p256::ecdsa::SigningKey::from_slice(&scalar)
+                        .map_err(|_| malformed("invalid ECDSA NIST P-256 private key"))?,
+                )
+            }
+            name => return Err(Error::UnsupportedAlgorithm { name: name.into() }),
+        };
+        Ok(PrivateKey { public, secret })
+    }
+
+    /// The public key in the SSH wire format, as it appears in the allowed signers file.
+    pub fn public_key(&self) -> &[u8] {
+        &self.public
+    }
+
+    /// The fingerprint of the key, as `ssh-keygen` shows it.
+    pub fn fingerprint(&self) -> String {
+        fingerprint(&self.public)
+    }
+}
+
+impl Sign for PrivateKey {
+    type Error = Error;
+
+    fn sign(&mut self, data: &[u8]) -> Result<BString, Self::Error> {
+        const HASH_ALGORITHM: &[u8] = b"sha512";
+        let signed = signed_data(NAMESPACE.as_bytes(), b"", HASH_ALGORITHM, &sha2::Sha512::digest(data));
+        let mut signature = Writer::default();
+        match &self.secret {
+            Secret::Ed25519(key) => {
+                signature.string(b"ssh-ed25519");
+                signature.string(&ed25519_dalek::Signer::sign(key, &signed).to_bytes());
+            }
+            Secret::Rsa(key) => {
+                let digest = sha2::Sha512::digest(&signed);
+                let value = key
+                    .sign(rsa::Pkcs1v15Sign::new::<sha2::Sha512>(), &digest)
+                    .map_err(|_| malformed("the RSA key cannot sign"))?;
+                signature.string(b"rsa-sha2-512");
+                signature.string(&value);
+            }
+            Secret::P256(key) => {
+                let value: p256::ecdsa::Signature = key
+                    .sign_prehash(&sha2::Sha256::digest(&signed))
+                    .map_err(|_| malformed("the ECDSA key cannot sign"))?;
+                let (r, s) = value.split_bytes();
+                let mut scalars = Writer::default();
+                scalars.mpint(&r);
+                scalars.mpint(&s);
+                signature.string(b"ecdsa-sha2-nistp256");
+                signature.string(&scalars.0);
+            }
+        }
+
+        let mut blob = Writer(MAGIC.to_vec());
+        blob.0.extend_from_slice(&1u32.to_be_bytes());
+        blob.string(&self.public);
+        blob.string(NAMESPACE.as_bytes());
+        blob.string(b"");
+        blob.string(HASH_ALGORITHM);
+        blob.string(&signature.0);
+        Ok(armor::encode(&blob.0, "SSH SIGNATURE", armor::Kind::Ssh))
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{fixture_bytes, tamper, trust};
+use git_sign::{openpgp, Error, Kind, Signer, Trust};
+
+fn signer(identity: &str, fingerprint: &str) -> Signer {
+    Signer {
+        kind: Kind::OpenPgp,
+        identity: identity.into(),
+        fingerprint: fingerprint.into(),
+    }
+}
+
+#[test]
+fn commits_signed_with_rsa_ed25519_p256_and_subkeys_are_verified() {
+    for (fixture, expected) in &[
+        (
+            "commit/pgp-rsa.txt",
+            signer(
+                "Rsa Signer <rsa@example.com>",
+                "4FDE2EE278A93456CBFD6F6DD7BE062B1718B3AA",
+            ),
+        ),
+        (
+            "commit/pgp-ed25519.txt",
+            signer("Ed Signer <ed@example.com>", "1B1CECC4784535F0A700CCD549BA001CC2A2A488"),
+        ),
+        (
+            "commit/pgp-p256.txt",
+            signer("Ec Signer <ec@example.com>", "CD24E0DFAD6FBFCFA02B40646EE14EF229D02E33"),
+        ),
+        (
+            "commit/pgp-subkey.txt",
+            signer(
+                "Sub Signer <sub@example.com>",
+                "8CE3EACC6EF1F238184F7A6764943FD1A5BE356E",
+            ),
+        ),
+    ] {
+        let data = fixture_bytes(fixture);
+        assert_eq!(&git_sign::verify_commit(&data, &trust()).expect(fixture), expected);
+        assert!(
+            matches!(
+                git_sign::verify_commit(&tamper(&data), &trust()),
+                Err(Error::OpenPgp(openpgp::Error::BadSignature))
+            ),
+            "{} must not verify once changed",
+            fixture
+        );
+    }
+}
+
+#[test]
+fn tags_are_verified() {
+    let data = fixture_bytes("tag/pgp-ed25519.txt");
+    assert_eq!(
+        git_sign::verify_tag(&data, &trust()).unwrap(),
+        signer("Ed Signer <ed@example.com>", "1B1CECC4784535F0A700CCD549BA001CC2A2A488")
+    );
+    assert!(matches!(
+        git_sign::verify_tag(&tamper(&data), &trust()),
+        Err(Error::OpenPgp(openpgp::Error::BadSignature))
+    ));
+}
+
+#[test]
+fn signatures_of_keys_not_in_the_keyring_are_rejected() {
+    let data = fixture_bytes("commit/pgp-subkey.txt");
+    assert!(matches!(
+        git_sign::verify_commit(&data, &Trust::default()),
+        Err(Error::OpenPgp(openpgp::Error::UnknownKey { key_id })) if key_id == "64943FD1A5BE356E"
+    ));
+}
+
+#[test]
+fn keyrings_contain_keys_and_subkeys_of_supported_algorithms() {
+    let keyring = openpgp::Keyring::from_bytes(&fixture_bytes("keys/keyring.asc")).unwrap();
+    let keys: Vec<_> = keyring
+        .keys()
+        .iter()
+        .map(|key| (key.fingerprint.as_str(), key.user_id.as_ref().map(|id| id.to_string())))
+        .collect();
+    assert_eq!(keys.len(), 5, "4 primary keys and a subkey");
+    assert_eq!(
+        keys[4],
+        (
+            "8CE3EACC6EF1F238184F7A6764943FD1A5BE356E",
+            Some("Sub Signer <sub@example.com>".into())
+        )
+    );
+
+    assert!(matches!(
+        openpgp::Keyring::from_bytes(
+            b"-----BEGIN PGP PUBLIC KEY BLOCK-----\n\nAAAA\n=AAAA\n-----END PGP PUBLIC KEY BLOCK-----\n"
+        ),
+        Err(openpgp::Error::Malformed { .. })
+    ));
+}
+
+fn verify_with_keyring(fixture: &str, keyring: &str) -> Result<git_sign::Signer, Error> {
+    let keyring = openpgp::Keyring::from_bytes(&fixture_bytes(keyring)).expect("valid keyring");
+    git_sign::verify_commit(&fixture_bytes(fixture), &Trust::default().with_keyring(keyring))
+}
+
+#[test]
+fn signatures_of_revoked_keys_are_rejected() {
+    assert!(matches!(
+        verify_with_keyring("commit/pgp-revoked.txt", "keys/revoked.asc"),
+        Err(Error::OpenPgp(openpgp::Error::RevokedKey { fingerprint }))
+            if fingerprint == "C5DF98E44505938839A087D3549AB8E8F0630083"
+    ));
+}
+
+#[test]
+fn signatures_made_after_the_key_expired_are_rejected() {
+    assert!(matches!(
+        verify_with_keyring("commit/pgp-expired.txt", "keys/expired.asc"),
+        Err(Error::OpenPgp(openpgp::Error::ExpiredKey { fingerprint }))
+            if fingerprint == "3DB6E58ED101E95D460ACD6833E7DA3F7B6F298C"
+    ));
+}
+
+#[test]
+fn subkeys_without_binding_signature_are_not_trusted() {
+    let keyring = openpgp::Keyring::from_bytes(&fixture_bytes("keys/unbound.asc")).unwrap();
+    assert_eq!(
+        keyring.keys().len(),
+        1,
+        "only the primary key, its subkey lacks a binding signature"
+    );
+    assert!(matches!(
+        verify_with_keyring("commit/pgp-unbound.txt", "keys/unbound.asc"),
+        Err(Error::OpenPgp(openpgp::Error::UnknownKey { key_id })) if key_id == "552A4DC6D69C76C3"
+    ));
+}
@@ -0,0 +1,40 @@
+use git_object::bstr::ByteSlice;
+use git_sign::Trust;
+use std::path::PathBuf;
+
+mod openpgp;
+mod signing;
+mod ssh;
+
+fn fixture_bytes(path: &str) -> Vec<u8> {
+    std::fs::read(PathBuf::from("tests/fixtures").join(path)).expect("fixture to be present")
+}
+
+fn trust() -> Trust {
+    Trust::default()
+        .with_keyring(
+            git_sign::openpgp::Keyring::from_bytes(&fixture_bytes("keys/keyring.asc")).expect("valid keyring"),
+        )
+        .with_allowed_signers(
+            git_sign::ssh::AllowedSigners::from_bytes(&fixture_bytes("keys/allowed_signers"))
+                .expect("valid allowed signers"),
+        )
+}
+
+/// Change the time of the signed object `data`.
+fn tamper(data: &[u8]) -> Vec<u8> {
+    data.replace("1700000000", "1700000001")
+}
+
+#[test]
+fn unsigned_objects_cannot_be_verified() {
+    let data = b"tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904\nauthor a <a@example.com> 1 +0000\ncommitter a <a@example.com> 1 +0000\n\nmessage\n";
+    assert!(matches!(
+        git_sign::verify_commit(data, &trust()),
+        Err(git_sign::Error::Unsigned)
+    ));
+    assert!(matches!(
+        git_sign::verify(b"data", b"-----BEGIN SIGNED MESSAGE-----\n", None, &trust()),
+        Err(git_sign::Error::UnknownKind)
+    ));
+}
@@ -0,0 +1,167 @@
+use crate::{fixture_bytes, trust};
+use git_object::{borrowed, owned};
+use git_sign::{openpgp, sign, ssh, Kind};
+
+fn signature(signature: &borrowed::Signature<'_>) -> owned::Signature {
+    owned::Signature {
+        name: signature.name.to_owned(),
+        email: signature.email.to_owned(),
+        time: signature.time,
+    }
+}
+
+/// The commit of `fixture` without its signature.
+fn commit(fixture: &str) -> owned::Commit {
+    let data = fixture_bytes(fixture);
+    let commit = borrowed::Commit::from_bytes(&data).expect("valid commit");
+    owned::Commit {
+        tree: commit.tree(),
+        parents: commit
+            .parents
+            .iter()
+            .map(|id| owned::Id::from_40_bytes_in_hex(id).expect("valid id"))
+            .collect(),
+        author: signature(&commit.author),
+        committer: signature(&commit.committer),
+        encoding: commit.encoding.map(ToOwned::to_owned),
+        message: commit.message.to_owned(),
+        extra_headers: commit
+            .extra_headers
+            .iter()
+            .filter(|(name, _)| *name != "gpgsig")
+            .map(|(name, value)| ((*name).to_owned(), value.as_ref().to_owned()))
+            .collect(),
+    }
+}
+
+/// The tag of `fixture` without its signature.
+fn tag(fixture: &str) -> owned::Tag {
+    let data = fixture_bytes(fixture);
+    let signed = git_object::signed::tag(&data).expect("signed tag");
+    let tag = borrowed::Tag::from_bytes(&signed.payload).expect("valid tag");
+    owned::Tag {
+        target: tag.target(),
+        name: tag.name.to_owned(),
+        target_kind: tag.target_kind,
+        message: tag.message.to_owned(),
+        signature: tag.signature.as_ref().map(signature),
+        pgp_signature: None,
+    }
+}
+
+fn to_bytes(write: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> Vec<u8> {
+    let mut buf = Vec::new();
+    write(&mut buf).expect("writing to memory works");
+    buf
+}
+
+fn ssh_key(name: &str) -> ssh::PrivateKey {
+    ssh::PrivateKey::from_bytes(&fixture_bytes(&format!("keys/ssh_{}", name))).expect("valid private key")
+}
+
+#[test]
+fn commits_signed_with_ed25519_and_rsa_ssh_keys_are_identical_to_the_ones_git_signs() {
+    for (key, fixture) in &[("ed25519", "commit/ssh-ed25519.txt"), ("rsa", "commit/ssh-rsa.txt")] {
+        let mut commit = commit(fixture);
+        sign::commit(&mut commit, &mut ssh_key(key)).unwrap();
+        assert_eq!(
+            to_bytes(|buf| commit.write_to(buf)),
+            fixture_bytes(fixture),
+            "these signatures are deterministic"
+        );
+    }
+}
+
+#[test]
+fn commits_signed_with_ecdsa_ssh_keys_are_verified() {
+    let key = ssh_key("ecdsa");
+    let mut commit = commit("commit/ssh-ecdsa.txt");
+    commit
+        .extra_headers
+        .push(("gpgsig".into(), "previous signature".into()));
+    sign::commit(&mut commit, &mut ssh_key("ecdsa")).unwrap();
+
+    assert_eq!(
+        commit.extra_headers.iter().filter(|(name, _)| name == "gpgsig").count(),
+        1,
+        "previous signatures are replaced"
+    );
+    let signer = git_sign::verify_commit(&to_bytes(|buf| commit.write_to(buf)), &trust()).unwrap();
+    assert_eq!(signer.kind, Kind::Ssh);
+    assert_eq!(signer.fingerprint, key.fingerprint());
+}
+
+#[test]
+fn tags_signed_with_ssh_keys_are_identical_to_the_ones_git_signs() {
+    let mut tag = tag("tag/ssh-ed25519.txt");
+    sign::tag(&mut tag, &mut ssh_key("ed25519")).unwrap();
+    assert_eq!(to_bytes(|buf| tag.write_to(buf)), fixture_bytes("tag/ssh-ed25519.txt"));
+}
+
+#[test]
+fn commits_and_tags_signed_with_openpgp_keys_are_verified() {
+    let secret_keys = fixture_bytes("keys/secret.asc");
+    for (id, expected_fingerprint) in &[
+        (Some("D7BE062B1718B3AA"), "4FDE2EE278A93456CBFD6F6DD7BE062B1718B3AA"),
+        (Some("0x6EE14EF229D02E33"), "CD24E0DFAD6FBFCFA02B40646EE14EF229D02E33"),
+        (Some("ed@example.com"), "1B1CECC4784535F0A700CCD549BA001CC2A2A488"),
+        (Some("Sub Signer"), "8CE3EACC6EF1F238184F7A6764943FD1A5BE356E"),
+        (None, "1B1CECC4784535F0A700CCD549BA001CC2A2A488"),
+    ] {
+        let mut key = openpgp::SecretKey::from_bytes(&secret_keys, *id).unwrap();
+        assert_eq!(key.fingerprint(), *expected_fingerprint);
+
+        let mut commit = commit("commit/pgp-ed25519.txt");
+        sign::commit(&mut commit, &mut key).unwrap();
+        let signer = git_sign::verify_commit(&to_bytes(|buf| commit.write_to(buf)), &trust()).unwrap();
+        assert_eq!(signer.kind, Kind::OpenPgp);
+        assert_eq!(signer.fingerprint, *expected_fingerprint);
+        assert_eq!(Some(signer.identity.as_ref()), key.user_id());
+
+        let mut tag = tag("tag/pgp-ed25519.txt");
+        sign::tag(&mut tag, &mut key).unwrap();
+        let signer = git_sign::verify_tag(&to_bytes(|buf| tag.write_to(buf)), &trust()).unwrap();
+        assert_eq!(signer.fingerprint, *expected_fingerprint);
+    }
+}
+
+#[test]
+fn openpgp_keys_that_cannot_sign_are_not_selected() {
+    let secret_keys = fixture_bytes("keys/secret.asc");
+    assert!(matches!(
+        openpgp::SecretKey::from_bytes(&secret_keys, Some("32DABD4ACFF625B8!")),
+        Err(openpgp::Error::NoMatchingSecretKey { .. })
+    ));
+    assert!(matches!(
+        openpgp::SecretKey::from_bytes(&secret_keys, Some("nobody@example.com")),
+        Err(openpgp::Error::NoMatchingSecretKey { .. })
+    ));
+}
+
+#[test]
+fn encrypted_ssh_keys_are_not_supported() {
+    assert!(matches!(
+        ssh::PrivateKey::from_bytes(&fixture_bytes("keys/ssh_encrypted")),
+        Err(ssh::Error::EncryptedKey)
+    ));
+}
+
+#[test]
+fn tags_without_message_cannot_be_signed() {
+    let mut tag = tag("tag/ssh-ed25519.txt");
+    tag.message = "\n".into();
+    assert!(matches!(
+        sign::tag(&mut tag, &mut ssh_key("ed25519")),
+        Err(sign::Error::EmptyMessage)
+    ));
+}
+
+#[test]
+fn programs_that_cannot_be_run_fail_to_sign() {
+    let mut commit = commit("commit/ssh-ed25519.txt");
+    let mut program = sign::Program::ssh_keygen("tests/fixtures/keys/ssh_ed25519").with_program("does-not-exist");
+    assert!(matches!(
+        sign::commit(&mut commit, &mut program),
+        Err(sign::Error::Sign(sign::program::Error::Io { .. }))
+    ));
+}
@@ -0,0 +1,133 @@
+use crate::{fixture_bytes, tamper, trust};
+use git_object::bstr::{ByteSlice, ByteVec};
+use git_sign::{ssh, Error, Kind, Signer, Trust};
+
+fn signer(identity: &str, fingerprint: &str) -> Signer {
+    Signer {
+        kind: Kind::Ssh,
+        identity: identity.into(),
+        fingerprint: fingerprint.into(),
+    }
+}
+
+/// The ed25519 key of the allowed signers with `options`.
+fn ed25519_with_options(options: &str) -> Trust {
+    let mut allowed_signers = fixture_bytes("keys/allowed_signers")
+        .lines()
+        .next()
+        .expect("ed25519 key first")
+        .to_owned();
+    allowed_signers.insert_str("ed25519@example.com ".len(), format!("{} ", options));
+    Trust::default().with_allowed_signers(ssh::AllowedSigners::from_bytes(&allowed_signers).unwrap())
+}
+
+#[test]
+fn commits_signed_with_ed25519_rsa_and_ecdsa_are_verified() {
+    for (fixture, expected) in &[
+        (
+            "commit/ssh-ed25519.txt",
+            signer(
+                "ed25519@example.com",
+                "SHA256:q7keHyLmhAqFHWdMKSYKzm+cSvfzv/Gjw/z8+GN2jks",
+            ),
+        ),
+        (
+            "commit/ssh-rsa.txt",
+            signer("rsa@example.com", "SHA256:dn2LsYegoYIkOIb8sm5QR5LkowTkqBX6p+/hdgA7hbY"),
+        ),
+        (
+            "commit/ssh-ecdsa.txt",
+            signer(
+                "ecdsa@example.com",
+                "SHA256:Ooi9Z+YgoAUYCreevuOwQLGWuImQNVBT6iFbnEYkQ9A",
+            ),
+        ),
+    ] {
+        let data = fixture_bytes(fixture);
+        assert_eq!(&git_sign::verify_commit(&data, &trust()).expect(fixture), expected);
+        assert!(
+            matches!(
+                git_sign::verify_commit(&tamper(&data), &trust()),
+                Err(Error::Ssh(ssh::Error::BadSignature))
+            ),
+            "{} must not verify once changed",
+            fixture
+        );
+    }
+}
+
+#[test]
+fn tags_are_verified() {
+    let data = fixture_bytes("tag/ssh-ed25519.txt");
+    assert_eq!(
+        git_sign::verify_tag(&data, &trust()).unwrap(),
+        signer(
+            "ed25519@example.com",
+            "SHA256:q7keHyLmhAqFHWdMKSYKzm+cSvfzv/Gjw/z8+GN2jks"
+        )
+    );
+    assert!(matches!(
+        git_sign::verify_tag(&tamper(&data), &trust()),
+        Err(Error::Ssh(ssh::Error::BadSignature))
+    ));
+}
+
+#[test]
+fn keys_must_be_allowed_for_the_git_namespace_at_the_time_of_the_commit() {
+    let data = fixture_bytes("commit/ssh-ed25519.txt");
+    let is_allowed = |trust: &Trust| match git_sign::verify_commit(&data, trust) {
+        Ok(_) => true,
+        Err(Error::Ssh(ssh::Error::NotAllowed { fingerprint })) => {
+            assert_eq!(fingerprint, "SHA256:q7keHyLmhAqFHWdMKSYKzm+cSvfzv/Gjw/z8+GN2jks");
+            false
+        }
+        Err(err) => panic!("unexpected error: {}", err),
+    };
+    assert!(!is_allowed(&Trust::default()));
+    assert!(is_allowed(&ed25519_with_options(r#"namespaces="file,g*""#)));
+    assert!(!is_allowed(&ed25519_with_options(r#"namespaces="file""#)));
+    assert!(!is_allowed(&ed25519_with_options(r#"namespaces="*,!git""#)));
+    assert!(!is_allowed(&ed25519_with_options("cert-authority")));
+
+    // The commit was made at 2023-11-14 22:13:20 UTC.
+    assert!(is_allowed(&ed25519_with_options(
+        r#"valid-after="20231114221320Z",valid-before="20231114221320""#
+    )));
+    assert!(!is_allowed(&ed25519_with_options(r#"valid-after="202311142214""#)));
+    assert!(!is_allowed(&ed25519_with_options("valid-before=20231114")));
+}
+
+#[test]
+fn allowed_signers_support_comments_quotes_and_options() {
+    let allowed_signers = ssh::AllowedSigners::from_bytes(
+        b"# comment
+
+\"a@example.com,b@example.com\" namespaces=\"git\",valid-after=19700102 ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIDDqMgTPE8Wci6t5yJzjCQfr1MNCpxkaa9VWUzzb2COx comment
+",
+    )
+    .unwrap();
+    let entry = &allowed_signers.entries()[0];
+    assert_eq!(entry.principals, "a@example.com,b@example.com");
+    assert_eq!(entry.namespaces.as_ref().map(|n| n.as_bytes()), Some(&b"git"[..]));
+    assert_eq!(entry.valid_after, Some(86400));
+    assert_eq!(entry.valid_before, None);
+    assert!(!entry.cert_authority);
+    assert_eq!(entry.key.len(), 51);
+
+    for (input, line) in &[
+        ("a@example.com\n", 1),
+        ("# comment\na@example.com ssh-ed25519\n", 2),
+        ("a@example.com unknown-option ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIDDqMgTPE8Wci6t5yJzjCQfr1MNCpxkaa9VWUzzb2COx\n", 1),
+        ("a@example.com valid-after=2023 ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIDDqMgTPE8Wci6t5yJzjCQfr1MNCpxkaa9VWUzzb2COx\n", 1),
+        ("a@example.com ssh-rsa AAAAC3NzaC1lZDI1NTE5AAAAIDDqMgTPE8Wci6t5yJzjCQfr1MNCpxkaa9VWUzzb2COx\n", 1),
+    ] {
+        assert!(
+            matches!(
+                ssh::AllowedSigners::from_bytes(input.as_bytes()),
+                Err(ssh::Error::AllowedSigners { line: actual, .. }) if actual == *line
+            ),
+            "{:?} is invalid",
+            input
+        );
+    }
+}
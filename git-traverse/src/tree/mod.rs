@@ -0,0 +1,31 @@
+use git_object::{bstr::BStr, owned, TreeMode};
+
+mod traverse;
+pub use traverse::{breadthfirst, depthfirst, Error};
+
+pub mod recorder;
+pub use recorder::Recorder;
+
+/// An entry of a tree seen during traversal.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub struct Entry<'a> {
+    pub mode: TreeMode,
+    pub oid: owned::Id,
+    /// The path of the entry relative to the root tree, with components separated by `/`.
+    pub path: &'a BStr,
+}
+
+/// Tells the traversal how to proceed after an entry was seen.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+pub enum Action {
+    Continue,
+    /// Don't traverse the entries of the tree that was just seen, or continue if it isn't a tree.
+    Skip,
+    /// Stop the traversal and make it fail with [`Error::Cancelled`].
+    Cancel,
+}
+
+/// Receives all entries seen during a traversal.
+pub trait Visit {
+    fn visit(&mut self, entry: Entry<'_>) -> Action;
+}
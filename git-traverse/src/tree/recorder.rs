@@ -0,0 +1,34 @@
+use crate::tree::{self, Action, Visit};
+use git_object::{bstr::BString, owned, TreeMode};
+
+/// An owned version of [`tree::Entry`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    pub mode: TreeMode,
+    pub oid: owned::Id,
+    pub path: BString,
+}
+
+impl From<tree::Entry<'_>> for Entry {
+    fn from(entry: tree::Entry<'_>) -> Self {
+        Entry {
+            mode: entry.mode,
+            oid: entry.oid,
+            path: entry.path.to_owned(),
+        }
+    }
+}
+
+/// A [`Visit`] implementation which records all entries it sees, traversing all trees.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Default)]
+pub struct Recorder {
+    pub records: Vec<Entry>,
+}
+
+impl Visit for Recorder {
+    fn visit(&mut self, entry: tree::Entry<'_>) -> Action {
+        self.records.push(entry.into());
+        Action::Continue
+    }
+}
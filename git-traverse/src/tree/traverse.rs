@@ -0,0 +1,122 @@
+use crate::tree::{Action, Entry, Visit};
+use git_object::{
+    borrowed::{self, TreeIter},
+    bstr::{BString, ByteSlice},
+    find, owned, TreeMode,
+};
+use std::collections::VecDeque;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error<E>
+where
+    E: std::error::Error + 'static,
+{
+    #[error(transparent)]
+    Find(#[from] find::Error<E>),
+    #[error("The delegate cancelled the operation")]
+    Cancelled,
+}
+
+/// Pass all entries of the tree `root` and its subtrees to `visit`, entering each subtree right after it was seen,
+/// similar to `git ls-tree -r -t`.
+///
+/// Trees are obtained with [`find`][git_object::find].
+/// Entries are seen in the order they are stored in, and subtrees for which `visit` returns [`Action::Skip`] aren't
+/// entered.
+pub fn depthfirst<Find, E>(root: owned::Id, find: Find, visit: &mut impl Visit) -> Result<(), Error<E>>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    E: std::error::Error + 'static,
+{
+    State {
+        find,
+        visit,
+        buffers: Vec::new(),
+        path: BString::default(),
+    }
+    .depthfirst(root)
+}
+
+/// Pass all entries of the tree `root` to `visit`, followed by the entries of each of its subtrees in the order they
+/// were seen, level by level.
+///
+/// `find` and the way [`Action::Skip`] is handled is the same as in [`depthfirst()`].
+pub fn breadthfirst<Find, E>(root: owned::Id, find: Find, visit: &mut impl Visit) -> Result<(), Error<E>>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    E: std::error::Error + 'static,
+{
+    let mut state = State {
+        find,
+        visit,
+        buffers: Vec::new(),
+        path: BString::default(),
+    };
+    let mut trees = VecDeque::new();
+    trees.push_back((root, BString::default()));
+    let mut buf = Vec::new();
+    while let Some((oid, path)) = trees.pop_front() {
+        state.load(oid, &mut buf)?;
+        state.path = path;
+        for entry in TreeIter::from_bytes(&buf) {
+            let entry = entry.map_err(|err| find::Error::Decode { source: err, oid })?;
+            let previous_len = git_object::tree::push_path_component(&mut state.path, entry.filename);
+            if state.visit(entry.mode, entry.oid.into())? && entry.mode == TreeMode::Tree {
+                trees.push_back((entry.oid.into(), state.path.clone()));
+            }
+            state.path.truncate(previous_len);
+        }
+    }
+    Ok(())
+}
+
+struct State<'a, Find, V> {
+    find: Find,
+    visit: &'a mut V,
+    /// Buffers for tree data, reused across subtrees.
+    buffers: Vec<Vec<u8>>,
+    /// The path of the tree currently being traversed.
+    path: BString,
+}
+
+impl<'a, Find, V, E> State<'a, Find, V>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), E>>,
+    V: Visit,
+    E: std::error::Error + 'static,
+{
+    fn depthfirst(&mut self, oid: owned::Id) -> Result<(), Error<E>> {
+        let mut buf = self.buffers.pop().unwrap_or_default();
+        let res = self.load(oid, &mut buf).and_then(|_| {
+            for entry in TreeIter::from_bytes(&buf) {
+                let entry = entry.map_err(|err| find::Error::Decode { source: err, oid })?;
+                let previous_len = git_object::tree::push_path_component(&mut self.path, entry.filename);
+                if self.visit(entry.mode, entry.oid.into())? && entry.mode == TreeMode::Tree {
+                    self.depthfirst(entry.oid.into())?;
+                }
+                self.path.truncate(previous_len);
+            }
+            Ok(())
+        });
+        self.buffers.push(buf);
+        res
+    }
+
+    /// Pass the entry at our path to the visitor and return true if it may be entered.
+    fn visit(&mut self, mode: TreeMode, oid: owned::Id) -> Result<bool, Error<E>> {
+        match self.visit.visit(Entry {
+            mode,
+            oid,
+            path: self.path.as_bstr(),
+        }) {
+            Action::Continue => Ok(true),
+            Action::Skip => Ok(false),
+            Action::Cancel => Err(Error::Cancelled),
+        }
+    }
+
+    /// Fill `buf` with the data of the tree `oid`.
+    fn load(&mut self, oid: owned::Id, buf: &mut Vec<u8>) -> Result<(), Error<E>> {
+        Ok(find::load(&mut self.find, oid, buf)?)
+    }
+}
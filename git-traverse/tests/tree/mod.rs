@@ -0,0 +1,145 @@
+use git_object::{bstr::ByteSlice, find, owned, TreeMode};
+use git_traverse::tree::{self, recorder, Action, Entry, Recorder, Visit};
+use std::{collections::HashMap, convert::Infallible};
+
+fn id(n: u8) -> owned::Id {
+    owned::Id::from([n; 20])
+}
+
+/// Encode a tree from entries of a mode, a name and the byte its made-up id consists of, in the given order.
+fn tree(entries: &[(&str, &str, u8)]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for (mode, name, id_byte) in entries {
+        data.extend_from_slice(format!("{} {}\0", mode, name).as_bytes());
+        data.extend_from_slice(&[*id_byte; 20]);
+    }
+    data
+}
+
+const ROOT: u8 = 12;
+const SUB: u8 = 11;
+const LEAF: u8 = 10;
+
+/// The trees `ROOT` with the subtree `sub` which has the subtree `leaf`, whose entries aren't sorted to show that they
+/// are seen in the order they are stored in.
+fn nested() -> HashMap<owned::Id, Vec<u8>> {
+    vec![
+        (id(LEAF), tree(&[("100644", "c", 3)])),
+        (id(SUB), tree(&[("40000", "leaf", LEAF), ("120000", "b", 2)])),
+        (
+            id(ROOT),
+            tree(&[("100644", "a", 1), ("40000", "sub", SUB), ("100755", "z", 4)]),
+        ),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn find(
+    trees: &HashMap<owned::Id, Vec<u8>>,
+) -> impl FnMut(git_object::borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), Infallible>> + '_ {
+    move |oid, buf| {
+        buf.extend_from_slice(trees.get(&owned::Id::from(oid))?);
+        Some(Ok(()))
+    }
+}
+
+fn entry(mode: TreeMode, oid: owned::Id, path: &str) -> recorder::Entry {
+    recorder::Entry {
+        mode,
+        oid,
+        path: path.into(),
+    }
+}
+
+#[test]
+fn depthfirst_enters_subtrees_right_after_seeing_them() {
+    let trees = nested();
+    let (root, sub, leaf) = (id(ROOT), id(SUB), id(LEAF));
+    let mut recorder = Recorder::default();
+    tree::depthfirst(root, find(&trees), &mut recorder).expect("traversal to succeed");
+    assert_eq!(
+        recorder.records,
+        vec![
+            entry(TreeMode::Blob, id(1), "a"),
+            entry(TreeMode::Tree, sub, "sub"),
+            entry(TreeMode::Tree, leaf, "sub/leaf"),
+            entry(TreeMode::Blob, id(3), "sub/leaf/c"),
+            entry(TreeMode::Link, id(2), "sub/b"),
+            entry(TreeMode::BlobExecutable, id(4), "z"),
+        ]
+    );
+}
+
+#[test]
+fn breadthfirst_sees_all_entries_of_a_level_first() {
+    let trees = nested();
+    let (root, sub, leaf) = (id(ROOT), id(SUB), id(LEAF));
+    let mut recorder = Recorder::default();
+    tree::breadthfirst(root, find(&trees), &mut recorder).expect("traversal to succeed");
+    assert_eq!(
+        recorder.records,
+        vec![
+            entry(TreeMode::Blob, id(1), "a"),
+            entry(TreeMode::Tree, sub, "sub"),
+            entry(TreeMode::BlobExecutable, id(4), "z"),
+            entry(TreeMode::Tree, leaf, "sub/leaf"),
+            entry(TreeMode::Link, id(2), "sub/b"),
+            entry(TreeMode::Blob, id(3), "sub/leaf/c"),
+        ]
+    );
+}
+
+#[test]
+fn skipped_trees_are_not_entered_and_cancellation_stops_the_traversal() {
+    struct SkipLeafCancelAtZ(Vec<String>);
+    impl Visit for SkipLeafCancelAtZ {
+        fn visit(&mut self, entry: Entry<'_>) -> Action {
+            self.0.push(entry.path.to_string());
+            match entry.path.as_bytes() {
+                b"sub/leaf" => Action::Skip,
+                b"z" => Action::Cancel,
+                _ => Action::Continue,
+            }
+        }
+    }
+    let trees = nested();
+    let root = id(ROOT);
+
+    let mut visit = SkipLeafCancelAtZ(Vec::new());
+    assert!(matches!(
+        tree::depthfirst(root, find(&trees), &mut visit),
+        Err(tree::Error::Cancelled)
+    ));
+    assert_eq!(visit.0, vec!["a", "sub", "sub/leaf", "sub/b", "z"]);
+
+    let mut visit = SkipLeafCancelAtZ(Vec::new());
+    assert!(matches!(
+        tree::breadthfirst(root, find(&trees), &mut visit),
+        Err(tree::Error::Cancelled)
+    ));
+    assert_eq!(visit.0, vec!["a", "sub", "z"]);
+}
+
+#[test]
+fn missing_and_corrupt_trees_are_reported() {
+    let (root, missing) = (id(1), id(100));
+    let trees = vec![(root, tree(&[("40000", "missing", 100)]))].into_iter().collect();
+    assert!(matches!(
+        tree::depthfirst(root, find(&trees), &mut Recorder::default()),
+        Err(tree::Error::Find(find::Error::NotFound { oid })) if oid == missing
+    ));
+    assert!(matches!(
+        tree::breadthfirst(root, find(&trees), &mut Recorder::default()),
+        Err(tree::Error::Find(find::Error::NotFound { oid })) if oid == missing
+    ));
+
+    let corrupt = |_oid: git_object::borrowed::Id<'_>, buf: &mut Vec<u8>| -> Option<Result<(), Infallible>> {
+        buf.extend_from_slice(b"100644 a");
+        Some(Ok(()))
+    };
+    assert!(matches!(
+        tree::depthfirst(root, corrupt, &mut Recorder::default()),
+        Err(tree::Error::Find(find::Error::Decode { oid, .. })) if oid == root
+    ));
+}
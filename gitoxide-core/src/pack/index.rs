@@ -53,6 +53,17 @@ pub struct Context<W: io::Write> {
     pub iteration_mode: IterationMode,
     pub format: OutputFormat,
     pub out: W,
+    /// The maximum size in bytes of objects and deltas in the pack, or unlimited if unset, as git doesn't limit it either.
+    ///
+    /// Buffers for objects are allocated with the size declared in their pack entry header before decompressing them,
+    /// so set it when indexing packs from untrusted sources to bound the memory a crafted header can claim.
+    pub max_object_size: Option<u64>,
+    /// The maximum length of delta chains in the pack, or
+    /// [`DEFAULT_MAX_DELTA_CHAIN_LENGTH`][super::DEFAULT_MAX_DELTA_CHAIN_LENGTH] if unset.
+    pub max_delta_chain_length: Option<u32>,
+    /// The maximum amount of bytes of resolved objects to keep in memory as delta bases while indexing, or unlimited
+    /// if unset.
+    pub max_resolved_base_bytes: Option<u64>,
 }
 
 pub fn stream_len(mut s: impl io::Seek) -> io::Result<u64> {
@@ -78,6 +89,8 @@ pub fn from_pack(
         thread_limit: ctx.thread_limit,
         iteration_mode: ctx.iteration_mode.into(),
         index_kind: pack::index::Kind::default(),
+        limits: super::limits(ctx.max_object_size, ctx.max_delta_chain_length),
+        max_resolved_base_bytes: ctx.max_resolved_base_bytes,
     };
     let out = ctx.out;
     let format = ctx.format;
@@ -4,3 +4,14 @@ pub mod verify;
 
 pub mod receive;
 pub use receive::receive;
+
+/// The maximum length of delta chains in pack data we index unless configured otherwise, which is the deepest
+/// chain `git pack-objects` produces.
+pub const DEFAULT_MAX_DELTA_CHAIN_LENGTH: u32 = 4095;
+
+fn limits(max_object_size: Option<u64>, max_delta_chain_length: Option<u32>) -> git_odb::Limits {
+    git_odb::Limits {
+        max_object_size,
+        max_delta_chain_length: Some(max_delta_chain_length.unwrap_or(DEFAULT_MAX_DELTA_CHAIN_LENGTH)),
+    }
+}
@@ -6,12 +6,13 @@ use git_object::{
 };
 use git_odb::pack;
 use git_protocol::{
-    fetch::{Action, Arguments, Ref, Response},
+    fetch::{response::ShallowUpdate, Action, Arguments, Ref, Response},
     git_transport::{self, client::Capabilities},
 };
 use std::{
+    collections::BTreeSet,
     io::{self, BufRead},
-    path::PathBuf,
+    path::{Path, PathBuf},
 };
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
@@ -20,6 +21,20 @@ pub struct Context<W: io::Write> {
     pub thread_limit: Option<usize>,
     pub format: OutputFormat,
     pub out: W,
+    /// If set, only receive commits more recent than this date, like `git fetch --shallow-since`. It can be any date
+    /// git accepts, like `2020-09-17` or `2 weeks ago`.
+    pub deepen_since: Option<String>,
+    /// The maximum size in bytes of objects and deltas in the pack, or unlimited if unset, as git doesn't limit it either.
+    ///
+    /// Buffers for objects are allocated with the size declared in their pack entry header before decompressing them,
+    /// so set it when indexing packs from untrusted sources to bound the memory a crafted header can claim.
+    pub max_object_size: Option<u64>,
+    /// The maximum length of delta chains in the pack, or
+    /// [`DEFAULT_MAX_DELTA_CHAIN_LENGTH`][super::DEFAULT_MAX_DELTA_CHAIN_LENGTH] if unset.
+    pub max_delta_chain_length: Option<u32>,
+    /// The maximum amount of bytes of resolved objects to keep in memory as delta bases while indexing, or unlimited
+    /// if unset.
+    pub max_resolved_base_bytes: Option<u64>,
 }
 
 struct CloneDelegate<W: io::Write> {
@@ -27,6 +42,8 @@ struct CloneDelegate<W: io::Write> {
     directory: Option<PathBuf>,
     refs_directory: Option<PathBuf>,
     ref_filter: Option<&'static [&'static str]>,
+    deepen_since: Option<git_object::Time>,
+    deepen_since_unsupported: bool,
 }
 static FILTER: &[&str] = &["HEAD", "refs/tags", "refs/heads"];
 
@@ -46,12 +63,22 @@ impl<W: io::Write> git_protocol::fetch::Delegate for CloneDelegate<W> {
         &mut self,
         version: git_transport::Protocol,
         _server: &Capabilities,
-        _features: &mut Vec<(&str, Option<&str>)>,
+        features: &mut Vec<(&str, Option<&str>)>,
         _refs: &[Ref],
     ) -> Action {
         if version == git_transport::Protocol::V1 {
             self.ref_filter = Some(&FILTER);
         }
+        if self.deepen_since.is_some() {
+            let required = match version {
+                git_transport::Protocol::V1 => "deepen-since",
+                git_transport::Protocol::V2 => "shallow",
+            };
+            if !features.iter().any(|(name, _)| *name == required) {
+                self.deepen_since_unsupported = true;
+                return Action::Close;
+            }
+        }
         Action::Continue
     }
 
@@ -67,6 +94,9 @@ impl<W: io::Write> git_protocol::fetch::Delegate for CloneDelegate<W> {
                 None => arguments.want(id.to_borrowed()),
             }
         }
+        if let Some(since) = self.deepen_since {
+            arguments.deepen_since(since.time as usize);
+        }
         Action::Close
     }
 
@@ -75,15 +105,22 @@ impl<W: io::Write> git_protocol::fetch::Delegate for CloneDelegate<W> {
         input: impl BufRead,
         progress: impl Progress,
         refs: &[Ref],
-        _previous: &Response,
+        response: &Response,
     ) -> io::Result<()> {
         let options = pack::bundle::write::Options {
             thread_limit: self.ctx.thread_limit,
             index_kind: pack::index::Kind::V2,
             iteration_mode: pack::data::iter::Mode::Verify,
+            limits: super::limits(self.ctx.max_object_size, self.ctx.max_delta_chain_length),
+            max_resolved_base_bytes: self.ctx.max_resolved_base_bytes,
         };
-        let outcome = pack::bundle::Bundle::write_stream_to_directory(input, self.directory.take(), progress, options)
+        let directory = self.directory.take();
+        let outcome = pack::bundle::Bundle::write_stream_to_directory(input, directory.clone(), progress, options)
             .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+        let shallow_updates = response.shallow_updates();
+        if let Some(directory) = directory.filter(|_| !shallow_updates.is_empty()) {
+            write_shallow_file(&directory.join("shallow"), shallow_updates)?;
+        }
 
         if let Some(directory) = self.refs_directory.take() {
             let assure_dir = |path: &git_object::bstr::BString| {
@@ -103,11 +140,12 @@ impl<W: io::Write> git_protocol::fetch::Delegate for CloneDelegate<W> {
         }
 
         match self.ctx.format {
-            OutputFormat::Human => drop(print(&mut self.ctx.out, outcome, refs)),
+            OutputFormat::Human => drop(print(&mut self.ctx.out, outcome, refs, shallow_updates)),
             #[cfg(feature = "serde1")]
-            OutputFormat::Json => {
-                serde_json::to_writer_pretty(&mut self.ctx.out, &JSONOutcome::from_outcome_and_refs(outcome, refs))?
-            }
+            OutputFormat::Json => serde_json::to_writer_pretty(
+                &mut self.ctx.out,
+                &JSONOutcome::from_outcome_and_refs(outcome, refs, shallow_updates),
+            )?,
         };
         Ok(())
     }
@@ -142,20 +180,50 @@ pub struct JSONOutcome {
     pub data_path: Option<PathBuf>,
 
     pub refs: Vec<JsonRef>,
+    pub shallow_updates: Vec<ShallowUpdate>,
 }
 
 impl JSONOutcome {
-    pub fn from_outcome_and_refs(v: pack::bundle::write::Outcome, refs: &[Ref]) -> Self {
+    pub fn from_outcome_and_refs(
+        v: pack::bundle::write::Outcome,
+        refs: &[Ref],
+        shallow_updates: &[ShallowUpdate],
+    ) -> Self {
         JSONOutcome {
             index: v.index.into(),
             pack_kind: v.pack_kind,
             index_path: v.index_path,
             data_path: v.data_path,
             refs: refs.iter().cloned().map(Into::into).collect(),
+            shallow_updates: shallow_updates.to_vec(),
         }
     }
 }
 
+/// Apply `updates` to the shallow file at `path` like git does, which lists the ids of commits whose parents are
+/// missing, and remove it if no commit is shallow anymore.
+fn write_shallow_file(path: &Path, updates: &[ShallowUpdate]) -> io::Result<()> {
+    let mut shallows: BTreeSet<_> = match std::fs::read_to_string(path) {
+        Ok(content) => content.lines().map(ToOwned::to_owned).collect(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => BTreeSet::new(),
+        Err(err) => return Err(err),
+    };
+    for update in updates {
+        match update {
+            ShallowUpdate::Shallow(id) => shallows.insert(id.to_string()),
+            ShallowUpdate::Unshallow(id) => shallows.remove(&id.to_string()),
+        };
+    }
+    if shallows.is_empty() {
+        return match std::fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        };
+    }
+    let content: String = shallows.into_iter().map(|id| id + "\n").collect();
+    std::fs::write(path, content)
+}
+
 fn print_hash_and_path(out: &mut impl io::Write, name: &str, id: owned::Id, path: Option<PathBuf>) -> io::Result<()> {
     match path {
         Some(path) => writeln!(out, "{}: {} ({})", name, id, path.display()),
@@ -163,9 +231,20 @@ fn print_hash_and_path(out: &mut impl io::Write, name: &str, id: owned::Id, path
     }
 }
 
-fn print(out: &mut impl io::Write, res: pack::bundle::write::Outcome, refs: &[Ref]) -> io::Result<()> {
+fn print(
+    out: &mut impl io::Write,
+    res: pack::bundle::write::Outcome,
+    refs: &[Ref],
+    shallow_updates: &[ShallowUpdate],
+) -> io::Result<()> {
     print_hash_and_path(out, "index", res.index.index_hash, res.index_path)?;
     print_hash_and_path(out, "pack", res.index.data_hash, res.data_path)?;
+    for update in shallow_updates {
+        match update {
+            ShallowUpdate::Shallow(id) => writeln!(out, "shallow: {}", id)?,
+            ShallowUpdate::Unshallow(id) => writeln!(out, "unshallow: {}", id)?,
+        }
+    }
     writeln!(out)?;
     crate::remote::refs::print(out, refs)?;
     Ok(())
@@ -179,13 +258,26 @@ pub fn receive<P: Progress, W: io::Write>(
     progress: P,
     ctx: Context<W>,
 ) -> anyhow::Result<()> {
+    let deepen_since = ctx
+        .deepen_since
+        .as_deref()
+        .map(|date| {
+            let local_offset = time::UtcOffset::try_current_local_offset().map_or(0, |offset| offset.as_seconds());
+            git_object::time::parse(date, std::time::SystemTime::now(), local_offset)
+        })
+        .transpose()?;
     let transport = git_protocol::git_transport::client::connect(url.as_bytes(), protocol.unwrap_or_default().into())?;
     let mut delegate = CloneDelegate {
         ctx,
         directory,
         refs_directory,
         ref_filter: None,
+        deepen_since,
+        deepen_since_unsupported: false,
     };
     git_protocol::fetch(transport, &mut delegate, git_protocol::credentials::helper, progress)?;
+    if delegate.deepen_since_unsupported {
+        anyhow::bail!("The server does not support receiving commits since a date")
+    }
     Ok(())
 }
@@ -2,9 +2,9 @@ use crate::OutputFormat;
 use anyhow::{anyhow, Context as AnyhowContext, Result};
 use bytesize::ByteSize;
 use git_features::progress::{self, Progress};
-use git_object::{owned, Kind};
+use git_object::{fsck, owned, Kind};
 use git_odb::pack::{self, index};
-use std::{io, path::Path, str::FromStr};
+use std::{collections::BTreeMap, io, path::Path, str::FromStr};
 
 pub use index::verify::Mode;
 
@@ -12,11 +12,13 @@ pub use index::verify::Mode;
 pub enum Algorithm {
     LessTime,
     LessMemory,
+    /// Use about as little memory as `LessMemory`, but resolve each delta only once by processing them right after their base.
+    Balanced,
 }
 
 impl Algorithm {
     pub fn variants() -> &'static [&'static str] {
-        &["less-time", "less-memory"]
+        &["less-time", "less-memory", "balanced"]
     }
 }
 
@@ -28,6 +30,7 @@ impl FromStr for Algorithm {
         Ok(match s_lc.as_str() {
             "less-memory" => Algorithm::LessMemory,
             "less-time" => Algorithm::LessTime,
+            "balanced" => Algorithm::Balanced,
             _ => return Err(format!("Invalid verification algorithm: '{}'", s)),
         })
     }
@@ -38,14 +41,60 @@ impl From<Algorithm> for index::traverse::Algorithm {
         match v {
             Algorithm::LessMemory => index::traverse::Algorithm::Lookup,
             Algorithm::LessTime => index::traverse::Algorithm::DeltaTreeLookup,
+            Algorithm::Balanced => index::traverse::Algorithm::DeltaDagLookup,
         }
     }
 }
 
+/// The severity of a fsck check like `missingEmail=ignore`, overriding its default like `fsck.<id>` does in git.
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Copy)]
+pub struct FsckSeverity {
+    pub check: fsck::Check,
+    pub severity: fsck::Severity,
+}
+
+impl FromStr for FsckSeverity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(2, '=');
+        let (check, severity) = match (parts.next(), parts.next()) {
+            (Some(check), Some(severity)) => (check, severity),
+            _ => {
+                return Err(format!(
+                    "Expected '<id>=<level>' like 'missingEmail=ignore', got '{}'",
+                    s
+                ))
+            }
+        };
+        Ok(FsckSeverity {
+            check: check.parse().map_err(|err: fsck::Error| err.to_string())?,
+            severity: severity.parse().map_err(|err: fsck::Error| err.to_string())?,
+        })
+    }
+}
+
+/// Return the options to check objects with, which turn warnings into errors if `strict` is set, like
+/// `transfer.fsckObjects` does in git, and report checks with the given `severities`.
+pub fn fsck_options(strict: bool, severities: impl IntoIterator<Item = FsckSeverity>) -> fsck::Options {
+    let options = if strict {
+        fsck::Options::strict()
+    } else {
+        fsck::Options::default()
+    };
+    severities
+        .into_iter()
+        .fold(options, |options, FsckSeverity { check, severity }| {
+            options.with_severity(check, severity)
+        })
+}
+
 /// A general purpose context for many operations provided here
 pub struct Context<W1: io::Write, W2: io::Write> {
     /// If set, provide statistics to `out` in the given format
     pub output_statistics: Option<OutputFormat>,
+    /// If set, list all entries of the pack to `out` in the given format, similar to `git verify-pack -v`
+    pub output_entries: Option<OutputFormat>,
     /// A stream to which to output operation results
     pub out: W1,
     /// A stream to which to errors
@@ -62,6 +111,7 @@ impl Default for Context<Vec<u8>, Vec<u8>> {
     fn default() -> Self {
         Context {
             output_statistics: None,
+            output_entries: None,
             thread_limit: None,
             mode: index::verify::Mode::Sha1CRC32,
             algorithm: Algorithm::LessMemory,
@@ -101,6 +151,7 @@ pub fn pack_or_pack_index<W1, W2>(
         mut err,
         mode,
         output_statistics,
+        output_entries,
         thread_limit,
         algorithm,
     }: Context<W1, W2>,
@@ -157,6 +208,19 @@ where
         }
         ext => return Err(anyhow!("Unknown extension {:?}, expecting 'idx' or 'pack'", ext)),
     };
+    if let Some(format) = output_entries {
+        let pack_path = path.with_extension("pack");
+        let idx = index::File::at(path.with_extension("idx")).with_context(|| "Could not open pack index file")?;
+        let pack = pack::data::File::at(&pack_path).with_context(|| "Could not open pack file")?;
+        let entries = idx
+            .list_entries(&pack, progress::Discard)
+            .with_context(|| "Could not list pack entries")?;
+        match format {
+            OutputFormat::Human => drop(print_entries(&mut out, &entries, &pack_path)),
+            #[cfg(feature = "serde1")]
+            OutputFormat::Json => serde_json::to_writer_pretty(&mut out, &entries)?,
+        };
+    }
     if let Some(stats) = res.1.as_ref() {
         match output_statistics {
             Some(OutputFormat::Human) => drop(print_statistics(&mut out, stats)),
@@ -168,6 +232,36 @@ where
     Ok(res)
 }
 
+fn print_entries(out: &mut impl io::Write, entries: &[index::list::Entry], pack_path: &Path) -> io::Result<()> {
+    let mut objects_per_chain_length = BTreeMap::new();
+    for entry in entries {
+        write!(
+            out,
+            "{} {:<6} {} {} {}",
+            entry.id,
+            entry.kind.to_string(),
+            entry.size,
+            entry.size_in_pack,
+            entry.pack_offset
+        )?;
+        match entry.delta {
+            Some(delta) => writeln!(out, " {} {}", delta.chain_length, delta.base_id)?,
+            None => writeln!(out)?,
+        }
+        *objects_per_chain_length
+            .entry(entry.delta.map_or(0, |d| d.chain_length))
+            .or_insert(0usize) += 1;
+    }
+    for (chain_length, count) in objects_per_chain_length {
+        let objects = if count == 1 { "object" } else { "objects" };
+        match chain_length {
+            0 => writeln!(out, "non delta: {} {}", count, objects)?,
+            _ => writeln!(out, "chain length = {}: {} {}", chain_length, count, objects)?,
+        }
+    }
+    writeln!(out, "{}: ok", pack_path.display())
+}
+
 fn print_statistics(out: &mut impl io::Write, stats: &index::traverse::Outcome) -> io::Result<()> {
     writeln!(out, "objects per delta chain length")?;
     let mut chain_length_to_object: Vec<_> = stats.objects_per_chain_length.iter().map(|(a, b)| (*a, *b)).collect();
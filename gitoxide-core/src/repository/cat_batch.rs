@@ -0,0 +1,302 @@
+use super::lookup::{self, Revision};
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use git_features::progress;
+use git_object::{bstr::ByteSlice, owned, Kind};
+use git_odb::{compound, loose, pack};
+use std::{
+    collections::HashMap,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+/// The format used by `git cat-file --batch` and `--batch-check` if no format is given
+pub const DEFAULT_FORMAT: &str = "%(objectname) %(objecttype) %(objectsize)";
+
+pub struct Context<R: io::BufRead, W: io::Write> {
+    /// The stream from which to read one object name per line, unless `all_objects` is set
+    pub input: R,
+    /// The stream to which to write the response for each object
+    pub out: W,
+    /// The format of the line to write for each object, like [`DEFAULT_FORMAT`], which is used if unset
+    pub object_format: Option<String>,
+    /// If true, write the object data after each line, like `git cat-file --batch`, otherwise only write the
+    /// line, like `git cat-file --batch-check`
+    pub contents: bool,
+    /// If true, ignore `input` and write all objects in the repository and its alternates, ordered by object name
+    pub all_objects: bool,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+enum Atom {
+    Literal(String),
+    ObjectName,
+    ObjectType,
+    ObjectSize,
+    ObjectSizeDisk,
+    DeltaBase,
+    Rest,
+}
+
+fn parse_format(format: &str) -> Result<Vec<Atom>> {
+    let mut atoms = Vec::new();
+    let mut rest = format;
+    while let Some(start) = rest.find("%(") {
+        if start > 0 {
+            atoms.push(Atom::Literal(rest[..start].to_owned()));
+        }
+        let end = rest[start..]
+            .find(')')
+            .ok_or_else(|| anyhow!("Unterminated format element in '{}'", format))?;
+        atoms.push(match &rest[start + 2..start + end] {
+            "objectname" => Atom::ObjectName,
+            "objecttype" => Atom::ObjectType,
+            "objectsize" => Atom::ObjectSize,
+            "objectsize:disk" => Atom::ObjectSizeDisk,
+            "deltabase" => Atom::DeltaBase,
+            "rest" => Atom::Rest,
+            atom => bail!("Unknown format element: {}", atom),
+        });
+        rest = &rest[start + end + 1..];
+    }
+    if !rest.is_empty() {
+        atoms.push(Atom::Literal(rest.to_owned()));
+    }
+    Ok(atoms)
+}
+
+/// Where an object is stored, to obtain information that `compound::Db::locate()` doesn't provide
+enum Storage<'a> {
+    Packed { bundle: &'a pack::Bundle, pack_offset: u64 },
+    Loose { db: &'a loose::Db, path: PathBuf },
+}
+
+/// Find `id` in the same order as `compound::Db::locate()` does.
+fn storage<'a>(db: &'a compound::Db, id: &owned::Id) -> Option<Storage<'a>> {
+    for alternate in &db.alternates {
+        if let Some(storage) = storage(alternate, id) {
+            return Some(storage);
+        }
+    }
+    for bundle in &db.packs {
+        if let Some(index) = bundle.index.lookup(id.to_borrowed()) {
+            return Some(Storage::Packed {
+                bundle,
+                pack_offset: bundle.index.pack_offset_at_index(index),
+            });
+        }
+    }
+    let hex = id.to_sha1_hex_string();
+    let path = db.loose.path.join(&hex[..2]).join(&hex[2..]);
+    if path.is_file() {
+        Some(Storage::Loose { db: &db.loose, path })
+    } else {
+        None
+    }
+}
+
+struct Server<'a> {
+    git_dir: &'a Path,
+    db: &'a compound::Db,
+    atoms: Vec<Atom>,
+    contents: bool,
+    buf: Vec<u8>,
+    /// All entries of the packs we needed entry details for, sorted by pack offset, keyed by their bundle's address
+    pack_entries: HashMap<*const pack::Bundle, Vec<pack::index::list::Entry>>,
+}
+
+impl<'a> Server<'a> {
+    fn needs_rest(&self) -> bool {
+        self.atoms.contains(&Atom::Rest)
+    }
+
+    fn needs_storage(&self) -> bool {
+        self.atoms
+            .iter()
+            .any(|a| matches!(a, Atom::ObjectSizeDisk | Atom::DeltaBase))
+    }
+
+    fn pack_entry(&mut self, bundle: &pack::Bundle, pack_offset: u64) -> Result<pack::index::list::Entry> {
+        let entries = match self.pack_entries.entry(bundle as *const _) {
+            std::collections::hash_map::Entry::Occupied(e) => e.into_mut(),
+            std::collections::hash_map::Entry::Vacant(e) => e.insert(
+                bundle
+                    .index
+                    .list_entries(&bundle.pack, progress::Discard)
+                    .with_context(|| format!("Could not list entries of pack at '{}'", bundle.pack.path().display()))?,
+            ),
+        };
+        entries
+            .binary_search_by_key(&pack_offset, |e| e.pack_offset)
+            .map(|idx| entries[idx])
+            .map_err(|_| anyhow!("No pack entry at offset {}", pack_offset))
+    }
+
+    /// Return the kind and size of the object at `storage` as obtained from its header.
+    fn header(storage: &Storage<'_>, id: &owned::Id) -> Result<(Kind, u64)> {
+        Ok(match storage {
+            Storage::Packed { bundle, pack_offset } => {
+                let header = bundle
+                    .pack
+                    .decode_header(bundle.pack.entry(*pack_offset), |id| {
+                        bundle
+                            .index
+                            .lookup(id)
+                            .map(|idx| bundle.index.pack_offset_at_index(idx))
+                    })
+                    .with_context(|| format!("Could not decode header of object {}", id))?;
+                (header.kind, header.object_size)
+            }
+            Storage::Loose { db, .. } => {
+                let object = db
+                    .locate(id.to_borrowed())
+                    .ok_or_else(|| anyhow!("Loose object {} vanished while reading it", id))??;
+                (object.kind, object.size as u64)
+            }
+        })
+    }
+
+    /// Write the response for `name` to `out`, with `rest` being the input after the object name.
+    fn serve(&mut self, name: &str, rest: &str, out: &mut impl io::Write) -> Result<()> {
+        let missing = |out: &mut dyn io::Write| -> Result<()> {
+            writeln!(out, "{} missing", name)?;
+            Ok(())
+        };
+        let id = match lookup::revision(self.git_dir, self.db, name)? {
+            Revision::Found(id) => id,
+            Revision::Missing => return missing(out),
+            Revision::Ambiguous => {
+                writeln!(out, "{} ambiguous", name)?;
+                return Ok(());
+            }
+        };
+        let storage = match storage(self.db, &id) {
+            Some(storage) => storage,
+            None => return missing(out),
+        };
+        let (disk_size, delta_base) = if self.needs_storage() {
+            match &storage {
+                Storage::Packed { bundle, pack_offset } => {
+                    let entry = self.pack_entry(bundle, *pack_offset)?;
+                    (entry.size_in_pack, entry.delta.map(|d| d.base_id))
+                }
+                Storage::Loose { path, .. } => (
+                    path.metadata()
+                        .with_context(|| format!("Could not read metadata of '{}'", path.display()))?
+                        .len(),
+                    None,
+                ),
+            }
+        } else {
+            (0, None)
+        };
+
+        // Without contents, kind and size are known from headers without decompressing and resolving objects.
+        let (kind, size, mut object) = if self.contents {
+            let object = match self.db.locate(id.to_borrowed(), &mut self.buf) {
+                Some(object) => object?,
+                None => return missing(out),
+            };
+            (object.kind(), object.size() as u64, Some(object))
+        } else {
+            let (kind, size) = Self::header(&storage, &id)?;
+            (kind, size, None)
+        };
+        for atom in &self.atoms {
+            match atom {
+                Atom::Literal(text) => out.write_all(text.as_bytes())?,
+                Atom::ObjectName => write!(out, "{}", id)?,
+                Atom::ObjectType => write!(out, "{}", kind)?,
+                Atom::ObjectSize => write!(out, "{}", size)?,
+                Atom::ObjectSizeDisk => write!(out, "{}", disk_size)?,
+                Atom::DeltaBase => write!(out, "{}", delta_base.unwrap_or_else(owned::Id::null_sha1))?,
+                Atom::Rest => out.write_all(rest.as_bytes())?,
+            }
+        }
+        writeln!(out)?;
+        if let Some(object) = &mut object {
+            match object {
+                compound::Object::Borrowed(object) => out.write_all(object.data)?,
+                compound::Object::Loose(object) => {
+                    io::copy(&mut object.stream()?, out)?;
+                }
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+fn all_object_ids(db: &compound::Db, out: &mut Vec<owned::Id>) -> Result<()> {
+    for bundle in &db.packs {
+        out.extend(bundle.index.iter().map(|e| e.oid));
+    }
+    for id in db.loose.iter() {
+        out.push(id?);
+    }
+    for alternate in &db.alternates {
+        all_object_ids(alternate, out)?;
+    }
+    Ok(())
+}
+
+/// Serve objects of the repository at `repository` in the format of `git cat-file --batch` or `--batch-check`, for
+/// each object name read from `input`, or for all objects.
+///
+/// Object names are full or abbreviated object ids, references and `<rev>:<path>`, see [`lookup::revision()`].
+///
+/// Responses are flushed after each object name read from `input` so that this can be driven over pipes.
+pub fn serve(
+    repository: impl AsRef<Path>,
+    Context {
+        mut input,
+        out,
+        object_format,
+        contents,
+        all_objects,
+    }: Context<impl io::BufRead, impl io::Write>,
+) -> Result<()> {
+    let git_dir = super::lookup::git_dir(repository.as_ref())?;
+    let db = compound::Db::at(git_dir.join("objects"))
+        .with_context(|| format!("Could not open objects database of '{}'", git_dir.display()))?;
+    let mut out = io::BufWriter::new(out);
+    let mut server = Server {
+        git_dir: &git_dir,
+        db: &db,
+        atoms: parse_format(object_format.as_deref().unwrap_or(DEFAULT_FORMAT))?,
+        contents,
+        buf: Vec::new(),
+        pack_entries: HashMap::new(),
+    };
+
+    if all_objects {
+        let mut ids = Vec::new();
+        all_object_ids(&db, &mut ids)?;
+        ids.sort();
+        ids.dedup();
+        for id in ids {
+            server.serve(&id.to_sha1_hex_string(), "", &mut out)?;
+        }
+        out.flush()?;
+        return Ok(());
+    }
+
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if input.read_until(b'\n', &mut line)? == 0 {
+            break;
+        }
+        let line = line.strip_suffix(b"\n").unwrap_or(&line).to_str_lossy();
+        let (name, rest) = if server.needs_rest() {
+            match line.find(char::is_whitespace) {
+                Some(pos) => (&line[..pos], line[pos..].trim_start()),
+                None => (line.as_ref(), ""),
+            }
+        } else {
+            (line.as_ref(), "")
+        };
+        server.serve(name, rest, &mut out)?;
+        out.flush()?;
+    }
+    Ok(())
+}
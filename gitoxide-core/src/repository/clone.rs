@@ -0,0 +1,190 @@
+use super::lookup::git_dir;
+use crate::OutputFormat;
+use anyhow::{anyhow, bail, Context as AnyhowContext, Result};
+use git_odb::compound::{self, local_clone};
+use std::{fs, io, path::Path, str::FromStr};
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum CloneMode {
+    Link,
+    Copy,
+    Reference,
+}
+
+impl CloneMode {
+    pub fn variants() -> &'static [&'static str] {
+        &["link", "copy", "reference"]
+    }
+}
+
+impl FromStr for CloneMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use CloneMode::*;
+        let slc = s.to_ascii_lowercase();
+        Ok(match slc.as_str() {
+            "link" => Link,
+            "copy" => Copy,
+            "reference" => Reference,
+            _ => return Err("invalid value".into()),
+        })
+    }
+}
+
+impl From<CloneMode> for local_clone::Mode {
+    fn from(v: CloneMode) -> Self {
+        use local_clone::Mode::*;
+        match v {
+            CloneMode::Link => Link,
+            CloneMode::Copy => Copy,
+            CloneMode::Reference => Reference,
+        }
+    }
+}
+
+pub struct Context<W: io::Write> {
+    pub format: OutputFormat,
+    pub out: W,
+}
+
+/// Clone the repository at `source`, a path or `file://` URL to a repository or its `.git` directory, into a new bare
+/// repository at `destination` without generating any pack, similar to `git clone --bare --local`.
+///
+/// Depending on `mode`, objects are hardlinked, copied or referenced via alternates. References are always copied.
+pub fn clone_local(
+    source: &str,
+    destination: impl AsRef<Path>,
+    mode: CloneMode,
+    Context { format, mut out }: Context<impl io::Write>,
+) -> Result<()> {
+    let source = git_dir(Path::new(source.strip_prefix("file://").unwrap_or(source)))?;
+    let destination = destination.as_ref();
+    if destination.exists()
+        && fs::read_dir(destination)
+            .with_context(|| format!("Could not read destination directory at '{}'", destination.display()))?
+            .next()
+            .is_some()
+    {
+        bail!(
+            "Refusing to clone into non-empty directory at '{}'",
+            destination.display()
+        );
+    }
+    fs::create_dir_all(destination)?;
+
+    let db = compound::Db::at(source.join("objects"))
+        .with_context(|| format!("Could not open objects database of '{}'", source.display()))?;
+    let outcome = db
+        .clone_local(destination.join("objects"), mode.into())
+        .with_context(|| "Could not clone objects")?;
+
+    copy_recursively(&source.join("refs"), &destination.join("refs"))?;
+    for file in &["HEAD", "packed-refs"] {
+        let path = source.join(file);
+        if path.is_file() {
+            fs::copy(&path, destination.join(file)).with_context(|| format!("Could not copy '{}'", path.display()))?;
+        }
+    }
+    let config_path = destination.join("config");
+    let url = source.canonicalize()?;
+    let url = url
+        .to_str()
+        .ok_or_else(|| anyhow!("The source path '{}' is not valid UTF-8", url.display()))?;
+    let write_config = |filemode: bool| {
+        fs::write(
+            &config_path,
+            format!(
+                "[core]\n\trepositoryformatversion = 0\n\tfilemode = {}\n\tbare = true\n[remote \"origin\"]\n\turl = {}\n",
+                filemode,
+                quote_config_value(url)
+            ),
+        )
+        .with_context(|| format!("Could not write '{}'", config_path.display()))
+    };
+    write_config(true)?;
+    if !filemode_is_trustworthy(&config_path)? {
+        write_config(false)?;
+    }
+
+    match format {
+        OutputFormat::Human => print(&mut out, &outcome)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut out, &outcome)?,
+    };
+    Ok(())
+}
+
+/// Return the `value` in a form suitable for the right-hand side of an assignment in a git configuration file, quoting
+/// and escaping it like `git config` does.
+fn quote_config_value(value: &str) -> String {
+    let needs_quotes = value.starts_with(' ') || value.ends_with(' ') || value.contains(&[';', '#'][..]);
+    let mut out = String::with_capacity(value.len() + 2);
+    if needs_quotes {
+        out.push('"');
+    }
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    if needs_quotes {
+        out.push('"');
+    }
+    out
+}
+
+/// Return true if toggling the executable bit of the file at `path` is observable, like `git init` tests to decide the
+/// value of `core.filemode`. The file mode is restored afterwards.
+#[cfg(unix)]
+fn filemode_is_trustworthy(path: &Path) -> Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = fs::symlink_metadata(path)?.permissions().mode();
+    let toggled = mode ^ 0o100;
+    if fs::set_permissions(path, fs::Permissions::from_mode(toggled)).is_err() {
+        return Ok(false);
+    }
+    let trustworthy = fs::symlink_metadata(path)?.permissions().mode() == toggled;
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))?;
+    Ok(trustworthy)
+}
+
+#[cfg(not(unix))]
+fn filemode_is_trustworthy(_path: &Path) -> Result<bool> {
+    Ok(false)
+}
+
+fn copy_recursively(source: &Path, destination: &Path) -> Result<()> {
+    fs::create_dir_all(destination)?;
+    if !source.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(source)? {
+        let path = entry?.path();
+        let target = destination.join(path.file_name().expect("directory entries have names"));
+        if path.is_dir() {
+            copy_recursively(&path, &target)?;
+        } else {
+            fs::copy(&path, &target).with_context(|| format!("Could not copy '{}'", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+fn print(out: &mut impl io::Write, outcome: &local_clone::Outcome) -> io::Result<()> {
+    writeln!(
+        out,
+        "files linked: {}, files copied: {} ({})",
+        outcome.files_linked,
+        outcome.files_copied,
+        bytesize::ByteSize(outcome.bytes_copied)
+    )?;
+    for alternate in &outcome.alternates {
+        writeln!(out, "alternate: {}", alternate.display())?;
+    }
+    Ok(())
+}
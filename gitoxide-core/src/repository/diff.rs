@@ -0,0 +1,319 @@
+use super::{
+    lookup::{find_fn, load, tree_id},
+    tree_list::mode_str,
+};
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+pub use git_diff::tree::rewrites;
+use git_diff::{blob, tree};
+use git_object::{owned, TreeMode};
+use git_odb::compound;
+use std::{
+    io::{self, Write},
+    path::Path,
+    str::FromStr,
+};
+
+/// The amount of hex characters used to abbreviate object ids in `index` lines.
+const ABBREV_LEN: usize = 7;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Algorithm {
+    Myers,
+    Minimal,
+    Histogram,
+}
+
+impl Algorithm {
+    pub fn variants() -> &'static [&'static str] {
+        &["default", "myers", "minimal", "histogram"]
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Algorithm::*;
+        let slc = s.to_ascii_lowercase();
+        Ok(match slc.as_str() {
+            "default" | "myers" => Myers,
+            "minimal" => Minimal,
+            "histogram" => Histogram,
+            _ => return Err(format!("Unknown diff algorithm: {:?}", s)),
+        })
+    }
+}
+
+impl From<Algorithm> for blob::Algorithm {
+    fn from(v: Algorithm) -> Self {
+        match v {
+            Algorithm::Myers => blob::Algorithm::Myers,
+            Algorithm::Minimal => blob::Algorithm::MyersMinimal,
+            Algorithm::Histogram => blob::Algorithm::Histogram,
+        }
+    }
+}
+
+pub struct Context<W: io::Write> {
+    /// The stream to which to write the patch
+    pub out: W,
+    /// The algorithm to use to compute changes between blobs
+    pub algorithm: Algorithm,
+    /// The amount of unchanged lines to show around changed lines
+    pub context_lines: usize,
+    /// If set, find renames and copies and show them as such instead of as additions and deletions
+    pub renames: Option<rewrites::Options>,
+}
+
+/// A file before or after the change, with `None` standing for a file that doesn't exist
+type Side = Option<(TreeMode, owned::Id)>;
+
+struct Printer<'a, W> {
+    db: &'a compound::Db,
+    out: W,
+    algorithm: blob::Algorithm,
+    context_lines: usize,
+    previous_data: Vec<u8>,
+    current_data: Vec<u8>,
+}
+
+impl<'a, W: io::Write> Printer<'a, W> {
+    /// Write the patch turning `previous` into `current` at `path`.
+    fn file(&mut self, path: &[u8], previous: Side, current: Side) -> Result<()> {
+        let same_kind = match (previous, current) {
+            (Some((previous_mode, _)), Some((mode, _))) => kind_of(previous_mode) == kind_of(mode),
+            _ => true,
+        };
+        if !same_kind {
+            self.file(path, previous, None)?;
+            return self.file(path, None, current);
+        }
+        self.patch(path, path, previous, current, None)
+    }
+
+    /// Write the patch of a rename or copy, called `kind`.
+    fn rewrite(&mut self, rewrite: &rewrites::Rewrite, kind: &str) -> Result<()> {
+        self.patch(
+            &rewrite.source_path,
+            &rewrite.path,
+            Some((rewrite.source_entry_mode, rewrite.source_oid)),
+            Some((rewrite.entry_mode, rewrite.oid)),
+            Some((kind, rewrite.similarity)),
+        )
+    }
+
+    fn patch(
+        &mut self,
+        previous_path: &[u8],
+        path: &[u8],
+        previous: Side,
+        current: Side,
+        rewrite: Option<(&str, u8)>,
+    ) -> Result<()> {
+        write!(self.out, "diff --git a/")?;
+        self.out.write_all(previous_path)?;
+        write!(self.out, " b/")?;
+        self.out.write_all(path)?;
+        writeln!(self.out)?;
+        match (previous, current) {
+            (None, Some((mode, _))) => writeln!(self.out, "new file mode {}", mode_str(mode))?,
+            (Some((mode, _)), None) => writeln!(self.out, "deleted file mode {}", mode_str(mode))?,
+            (Some((previous_mode, _)), Some((mode, _))) if previous_mode != mode => {
+                writeln!(self.out, "old mode {}", mode_str(previous_mode))?;
+                writeln!(self.out, "new mode {}", mode_str(mode))?;
+            }
+            _ => {}
+        }
+        if let Some((kind, similarity)) = rewrite {
+            writeln!(self.out, "similarity index {}%", similarity)?;
+            write!(self.out, "{} from ", kind)?;
+            self.out.write_all(previous_path)?;
+            write!(self.out, "\n{} to ", kind)?;
+            self.out.write_all(path)?;
+            writeln!(self.out)?;
+        }
+
+        let null = owned::Id::null_sha1();
+        let (previous_id, id) = (previous.map_or(null, |s| s.1), current.map_or(null, |s| s.1));
+        if previous_id == id {
+            return Ok(());
+        }
+        write!(
+            self.out,
+            "index {}..{}",
+            &previous_id.to_sha1_hex_string()[..ABBREV_LEN],
+            &id.to_sha1_hex_string()[..ABBREV_LEN]
+        )?;
+        match (previous, current) {
+            (Some((previous_mode, _)), Some((mode, _))) if previous_mode == mode => {
+                writeln!(self.out, " {}", mode_str(mode))?
+            }
+            _ => writeln!(self.out)?,
+        }
+
+        let mut previous_data = std::mem::take(&mut self.previous_data);
+        let mut current_data = std::mem::take(&mut self.current_data);
+        self.data(previous, &mut previous_data)?;
+        self.data(current, &mut current_data)?;
+        let res = self.contents(
+            previous_path,
+            path,
+            previous.is_some(),
+            &previous_data,
+            current.is_some(),
+            &current_data,
+        );
+        self.previous_data = previous_data;
+        self.current_data = current_data;
+        res
+    }
+
+    fn contents(
+        &mut self,
+        previous_path: &[u8],
+        path: &[u8],
+        has_previous: bool,
+        previous: &[u8],
+        has_current: bool,
+        current: &[u8],
+    ) -> Result<()> {
+        let write_name = |out: &mut W, prefix: &str, path: &[u8], exists: bool| -> io::Result<()> {
+            if exists {
+                out.write_all(prefix.as_bytes())?;
+                out.write_all(path)
+            } else {
+                out.write_all(b"/dev/null")
+            }
+        };
+        if blob::is_binary(previous) || blob::is_binary(current) {
+            write!(self.out, "Binary files ")?;
+            write_name(&mut self.out, "a/", previous_path, has_previous)?;
+            write!(self.out, " and ")?;
+            write_name(&mut self.out, "b/", path, has_current)?;
+            writeln!(self.out, " differ")?;
+            return Ok(());
+        }
+
+        let (previous, current) = if self.context_lines == 0 {
+            trim_common_tail(previous, current)
+        } else {
+            (previous, current)
+        };
+        let diff = blob::diff(previous, current, self.algorithm);
+        if diff.changes.is_empty() {
+            return Ok(());
+        }
+        write!(self.out, "--- ")?;
+        write_name(&mut self.out, "a/", previous_path, has_previous)?;
+        write!(self.out, "\n+++ ")?;
+        write_name(&mut self.out, "b/", path, has_current)?;
+        writeln!(self.out)?;
+        blob::unified::write(&mut self.out, &diff, self.context_lines)?;
+        Ok(())
+    }
+
+    /// Put the data of `side` into `buf`, which is empty if the file doesn't exist.
+    fn data(&self, side: Side, buf: &mut Vec<u8>) -> Result<()> {
+        buf.clear();
+        match side {
+            None => {}
+            Some((TreeMode::Commit, id)) => writeln!(buf, "Subproject commit {}", id)?,
+            Some((_, id)) => {
+                load(self.db, &id, buf)?.ok_or_else(|| anyhow!("Blob {} does not exist", id))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Remove the common end of `previous` and `current` in blocks of 1KB, like git does if no context is shown, which
+/// can affect where changes are placed.
+fn trim_common_tail<'a>(previous: &'a [u8], current: &'a [u8]) -> (&'a [u8], &'a [u8]) {
+    const BLOCK: usize = 1024;
+    let mut trimmed = 0;
+    while BLOCK + trimmed <= previous.len().min(current.len())
+        && previous[previous.len() - trimmed - BLOCK..previous.len() - trimmed]
+            == current[current.len() - trimmed - BLOCK..current.len() - trimmed]
+    {
+        trimmed += BLOCK;
+    }
+    let tail = &previous[previous.len() - trimmed..];
+    let keep = tail.iter().position(|b| *b == b'\n').map_or(trimmed, |pos| pos + 1);
+    (
+        &previous[..previous.len() - trimmed + keep],
+        &current[..current.len() - trimmed + keep],
+    )
+}
+
+/// Files which can be turned into each other by changing their contents, so their patches can be shown as a
+/// modification.
+fn kind_of(mode: TreeMode) -> TreeMode {
+    match mode {
+        TreeMode::BlobExecutable => TreeMode::Blob,
+        mode => mode,
+    }
+}
+
+/// Write a patch like `git diff --no-indent-heuristic` does for all changes between `previous` and `current` to `out`,
+/// both of which are ids of commits or trees in the repository at `repository`, optionally showing renames and copies.
+///
+/// Paths are written as they are without quoting, and object ids in `index` lines always have 7 characters.
+pub fn print(
+    repository: impl AsRef<Path>,
+    previous: &str,
+    current: &str,
+    Context {
+        out,
+        algorithm,
+        context_lines,
+        renames,
+    }: Context<impl io::Write>,
+) -> Result<()> {
+    let git_dir = super::lookup::git_dir(repository.as_ref())?;
+    let db = compound::Db::at(git_dir.join("objects"))
+        .with_context(|| format!("Could not open objects database of '{}'", git_dir.display()))?;
+    let (previous, current) = (tree_id(&db, previous)?, tree_id(&db, current)?);
+
+    let mut changes = tree::Recorder::default();
+    tree::changes(Some(previous), Some(current), find_fn(&db), &mut changes)?;
+
+    let mut printer = Printer {
+        db: &db,
+        out: io::BufWriter::new(out),
+        algorithm: algorithm.into(),
+        context_lines,
+        previous_data: Vec::new(),
+        current_data: Vec::new(),
+    };
+    let changes = match renames {
+        Some(options) => rewrites::detect(changes.records, find_fn(&db), &options)?,
+        None => changes.records.into_iter().map(rewrites::Change::Plain).collect(),
+    };
+    let is_file = |mode: TreeMode| mode != TreeMode::Tree;
+    for change in changes {
+        match change {
+            rewrites::Change::Plain(tree::Change::Addition { entry_mode, oid, path }) if is_file(entry_mode) => {
+                printer.file(&path, None, Some((entry_mode, oid)))?
+            }
+            rewrites::Change::Plain(tree::Change::Deletion { entry_mode, oid, path }) if is_file(entry_mode) => {
+                printer.file(&path, Some((entry_mode, oid)), None)?
+            }
+            rewrites::Change::Plain(tree::Change::Modification {
+                previous_entry_mode,
+                previous_oid,
+                entry_mode,
+                oid,
+                path,
+            }) if is_file(entry_mode) => printer.file(
+                &path,
+                Some((previous_entry_mode, previous_oid)),
+                Some((entry_mode, oid)),
+            )?,
+            rewrites::Change::Rename(rewrite) => printer.rewrite(&rewrite, "rename")?,
+            rewrites::Change::Copy(rewrite) => printer.rewrite(&rewrite, "copy")?,
+            rewrites::Change::Plain(_) => {}
+        }
+    }
+    printer.out.flush()?;
+    Ok(())
+}
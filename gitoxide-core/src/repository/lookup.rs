@@ -0,0 +1,221 @@
+//! Finding git directories and the objects within them, shared by the repository commands.
+use anyhow::{anyhow, bail, Context, Result};
+use git_object::{
+    borrowed::{self, CommitIter, TagIter},
+    bstr::ByteSlice,
+    owned, Kind,
+};
+use git_odb::compound;
+use std::{
+    collections::BTreeSet,
+    fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Load the object `id` from `db` into `buf` and return its kind, or `None` if it doesn't exist.
+pub fn load(db: &compound::Db, id: &owned::Id, buf: &mut Vec<u8>) -> Result<Option<Kind>> {
+    buf.clear();
+    let mut tmp = Vec::new();
+    Ok(match db.locate(id.to_borrowed(), &mut tmp) {
+        Some(object) => Some(match object? {
+            compound::Object::Borrowed(object) => {
+                buf.extend_from_slice(object.data);
+                object.kind
+            }
+            compound::Object::Loose(mut object) => {
+                object.stream()?.read_to_end(buf)?;
+                object.kind
+            }
+        }),
+        None => None,
+    })
+}
+
+/// Return a function to find objects in `db` for use with algorithms taking a `find` function, like tree traversals.
+pub fn find_fn(db: &compound::Db) -> impl FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), io::Error>> + '_ {
+    move |id, buf| match load(db, &id.into(), buf) {
+        Ok(Some(_)) => Some(Ok(())),
+        Ok(None) => None,
+        Err(err) => Some(Err(io::Error::other(err))),
+    }
+}
+
+/// Return the tree `name` refers to, which is the id of a tree or of a commit or tag pointing to one.
+pub fn tree_id(db: &compound::Db, name: &str) -> Result<owned::Id> {
+    let id = owned::Id::from_40_bytes_in_hex(name.as_bytes())
+        .map_err(|_| anyhow!("'{}' is not a full hexadecimal object id", name))?;
+    peel_to_tree(db, id)
+}
+
+/// Return the tree `id` refers to, which is a tree or a commit or tag pointing to one.
+pub fn peel_to_tree(db: &compound::Db, mut id: owned::Id) -> Result<owned::Id> {
+    let mut buf = Vec::new();
+    loop {
+        match load(db, &id, &mut buf)?.ok_or_else(|| anyhow!("Object {} does not exist", id))? {
+            Kind::Tree => return Ok(id),
+            Kind::Commit => id = CommitIter::from_bytes(&buf).tree_id()?,
+            Kind::Tag => id = TagIter::from_bytes(&buf).target_id()?,
+            Kind::Blob => bail!("'{}' is a blob, not a tree-ish", id),
+        }
+    }
+}
+
+/// Return the git directory of the repository at `path`, which is either its working tree or the git directory itself.
+pub fn git_dir(path: &Path) -> Result<PathBuf> {
+    let dot_git = path.join(".git");
+    let git_dir = if dot_git.is_dir() { dot_git } else { path.to_owned() };
+    if !git_dir.join("objects").is_dir() {
+        return Err(anyhow!("'{}' is not a git repository", path.display()));
+    }
+    Ok(git_dir)
+}
+
+/// The object a revision resolves to
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Revision {
+    Found(owned::Id),
+    Missing,
+    /// The revision is an abbreviated object id which matches more than one object
+    Ambiguous,
+}
+
+/// The least amount of hexadecimal characters of an abbreviated object id, like git requires it.
+const MIN_ABBREVIATED_ID_LEN: usize = 4;
+
+/// The patterns by which git expands reference names, in order of precedence.
+const REF_PATTERNS: &[&str] = &[
+    "{}",
+    "refs/{}",
+    "refs/tags/{}",
+    "refs/heads/{}",
+    "refs/remotes/{}",
+    "refs/remotes/{}/HEAD",
+];
+
+/// Resolve the revision `name` in the repository at `git_dir` whose objects are in `db`, like `git rev-parse` does for
+/// full or abbreviated object ids, references like `HEAD` or `main` and `<rev>:<path>` to refer to the entry at `path`
+/// in the tree of `<rev>`. All other kinds of revisions are considered missing.
+pub fn revision(git_dir: &Path, db: &compound::Db, name: &str) -> Result<Revision> {
+    if let Some((rev, path)) = name.split_once(':') {
+        // `:<path>` refers to the index, which we don't read.
+        if rev.is_empty() {
+            return Ok(Revision::Missing);
+        }
+        return Ok(match revision(git_dir, db, rev)? {
+            Revision::Found(id) => match peel_to_tree(db, id) {
+                Ok(tree) => tree_entry(db, tree, path)?.map_or(Revision::Missing, Revision::Found),
+                Err(_) => Revision::Missing,
+            },
+            other => other,
+        });
+    }
+    if let Ok(id) = owned::Id::from_40_bytes_in_hex(name.as_bytes()) {
+        return Ok(Revision::Found(id));
+    }
+    if let Some(id) = reference(git_dir, name)? {
+        return Ok(Revision::Found(id));
+    }
+    if (MIN_ABBREVIATED_ID_LEN..40).contains(&name.len()) && name.bytes().all(|b| b.is_ascii_hexdigit()) {
+        let mut ids = BTreeSet::new();
+        objects_with_prefix(db, &name.to_ascii_lowercase(), &mut ids)?;
+        let mut ids = ids.into_iter();
+        return Ok(match (ids.next(), ids.next()) {
+            (Some(id), None) => Revision::Found(id),
+            (Some(_), Some(_)) => Revision::Ambiguous,
+            (None, _) => Revision::Missing,
+        });
+    }
+    Ok(Revision::Missing)
+}
+
+/// Return the id the reference `name` points to after expanding it like git does, or `None` if there is no such
+/// reference.
+fn reference(git_dir: &Path, name: &str) -> Result<Option<owned::Id>> {
+    let name = if name == "@" { "HEAD" } else { name };
+    if git_ref::validated::name(name.as_bytes().as_bstr()).is_err() {
+        return Ok(None);
+    }
+    // Only all-uppercase names like `HEAD` or `FETCH_HEAD` are looked up directly in the git directory.
+    let is_pseudo_ref = name.bytes().all(|b| b.is_ascii_uppercase() || b == b'_');
+    for pattern in REF_PATTERNS {
+        let full_name = pattern.replace("{}", name);
+        if !full_name.starts_with("refs/") && !is_pseudo_ref {
+            continue;
+        }
+        if let Some(id) = git_ref::file::peel_to_id(git_dir, &full_name)
+            .with_context(|| format!("Could not resolve reference '{}'", full_name))?
+        {
+            return Ok(Some(id.into()));
+        }
+    }
+    Ok(None)
+}
+
+/// Add the ids of the objects in `db` and its alternates starting with the lowercase hexadecimal `prefix` to `ids`,
+/// stopping once there are two of them as that's enough to know `prefix` is ambiguous.
+fn objects_with_prefix(db: &compound::Db, prefix: &str, ids: &mut BTreeSet<owned::Id>) -> Result<()> {
+    let has_prefix = |id: &owned::Id| id.to_sha1_hex().starts_with(prefix.as_bytes());
+    let mut first_candidate = [b'0'; 40];
+    first_candidate[..prefix.len()].copy_from_slice(prefix.as_bytes());
+    let first_candidate = owned::Id::from_40_bytes_in_hex(&first_candidate)?;
+    for bundle in &db.packs {
+        let index = &bundle.index;
+        let (mut start, mut end) = (0, index.num_objects());
+        while start < end {
+            let mid = (start + end) / 2;
+            if index.oid_at_index(mid) < first_candidate.to_borrowed() {
+                start = mid + 1;
+            } else {
+                end = mid;
+            }
+        }
+        for id in (start..index.num_objects()).map(|idx| owned::Id::from(index.oid_at_index(idx))) {
+            if !has_prefix(&id) || ids.len() > 1 {
+                break;
+            }
+            ids.insert(id);
+        }
+    }
+
+    let directory = db.loose.path.join(&prefix[..2]);
+    match fs::read_dir(&directory) {
+        Ok(entries) => {
+            for entry in entries {
+                let name = entry?.file_name();
+                let id = name.to_str().and_then(|name| {
+                    owned::Id::from_40_bytes_in_hex(format!("{}{}", &prefix[..2], name).as_bytes()).ok()
+                });
+                if let Some(id) = id.filter(|id| has_prefix(id)) {
+                    ids.insert(id);
+                }
+            }
+        }
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err).with_context(|| format!("Could not read directory '{}'", directory.display())),
+    }
+
+    for alternate in &db.alternates {
+        if ids.len() > 1 {
+            break;
+        }
+        objects_with_prefix(alternate, prefix, ids)?;
+    }
+    Ok(())
+}
+
+/// Return the id of the entry at the slash-separated `path` in the tree `id`, or `None` if there is no such entry.
+fn tree_entry(db: &compound::Db, mut id: owned::Id, path: &str) -> Result<Option<owned::Id>> {
+    let mut buf = Vec::new();
+    for component in path.split('/').filter(|c| !c.is_empty()) {
+        if load(db, &id, &mut buf)? != Some(Kind::Tree) {
+            return Ok(None);
+        }
+        let tree = borrowed::Tree::from_bytes(&buf)?;
+        match tree.entries.iter().find(|e| e.filename == component.as_bytes()) {
+            Some(entry) => id = entry.oid.into(),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(id))
+}
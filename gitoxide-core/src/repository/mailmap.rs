@@ -0,0 +1,96 @@
+use super::lookup::{find_fn, peel_to_tree};
+use crate::OutputFormat;
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use git_mailmap::Snapshot;
+use git_object::{borrowed, bstr::ByteSlice};
+use git_odb::compound;
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+pub struct Context<W: io::Write> {
+    /// The format in which to write the contacts to `out`
+    pub format: OutputFormat,
+    /// The stream to which to write the contacts
+    pub out: W,
+    /// Additional `.mailmap` files to read after the one in the tree of `HEAD`, whose entries take precedence, like
+    /// `mailmap.file`
+    pub files: Vec<PathBuf>,
+}
+
+#[cfg_attr(feature = "serde1", derive(serde::Serialize))]
+struct Contact {
+    name: String,
+    email: String,
+}
+
+/// Write the canonical name and email of each of `contacts`, which are `Name <email>` or `<email>`, like
+/// `git check-mailmap` does.
+///
+/// The mapping is read from the `.mailmap` file in the tree of `HEAD` of the repository at `repository`, followed by
+/// the given `files`.
+pub fn check(
+    repository: impl AsRef<Path>,
+    contacts: &[String],
+    Context { format, out, files }: Context<impl io::Write>,
+) -> Result<()> {
+    let git_dir = super::lookup::git_dir(repository.as_ref())?;
+    let db = compound::Db::at(git_dir.join("objects"))
+        .with_context(|| format!("Could not open objects database of '{}'", git_dir.display()))?;
+    let mut snapshot = match git_ref::file::peel_to_id(&git_dir, "HEAD")? {
+        Some(head) => {
+            let find = find_fn(&db);
+            git_mailmap::load::from_tree(peel_to_tree(&db, head.into())?, find)?.unwrap_or_default()
+        }
+        None => Snapshot::default(),
+    };
+    for file in &files {
+        let data =
+            std::fs::read(file).with_context(|| format!("Could not read mailmap file at '{}'", file.display()))?;
+        snapshot.merge(git_mailmap::parse(&data).filter_map(Result::ok));
+    }
+
+    let mut resolved = Vec::with_capacity(contacts.len());
+    for contact in contacts {
+        let signature = parse_contact(contact).ok_or_else(|| anyhow!("Unable to parse contact: {}", contact))?;
+        let signature = snapshot.resolve(&signature);
+        resolved.push(Contact {
+            name: signature.name.to_str_lossy().into_owned(),
+            email: signature.email.to_str_lossy().into_owned(),
+        });
+    }
+
+    match format {
+        OutputFormat::Human => {
+            let mut out = io::BufWriter::new(out);
+            for contact in resolved {
+                if contact.name.is_empty() {
+                    writeln!(out, "<{}>", contact.email)?;
+                } else {
+                    writeln!(out, "{} <{}>", contact.name, contact.email)?;
+                }
+            }
+            out.flush()?;
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(out, &resolved)?,
+    }
+    Ok(())
+}
+
+/// Parse `Name <email>` or `<email>` into a signature with the given name and email.
+fn parse_contact(contact: &str) -> Option<borrowed::Signature<'_>> {
+    let contact = contact.trim();
+    let email_start = contact.find('<')?;
+    let email = contact[email_start + 1..].strip_suffix('>')?;
+    Some(borrowed::Signature {
+        name: contact[..email_start].trim_end().into(),
+        email: email.into(),
+        time: git_object::Time {
+            time: 0,
+            offset: 0,
+            sign: git_object::Sign::Plus,
+        },
+    })
+}
@@ -1,5 +1,15 @@
 use anyhow::{Context as AnyhowContext, Result};
 
+pub mod cat_batch;
+mod clone;
+pub mod diff;
+mod lookup;
+pub mod mailmap;
+pub mod size;
+pub mod tree_list;
+
+pub use clone::{clone_local, CloneMode, Context};
+
 pub fn init() -> Result<()> {
     git_repository::init::repository().with_context(|| "Repository initialization failed")
 }
@@ -0,0 +1,548 @@
+use crate::OutputFormat;
+use anyhow::{anyhow, Context as AnyhowContext, Result};
+use bytesize::ByteSize;
+use git_features::{
+    parallel,
+    progress::{self, Progress},
+};
+use git_object::{
+    borrowed,
+    bstr::{BString, ByteSlice},
+    owned, Kind, TreeMode,
+};
+use git_odb::{compound, pack};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+    io,
+    path::Path,
+};
+
+/// A general purpose context for many operations provided here
+pub struct Context<W: io::Write> {
+    /// The format in which to write the analysis to `out`
+    pub format: OutputFormat,
+    /// A stream to which to output the analysis
+    pub out: W,
+    /// If set, don't use more than this amount of threads.
+    /// Otherwise, usually use as many threads as there are logical cores.
+    /// A value of 0 is interpreted as no-limit
+    pub thread_limit: Option<usize>,
+    /// The amount of objects to list in each of the top lists, like the largest blobs
+    pub limit: usize,
+}
+
+/// Counts and sizes of all objects of one kind
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct KindStatistics {
+    pub count: u64,
+    /// The sum of the sizes of all objects after decompression and delta resolution
+    pub total_size: u64,
+    pub largest_size: u64,
+}
+
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Blob {
+    pub id: owned::Id,
+    pub size: u64,
+    /// A path at which the blob can be found, relative to one of the root trees referencing it, if one was found.
+    pub path: Option<BString>,
+}
+
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Tree {
+    pub id: owned::Id,
+    /// The amount of entries in the tree itself
+    pub num_entries: usize,
+    /// The amount of nested trees including this one, i.e. 1 for a tree without subtrees
+    pub depth: usize,
+}
+
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeltaChain {
+    pub id: owned::Id,
+    pub kind: Kind,
+    /// The amount of deltas to apply to obtain the object, 0 for objects which are no delta
+    pub length: u32,
+}
+
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Commit {
+    pub id: owned::Id,
+    pub num_parents: usize,
+}
+
+/// The result of analysing all objects of a repository, including those of its alternates.
+///
+/// Objects stored more than once, for example in multiple packs, are only counted once.
+/// Objects with the same rank in the top lists are ordered by id.
+#[derive(PartialEq, Eq, Debug, Hash, Clone, Default)]
+#[cfg_attr(feature = "serde1", derive(serde::Serialize, serde::Deserialize))]
+pub struct Outcome {
+    pub commits: KindStatistics,
+    pub trees: KindStatistics,
+    pub blobs: KindStatistics,
+    pub tags: KindStatistics,
+    /// The amount of objects per delta chain length, with loose objects having a length of 0
+    pub objects_per_chain_length: BTreeMap<u32, u32>,
+    pub largest_blobs: Vec<Blob>,
+    pub deepest_trees: Vec<Tree>,
+    pub widest_trees: Vec<Tree>,
+    pub longest_delta_chains: Vec<DeltaChain>,
+    pub commits_with_most_parents: Vec<Commit>,
+}
+
+/// Analyse all objects in the repository at `repository`, a path to a repository or its `.git` directory, in the
+/// spirit of [git-sizer](https://github.com/github/git-sizer), and write the result to `out`.
+pub fn analyse(
+    repository: impl AsRef<Path>,
+    progress: Option<impl Progress>,
+    Context {
+        format,
+        mut out,
+        thread_limit,
+        limit,
+    }: Context<impl io::Write>,
+) -> Result<Outcome> {
+    let git_dir = super::lookup::git_dir(repository.as_ref())?;
+    let db = compound::Db::at(git_dir.join("objects"))
+        .with_context(|| format!("Could not open objects database of '{}'", git_dir.display()))?;
+    let mut progress = progress::DoOrDiscard::from(progress);
+
+    let mut state = State {
+        limit,
+        ..Default::default()
+    };
+    state = collect(&db, &mut Vec::new(), state, thread_limit, &mut progress)?;
+    let outcome = state.into_outcome(&db, progress.add_child("finding blob paths"));
+
+    match format {
+        OutputFormat::Human => print(&mut out, &outcome)?,
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => serde_json::to_writer_pretty(&mut out, &outcome)?,
+    };
+    Ok(outcome)
+}
+
+/// Add all objects in `db` and its alternates to `state`, except for those which are also stored in the `done`
+/// databases or one of the packs of `db` which was already looked at.
+fn collect<'a>(
+    db: &'a compound::Db,
+    done: &mut Vec<&'a compound::Db>,
+    mut state: State,
+    thread_limit: Option<usize>,
+    progress: &mut impl Progress,
+) -> Result<State> {
+    for (pack_index, bundle) in db.packs.iter().enumerate() {
+        let seen_before = |id: borrowed::Id<'_>| {
+            contains(done, id) || db.packs[..pack_index].iter().any(|b| b.index.lookup(id).is_some())
+        };
+        let (_, new_state, _) = bundle
+            .index
+            .traverse_objects(
+                &bundle.pack,
+                Some(progress.add_child(format!("{}", bundle.pack.path().display()))),
+                thread_limit,
+                pack::index::traverse::SafetyCheck::SkipFileAndObjectChecksumVerification,
+                || {
+                    |object: pack::index::traverse::Object<'_>, _progress: &mut _| {
+                        if seen_before(object.id.to_borrowed()) {
+                            return Ok(None);
+                        }
+                        Ok(Some(Item::from_object(
+                            object.id,
+                            object.data.len() as u64,
+                            object.delta_depth,
+                            &borrowed::Object::from_bytes(object.kind, object.data)?,
+                        )))
+                    }
+                },
+                pack::cache::DecodeEntryLRU::default,
+                state,
+            )
+            .with_context(|| format!("Failed to traverse pack at '{}'", bundle.pack.path().display()))?;
+        state = new_state;
+    }
+
+    let mut loose_progress = progress.add_child("loose objects");
+    loose_progress.init(None, progress::count("objects"));
+    for id in db.loose.iter() {
+        let id = id?;
+        if contains(done, id.to_borrowed()) || db.packs.iter().any(|b| b.index.lookup(id.to_borrowed()).is_some()) {
+            continue;
+        }
+        let mut object = db
+            .loose
+            .locate(id.to_borrowed())
+            .ok_or_else(|| anyhow!("Loose object {} vanished while reading it", id))??;
+        let size = object.size as u64;
+        let item = match object.kind {
+            Kind::Tree | Kind::Commit => Item::from_object(id, size, 0, &object.decode()?),
+            kind => Item::new(id, kind, size, 0),
+        };
+        state.add(item);
+        loose_progress.inc();
+    }
+
+    done.push(db);
+    for alternate in &db.alternates {
+        state = collect(alternate, done, state, thread_limit, progress)?;
+    }
+    Ok(state)
+}
+
+/// Return true if the object `id` is stored in one of the packs or among the loose objects of any of the `dbs`,
+/// without looking at their alternates.
+fn contains(dbs: &[&compound::Db], id: borrowed::Id<'_>) -> bool {
+    dbs.iter()
+        .any(|db| db.packs.iter().any(|b| b.index.lookup(id).is_some()) || db.loose.contains(id))
+}
+
+/// Information about a single object as needed for the analysis
+struct Item {
+    id: owned::Id,
+    kind: Kind,
+    size: u64,
+    delta_depth: u32,
+    num_parents: usize,
+    num_entries: usize,
+    subtrees: Vec<owned::Id>,
+}
+
+impl Item {
+    fn new(id: owned::Id, kind: Kind, size: u64, delta_depth: u32) -> Self {
+        Item {
+            id,
+            kind,
+            size,
+            delta_depth,
+            num_parents: 0,
+            num_entries: 0,
+            subtrees: Vec::new(),
+        }
+    }
+
+    fn from_object(id: owned::Id, size: u64, delta_depth: u32, object: &borrowed::Object<'_>) -> Self {
+        let mut item = Item::new(id, object.kind(), size, delta_depth);
+        match object {
+            borrowed::Object::Tree(tree) => {
+                item.num_entries = tree.entries.len();
+                item.subtrees = tree
+                    .entries
+                    .iter()
+                    .filter(|e| e.mode == TreeMode::Tree)
+                    .map(|e| e.oid.into())
+                    .collect();
+            }
+            borrowed::Object::Commit(commit) => item.num_parents = commit.parents.len(),
+            borrowed::Object::Blob(_) | borrowed::Object::Tag(_) => {}
+        }
+        item
+    }
+}
+
+/// A tree along with the ids of its subtrees, as needed to compute tree depths and to find blob paths.
+struct TreeInfo {
+    id: owned::Id,
+    num_entries: usize,
+    subtrees: Vec<owned::Id>,
+}
+
+#[derive(Default)]
+struct State {
+    limit: usize,
+    outcome: Outcome,
+    largest_blobs: Vec<(u64, owned::Id)>,
+    /// All trees, sorted by id once all objects were added.
+    trees: Vec<TreeInfo>,
+}
+
+impl State {
+    fn add(&mut self, item: Item) {
+        let out = &mut self.outcome;
+        let stats = match item.kind {
+            Kind::Commit => &mut out.commits,
+            Kind::Tree => &mut out.trees,
+            Kind::Blob => &mut out.blobs,
+            Kind::Tag => &mut out.tags,
+        };
+        stats.count += 1;
+        stats.total_size += item.size;
+        stats.largest_size = stats.largest_size.max(item.size);
+        *out.objects_per_chain_length.entry(item.delta_depth).or_insert(0) += 1;
+
+        let limit = self.limit;
+        keep_largest(
+            &mut out.longest_delta_chains,
+            DeltaChain {
+                id: item.id,
+                kind: item.kind,
+                length: item.delta_depth,
+            },
+            limit,
+            |c| (c.length as u64, c.id),
+        );
+        match item.kind {
+            Kind::Blob => keep_largest(&mut self.largest_blobs, (item.size, item.id), limit, |b| *b),
+            Kind::Commit => keep_largest(
+                &mut out.commits_with_most_parents,
+                Commit {
+                    id: item.id,
+                    num_parents: item.num_parents,
+                },
+                limit,
+                |c| (c.num_parents as u64, c.id),
+            ),
+            Kind::Tree => self.trees.push(TreeInfo {
+                id: item.id,
+                num_entries: item.num_entries,
+                subtrees: item.subtrees,
+            }),
+            Kind::Tag => {}
+        }
+    }
+
+    fn into_outcome(self, db: &compound::Db, progress: impl Progress) -> Outcome {
+        let State {
+            limit,
+            mut outcome,
+            largest_blobs,
+            mut trees,
+        } = self;
+        trees.sort_by_key(|tree| tree.id);
+
+        for (tree, depth) in trees.iter().zip(tree_depths(&trees)) {
+            let tree = Tree {
+                id: tree.id,
+                num_entries: tree.num_entries,
+                depth,
+            };
+            keep_largest(&mut outcome.deepest_trees, tree, limit, |t| (t.depth as u64, t.id));
+            keep_largest(&mut outcome.widest_trees, tree, limit, |t| (t.num_entries as u64, t.id));
+        }
+
+        let mut paths = blob_paths(db, &trees, largest_blobs.iter().map(|(_, id)| *id).collect(), progress);
+        outcome.largest_blobs = largest_blobs
+            .into_iter()
+            .map(|(size, id)| Blob {
+                id,
+                size,
+                path: paths.remove(&id),
+            })
+            .collect();
+        outcome
+    }
+}
+
+impl parallel::Reducer for State {
+    type Input = Vec<Option<Item>>;
+    type Output = State;
+    type Error = borrowed::Error;
+
+    fn feed(&mut self, items: Self::Input) -> Result<(), Self::Error> {
+        for item in items.into_iter().flatten() {
+            self.add(item);
+        }
+        Ok(())
+    }
+
+    fn finalize(self) -> Result<Self::Output, Self::Error> {
+        Ok(self)
+    }
+}
+
+/// Insert `item` into `items`, which is sorted by the value returned by `key` in descending order and by its id in
+/// ascending order, and keep no more than `limit` items.
+fn keep_largest<T>(items: &mut Vec<T>, item: T, limit: usize, key: impl Fn(&T) -> (u64, owned::Id)) {
+    let rank = |item: &T| {
+        let (value, id) = key(item);
+        (value, Reverse(id))
+    };
+    let item_rank = rank(&item);
+    let pos = items.partition_point(|existing| rank(existing) > item_rank);
+    if pos < limit {
+        items.insert(pos, item);
+        items.truncate(limit);
+    }
+}
+
+/// Return the index of the tree with `id` in `trees`, which are sorted by id.
+fn tree_index(trees: &[TreeInfo], id: &owned::Id) -> Option<usize> {
+    trees.binary_search_by(|tree| tree.id.cmp(id)).ok()
+}
+
+/// Return the amount of nested trees for each of the `trees`, without recursion to support arbitrarily deep trees.
+fn tree_depths(trees: &[TreeInfo]) -> Vec<usize> {
+    const UNKNOWN: usize = 0;
+    const IN_PROGRESS: usize = usize::MAX;
+    let mut depths = vec![UNKNOWN; trees.len()];
+    let mut stack = Vec::new();
+    for start in 0..trees.len() {
+        stack.push((start, false));
+        while let Some((idx, subtrees_done)) = stack.pop() {
+            let subtrees = trees[idx].subtrees.iter().filter_map(|id| tree_index(trees, id));
+            if subtrees_done {
+                depths[idx] = 1 + subtrees
+                    .map(|idx| depths[idx])
+                    // Trees can't form cycles, but broken repositories shouldn't make us loop forever.
+                    .filter(|depth| *depth != IN_PROGRESS)
+                    .max()
+                    .unwrap_or(0);
+            } else if depths[idx] == UNKNOWN {
+                depths[idx] = IN_PROGRESS;
+                stack.push((idx, true));
+                stack.extend(subtrees.filter(|idx| depths[*idx] == UNKNOWN).map(|idx| (idx, false)));
+            }
+        }
+    }
+    depths
+}
+
+/// Load the tree `id` from `db` into `buf` and return what `f` makes of it, or `None` if it couldn't be loaded.
+fn with_tree<T>(
+    db: &compound::Db,
+    id: &owned::Id,
+    buf: &mut Vec<u8>,
+    f: impl FnOnce(&borrowed::Tree<'_>) -> T,
+) -> Option<T> {
+    let mut object = db.locate(id.to_borrowed(), buf)?.ok()?;
+    let result = match object.decode() {
+        Ok(borrowed::Object::Tree(tree)) => Some(f(&tree)),
+        _ => None,
+    };
+    result
+}
+
+/// Find a path for each of the given `blobs`, relative to a tree which isn't referenced by any other tree.
+///
+/// Only the ids of subtrees are kept in memory, so their names are read from the object database again.
+fn blob_paths(
+    db: &compound::Db,
+    trees: &[TreeInfo],
+    mut blobs: BTreeSet<owned::Id>,
+    mut progress: impl Progress,
+) -> BTreeMap<owned::Id, BString> {
+    progress.init(Some(trees.len()), progress::count("trees"));
+    let mut containing_tree = BTreeMap::new();
+    let mut buf = Vec::new();
+    for (tree_idx, tree) in trees.iter().enumerate() {
+        if blobs.is_empty() {
+            break;
+        }
+        progress.inc();
+        with_tree(db, &tree.id, &mut buf, |tree| {
+            for entry in tree.entries.iter().filter(|e| e.mode != TreeMode::Tree) {
+                let blob_id = owned::Id::from(entry.oid);
+                if blobs.remove(&blob_id) {
+                    containing_tree.insert(blob_id, (tree_idx, entry.filename.to_owned()));
+                }
+            }
+        });
+    }
+
+    let mut parents = vec![None; trees.len()];
+    for (parent_idx, tree) in trees.iter().enumerate() {
+        for idx in tree.subtrees.iter().filter_map(|id| tree_index(trees, id)) {
+            parents[idx].get_or_insert(parent_idx);
+        }
+    }
+    containing_tree
+        .into_iter()
+        .filter_map(|(blob_id, (mut tree_idx, name))| {
+            let mut components = vec![name];
+            // Trees can't form cycles, but broken repositories shouldn't make us loop forever.
+            while let Some(parent_idx) = parents[tree_idx].filter(|_| components.len() <= trees.len()) {
+                let tree_id = trees[tree_idx].id;
+                let name = with_tree(db, &trees[parent_idx].id, &mut buf, |parent| {
+                    parent
+                        .entries
+                        .iter()
+                        .find(|e| e.mode == TreeMode::Tree && owned::Id::from(e.oid) == tree_id)
+                        .map(|e| e.filename.to_owned())
+                })??;
+                components.push(name);
+                tree_idx = parent_idx;
+            }
+            let mut path = BString::from(Vec::new());
+            for component in components.iter().rev() {
+                if !path.is_empty() {
+                    path.push(b'/');
+                }
+                path.extend_from_slice(component);
+            }
+            Some((blob_id, path))
+        })
+        .collect()
+}
+
+fn print(out: &mut impl io::Write, outcome: &Outcome) -> io::Result<()> {
+    let width = 10;
+    writeln!(out, "objects by kind")?;
+    writeln!(
+        out,
+        "\t{:<width$} {:>10} {:>12} {:>12}",
+        "kind",
+        "count",
+        "total size",
+        "largest",
+        width = width
+    )?;
+    for (name, stats) in &[
+        ("commits", outcome.commits),
+        ("trees", outcome.trees),
+        ("blobs", outcome.blobs),
+        ("tags", outcome.tags),
+    ] {
+        writeln!(
+            out,
+            "\t{:<width$} {:>10} {:>12} {:>12}",
+            name,
+            stats.count,
+            ByteSize(stats.total_size).to_string(),
+            ByteSize(stats.largest_size).to_string(),
+            width = width
+        )?;
+    }
+
+    writeln!(out, "\nobjects per delta chain length")?;
+    for (chain_length, object_count) in &outcome.objects_per_chain_length {
+        writeln!(out, "\t{:>2}: {}", chain_length, object_count)?;
+    }
+
+    writeln!(out, "\nlargest blobs")?;
+    for blob in &outcome.largest_blobs {
+        write!(out, "\t{} {:>12}", blob.id, ByteSize(blob.size).to_string())?;
+        match &blob.path {
+            Some(path) => writeln!(out, " {}", path.to_str_lossy())?,
+            None => writeln!(out)?,
+        }
+    }
+    writeln!(out, "\ndeepest trees")?;
+    for tree in &outcome.deepest_trees {
+        writeln!(out, "\t{} depth {}", tree.id, tree.depth)?;
+    }
+    writeln!(out, "\nwidest trees")?;
+    for tree in &outcome.widest_trees {
+        writeln!(out, "\t{} {} entries", tree.id, tree.num_entries)?;
+    }
+    writeln!(out, "\nlongest delta chains")?;
+    for chain in &outcome.longest_delta_chains {
+        writeln!(
+            out,
+            "\t{} {:<6} {} deltas",
+            chain.id,
+            chain.kind.to_string(),
+            chain.length
+        )?;
+    }
+    writeln!(out, "\ncommits with most parents")?;
+    for commit in &outcome.commits_with_most_parents {
+        writeln!(out, "\t{} {} parents", commit.id, commit.num_parents)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,142 @@
+use super::lookup::{find_fn, tree_id};
+use crate::OutputFormat;
+use anyhow::{Context as AnyhowContext, Result};
+use git_object::{borrowed, bstr::ByteSlice, owned, TreeMode};
+use git_odb::compound;
+use git_traverse::tree::{self, Action, Visit};
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+pub struct Context<W: io::Write> {
+    /// The format in which to write the entries to `out`
+    pub format: OutputFormat,
+    /// The stream to which to write the entries
+    pub out: W,
+    /// If true, list all entries of a level before the entries of its subtrees, otherwise list the entries of a
+    /// subtree right after the subtree itself
+    pub breadth_first: bool,
+    /// If true, list subtrees as well, like `git ls-tree -r -t`, otherwise only list the entries within them
+    pub trees: bool,
+    /// If true, only list the paths of entries, like `git ls-tree --name-only`
+    pub name_only: bool,
+}
+
+/// Writes entries as they are seen, in the format of `git ls-tree`.
+struct Printer<W> {
+    out: W,
+    trees: bool,
+    name_only: bool,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> Printer<W> {
+    fn write(&mut self, entry: &tree::Entry<'_>) -> io::Result<()> {
+        if self.name_only {
+            return writeln!(self.out, "{}", entry.path);
+        }
+        let kind = match entry.mode {
+            TreeMode::Tree => "tree",
+            TreeMode::Commit => "commit",
+            TreeMode::Blob | TreeMode::BlobExecutable | TreeMode::Link => "blob",
+        };
+        self.out
+            .write_all(format!("{} {} {}\t", mode_str(entry.mode), kind, entry.oid).as_bytes())?;
+        self.out.write_all(entry.path.as_bytes())?;
+        self.out.write_all(b"\n")
+    }
+}
+
+impl<W: io::Write> Visit for Printer<W> {
+    fn visit(&mut self, entry: tree::Entry<'_>) -> Action {
+        if entry.mode == TreeMode::Tree && !self.trees {
+            return Action::Continue;
+        }
+        match self.write(&entry) {
+            Ok(()) => Action::Continue,
+            Err(err) => {
+                self.error = Some(err);
+                Action::Cancel
+            }
+        }
+    }
+}
+
+fn traverse<Find>(
+    root: owned::Id,
+    breadth_first: bool,
+    find: Find,
+    visit: &mut impl Visit,
+) -> Result<(), tree::Error<io::Error>>
+where
+    Find: FnMut(borrowed::Id<'_>, &mut Vec<u8>) -> Option<Result<(), io::Error>>,
+{
+    if breadth_first {
+        tree::breadthfirst(root, find, visit)
+    } else {
+        tree::depthfirst(root, find, visit)
+    }
+}
+
+/// List all entries of the tree `treeish` and its subtrees recursively, like `git ls-tree -r` does, where `treeish` is
+/// the id of a tree or of a commit or tag pointing to one in the repository at `repository`.
+///
+/// Paths are written as they are without quoting.
+pub fn list(
+    repository: impl AsRef<Path>,
+    treeish: &str,
+    Context {
+        format,
+        out,
+        breadth_first,
+        trees,
+        name_only,
+    }: Context<impl io::Write>,
+) -> Result<()> {
+    let git_dir = super::lookup::git_dir(repository.as_ref())?;
+    let db = compound::Db::at(git_dir.join("objects"))
+        .with_context(|| format!("Could not open objects database of '{}'", git_dir.display()))?;
+    let root = tree_id(&db, treeish)?;
+    let find = find_fn(&db);
+
+    match format {
+        OutputFormat::Human => {
+            let mut printer = Printer {
+                out: io::BufWriter::new(out),
+                trees,
+                name_only,
+                error: None,
+            };
+            let res = traverse(root, breadth_first, find, &mut printer);
+            if let Some(err) = printer.error {
+                return Err(err.into());
+            }
+            res?;
+            printer.out.flush()?;
+        }
+        #[cfg(feature = "serde1")]
+        OutputFormat::Json => {
+            let mut recorder = tree::Recorder::default();
+            traverse(root, breadth_first, find, &mut recorder)?;
+            let entries: Vec<tree::recorder::Entry> = recorder
+                .records
+                .into_iter()
+                .filter(|entry| trees || entry.mode != TreeMode::Tree)
+                .collect();
+            serde_json::to_writer_pretty(out, &entries)?;
+        }
+    }
+    Ok(())
+}
+
+/// Return the `mode` as git prints it, with six octal digits.
+pub(super) fn mode_str(mode: TreeMode) -> &'static str {
+    match mode {
+        TreeMode::Tree => "040000",
+        TreeMode::Blob => "100644",
+        TreeMode::BlobExecutable => "100755",
+        TreeMode::Link => "120000",
+        TreeMode::Commit => "160000",
+    }
+}
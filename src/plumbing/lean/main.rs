@@ -2,7 +2,10 @@ use crate::shared::ProgressRange;
 use anyhow::Result;
 use git_features::progress;
 use gitoxide_core::{self as core, OutputFormat};
-use std::io::{self, stderr, stdout};
+use std::{
+    io::{self, stderr, stdout},
+    path::PathBuf,
+};
 
 #[cfg(not(any(feature = "prodash-render-line-crossterm", feature = "prodash-render-line-termion")))]
 fn prepare(verbose: bool, name: &str, _: impl Into<Option<ProgressRange>>) -> ((), Option<prodash::progress::Log>) {
@@ -54,6 +57,10 @@ pub fn main() -> Result<()> {
             url,
             directory,
             refs_directory,
+            deepen_since,
+            max_object_size,
+            max_delta_chain_length,
+            max_resolved_base_bytes,
         }) => {
             let (_handle, progress) = prepare(verbose, "pack-receive", core::pack::receive::PROGRESS_RANGE);
             core::pack::receive(
@@ -66,6 +73,10 @@ pub fn main() -> Result<()> {
                     thread_limit,
                     format: OutputFormat::Human,
                     out: io::stdout(),
+                    deepen_since,
+                    max_object_size,
+                    max_delta_chain_length,
+                    max_resolved_base_bytes,
                 },
             )
         }
@@ -73,6 +84,9 @@ pub fn main() -> Result<()> {
             iteration_mode,
             pack_path,
             directory,
+            max_object_size,
+            max_delta_chain_length,
+            max_resolved_base_bytes,
         }) => {
             let (_handle, progress) = prepare(verbose, "pack-explode", core::pack::index::PROGRESS_RANGE);
             core::pack::index::from_pack(
@@ -84,6 +98,9 @@ pub fn main() -> Result<()> {
                     iteration_mode: iteration_mode.unwrap_or_default(),
                     format: OutputFormat::Human,
                     out: io::stdout(),
+                    max_object_size,
+                    max_delta_chain_length,
+                    max_resolved_base_bytes,
                 },
             )
         }
@@ -114,9 +131,19 @@ pub fn main() -> Result<()> {
             statistics,
             algorithm,
             decode,
+            fsck,
+            fsck_strict,
+            fsck_severity,
             re_encode,
+            list_entries,
         }) => {
             use self::core::pack::verify;
+            if fsck && re_encode {
+                anyhow::bail!("--fsck and --re-encode can't be used together");
+            }
+            if !fsck && (fsck_strict || !fsck_severity.is_empty()) {
+                anyhow::bail!("--fsck-strict and --fsck-severity require --fsck");
+            }
             let (_handle, progress) = prepare(verbose, "pack-verify", None);
             core::pack::verify::pack_or_pack_index(
                 path,
@@ -127,12 +154,20 @@ pub fn main() -> Result<()> {
                     } else {
                         None
                     },
+                    output_entries: if list_entries {
+                        Some(core::OutputFormat::Human)
+                    } else {
+                        None
+                    },
                     algorithm: algorithm.unwrap_or(verify::Algorithm::LessTime),
                     thread_limit,
-                    mode: match (decode, re_encode) {
-                        (true, false) => verify::Mode::Sha1CRC32Decode,
-                        (true, true) | (false, true) => verify::Mode::Sha1CRC32DecodeEncode,
-                        (false, false) => verify::Mode::Sha1CRC32,
+                    mode: match (decode, fsck, re_encode) {
+                        (_, false, true) => verify::Mode::Sha1CRC32DecodeEncode,
+                        (_, true, _) => {
+                            verify::Mode::Sha1CRC32DecodeFsck(verify::fsck_options(fsck_strict, fsck_severity))
+                        }
+                        (true, false, false) => verify::Mode::Sha1CRC32Decode,
+                        (false, false, false) => verify::Mode::Sha1CRC32,
                     },
                     out: stdout(),
                     err: stderr(),
@@ -140,5 +175,77 @@ pub fn main() -> Result<()> {
             )
             .map(|_| ())
         }
+        SubCommands::RepositoryCloneLocal(RepositoryCloneLocal {
+            mode,
+            source,
+            destination,
+        }) => core::repository::clone_local(
+            &source,
+            destination,
+            mode.unwrap_or(core::repository::CloneMode::Link),
+            core::repository::Context {
+                format: OutputFormat::Human,
+                out: stdout(),
+            },
+        ),
+        SubCommands::RepositorySize(RepositorySize { limit, repository }) => {
+            let (_handle, progress) = prepare(verbose, "repository-size", None);
+            core::repository::size::analyse(
+                repository.unwrap_or_else(|| PathBuf::from(".")),
+                progress,
+                core::repository::size::Context {
+                    format: OutputFormat::Human,
+                    out: stdout(),
+                    thread_limit,
+                    limit: limit.unwrap_or(10),
+                },
+            )
+            .map(|_| ())
+        }
+        SubCommands::RepositoryCatBatch(RepositoryCatBatch {
+            check,
+            all_objects,
+            object_format,
+            repository,
+        }) => core::repository::cat_batch::serve(
+            repository.unwrap_or_else(|| PathBuf::from(".")),
+            core::repository::cat_batch::Context {
+                input: io::stdin().lock(),
+                out: stdout(),
+                object_format,
+                contents: !check,
+                all_objects,
+            },
+        ),
+        SubCommands::TreeList(TreeList {
+            trees,
+            name_only,
+            breadth_first,
+            repository,
+            treeish,
+        }) => core::repository::tree_list::list(
+            repository.unwrap_or_else(|| PathBuf::from(".")),
+            &treeish,
+            core::repository::tree_list::Context {
+                format: OutputFormat::Human,
+                out: stdout(),
+                breadth_first,
+                trees,
+                name_only,
+            },
+        ),
+        SubCommands::MailmapCheck(MailmapCheck {
+            files,
+            repository,
+            contacts,
+        }) => core::repository::mailmap::check(
+            repository.unwrap_or_else(|| PathBuf::from(".")),
+            &contacts,
+            core::repository::mailmap::Context {
+                format: OutputFormat::Human,
+                out: stdout(),
+                files,
+            },
+        ),
     }
 }
@@ -32,6 +32,11 @@ pub enum SubCommands {
     IndexFromPack(IndexFromPack),
     RemoteRefList(RemoteRefList),
     PackReceive(PackReceive),
+    RepositoryCloneLocal(RepositoryCloneLocal),
+    RepositorySize(RepositorySize),
+    RepositoryCatBatch(RepositoryCatBatch),
+    TreeList(TreeList),
+    MailmapCheck(MailmapCheck),
 }
 
 /// Create an index from a packfile.
@@ -56,6 +61,18 @@ pub struct IndexFromPack {
     #[argh(option, short = 'p')]
     pub pack_path: Option<PathBuf>,
 
+    /// the maximum size in bytes of objects and deltas in the pack, unlimited by default.
+    #[argh(option)]
+    pub max_object_size: Option<u64>,
+
+    /// the maximum length of delta chains in the pack, defaults to 4095.
+    #[argh(option)]
+    pub max_delta_chain_length: Option<u32>,
+
+    /// the maximum amount of bytes of resolved objects to keep in memory as delta bases while indexing, unlimited by default.
+    #[argh(option)]
+    pub max_resolved_base_bytes: Option<u64>,
+
     /// the folder into which to place the pack and the generated index file
     ///
     /// If unset, only informational output will be provided to standard output.
@@ -98,13 +115,31 @@ pub struct PackReceive {
     #[argh(option, short = 'r')]
     pub refs_directory: Option<PathBuf>,
 
+    /// only receive commits more recent than this date, like `--shallow-since`, as any date git accepts like
+    /// '2020-09-17' or '2 weeks ago'.
+    #[argh(option)]
+    pub deepen_since: Option<String>,
+
+    /// the maximum size in bytes of objects and deltas in the pack, unlimited by default.
+    #[argh(option)]
+    pub max_object_size: Option<u64>,
+
+    /// the maximum length of delta chains in the pack, defaults to 4095.
+    #[argh(option)]
+    pub max_delta_chain_length: Option<u32>,
+
+    /// the maximum amount of bytes of resolved objects to keep in memory as delta bases while indexing, unlimited by default.
+    #[argh(option)]
+    pub max_resolved_base_bytes: Option<u64>,
+
     /// the URLs or path from which to receive the pack.
     ///
     /// See here for a list of supported URLs: https://www.git-scm.com/docs/git-clone#_git_urls
     #[argh(positional)]
     pub url: String,
 
-    /// the directory into which to write the received pack and index.
+    /// the directory into which to write the received pack and index, along with a `shallow` file listing the
+    /// commits whose parents weren't received when deepening.
     ///
     /// If unset, they will be discarded.
     #[argh(positional)]
@@ -166,6 +201,24 @@ pub struct PackVerify {
     /// This will reduce overall performance.
     pub decode: bool,
 
+    #[argh(switch)]
+    /// decode tags, commits and trees and check them for problems like `git fsck` does, failing on errors.
+    ///
+    /// This finds objects which parse but are malformed or dangerous, like unsorted trees, trees with '.git'
+    /// entries or signatures with invalid emails or timezones. It implies '--decode' and can't be used with
+    /// '--re-encode'.
+    pub fsck: bool,
+
+    #[argh(switch)]
+    /// turn the warnings of '--fsck' into errors, like git does when receiving objects with 'transfer.fsckObjects'.
+    pub fsck_strict: bool,
+
+    #[argh(option)]
+    /// report the check with the given id at the given level with '--fsck', like 'missingEmail=ignore'. May be repeated.
+    ///
+    /// This is what 'fsck.<id>' configures in git. Levels are 'error', 'warn', 'info' and 'ignore'.
+    pub fsck_severity: Vec<core::pack::verify::FsckSeverity>,
+
     #[argh(switch)]
     /// decode and parse tags, commits and trees to validate their correctness, and re-encode them.
     ///
@@ -178,13 +231,131 @@ pub struct PackVerify {
     #[argh(option)]
     /// the algorithm used to verify the pack. They differ in costs.
     ///
-    /// Possible values are "less-time" and "less-memory". Default is "less-memory".
+    /// Possible values are "less-time", "less-memory" and "balanced". Default is "less-memory".
     pub algorithm: Option<core::pack::verify::Algorithm>,
 
     /// output statistical information about the pack
     #[argh(switch, short = 's')]
     pub statistics: bool,
+    /// list every entry of the pack with its id, kind, size, size in pack, offset, and if it is a delta, its
+    /// delta chain length and base id, like `git verify-pack -v`
+    #[argh(switch, short = 'l')]
+    pub list_entries: bool,
     /// the '.pack' or '.idx' file whose checksum to validate.
     #[argh(positional)]
     pub path: PathBuf,
 }
+
+/// Clone a repository on the local file system into a new bare repository without generating a pack.
+///
+/// This is the plumbing equivalent of `git clone --bare --local`.
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "repository-clone-local")]
+pub struct RepositoryCloneLocal {
+    /// determines how objects are made available in the clone, defaults to 'link'
+    ///
+    /// Valid values are
+    ///
+    ///  **link** hardlink objects, and copy them if that fails, for example across file systems,
+    ///  **copy** always copy objects,
+    ///  **reference** don't copy any object but register the source objects directory as alternate
+    #[argh(option, short = 'm')]
+    pub mode: Option<core::repository::CloneMode>,
+
+    /// the path or file:// URL to the repository to clone, or its .git directory
+    #[argh(positional)]
+    pub source: String,
+
+    /// the directory into which to clone. It must not exist or be empty.
+    #[argh(positional)]
+    pub destination: PathBuf,
+}
+
+/// Analyse the size of all objects in a repository to find those that may cause trouble, like `git-sizer`.
+///
+/// Lists object counts and sizes by kind, the largest blobs, the deepest and widest trees, the longest
+/// delta chains and the commits with the most parents.
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "repository-size")]
+pub struct RepositorySize {
+    /// the amount of objects to show in each list of largest or deepest objects, defaults to 10
+    #[argh(option, short = 'n')]
+    pub limit: Option<usize>,
+
+    /// the path to the repository to analyse, or its .git directory. Defaults to the current directory.
+    #[argh(positional)]
+    pub repository: Option<PathBuf>,
+}
+
+/// Read object names from standard input and write their information and contents to standard output.
+///
+/// This is the plumbing equivalent of `git cat-file --batch` and `git cat-file --batch-check`, using the same
+/// input and output format.
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "repository-cat-batch")]
+pub struct RepositoryCatBatch {
+    /// only write the line describing each object, but not its contents, like `--batch-check`.
+    #[argh(switch, short = 'c')]
+    pub check: bool,
+
+    /// ignore standard input and write all objects in the repository and its alternates, like `--batch-all-objects`.
+    #[argh(switch, short = 'a')]
+    pub all_objects: bool,
+
+    /// the format of the line written for each object, defaults to '%(objectname) %(objecttype) %(objectsize)'.
+    ///
+    /// Supported elements are %(objectname), %(objecttype), %(objectsize), %(objectsize:disk), %(deltabase) and %(rest).
+    #[argh(option, short = 'o')]
+    pub object_format: Option<String>,
+
+    /// the path to the repository to read objects from, or its .git directory. Defaults to the current directory.
+    #[argh(positional)]
+    pub repository: Option<PathBuf>,
+}
+
+/// List all entries of a tree and its subtrees, like `git ls-tree -r`.
+///
+/// Each entry is written as '<mode> <type> <object>' followed by a tab and its path, unless only names are listed.
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "tree-list")]
+pub struct TreeList {
+    /// list subtrees as well, instead of only the entries within them, like `-t`.
+    #[argh(switch, short = 't')]
+    pub trees: bool,
+
+    /// only list the path of each entry, like `--name-only`.
+    #[argh(switch, short = 'n')]
+    pub name_only: bool,
+
+    /// list all entries of a tree before the entries of its subtrees, instead of listing the entries of a subtree
+    /// right after the subtree itself.
+    #[argh(switch, short = 'b')]
+    pub breadth_first: bool,
+
+    /// the path to the repository to read objects from, or its .git directory. Defaults to the current directory.
+    #[argh(option, short = 'r')]
+    pub repository: Option<PathBuf>,
+
+    /// the full hexadecimal id of the tree to list, or of a commit or tag pointing to it
+    #[argh(positional)]
+    pub treeish: String,
+}
+
+/// Show the canonical name and email of contacts according to the `.mailmap` file, like `git check-mailmap`.
+///
+/// The mapping is read from the `.mailmap` file in the tree of HEAD, followed by the given files.
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "mailmap-check")]
+pub struct MailmapCheck {
+    /// read additional mappings from this file, which take precedence, like `mailmap.file`. May be repeated.
+    #[argh(option, short = 'f', long = "file")]
+    pub files: Vec<PathBuf>,
+
+    /// the path to the repository to read objects from, or its .git directory. Defaults to the current directory.
+    #[argh(option, short = 'r')]
+    pub repository: Option<PathBuf>,
+
+    /// the contacts to look up, as 'Name <email>' or '<email>'
+    #[argh(positional)]
+    pub contacts: Vec<String>,
+}
@@ -130,6 +130,10 @@ pub fn main() -> Result<()> {
             url,
             directory,
             refs_directory,
+            deepen_since,
+            max_object_size,
+            max_delta_chain_length,
+            max_resolved_base_bytes,
         } => prepare_and_run(
             "pack-receive",
             verbose,
@@ -147,6 +151,10 @@ pub fn main() -> Result<()> {
                         thread_limit,
                         format,
                         out,
+                        deepen_since,
+                        max_object_size,
+                        max_delta_chain_length,
+                        max_resolved_base_bytes,
                     },
                 )
             },
@@ -174,6 +182,9 @@ pub fn main() -> Result<()> {
             iteration_mode,
             pack_path,
             directory,
+            max_object_size,
+            max_delta_chain_length,
+            max_resolved_base_bytes,
         } => prepare_and_run(
             "pack-index-from-data",
             verbose,
@@ -190,6 +201,9 @@ pub fn main() -> Result<()> {
                         iteration_mode,
                         format,
                         out,
+                        max_object_size,
+                        max_delta_chain_length,
+                        max_resolved_base_bytes,
                     },
                 )
             },
@@ -226,8 +240,12 @@ pub fn main() -> Result<()> {
             path,
             algorithm,
             decode,
+            fsck,
+            fsck_strict,
+            fsck_severity,
             re_encode,
             statistics,
+            list_entries,
         } => prepare_and_run(
             "pack-verify",
             verbose,
@@ -235,17 +253,22 @@ pub fn main() -> Result<()> {
             progress_keep_open,
             None,
             move |progress, out, err| {
-                let mode = match (decode, re_encode) {
-                    (true, false) => verify::Mode::Sha1CRC32Decode,
-                    (true, true) | (false, true) => verify::Mode::Sha1CRC32DecodeEncode,
-                    (false, false) => verify::Mode::Sha1CRC32,
+                let mode = match (decode, fsck, re_encode) {
+                    (_, _, true) => verify::Mode::Sha1CRC32DecodeEncode,
+                    (_, true, false) => {
+                        verify::Mode::Sha1CRC32DecodeFsck(verify::fsck_options(fsck_strict, fsck_severity))
+                    }
+                    (true, false, false) => verify::Mode::Sha1CRC32Decode,
+                    (false, false, false) => verify::Mode::Sha1CRC32,
                 };
                 let output_statistics = if statistics { Some(format) } else { None };
+                let output_entries = if list_entries { Some(format) } else { None };
                 verify::pack_or_pack_index(
                     path,
                     progress,
                     verify::Context {
                         output_statistics,
+                        output_entries,
                         thread_limit,
                         algorithm,
                         mode,
@@ -256,6 +279,108 @@ pub fn main() -> Result<()> {
             },
         )
         .map(|_| ()),
+        Subcommands::RepositoryCloneLocal {
+            mode,
+            source,
+            destination,
+        } => prepare_and_run(
+            "repository-clone-local",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::clone_local(&source, destination, mode, core::repository::Context { format, out })
+            },
+        ),
+        Subcommands::RepositorySize { limit, repository } => prepare_and_run(
+            "repository-size",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |progress, out, _err| {
+                core::repository::size::analyse(
+                    repository,
+                    progress,
+                    core::repository::size::Context {
+                        format,
+                        out,
+                        thread_limit,
+                        limit,
+                    },
+                )
+            },
+        )
+        .map(|_| ()),
+        Subcommands::RepositoryCatBatch {
+            check,
+            all_objects,
+            object_format,
+            repository,
+        } => prepare_and_run(
+            "repository-cat-batch",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::cat_batch::serve(
+                    repository,
+                    core::repository::cat_batch::Context {
+                        input: std::io::stdin().lock(),
+                        out,
+                        object_format,
+                        contents: !check,
+                        all_objects,
+                    },
+                )
+            },
+        ),
+        Subcommands::TreeList {
+            trees,
+            name_only,
+            breadth_first,
+            repository,
+            treeish,
+        } => prepare_and_run(
+            "tree-list",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::tree_list::list(
+                    repository,
+                    &treeish,
+                    core::repository::tree_list::Context {
+                        format,
+                        out,
+                        breadth_first,
+                        trees,
+                        name_only,
+                    },
+                )
+            },
+        ),
+        Subcommands::MailmapCheck {
+            files,
+            repository,
+            contacts,
+        } => prepare_and_run(
+            "mailmap-check",
+            verbose,
+            progress,
+            progress_keep_open,
+            None,
+            move |_progress, out, _err| {
+                core::repository::mailmap::check(
+                    repository,
+                    &contacts,
+                    core::repository::mailmap::Context { format, out, files },
+                )
+            },
+        ),
     }?;
     Ok(())
 }
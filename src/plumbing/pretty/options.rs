@@ -55,12 +55,31 @@ pub enum Subcommands {
         #[clap(long, short = 'r')]
         refs_directory: Option<PathBuf>,
 
+        /// Only receive commits more recent than this date, like `--shallow-since`, as any date git accepts like
+        /// '2020-09-17' or '2 weeks ago'.
+        #[clap(long)]
+        deepen_since: Option<String>,
+
+        /// The maximum size in bytes of objects and deltas in the pack, unlimited by default.
+        #[clap(long)]
+        max_object_size: Option<u64>,
+
+        /// The maximum length of delta chains in the pack, defaults to 4095.
+        #[clap(long)]
+        max_delta_chain_length: Option<u32>,
+
+        /// The maximum amount of bytes of resolved objects to keep in memory as delta bases while indexing, at the
+        /// cost of resolving them again when needed. Unlimited by default.
+        #[clap(long)]
+        max_resolved_base_bytes: Option<u64>,
+
         /// The URLs or path from which to receive the pack.
         ///
         /// See here for a list of supported URLs: https://www.git-scm.com/docs/git-clone#_git_urls
         url: String,
 
-        /// The directory into which to write the received pack and index.
+        /// The directory into which to write the received pack and index, along with a `shallow` file listing the
+        /// commits whose parents weren't received when deepening.
         ///
         /// If unset, they will be discarded.
         directory: Option<PathBuf>,
@@ -105,6 +124,19 @@ pub enum Subcommands {
         #[clap(long, short = 'p')]
         pack_path: Option<PathBuf>,
 
+        /// The maximum size in bytes of objects and deltas in the pack, unlimited by default.
+        #[clap(long)]
+        max_object_size: Option<u64>,
+
+        /// The maximum length of delta chains in the pack, defaults to 4095.
+        #[clap(long)]
+        max_delta_chain_length: Option<u32>,
+
+        /// The maximum amount of bytes of resolved objects to keep in memory as delta bases while indexing, at the
+        /// cost of resolving them again when needed. Unlimited by default.
+        #[clap(long)]
+        max_resolved_base_bytes: Option<u64>,
+
         /// The folder into which to place the pack and the generated index file
         ///
         /// If unset, only informational output will be provided to standard output.
@@ -157,6 +189,10 @@ pub enum Subcommands {
         /// output statistical information about the pack
         #[clap(long, short = 's')]
         statistics: bool,
+        /// list every entry of the pack with its id, kind, size, size in pack, offset, and if it is a delta, its
+        /// delta chain length and base id, like `git verify-pack -v`
+        #[clap(long, short = 'l')]
+        list_entries: bool,
         /// The algorithm used to verify the pack. They differ in costs.
         #[clap(
             long,
@@ -173,6 +209,23 @@ pub enum Subcommands {
         /// This will reduce overall performance.
         decode: bool,
 
+        #[clap(long, conflicts_with("re-encode"))]
+        /// Decode tags, commits and trees and check them for problems like `git fsck` does, failing on errors.
+        ///
+        /// This finds objects which parse but are malformed or dangerous, like unsorted trees, trees with '.git'
+        /// entries or signatures with invalid emails or timezones. It implies '--decode'.
+        fsck: bool,
+
+        #[clap(long, requires("fsck"))]
+        /// Turn the warnings of '--fsck' into errors, like git does when receiving objects with 'transfer.fsckObjects'.
+        fsck_strict: bool,
+
+        #[clap(long, requires("fsck"), number_of_values(1))]
+        /// Report the check with the given id at the given level with '--fsck', like 'missingEmail=ignore'. May be repeated.
+        ///
+        /// This is what 'fsck.<id>' configures in git. Levels are 'error', 'warn', 'info' and 'ignore'.
+        fsck_severity: Vec<core::pack::verify::FsckSeverity>,
+
         #[clap(long)]
         /// Decode and parse tags, commits and trees to validate their correctness, and re-encode them.
         ///
@@ -186,4 +239,116 @@ pub enum Subcommands {
         #[clap(parse(from_os_str))]
         path: PathBuf,
     },
+    /// Clone a repository on the local file system into a new bare repository without generating a pack.
+    ///
+    /// This is the plumbing equivalent of `git clone --bare --local`.
+    #[clap(setting = AppSettings::ColoredHelp)]
+    #[clap(setting = AppSettings::DisableVersion)]
+    RepositoryCloneLocal {
+        /// Determines how objects are made available in the clone, defaults to 'link'
+        ///
+        /// Valid values are
+        ///
+        ///  **link** hardlink objects, and copy them if that fails, for example across file systems,
+        ///  **copy** always copy objects,
+        ///  **reference** don't copy any object but register the source objects directory as alternate
+        #[clap(
+            long,
+            short = 'm',
+            default_value = "link",
+            possible_values(core::repository::CloneMode::variants())
+        )]
+        mode: core::repository::CloneMode,
+
+        /// The path or file:// URL to the repository to clone, or its .git directory
+        source: String,
+
+        /// The directory into which to clone. It must not exist or be empty.
+        #[clap(parse(from_os_str))]
+        destination: PathBuf,
+    },
+    /// Analyse the size of all objects in a repository to find those that may cause trouble, like `git-sizer`.
+    ///
+    /// Lists object counts and sizes by kind, the largest blobs, the deepest and widest trees, the longest
+    /// delta chains and the commits with the most parents.
+    #[clap(setting = AppSettings::ColoredHelp)]
+    #[clap(setting = AppSettings::DisableVersion)]
+    RepositorySize {
+        /// The amount of objects to show in each list of largest or deepest objects
+        #[clap(long, short = 'n', default_value = "10")]
+        limit: usize,
+
+        /// The path to the repository to analyse, or its .git directory
+        #[clap(parse(from_os_str), default_value = ".")]
+        repository: PathBuf,
+    },
+    /// Read object names from standard input and write their information and contents to standard output.
+    ///
+    /// This is the plumbing equivalent of `git cat-file --batch` and `git cat-file --batch-check`, using the same
+    /// input and output format.
+    #[clap(setting = AppSettings::ColoredHelp)]
+    #[clap(setting = AppSettings::DisableVersion)]
+    RepositoryCatBatch {
+        /// Only write the line describing each object, but not its contents, like `--batch-check`.
+        #[clap(long, short = 'c')]
+        check: bool,
+
+        /// Ignore standard input and write all objects in the repository and its alternates, like `--batch-all-objects`.
+        #[clap(long, short = 'a')]
+        all_objects: bool,
+
+        /// The format of the line written for each object, defaults to '%(objectname) %(objecttype) %(objectsize)'.
+        ///
+        /// Supported elements are %(objectname), %(objecttype), %(objectsize), %(objectsize:disk), %(deltabase) and %(rest).
+        #[clap(long, short = 'o')]
+        object_format: Option<String>,
+
+        /// The path to the repository to read objects from, or its .git directory
+        #[clap(parse(from_os_str), default_value = ".")]
+        repository: PathBuf,
+    },
+    /// List all entries of a tree and its subtrees, like `git ls-tree -r`.
+    ///
+    /// Each entry is written as '<mode> <type> <object>' followed by a tab and its path, unless only names are listed.
+    #[clap(setting = AppSettings::ColoredHelp)]
+    #[clap(setting = AppSettings::DisableVersion)]
+    TreeList {
+        /// List subtrees as well, instead of only the entries within them, like `-t`.
+        #[clap(long, short = 't')]
+        trees: bool,
+
+        /// Only list the path of each entry, like `--name-only`.
+        #[clap(long, short = 'n')]
+        name_only: bool,
+
+        /// List all entries of a tree before the entries of its subtrees, instead of listing the entries of a subtree
+        /// right after the subtree itself.
+        #[clap(long, short = 'b')]
+        breadth_first: bool,
+
+        /// The path to the repository to read objects from, or its .git directory
+        #[clap(long, short = 'r', parse(from_os_str), default_value = ".")]
+        repository: PathBuf,
+
+        /// The full hexadecimal id of the tree to list, or of a commit or tag pointing to it
+        treeish: String,
+    },
+    /// Show the canonical name and email of contacts according to the `.mailmap` file, like `git check-mailmap`.
+    ///
+    /// The mapping is read from the `.mailmap` file in the tree of HEAD, followed by the given files.
+    #[clap(setting = AppSettings::ColoredHelp)]
+    #[clap(setting = AppSettings::DisableVersion)]
+    MailmapCheck {
+        /// Read additional mappings from this file, which take precedence, like `mailmap.file`. May be repeated.
+        #[clap(long = "file", short = 'f', parse(from_os_str))]
+        files: Vec<PathBuf>,
+
+        /// The path to the repository to read objects from, or its .git directory
+        #[clap(long, short = 'r', parse(from_os_str), default_value = ".")]
+        repository: PathBuf,
+
+        /// The contacts to look up, as 'Name <email>' or '<email>'
+        #[clap(required = true)]
+        contacts: Vec<String>,
+    },
 }
@@ -1,5 +1,7 @@
 mod options {
     use argh::FromArgs;
+    use gitoxide_core as core;
+    use std::path::PathBuf;
 
     #[derive(FromArgs)]
     /// The lean git
@@ -16,12 +18,57 @@ mod options {
     #[argh(subcommand)]
     pub enum SubCommands {
         Init(Init),
+        Diff(Diff),
     }
 
     /// Initialize the repository in the current directory.
     #[derive(FromArgs, PartialEq, Debug)]
     #[argh(subcommand, name = "init")]
     pub struct Init {}
+
+    /// Show the changes between two commits or trees as a patch, like `git diff`.
+    #[derive(FromArgs, PartialEq, Debug)]
+    #[argh(subcommand, name = "diff")]
+    pub struct Diff {
+        #[argh(option, short = 'a')]
+        /// the algorithm used to find the lines that changed, like git's `diff.algorithm`.
+        ///
+        /// Possible values are "default", "myers", "minimal" and "histogram". Default is "myers".
+        pub algorithm: Option<core::repository::diff::Algorithm>,
+
+        #[argh(option, short = 'U', default = "3")]
+        /// the amount of unchanged lines to show around each change.
+        pub unified: usize,
+
+        #[argh(switch)]
+        /// show renamed files as additions and deletions.
+        pub no_renames: bool,
+
+        #[argh(switch, short = 'C')]
+        /// also show files copied from modified files as copies.
+        pub find_copies: bool,
+
+        #[argh(option, short = 'M', default = "50")]
+        /// the minimum similarity in percent of two files to be considered a rename or copy.
+        pub rename_threshold: u8,
+
+        #[argh(option, short = 'l', default = "1000")]
+        /// only find renames and copies of identical files or files with the same name if there are more added files
+        /// times candidates than the square of this value, like `diff.renameLimit`. 0 means no limit.
+        pub rename_limit: usize,
+
+        #[argh(option, short = 'r', default = "PathBuf::from(\".\")")]
+        /// the path to the repository containing both commits, or its .git directory.
+        pub repository: PathBuf,
+
+        #[argh(positional)]
+        /// the full id of the commit or tree to compare with.
+        pub previous: String,
+
+        #[argh(positional)]
+        /// the full id of the commit or tree to show the changes of.
+        pub current: String,
+    }
 }
 
 use anyhow::Result;
@@ -34,5 +81,34 @@ pub fn main() -> Result<()> {
 
     match cli.subcommand {
         SubCommands::Init(_) => core::repository::init(),
+        SubCommands::Diff(Diff {
+            algorithm,
+            unified,
+            no_renames,
+            find_copies,
+            rename_threshold,
+            rename_limit,
+            repository,
+            previous,
+            current,
+        }) => core::repository::diff::print(
+            repository,
+            &previous,
+            &current,
+            core::repository::diff::Context {
+                out: std::io::stdout(),
+                algorithm: algorithm.unwrap_or(core::repository::diff::Algorithm::Myers),
+                context_lines: unified,
+                renames: if no_renames {
+                    None
+                } else {
+                    Some(core::repository::diff::rewrites::Options {
+                        percentage: rename_threshold,
+                        limit: rename_limit,
+                        copies: find_copies,
+                    })
+                },
+            },
+        ),
     }
 }
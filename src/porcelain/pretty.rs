@@ -4,6 +4,8 @@ use gitoxide_core as core;
 
 mod options {
     use clap::{AppSettings, Clap};
+    use gitoxide_core as core;
+    use std::path::PathBuf;
 
     #[derive(Debug, Clap)]
     #[clap(about = "The rusty git", version = clap::crate_version!())]
@@ -21,6 +23,50 @@ mod options {
         #[clap(setting = AppSettings::ColoredHelp)]
         #[clap(setting = AppSettings::DisableVersion)]
         Init,
+        /// Show the changes between two commits or trees as a patch, like `git diff`.
+        #[clap(setting = AppSettings::ColoredHelp)]
+        #[clap(setting = AppSettings::DisableVersion)]
+        Diff {
+            /// The algorithm used to find the lines that changed, like git's `diff.algorithm`.
+            #[clap(
+                long,
+                short = 'a',
+                default_value = "default",
+                possible_values(core::repository::diff::Algorithm::variants())
+            )]
+            algorithm: core::repository::diff::Algorithm,
+
+            /// The amount of unchanged lines to show around each change.
+            #[clap(long, short = 'U', default_value = "3")]
+            unified: usize,
+
+            /// Show renamed files as additions and deletions.
+            #[clap(long)]
+            no_renames: bool,
+
+            /// Also show files copied from modified files as copies.
+            #[clap(long, short = 'C', conflicts_with("no-renames"))]
+            find_copies: bool,
+
+            /// The minimum similarity in percent of two files to be considered a rename or copy.
+            #[clap(long, short = 'M', default_value = "50")]
+            rename_threshold: u8,
+
+            /// Only find renames and copies of identical files or files with the same name if there are more
+            /// added files times candidates than the square of this value, like `diff.renameLimit`. 0 means no limit.
+            #[clap(long, short = 'l', default_value = "1000")]
+            rename_limit: usize,
+
+            /// The path to the repository containing both commits, or its .git directory.
+            #[clap(long, short = 'r', parse(from_os_str), default_value = ".")]
+            repository: PathBuf,
+
+            /// The full id of the commit or tree to compare with.
+            previous: String,
+
+            /// The full id of the commit or tree to show the changes of.
+            current: String,
+        },
     }
 }
 
@@ -30,6 +76,35 @@ pub fn main() -> Result<()> {
     git_features::interrupt::init_handler(std::io::stderr());
     match args.cmd {
         Subcommands::Init => core::repository::init(),
+        Subcommands::Diff {
+            algorithm,
+            unified,
+            no_renames,
+            find_copies,
+            rename_threshold,
+            rename_limit,
+            repository,
+            previous,
+            current,
+        } => core::repository::diff::print(
+            repository,
+            &previous,
+            &current,
+            core::repository::diff::Context {
+                out: std::io::stdout(),
+                algorithm,
+                context_lines: unified,
+                renames: if no_renames {
+                    None
+                } else {
+                    Some(core::repository::diff::rewrites::Options {
+                        percentage: rename_threshold,
+                        limit: rename_limit,
+                        copies: find_copies,
+                    })
+                },
+            },
+        ),
     }?;
     Ok(())
 }
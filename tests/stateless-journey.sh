@@ -203,6 +203,22 @@ snapshot="$snapshot/plumbing"
       )
       fi
     )
+    (with "a commit newer than the others"
+      GIT_AUTHOR_DATE="2020-09-10 09:06:03 +0800" GIT_COMMITTER_DATE="2020-09-10 09:06:03 +0800" \
+        git commit --allow-empty -m "fourth" &>/dev/null
+      (with "file:// protocol version 2 and --deepen-since"
+        mkdir out
+        it "generates the correct output" && {
+          WITH_SNAPSHOT="$snapshot/file-v2-deepen-since-with-output" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" pack-receive -p 2 --deepen-since "2020-09-10 00:00:00 +0800" .git out/
+        }
+        it "writes the shallow commits into the output directory" && {
+          WITH_SNAPSHOT="$snapshot/shallow-in-output-dir" \
+          expect_run $SUCCESSFULLY cat out/shallow
+        }
+        rm -Rf out
+      )
+    )
   )
 )
 (when "running 'remote-ref-list'"
@@ -310,6 +326,18 @@ snapshot="$snapshot/plumbing"
       )
     )
   )
+  (with "a pack with objects larger than --max-object-size"
+    it "fails with a helpful error message" && {
+      WITH_SNAPSHOT="$snapshot/max-object-size-failure" \
+      expect_run $WITH_FAILURE "$exe_plumbing" pack-index-from-data --max-object-size 100 -p "$PACK_FILE"
+    }
+  )
+  (with "a pack with delta chains longer than --max-delta-chain-length"
+    it "fails with a helpful error message" && {
+      WITH_SNAPSHOT="$snapshot/max-delta-chain-length-failure" \
+      expect_run $WITH_FAILURE "$exe_plumbing" pack-index-from-data --max-delta-chain-length 1 -p "$PACK_FILE"
+    }
+  )
   (with "'restore' iteration mode"
     (sandbox
       cp "${PACK_FILE}" .
@@ -443,6 +471,12 @@ snapshot="$snapshot/plumbing"
         expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify --statistics "$PACK_INDEX_FILE"
       }
     )
+    (with "statistics and the balanced algorithm"
+      it "verifies the pack index successfully and with desired output" && {
+        WITH_SNAPSHOT="$snapshot/index-with-statistics-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify --algorithm balanced --statistics "$PACK_INDEX_FILE"
+      }
+    )
     (with "decode"
       it "verifies the pack index successfully and with desired output, and decodes all objects" && {
         WITH_SNAPSHOT="$snapshot/index-success" \
@@ -455,6 +489,34 @@ snapshot="$snapshot/plumbing"
         expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify --algorithm less-time --re-encode "$PACK_INDEX_FILE"
       }
     )
+    (with "strict fsck and configured severities"
+      it "verifies the pack index successfully and with desired output, and checks all objects" && {
+        WITH_SNAPSHOT="$snapshot/index-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify --fsck --fsck-strict --fsck-severity zeroPaddedFilemode=ignore "$PACK_INDEX_FILE"
+      }
+    )
+    if test "$kind" = "small"; then
+    (with "fsck and re-encode"
+      it "fails as both can't be done at once" && {
+        expect_run $WITH_FAILURE "$exe_plumbing" pack-verify --fsck --re-encode "$PACK_INDEX_FILE"
+      }
+    )
+    (with "fsck-strict but without fsck"
+      it "fails as there is nothing to be strict about" && {
+        expect_run $WITH_FAILURE "$exe_plumbing" pack-verify --fsck-strict "$PACK_INDEX_FILE"
+      }
+    )
+    fi
+    (sandbox
+      (with "list-entries"
+        cp "$PACK_INDEX_FILE" pack.idx
+        cp "${PACK_INDEX_FILE%.idx}.pack" pack.pack
+        it "verifies the pack index successfully and lists all entries like 'git verify-pack -v'" && {
+          WITH_SNAPSHOT="$snapshot/index-with-entries-success" \
+          expect_run $SUCCESSFULLY "$exe_plumbing" pack-verify --list-entries pack.idx
+        }
+      )
+    )
     if test "$kind" = "max"; then
     (with "statistics (JSON)"
       it "verifies the pack index successfully and with desired output" && {
@@ -476,3 +538,80 @@ snapshot="$snapshot/plumbing"
     )
   )
 )
+
+(when "running 'repository-clone-local'"
+  snapshot="$snapshot/repository-clone-local"
+  (small-repo-in-sandbox
+    git clone --bare --quiet . 'source;with#comment-chars'
+    (with "the copy mode"
+      it "clones all objects and reports them" && {
+        WITH_SNAPSHOT="$snapshot/copy-success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" repository-clone-local --mode copy 'source;with#comment-chars' clone
+      }
+      it "creates a repository git can read" && {
+        expect_run $SUCCESSFULLY git -C clone fsck --strict
+        WITH_SNAPSHOT="$snapshot/copy-refs" \
+        expect_run $SUCCESSFULLY git -C clone rev-parse HEAD dev annotated unannotated
+      }
+      it "writes a remote url that git reads back as the source path" && {
+        expect_run $SUCCESSFULLY test "$(git -C clone config remote.origin.url)" = "$(cd 'source;with#comment-chars' && pwd -P)"
+      }
+      it "detects whether the file system supports the executable bit like git init" && {
+        expect_run $SUCCESSFULLY test "$(git -C clone config core.filemode)" = "$(git config core.filemode)"
+      }
+    )
+    (with "a non-empty destination"
+      it "fails with a helpful error message" && {
+        WITH_SNAPSHOT="$snapshot/non-empty-destination-failure" \
+        expect_run $WITH_FAILURE "$exe_plumbing" repository-clone-local 'source;with#comment-chars' clone
+      }
+    )
+  )
+)
+
+(when "running 'repository-size'"
+  snapshot="$snapshot/repository-size"
+  (small-repo-in-sandbox
+    {
+      mkdir -p dir/subdir
+      echo nested > dir/subdir/c
+      git add dir
+      git commit -m "nested"
+      git repack -q
+    } &>/dev/null
+    (with "objects which are both packed and loose"
+      it "counts each object once and lists the top objects by size, then id" && {
+        WITH_SNAPSHOT="$snapshot/success" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" repository-size -n 3
+      }
+    )
+    if test "$kind" = "max"; then
+    (with "--format json"
+      it "generates the correct output in JSON format" && {
+        WITH_SNAPSHOT="$snapshot/success-json" \
+        expect_run $SUCCESSFULLY "$exe_plumbing" --format json repository-size -n 3 .git
+      }
+    )
+    fi
+  )
+)
+
+(when "running 'repository-cat-batch'"
+  snapshot="$snapshot/repository-cat-batch"
+  (small-repo-in-sandbox
+    {
+      git repack -q
+      git pack-refs --all
+      printf '%s\n' HEAD @ dev annotated refs/tags/unannotated HEAD:b HEAD: HEAD:missing "$(git rev-parse --short HEAD)" 0000 nonexistent > names
+    } &>/dev/null
+    (with "references, abbreviated ids and paths as object names"
+      it "writes the object of each name like 'git cat-file --batch-check'" && {
+        WITH_SNAPSHOT="$snapshot/check-success" \
+        expect_run_sh $SUCCESSFULLY "'$exe_plumbing' repository-cat-batch --check < names"
+      }
+      it "writes the same as git" && {
+        expect_run_sh $SUCCESSFULLY "diff <(git cat-file --batch < names) <('$exe_plumbing' repository-cat-batch < names)"
+      }
+    )
+  )
+)